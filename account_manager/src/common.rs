@@ -1,14 +1,22 @@
+use crate::wallet::create::{MNEMONIC_PASSPHRASE_FILE_FLAG, MNEMONIC_PASSPHRASE_FLAG};
 use account_utils::PlainText;
-use account_utils::{read_input_from_user, strip_off_newlines};
-use eth2_wallet::bip39::{Language, Mnemonic};
+use account_utils::{
+    mnemonic_from_phrase_any_language, read_input_from_user, read_password_from_user,
+    strip_off_newlines, ZeroizeString,
+};
+use clap::ArgMatches;
+use eth2_wallet::bip39::Mnemonic;
 use std::fs;
 use std::path::PathBuf;
 use std::str::from_utf8;
 use std::thread::sleep;
 use std::time::Duration;
+use types::Address;
 
 pub const MNEMONIC_PROMPT: &str = "Enter the mnemonic phrase:";
 pub const WALLET_NAME_PROMPT: &str = "Enter wallet name:";
+pub const MNEMONIC_PASSPHRASE_PROMPT: &str = "Enter the BIP-39 passphrase for the mnemonic:";
+pub const RETYPE_MNEMONIC_PASSPHRASE_PROMPT: &str = "Please re-enter the BIP-39 passphrase:";
 
 pub fn read_mnemonic_from_cli(
     mnemonic_path: Option<PathBuf>,
@@ -21,7 +29,7 @@ pub fn read_mnemonic_from_cli(
                 let bytes_no_newlines: PlainText = strip_off_newlines(bytes).into();
                 let phrase = from_utf8(&bytes_no_newlines.as_ref())
                     .map_err(|e| format!("Unable to derive mnemonic: {:?}", e))?;
-                Mnemonic::from_phrase(phrase, Language::English).map_err(|e| {
+                mnemonic_from_phrase_any_language(phrase).map_err(|e| {
                     format!(
                         "Unable to derive mnemonic from string {:?}: {:?}",
                         phrase, e
@@ -34,15 +42,15 @@ pub fn read_mnemonic_from_cli(
 
             let mnemonic = read_input_from_user(stdin_inputs)?;
 
-            match Mnemonic::from_phrase(mnemonic.as_str(), Language::English) {
+            match mnemonic_from_phrase_any_language(mnemonic.as_str()) {
                 Ok(mnemonic_m) => {
                     eprintln!("Valid mnemonic provided.");
                     eprintln!("");
                     sleep(Duration::from_secs(1));
                     break mnemonic_m;
                 }
-                Err(_) => {
-                    eprintln!("Invalid mnemonic");
+                Err(e) => {
+                    eprintln!("Invalid mnemonic: {}", e);
                 }
             }
         },
@@ -65,3 +73,61 @@ pub fn read_wallet_name_from_cli(
         }
     }
 }
+
+/// Reads an optional BIP-39 mnemonic passphrase from the file given by `--mnemonic-passphrase-file`
+/// or, if `--mnemonic-passphrase` is present, from an interactive prompt using tty unless the
+/// `--stdin-inputs` flag is provided. The passphrase is never accepted as a CLI argument.
+///
+/// Returns an empty passphrase if neither flag is present.
+pub fn read_mnemonic_passphrase_from_cli(
+    matches: &ArgMatches,
+    stdin_inputs: bool,
+) -> Result<ZeroizeString, String> {
+    let passphrase_path: Option<PathBuf> =
+        clap_utils::parse_optional(matches, MNEMONIC_PASSPHRASE_FILE_FLAG)?;
+
+    if let Some(path) = passphrase_path {
+        let bytes = fs::read(&path)
+            .map(strip_off_newlines)
+            .map_err(|e| format!("Unable to read {:?}: {:?}", path, e))?;
+        String::from_utf8(bytes)
+            .map(ZeroizeString::from)
+            .map_err(|_| format!("Passphrase in {:?} is not valid UTF-8", path))
+    } else if matches.is_present(MNEMONIC_PASSPHRASE_FLAG) {
+        loop {
+            eprintln!("");
+            eprintln!("{}", MNEMONIC_PASSPHRASE_PROMPT);
+            let passphrase = read_password_from_user(stdin_inputs)?;
+
+            eprintln!("{}", RETYPE_MNEMONIC_PASSPHRASE_PROMPT);
+            let retyped_passphrase = read_password_from_user(stdin_inputs)?;
+
+            if retyped_passphrase == passphrase {
+                break Ok(passphrase);
+            } else {
+                eprintln!("Passphrases do not match.");
+            }
+        }
+    } else {
+        Ok(ZeroizeString::from(String::new()))
+    }
+}
+
+/// Reads an optional `0x`-prefixed Eth1 address, to be used for `0x01` withdrawal credentials.
+pub fn read_withdrawal_address_from_cli(
+    matches: &ArgMatches,
+    name: &str,
+) -> Result<Option<Address>, String> {
+    matches
+        .value_of(name)
+        .map(|val| {
+            if val.starts_with("0x") {
+                val[2..]
+                    .parse()
+                    .map_err(|e| format!("Unable to parse --{}: {:?}", name, e))
+            } else {
+                Err(format!("Unable to parse --{}, must have 0x prefix", name))
+            }
+        })
+        .transpose()
+}
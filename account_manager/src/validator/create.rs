@@ -1,4 +1,4 @@
-use crate::common::read_wallet_name_from_cli;
+use crate::common::{read_wallet_name_from_cli, read_withdrawal_address_from_cli};
 use crate::wallet::create::STDIN_INPUTS_FLAG;
 use crate::{SECRETS_DIR_FLAG, WALLETS_DIR_FLAG};
 use account_utils::{
@@ -14,7 +14,7 @@ use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
 use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
-use types::EthSpec;
+use types::{EthSpec, WithdrawalCredentialsType};
 use validator_dir::Builder as ValidatorDirBuilder;
 
 pub const CMD: &str = "create";
@@ -22,6 +22,7 @@ pub const WALLET_NAME_FLAG: &str = "wallet-name";
 pub const WALLET_PASSWORD_FLAG: &str = "wallet-password";
 pub const DEPOSIT_GWEI_FLAG: &str = "deposit-gwei";
 pub const STORE_WITHDRAW_FLAG: &str = "store-withdrawal-keystore";
+pub const WITHDRAWAL_ADDRESS_FLAG: &str = "withdrawal-address";
 pub const COUNT_FLAG: &str = "count";
 pub const AT_MOST_FLAG: &str = "at-most";
 pub const WALLET_PASSWORD_PROMPT: &str = "Enter your wallet's password:";
@@ -84,6 +85,18 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                     instead generate them from the wallet seed when required.",
                 ),
         )
+        .arg(
+            Arg::with_name(WITHDRAWAL_ADDRESS_FLAG)
+                .long(WITHDRAWAL_ADDRESS_FLAG)
+                .value_name("ETH1_ADDRESS")
+                .help(
+                    "If present, the deposits will use 0x01 withdrawal credentials which commit \
+                    to this 0x-prefixed Eth1 address, instead of BLS withdrawal credentials \
+                    derived from the withdrawal keypair.",
+                )
+                .conflicts_with(STORE_WITHDRAW_FLAG)
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name(COUNT_FLAG)
                 .long(COUNT_FLAG)
@@ -136,6 +149,7 @@ pub fn cli_run<T: EthSpec>(
         .unwrap_or_else(|| spec.max_effective_balance);
     let count: Option<usize> = clap_utils::parse_optional(matches, COUNT_FLAG)?;
     let at_most: Option<usize> = clap_utils::parse_optional(matches, AT_MOST_FLAG)?;
+    let withdrawal_address = read_withdrawal_address_from_cli(matches, WITHDRAWAL_ADDRESS_FLAG)?;
 
     ensure_dir_exists(&validator_dir)?;
     ensure_dir_exists(&secrets_dir)?;
@@ -143,6 +157,12 @@ pub fn cli_run<T: EthSpec>(
     eprintln!("validator-dir path: {:?}", validator_dir);
     eprintln!("secrets-dir path {:?}", secrets_dir);
     eprintln!("wallets-dir path {:?}", wallet_base_dir);
+    if let Some(address) = withdrawal_address {
+        eprintln!(
+            "withdrawal credentials: {}",
+            WithdrawalCredentialsType::Eth1Address(address)
+        );
+    }
 
     let starting_validator_count = existing_validator_count(&validator_dir)?;
 
@@ -227,12 +247,18 @@ pub fn cli_run<T: EthSpec>(
                 )
             })?;
 
-        ValidatorDirBuilder::new(validator_dir.clone())
+        let mut builder = ValidatorDirBuilder::new(validator_dir.clone())
             .password_dir(secrets_dir.clone())
             .voting_keystore(keystores.voting, voting_password.as_bytes())
             .withdrawal_keystore(keystores.withdrawal, withdrawal_password.as_bytes())
             .create_eth1_tx_data(deposit_gwei, &spec)
-            .store_withdrawal_keystore(matches.is_present(STORE_WITHDRAW_FLAG))
+            .store_withdrawal_keystore(matches.is_present(STORE_WITHDRAW_FLAG));
+
+        if let Some(address) = withdrawal_address {
+            builder = builder.eth1_withdrawal_address(address);
+        }
+
+        builder
             .build()
             .map_err(|e| format!("Unable to build validator directory: {:?}", e))?;
 
@@ -3,7 +3,9 @@ pub mod exit;
 pub mod import;
 pub mod list;
 pub mod recover;
+pub mod reencrypt;
 pub mod slashing_protection;
+pub mod split;
 
 use crate::VALIDATOR_DIR_FLAG;
 use clap::{App, Arg, ArgMatches};
@@ -32,8 +34,10 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         .subcommand(import::cli_app())
         .subcommand(list::cli_app())
         .subcommand(recover::cli_app())
+        .subcommand(reencrypt::cli_app())
         .subcommand(slashing_protection::cli_app())
         .subcommand(exit::cli_app())
+        .subcommand(split::cli_app())
 }
 
 pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<(), String> {
@@ -49,11 +53,13 @@ pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<
         (create::CMD, Some(matches)) => create::cli_run::<T>(matches, env, validator_base_dir),
         (import::CMD, Some(matches)) => import::cli_run(matches, validator_base_dir),
         (list::CMD, Some(_)) => list::cli_run(validator_base_dir),
-        (recover::CMD, Some(matches)) => recover::cli_run(matches, validator_base_dir),
+        (recover::CMD, Some(matches)) => recover::cli_run::<T>(matches, env, validator_base_dir),
+        (reencrypt::CMD, Some(matches)) => reencrypt::cli_run(matches, validator_base_dir),
         (slashing_protection::CMD, Some(matches)) => {
             slashing_protection::cli_run(matches, env, validator_base_dir)
         }
         (exit::CMD, Some(matches)) => exit::cli_run(matches, env),
+        (split::CMD, Some(matches)) => split::cli_run(matches),
         (unknown, _) => Err(format!(
             "{} does not have a {} command. See --help",
             CMD, unknown
@@ -1,20 +1,27 @@
-use super::create::STORE_WITHDRAW_FLAG;
-use crate::common::read_mnemonic_from_cli;
+use super::create::{STORE_WITHDRAW_FLAG, WITHDRAWAL_ADDRESS_FLAG};
+use crate::common::{
+    read_mnemonic_from_cli, read_mnemonic_passphrase_from_cli, read_withdrawal_address_from_cli,
+};
 use crate::validator::create::COUNT_FLAG;
-use crate::wallet::create::STDIN_INPUTS_FLAG;
+use crate::wallet::create::{
+    mnemonic_passphrase_arg, mnemonic_passphrase_file_arg, STDIN_INPUTS_FLAG,
+};
 use crate::SECRETS_DIR_FLAG;
-use account_utils::eth2_keystore::{keypair_from_secret, Keystore, KeystoreBuilder};
-use account_utils::random_password;
+use account_utils::{find_voting_key_indices, random_password};
 use clap::{App, Arg, ArgMatches};
 use directory::ensure_dir_exists;
 use directory::{parse_path_or_default_with_flag, DEFAULT_SECRET_DIR};
+use environment::Environment;
 use eth2_wallet::bip39::Seed;
-use eth2_wallet::{recover_validator_secret_from_mnemonic, KeyType, ValidatorKeystores};
+use eth2_wallet::{ValidatorKeystores, ValidatorPathTemplate, INDEX_NODE};
 use std::path::PathBuf;
+use types::{EthSpec, PublicKey, WithdrawalCredentialsType};
 use validator_dir::Builder as ValidatorDirBuilder;
 pub const CMD: &str = "recover";
 pub const FIRST_INDEX_FLAG: &str = "first-index";
 pub const MNEMONIC_FLAG: &str = "mnemonic-path";
+pub const PATH_TEMPLATE_FLAG: &str = "path-template";
+pub const VOTING_PUBKEYS_FLAG: &str = "voting-pubkeys";
 
 pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
     App::new(CMD)
@@ -46,10 +53,38 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .long(MNEMONIC_FLAG)
                 .value_name("MNEMONIC_PATH")
                 .help(
-                    "If present, the mnemonic will be read in from this file.",
+                    "If present, the mnemonic will be read in from this file. The language of \
+                    the mnemonic is detected automatically.",
                 )
                 .takes_value(true)
         )
+        .arg(mnemonic_passphrase_arg())
+        .arg(mnemonic_passphrase_file_arg())
+        .arg(
+            Arg::with_name(PATH_TEMPLATE_FLAG)
+                .long(PATH_TEMPLATE_FLAG)
+                .value_name("PATH_TEMPLATE")
+                .help(
+                    "The EIP-2334 derivation path of the voting keys, with the key index \
+                    replaced by `i` (e.g., m/12381/3600/i/0/0). The withdrawal key is derived \
+                    from the parent of the voting key. A path without an `i` node recovers \
+                    the single key at exactly that path and requires a `--count` of 1.",
+                )
+                .takes_value(true)
+                .default_value("m/12381/3600/i/0/0"),
+        )
+        .arg(
+            Arg::with_name(VOTING_PUBKEYS_FLAG)
+                .long(VOTING_PUBKEYS_FLAG)
+                .value_name("VOTING_PUBKEYS")
+                .help(
+                    "A comma-separated list of 0x-prefixed voting public keys. If present, the \
+                    `--count` indices starting from `--first-index` are searched and only the \
+                    keys matching these public keys are recovered. No keys are recovered unless \
+                    all of them are found. Each public key may only be supplied once.",
+                )
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name(SECRETS_DIR_FLAG)
                 .long(SECRETS_DIR_FLAG)
@@ -69,6 +104,20 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                     instead generate them from the wallet seed when required.",
                 ),
         )
+        .arg(
+            Arg::with_name(WITHDRAWAL_ADDRESS_FLAG)
+                .long(WITHDRAWAL_ADDRESS_FLAG)
+                .value_name("ETH1_ADDRESS")
+                .help(
+                    "If present, eth1 deposit data for MAX_EFFECTIVE_BALANCE will be created \
+                    for each recovered validator, using 0x01 withdrawal credentials which commit \
+                    to this 0x-prefixed Eth1 address. This is only useful for validators which \
+                    have never been deposited: a deposit for an existing validator is a top-up, \
+                    which cannot change its withdrawal credentials.",
+                )
+                .conflicts_with(STORE_WITHDRAW_FLAG)
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name(STDIN_INPUTS_FLAG)
                 .long(STDIN_INPUTS_FLAG)
@@ -76,7 +125,12 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         )
 }
 
-pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), String> {
+pub fn cli_run<T: EthSpec>(
+    matches: &ArgMatches,
+    mut env: Environment<T>,
+    validator_dir: PathBuf,
+) -> Result<(), String> {
+    let spec = env.core_context().eth2_config.spec;
     let secrets_dir = if matches.value_of("datadir").is_some() {
         let path: PathBuf = clap_utils::parse_required(matches, "datadir")?;
         path.join(DEFAULT_SECRET_DIR)
@@ -86,10 +140,54 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
     let first_index: u32 = clap_utils::parse_required(matches, FIRST_INDEX_FLAG)?;
     let count: u32 = clap_utils::parse_required(matches, COUNT_FLAG)?;
     let mnemonic_path: Option<PathBuf> = clap_utils::parse_optional(matches, MNEMONIC_FLAG)?;
+    let path_template: ValidatorPathTemplate =
+        clap_utils::parse_required(matches, PATH_TEMPLATE_FLAG)?;
+    let voting_pubkeys = matches
+        .value_of(VOTING_PUBKEYS_FLAG)
+        .map(|pubkeys| {
+            pubkeys
+                .split(',')
+                .map(|pubkey| {
+                    pubkey
+                        .trim()
+                        .parse::<PublicKey>()
+                        .map_err(|e| format!("Invalid voting public key {}: {}", pubkey, e))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?;
+    if let Some(voting_pubkeys) = &voting_pubkeys {
+        for (i, voting_pubkey) in voting_pubkeys.iter().enumerate() {
+            if voting_pubkeys[..i].contains(voting_pubkey) {
+                return Err(format!(
+                    "Voting public key {} is supplied to --{} more than once",
+                    voting_pubkey, VOTING_PUBKEYS_FLAG
+                ));
+            }
+        }
+    }
     let stdin_inputs = matches.is_present(STDIN_INPUTS_FLAG);
+    let withdrawal_address = read_withdrawal_address_from_cli(matches, WITHDRAWAL_ADDRESS_FLAG)?;
+
+    if !path_template.has_index() && (first_index != 0 || count != 1) {
+        return Err(format!(
+            "The --{} has no `{}` node so only a single key can be recovered; --{} and --{} \
+            cannot be used",
+            PATH_TEMPLATE_FLAG, INDEX_NODE, FIRST_INDEX_FLAG, COUNT_FLAG
+        ));
+    }
+    let last_index = first_index
+        .checked_add(count)
+        .ok_or_else(|| format!("--{} plus --{} is too large", FIRST_INDEX_FLAG, COUNT_FLAG))?;
 
     eprintln!("validator-dir path: {:?}", validator_dir);
     eprintln!("secrets-dir path: {:?}", secrets_dir);
+    if let Some(address) = withdrawal_address {
+        eprintln!(
+            "withdrawal credentials: {}",
+            WithdrawalCredentialsType::Eth1Address(address)
+        );
+    }
 
     ensure_dir_exists(&validator_dir)?;
     ensure_dir_exists(&secrets_dir)?;
@@ -98,50 +196,72 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
     eprintln!("WARNING: KEY RECOVERY CAN LEAD TO DUPLICATING VALIDATORS KEYS, WHICH CAN LEAD TO SLASHING.");
     eprintln!("");
 
+    if withdrawal_address.is_some() {
+        eprintln!(
+            "WARNING: --{} ONLY APPLIES TO VALIDATORS WHICH HAVE NEVER BEEN DEPOSITED. A DEPOSIT \
+            FOR AN EXISTING VALIDATOR IS A TOP-UP, WHICH KEEPS ITS ORIGINAL WITHDRAWAL CREDENTIALS.",
+            WITHDRAWAL_ADDRESS_FLAG
+        );
+        eprintln!("");
+    }
+
     let mnemonic = read_mnemonic_from_cli(mnemonic_path, stdin_inputs)?;
+    let mnemonic_passphrase = read_mnemonic_passphrase_from_cli(matches, stdin_inputs)?;
+
+    let seed = Seed::new(&mnemonic, mnemonic_passphrase.as_str());
 
-    let seed = Seed::new(&mnemonic, "");
+    let indices: Vec<u32> = match &voting_pubkeys {
+        Some(voting_pubkeys) => {
+            eprintln!(
+                "Searching indices {}..{} of {} for {} voting key(s)",
+                first_index,
+                last_index,
+                path_template,
+                voting_pubkeys.len()
+            );
+            find_voting_key_indices(
+                seed.as_bytes(),
+                &path_template,
+                first_index..last_index,
+                voting_pubkeys,
+            )?
+        }
+        None => (first_index..last_index).collect(),
+    };
+    let total = indices.len();
 
-    for index in first_index..first_index + count {
+    for (i, index) in indices.into_iter().enumerate() {
         let voting_password = random_password();
         let withdrawal_password = random_password();
 
-        let derive = |key_type: KeyType, password: &[u8]| -> Result<Keystore, String> {
-            let (secret, path) =
-                recover_validator_secret_from_mnemonic(seed.as_bytes(), index, key_type)
-                    .map_err(|e| format!("Unable to recover validator keys: {:?}", e))?;
-
-            let keypair = keypair_from_secret(secret.as_bytes())
-                .map_err(|e| format!("Unable build keystore: {:?}", e))?;
-
-            KeystoreBuilder::new(&keypair, password, format!("{}", path))
-                .map_err(|e| format!("Unable build keystore: {:?}", e))?
-                .build()
-                .map_err(|e| format!("Unable build keystore: {:?}", e))
-        };
-
-        let keystores = ValidatorKeystores {
-            voting: derive(KeyType::Voting, voting_password.as_bytes())?,
-            withdrawal: derive(KeyType::Withdrawal, withdrawal_password.as_bytes())?,
-        };
+        let keystores = ValidatorKeystores::recover_from_seed(
+            seed.as_bytes(),
+            &path_template,
+            index,
+            voting_password.as_bytes(),
+            withdrawal_password.as_bytes(),
+        )
+        .map_err(|e| format!("Unable to recover validator keys: {:?}", e))?;
 
         let voting_pubkey = keystores.voting.pubkey().to_string();
 
-        ValidatorDirBuilder::new(validator_dir.clone())
+        let mut builder = ValidatorDirBuilder::new(validator_dir.clone())
             .password_dir(secrets_dir.clone())
             .voting_keystore(keystores.voting, voting_password.as_bytes())
             .withdrawal_keystore(keystores.withdrawal, withdrawal_password.as_bytes())
-            .store_withdrawal_keystore(matches.is_present(STORE_WITHDRAW_FLAG))
+            .store_withdrawal_keystore(matches.is_present(STORE_WITHDRAW_FLAG));
+
+        if let Some(address) = withdrawal_address {
+            builder = builder
+                .eth1_withdrawal_address(address)
+                .create_eth1_tx_data(spec.max_effective_balance, &spec);
+        }
+
+        builder
             .build()
             .map_err(|e| format!("Unable to build validator directory: {:?}", e))?;
 
-        println!(
-            "{}/{}\tIndex: {}\t0x{}",
-            index - first_index,
-            count - first_index,
-            index,
-            voting_pubkey
-        );
+        println!("{}/{}\tIndex: {}\t0x{}", i, total, index, voting_pubkey);
     }
 
     Ok(())
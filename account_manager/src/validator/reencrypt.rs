@@ -0,0 +1,170 @@
+use account_utils::{
+    is_password_sufficiently_complex, random_password, read_password,
+    validator_definitions::{ValidatorDefinitions, CONFIG_FILENAME, KEY_CACHE_FILENAME},
+    ZeroizeString,
+};
+use clap::{App, Arg, ArgMatches};
+use eth2_keystore::{json_keystore::KdfFunction, random_kdf};
+use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
+use std::convert::TryFrom;
+use std::fs;
+use std::path::PathBuf;
+use types::PublicKey;
+
+pub const CMD: &str = "reencrypt";
+pub const VALIDATOR_FLAG: &str = "validator";
+pub const NEW_PASSWORD_FILE_FLAG: &str = "new-password-file";
+pub const KDF_FLAG: &str = "kdf";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Re-encrypts the voting keystores of validators in the validator definitions file \
+            with a new password and key derivation function. The password stored in the \
+            secrets directory or in the validator definitions file is replaced. The validator \
+            client must not be running.",
+        )
+        .arg(
+            Arg::with_name(VALIDATOR_FLAG)
+                .long(VALIDATOR_FLAG)
+                .value_name("PUBKEY")
+                .help(
+                    "The voting public key of the validator to re-encrypt, or \"all\" to \
+                    re-encrypt every validator.",
+                )
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(NEW_PASSWORD_FILE_FLAG)
+                .long(NEW_PASSWORD_FILE_FLAG)
+                .value_name("NEW_PASSWORD_FILE_PATH")
+                .help(
+                    "A path to a file containing the new password for the keystores. If \
+                    not provided, a new random password is generated for each keystore.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(KDF_FLAG)
+                .long(KDF_FLAG)
+                .value_name("KDF")
+                .help("The key derivation function used by the re-encrypted keystores.")
+                .possible_values(&["scrypt", "pbkdf2"])
+                .default_value("scrypt")
+                .takes_value(true),
+        )
+}
+
+pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), String> {
+    let validator: String = clap_utils::parse_required(matches, VALIDATOR_FLAG)?;
+    let new_password_path: Option<PathBuf> =
+        clap_utils::parse_optional(matches, NEW_PASSWORD_FILE_FLAG)?;
+    let kdf_function =
+        KdfFunction::try_from(clap_utils::parse_required::<String>(matches, KDF_FLAG)?)?;
+
+    let voting_pubkey = if validator == "all" {
+        None
+    } else {
+        Some(
+            validator
+                .parse::<PublicKey>()
+                .map_err(|e| format!("Invalid voting public key {}: {}", validator, e))?,
+        )
+    };
+
+    let new_password = new_password_path
+        .map(|path| {
+            let password =
+                read_password(&path).map_err(|e| format!("Unable to read {:?}: {:?}", path, e))?;
+            is_password_sufficiently_complex(password.as_bytes())?;
+            Ok::<_, String>(password)
+        })
+        .transpose()?;
+
+    let slashing_protection_path = validator_dir.join(SLASHING_PROTECTION_FILENAME);
+    let slashing_protection =
+        SlashingDatabase::open_or_create(&slashing_protection_path).map_err(|e| {
+            format!(
+                "Unable to open or create slashing protection database at {}: {:?}",
+                slashing_protection_path.display(),
+                e
+            )
+        })?;
+
+    // Create an empty transaction and drop it. Used to test if the database is locked.
+    slashing_protection.test_transaction().map_err(|e| {
+        format!(
+            "Cannot re-encrypt keys while the validator client is running: {:?}",
+            e
+        )
+    })?;
+
+    let mut defs = ValidatorDefinitions::open(&validator_dir)
+        .map_err(|e| format!("Unable to open {}: {:?}", CONFIG_FILENAME, e))?;
+
+    let indices = defs
+        .as_slice()
+        .iter()
+        .enumerate()
+        .filter(|(_, def)| {
+            voting_pubkey
+                .as_ref()
+                .map_or(true, |pubkey| def.voting_public_key == *pubkey)
+        })
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+
+    if indices.is_empty() {
+        return Err(format!(
+            "No validators matching {} in {}",
+            validator, CONFIG_FILENAME
+        ));
+    }
+
+    // The cache is keyed by keystore UUID, which changes when a keystore is re-encrypted. Remove
+    // it so the validator client rebuilds it from the new keystores.
+    let key_cache_path = validator_dir.join(KEY_CACHE_FILENAME);
+    if key_cache_path.exists() {
+        fs::remove_file(&key_cache_path)
+            .map_err(|e| format!("Unable to remove {:?}: {:?}", key_cache_path, e))?;
+    }
+
+    for i in indices {
+        let password = new_password
+            .as_ref()
+            .map_or_else(random_password, |password| {
+                password.as_bytes().to_vec().into()
+            });
+        let password = String::from_utf8(password.as_bytes().to_vec())
+            .map_err(|e| format!("Password is not valid UTF-8: {:?}", e))?;
+
+        let def = &defs.as_slice()[i];
+        let voting_public_key = def.voting_public_key.clone();
+        let reencrypted = def
+            .reencrypt_voting_keystore(
+                ZeroizeString::from(password),
+                random_kdf(kdf_function.clone()),
+            )
+            .map_err(|e| {
+                format!(
+                    "Unable to re-encrypt keystore for {:?}: {:?}",
+                    voting_public_key, e
+                )
+            })?;
+
+        // Replace each keystore along with the definitions file, so that passwords stored in the
+        // definitions file always match the keystores on disk.
+        defs.commit_reencrypted_voting_keystore(&validator_dir, &reencrypted)
+            .map_err(|e| {
+                format!(
+                    "Unable to replace keystore for {:?}: {:?}",
+                    voting_public_key, e
+                )
+            })?;
+
+        eprintln!("Re-encrypted keystore for {:?}", voting_public_key);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,170 @@
+use crate::wallet::create::STDIN_INPUTS_FLAG;
+use account_utils::{
+    eth2_keystore::{Keystore, KeystoreBuilder},
+    key_share::{KeyShare, KEY_SHARE_FILENAME},
+    read_password, read_password_from_user, PlainText,
+};
+use bls::Keypair;
+use clap::{App, Arg, ArgMatches};
+use directory::ensure_dir_exists;
+use std::fs::OpenOptions;
+use std::path::PathBuf;
+use validator_dir::VOTING_KEYSTORE_FILE;
+
+pub const CMD: &str = "split";
+pub const KEYSTORE_FLAG: &str = "keystore";
+pub const PASSWORD_FILE_FLAG: &str = "password-file";
+pub const THRESHOLD_FLAG: &str = "threshold";
+pub const SHARES_FLAG: &str = "shares";
+pub const OUTPUT_DIR_FLAG: &str = "output-dir";
+pub const PASSWORD_PROMPT: &str = "Enter the keystore password";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Splits the secret key of an EIP-2335 voting keystore into shares, any threshold \
+            of which may produce signatures which combine into a signature from the original \
+            key. Each share is written to its own keystore, encrypted with the password of the \
+            original keystore.",
+        )
+        .arg(
+            Arg::with_name(KEYSTORE_FLAG)
+                .long(KEYSTORE_FLAG)
+                .value_name("KEYSTORE_PATH")
+                .help("The path to the EIP-2335 voting keystore to be split")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(PASSWORD_FILE_FLAG)
+                .long(PASSWORD_FILE_FLAG)
+                .value_name("PASSWORD_FILE_PATH")
+                .help("The path to the password file which unlocks the voting keystore")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(THRESHOLD_FLAG)
+                .long(THRESHOLD_FLAG)
+                .value_name("THRESHOLD")
+                .help("The number of shares required to produce a signature")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(SHARES_FLAG)
+                .long(SHARES_FLAG)
+                .value_name("SHARES")
+                .help("The total number of shares to create")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(OUTPUT_DIR_FLAG)
+                .long(OUTPUT_DIR_FLAG)
+                .value_name("OUTPUT_DIRECTORY")
+                .help(
+                    "The directory in which to write the share keystores. Each share is \
+                    written to a `share_<id>` sub-directory, alongside a `key_share.yml` file \
+                    which records the share identifier needed to combine its signatures.",
+                )
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(STDIN_INPUTS_FLAG)
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read all user inputs from stdin instead of tty."),
+        )
+}
+
+pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
+    let keystore_path: PathBuf = clap_utils::parse_required(matches, KEYSTORE_FLAG)?;
+    let password_file_path: Option<PathBuf> =
+        clap_utils::parse_optional(matches, PASSWORD_FILE_FLAG)?;
+    let threshold: u64 = clap_utils::parse_required(matches, THRESHOLD_FLAG)?;
+    let shares: u64 = clap_utils::parse_required(matches, SHARES_FLAG)?;
+    let output_dir: PathBuf = clap_utils::parse_required(matches, OUTPUT_DIR_FLAG)?;
+    let stdin_inputs = matches.is_present(STDIN_INPUTS_FLAG);
+
+    let keystore = Keystore::from_json_file(&keystore_path)
+        .map_err(|e| format!("Unable to read keystore JSON {:?}: {:?}", keystore_path, e))?;
+
+    let password: PlainText = match password_file_path {
+        Some(path) => read_password(&path)
+            .map_err(|e| format!("Unable to read password file {:?}: {:?}", path, e))?,
+        None => {
+            eprintln!("");
+            eprintln!("{} for validator in {:?}: ", PASSWORD_PROMPT, keystore_path);
+            read_password_from_user(stdin_inputs)?
+                .as_ref()
+                .to_vec()
+                .into()
+        }
+    };
+
+    let keypair = keystore
+        .decrypt_keypair(password.as_bytes())
+        .map_err(|e| format!("Error while decrypting keypair: {:?}", e))?;
+
+    let secret_shares = keypair
+        .sk
+        .split(threshold, shares)
+        .map_err(|e| format!("Unable to split secret key: {:?}", e))?;
+
+    ensure_dir_exists(&output_dir)?;
+
+    for (id, sk) in secret_shares {
+        let share_keypair = Keypair::from_components(sk.public_key(), sk);
+        let share_keystore = KeystoreBuilder::new(&share_keypair, password.as_bytes(), "".into())
+            .map_err(|e| format!("Unable to create keystore builder: {:?}", e))?
+            .description(format!(
+                "Share {} of a {}-of-{} split of validator {}",
+                id, threshold, shares, keypair.pk
+            ))
+            .build()
+            .map_err(|e| format!("Unable to build share keystore: {:?}", e))?;
+
+        let share_dir = output_dir.join(format!("share_{}", id));
+        ensure_dir_exists(&share_dir)?;
+
+        let path = share_dir.join(VOTING_KEYSTORE_FILE);
+        let file = OpenOptions::new()
+            .write(true)
+            .read(true)
+            .create_new(true)
+            .open(&path)
+            .map_err(|e| format!("Unable to create {:?}: {:?}", path, e))?;
+        share_keystore
+            .to_json_writer(file)
+            .map_err(|e| format!("Unable to write {:?}: {:?}", path, e))?;
+
+        KeyShare {
+            id,
+            threshold,
+            shares,
+            validator_public_key: keypair.pk.clone(),
+            share_public_key: share_keypair.pk.clone(),
+        }
+        .create(&share_dir)
+        .map_err(|e| {
+            format!(
+                "Unable to write {:?}: {:?}",
+                share_dir.join(KEY_SHARE_FILENAME),
+                e
+            )
+        })?;
+
+        eprintln!(
+            "Share {} (public key {}) written to {:?}",
+            id, share_keypair.pk, path
+        );
+    }
+
+    eprintln!("");
+    eprintln!(
+        "Any {} of the {} shares are required to sign for validator {}.",
+        threshold, shares, keypair.pk
+    );
+
+    Ok(())
+}
@@ -1,11 +1,12 @@
-use crate::common::read_wallet_name_from_cli;
+use crate::common::{read_mnemonic_passphrase_from_cli, read_wallet_name_from_cli};
 use crate::WALLETS_DIR_FLAG;
 use account_utils::{
-    is_password_sufficiently_complex, random_password, read_password_from_user, strip_off_newlines,
+    is_password_sufficiently_complex, mnemonic_language_from_code, random_password,
+    read_password_from_user, strip_off_newlines, MNEMONIC_LANGUAGES,
 };
 use clap::{App, Arg, ArgMatches};
 use eth2_wallet::{
-    bip39::{Language, Mnemonic, MnemonicType},
+    bip39::{Mnemonic, MnemonicType},
     PlainText,
 };
 use eth2_wallet_manager::{LockedWallet, WalletManager, WalletType};
@@ -24,6 +25,9 @@ pub const TYPE_FLAG: &str = "type";
 pub const MNEMONIC_FLAG: &str = "mnemonic-output-path";
 pub const STDIN_INPUTS_FLAG: &str = "stdin-inputs";
 pub const MNEMONIC_LENGTH_FLAG: &str = "mnemonic-length";
+pub const MNEMONIC_LANGUAGE_FLAG: &str = "mnemonic-language";
+pub const MNEMONIC_PASSPHRASE_FLAG: &str = "mnemonic-passphrase";
+pub const MNEMONIC_PASSPHRASE_FILE_FLAG: &str = "mnemonic-passphrase-file";
 pub const MNEMONIC_TYPES: &[MnemonicType] = &[
     MnemonicType::Words12,
     MnemonicType::Words15,
@@ -100,6 +104,46 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 })
                 .default_value("24"),
         )
+        .arg(
+            Arg::with_name(MNEMONIC_LANGUAGE_FLAG)
+                .long(MNEMONIC_LANGUAGE_FLAG)
+                .value_name("LANGUAGE_CODE")
+                .help("The BIP-39 wordlist to use for the mnemonic phrase.")
+                .takes_value(true)
+                .possible_values(
+                    &MNEMONIC_LANGUAGES
+                        .iter()
+                        .map(|(code, _)| *code)
+                        .collect::<Vec<_>>(),
+                )
+                .default_value("en"),
+        )
+        .arg(mnemonic_passphrase_arg())
+        .arg(mnemonic_passphrase_file_arg())
+}
+
+/// Returns the argument which requests that the user be prompted for a BIP-39 passphrase.
+pub fn mnemonic_passphrase_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name(MNEMONIC_PASSPHRASE_FLAG)
+        .long(MNEMONIC_PASSPHRASE_FLAG)
+        .help(
+            "If present, prompt for an optional BIP-39 passphrase (the \"25th word\") which \
+            protects the mnemonic. Use --stdin-inputs to read it from stdin instead of tty.",
+        )
+        .conflicts_with(MNEMONIC_PASSPHRASE_FILE_FLAG)
+}
+
+/// Returns the argument which provides a file containing a BIP-39 passphrase.
+pub fn mnemonic_passphrase_file_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name(MNEMONIC_PASSPHRASE_FILE_FLAG)
+        .long(MNEMONIC_PASSPHRASE_FILE_FLAG)
+        .value_name("PASSPHRASE_PATH")
+        .help(
+            "A path to a file containing an optional BIP-39 passphrase (the \"25th word\") \
+            which protects the mnemonic.",
+        )
+        .takes_value(true)
+        .conflicts_with(MNEMONIC_PASSPHRASE_FLAG)
 }
 
 pub fn cli_run(matches: &ArgMatches, wallet_base_dir: PathBuf) -> Result<(), String> {
@@ -109,9 +153,10 @@ pub fn cli_run(matches: &ArgMatches, wallet_base_dir: PathBuf) -> Result<(), Str
     //
     // The `tiny-bip39` crate uses `thread_rng()` for this entropy.
     let mnemonic_length = clap_utils::parse_required(matches, MNEMONIC_LENGTH_FLAG)?;
+    let mnemonic_language: String = clap_utils::parse_required(matches, MNEMONIC_LANGUAGE_FLAG)?;
     let mnemonic = Mnemonic::new(
         MnemonicType::for_word_count(mnemonic_length).expect("Mnemonic length already validated"),
-        Language::English,
+        mnemonic_language_from_code(&mnemonic_language)?,
     );
 
     let wallet = create_wallet_from_mnemonic(matches, &wallet_base_dir.as_path(), &mnemonic)?;
@@ -128,6 +173,13 @@ pub fn cli_run(matches: &ArgMatches, wallet_base_dir: PathBuf) -> Result<(), Str
     println!("This mnemonic can be used to fully restore your wallet, should ");
     println!("you lose the JSON file or your password. ");
     println!();
+    if matches.is_present(MNEMONIC_PASSPHRASE_FLAG)
+        || matches.is_present(MNEMONIC_PASSPHRASE_FILE_FLAG)
+    {
+        println!("The BIP-39 passphrase you supplied is also required to restore ");
+        println!("your wallet. It is not included in the mnemonic.");
+        println!();
+    }
     println!("It is very important that you DO NOT SHARE this mnemonic as it will ");
     println!("reveal the private keys of all validators and keys generated with  ");
     println!("this wallet. That would be catastrophic.");
@@ -185,12 +237,14 @@ pub fn create_wallet_from_mnemonic(
     };
 
     let wallet_name = read_wallet_name_from_cli(name, stdin_inputs)?;
+    let mnemonic_passphrase = read_mnemonic_passphrase_from_cli(matches, stdin_inputs)?;
 
     let wallet = mgr
-        .create_wallet(
+        .create_wallet_with_passphrase(
             wallet_name,
             wallet_type,
             &mnemonic,
+            mnemonic_passphrase.as_str(),
             wallet_password.as_bytes(),
         )
         .map_err(|e| format!("Unable to create wallet: {:?}", e))?;
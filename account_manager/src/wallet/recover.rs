@@ -1,5 +1,8 @@
 use crate::common::read_mnemonic_from_cli;
-use crate::wallet::create::{create_wallet_from_mnemonic, STDIN_INPUTS_FLAG};
+use crate::wallet::create::{
+    create_wallet_from_mnemonic, mnemonic_passphrase_arg, mnemonic_passphrase_file_arg,
+    STDIN_INPUTS_FLAG,
+};
 use crate::wallet::create::{HD_TYPE, NAME_FLAG, PASSWORD_FLAG, TYPE_FLAG};
 use clap::{App, Arg, ArgMatches};
 use std::path::PathBuf;
@@ -37,9 +40,14 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             Arg::with_name(MNEMONIC_FLAG)
                 .long(MNEMONIC_FLAG)
                 .value_name("MNEMONIC_PATH")
-                .help("If present, the mnemonic will be read in from this file.")
+                .help(
+                    "If present, the mnemonic will be read in from this file. The language of \
+                    the mnemonic is detected automatically.",
+                )
                 .takes_value(true),
         )
+        .arg(mnemonic_passphrase_arg())
+        .arg(mnemonic_passphrase_file_arg())
         .arg(
             Arg::with_name(TYPE_FLAG)
                 .long(TYPE_FLAG)
@@ -2,6 +2,7 @@ use crate::attestation_verification::{
     Error as AttestationError, SignatureVerifiedAttestation, VerifiedAggregatedAttestation,
     VerifiedUnaggregatedAttestation,
 };
+use crate::block_times_cache::{BlockTimesCache, LateBlock};
 use crate::block_verification::{
     check_block_is_finalized_descendant, check_block_relevancy, get_block_root,
     signature_verify_chain_segment, BlockError, FullyVerifiedBlock, GossipVerifiedBlock,
@@ -11,6 +12,7 @@ use crate::chain_config::ChainConfig;
 use crate::errors::{BeaconChainError as Error, BlockProductionError};
 use crate::eth1_chain::{Eth1Chain, Eth1ChainBackend};
 use crate::events::{EventHandler, EventKind};
+use crate::gossip_trace::GossipTrace;
 use crate::head_tracker::HeadTracker;
 use crate::migrate::BackgroundMigrator;
 use crate::naive_aggregation_pool::{Error as NaiveAggregationError, NaiveAggregationPool};
@@ -222,6 +224,10 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub(crate) snapshot_cache: TimeoutRwLock<SnapshotCache<T::EthSpec>>,
     /// Caches the shuffling for a given epoch and state root.
     pub(crate) shuffling_cache: TimeoutRwLock<ShufflingCache>,
+    /// An optional record of the timing of blocks and attestations received on gossip.
+    pub gossip_trace: Option<Mutex<GossipTrace>>,
+    /// Records the time taken by each stage of processing recent blocks.
+    pub(crate) block_times_cache: Mutex<BlockTimesCache>,
     /// Caches a map of `validator_index -> validator_pubkey`.
     pub(crate) validator_pubkey_cache: TimeoutRwLock<ValidatorPubkeyCache>,
     /// A list of any hard-coded forks that have been disabled.
@@ -1510,6 +1516,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 // Increment the Prometheus counter for block processing successes.
                 metrics::inc_counter(&metrics::BLOCK_PROCESSING_SUCCESSES);
 
+                self.observe_block_delays(block_root, &block.message);
+
                 let _ = self.event_handler.register(EventKind::BeaconBlockImported {
                     block_root,
                     block: Box::new(block),
@@ -1551,6 +1559,89 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
     }
 
+    /// Records that the block with `block_root` was received on gossip at `seen_timestamp` (since
+    /// the UNIX epoch) and exposes its delay from the start of its slot as a metric.
+    ///
+    /// Should be called once the block has passed gossip verification.
+    pub fn observe_gossip_block_arrival(
+        &self,
+        block_root: Hash256,
+        slot: Slot,
+        seen_timestamp: Duration,
+    ) {
+        if let Some(slot_start) = self.slot_clock.start_of(slot) {
+            let delay = seen_timestamp
+                .checked_sub(slot_start)
+                .unwrap_or_else(|| Duration::from_secs(0));
+            metrics::observe(&metrics::BLOCK_GOSSIP_ARRIVAL_DELAY, delay.as_secs_f64());
+        }
+
+        self.block_times_cache
+            .lock()
+            .set_time_observed(block_root, slot, seen_timestamp);
+    }
+
+    /// Reports the newly imported block with `block_root` if it arrived on gossip after the
+    /// attestation deadline (one third of the way through its slot).
+    ///
+    /// Late blocks are logged and added to the record returned by `Self::late_blocks`.
+    fn observe_block_delays(&self, block_root: Hash256, block: &BeaconBlock<T::EthSpec>) {
+        let mut block_times_cache = self.block_times_cache.lock();
+        block_times_cache.prune(block.slot);
+
+        let timings = if let Some(timings) = block_times_cache.get(&block_root) {
+            timings
+        } else {
+            return;
+        };
+
+        let arrival_delay = match (timings.observed, self.slot_clock.start_of(block.slot)) {
+            (Some(observed), Some(slot_start)) => observed
+                .checked_sub(slot_start)
+                .unwrap_or_else(|| Duration::from_secs(0)),
+            // Only blocks received on gossip are reported.
+            _ => return,
+        };
+
+        if arrival_delay <= self.slot_clock.slot_duration() / 3 {
+            return;
+        }
+
+        metrics::inc_counter(&metrics::LATE_BLOCKS);
+
+        let as_millis = |duration: Duration| duration.as_millis() as u64;
+        let late_block = LateBlock {
+            slot: block.slot,
+            block_root,
+            proposer_index: block.proposer_index,
+            arrival_delay_ms: as_millis(arrival_delay),
+            signature_verification_ms: timings.signature_verification.map(as_millis),
+            state_transition_ms: timings.state_transition.map(as_millis),
+            fork_choice_ms: timings.fork_choice.map(as_millis),
+            head_update_ms: None,
+        };
+
+        info!(
+            self.log,
+            "Late block imported";
+            "block_root" => format!("{}", block_root),
+            "slot" => block.slot,
+            "proposer_index" => block.proposer_index,
+            "arrival_delay_ms" => late_block.arrival_delay_ms,
+            "signature_verification_ms" => late_block.signature_verification_ms,
+            "state_transition_ms" => late_block.state_transition_ms,
+            "fork_choice_ms" => late_block.fork_choice_ms,
+        );
+
+        block_times_cache.add_late_block(late_block);
+    }
+
+    /// Returns the most recent blocks which arrived on gossip after the attestation deadline,
+    /// oldest first.
+    pub fn late_blocks(&self) -> Vec<LateBlock> {
+        self.block_times_cache.lock().late_blocks()
+    }
+
     /// Accepts a fully-verified block and imports it into the chain without performing any
     /// additional verification.
     ///
@@ -1631,6 +1722,19 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             }
         }
 
+        // The delay between the start of the block's slot and its arrival, used to determine if the
+        // block should receive the proposer boost. Blocks that were not received on gossip are
+        // considered to have arrived now.
+        let block_delay = self
+            .block_times_cache
+            .lock()
+            .get(&block_root)
+            .and_then(|timings| timings.observed)
+            .or_else(|| self.slot_clock.now_duration())
+            .zip(self.slot_clock.start_of(signed_block.message.slot))
+            .and_then(|(seen_timestamp, slot_start)| seen_timestamp.checked_sub(slot_start))
+            .unwrap_or_else(|| Duration::from_secs(0));
+
         let mut fork_choice = self.fork_choice.write();
 
         // Do not import a block that doesn't descend from the finalized root.
@@ -1674,9 +1778,22 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         {
             let _fork_choice_block_timer =
                 metrics::start_timer(&metrics::FORK_CHOICE_PROCESS_BLOCK_TIMES);
+            let fork_choice_timer = Instant::now();
             fork_choice
-                .on_block(current_slot, block, block_root, &state)
+                .on_block(
+                    current_slot,
+                    block,
+                    block_root,
+                    block_delay,
+                    &state,
+                    &self.spec,
+                )
                 .map_err(|e| BlockError::BeaconChainError(e.into()))?;
+            self.block_times_cache.lock().set_fork_choice_time(
+                block_root,
+                block.slot,
+                fork_choice_timer.elapsed(),
+            );
         }
 
         // Register each attestation in the block with the fork choice service.
@@ -1924,8 +2041,13 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     }
 
     fn fork_choice_internal(&self) -> Result<(), Error> {
+        let head_update_timer = Instant::now();
+
         // Determine the root of the block that is the head of the chain.
-        let beacon_block_root = self.fork_choice.write().get_head(self.slot()?)?;
+        let beacon_block_root = self
+            .fork_choice
+            .write()
+            .get_head(self.slot()?, &self.spec)?;
 
         let current_head = self.head_info()?;
         let old_finalized_checkpoint = current_head.finalized_checkpoint;
@@ -2042,6 +2164,15 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         metrics::stop_timer(update_head_timer);
 
+        let head_update_time = head_update_timer.elapsed();
+        metrics::observe(
+            &metrics::BLOCK_HEAD_UPDATE_TIMES,
+            head_update_time.as_secs_f64(),
+        );
+        self.block_times_cache
+            .lock()
+            .set_head_update_time(beacon_block_root, head_update_time);
+
         self.snapshot_cache
             .try_write_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
             .map(|mut snapshot_cache| {
@@ -2198,6 +2329,31 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         self.op_pool.prune_all(head_state, self.epoch()?);
 
+        if let Some(eth1_chain) = self.eth1_chain.as_ref() {
+            // Deposits may only be pruned once they have all been included in the finalized
+            // state, otherwise they may still be required for block production.
+            match self.get_state(&new_finalized_state_root, None) {
+                Ok(Some(finalized_state)) => {
+                    if finalized_state.eth1_deposit_index >= finalized_state.eth1_data.deposit_count
+                    {
+                        eth1_chain.finalize_eth1_data(finalized_state.eth1_data);
+                    }
+                }
+                Ok(None) => warn!(
+                    self.log,
+                    "Unable to finalize eth1 deposits";
+                    "error" => "missing finalized state",
+                    "state_root" => format!("{:?}", new_finalized_state_root),
+                ),
+                Err(e) => warn!(
+                    self.log,
+                    "Unable to finalize eth1 deposits";
+                    "error" => format!("{:?}", e),
+                    "state_root" => format!("{:?}", new_finalized_state_root),
+                ),
+            }
+        }
+
         self.store_migrator.process_finalization(
             new_finalized_state_root.into(),
             new_finalized_checkpoint,
@@ -166,6 +166,10 @@ pub struct BeaconForkChoiceStore<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<
     justified_checkpoint: Checkpoint,
     justified_balances: Vec<u64>,
     best_justified_checkpoint: Checkpoint,
+    /// The root of the block which currently receives the proposer boost.
+    ///
+    /// The boost only lasts until the end of the slot, so it is not persisted to disk.
+    proposer_boost_root: Hash256,
     _phantom: PhantomData<E>,
 }
 
@@ -175,7 +179,8 @@ where
     Hot: ItemStore<E>,
     Cold: ItemStore<E>,
 {
-    /// This implementation ignores the `store` and `slot_clock`.
+    /// This implementation ignores the `store`, `slot_clock` and the transient
+    /// `proposer_boost_root`.
     fn eq(&self, other: &Self) -> bool {
         self.balances_cache == other.balances_cache
             && self.time == other.time
@@ -228,6 +233,7 @@ where
             justified_balances: anchor_state.balances.clone().into(),
             finalized_checkpoint,
             best_justified_checkpoint: justified_checkpoint,
+            proposer_boost_root: Hash256::zero(),
             _phantom: PhantomData,
         }
     }
@@ -258,6 +264,7 @@ where
             justified_checkpoint: persisted.justified_checkpoint,
             justified_balances: persisted.justified_balances,
             best_justified_checkpoint: persisted.best_justified_checkpoint,
+            proposer_boost_root: Hash256::zero(),
             _phantom: PhantomData,
         })
     }
@@ -338,6 +345,14 @@ where
     fn set_best_justified_checkpoint(&mut self, checkpoint: Checkpoint) {
         self.best_justified_checkpoint = checkpoint
     }
+
+    fn proposer_boost_root(&self) -> Hash256 {
+        self.proposer_boost_root
+    }
+
+    fn set_proposer_boost_root(&mut self, proposer_boost_root: Hash256) {
+        self.proposer_boost_root = proposer_boost_root
+    }
 }
 
 /// A container which allows persisting the `BeaconForkChoiceStore` to the on-disk database.
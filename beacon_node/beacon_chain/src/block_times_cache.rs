@@ -0,0 +1,181 @@
+//! Provides a record of the time taken by each stage of processing recent blocks.
+//!
+//! The timings are collected as the block moves through gossip verification, signature
+//! verification, the state transition and fork choice. They are used to identify the blocks which
+//! arrived after the attestation deadline (see `BeaconChain::observe_block_delays`), which are
+//! retained in a bounded list of `LateBlock` records.
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use types::{Hash256, Slot};
+
+pub use eth2::lighthouse::LateBlock;
+
+/// The number of slots for which the timings of a block are retained after its slot.
+const RETENTION_SLOTS: u64 = 64;
+
+/// The maximum number of late blocks retained.
+pub const MAX_LATE_BLOCKS: usize = 256;
+
+/// The time taken by each stage of processing a single block.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct BlockTimings {
+    /// The time at which the block was first received on gossip, since the UNIX epoch.
+    pub observed: Option<Duration>,
+    pub signature_verification: Option<Duration>,
+    pub state_transition: Option<Duration>,
+    pub fork_choice: Option<Duration>,
+}
+
+struct CacheEntry {
+    slot: Slot,
+    timings: BlockTimings,
+}
+
+/// The timings of recent blocks and a record of the most recent late blocks.
+#[derive(Default)]
+pub struct BlockTimesCache {
+    cache: HashMap<Hash256, CacheEntry>,
+    late_blocks: VecDeque<LateBlock>,
+}
+
+impl BlockTimesCache {
+    /// Record that the block was received on gossip at `timestamp`, unless it has already been
+    /// received.
+    pub fn set_time_observed(&mut self, block_root: Hash256, slot: Slot, timestamp: Duration) {
+        let timings = self.timings_mut(block_root, slot);
+        if timings.observed.is_none() {
+            timings.observed = Some(timestamp);
+        }
+    }
+
+    pub fn set_signature_verification_time(
+        &mut self,
+        block_root: Hash256,
+        slot: Slot,
+        duration: Duration,
+    ) {
+        self.timings_mut(block_root, slot).signature_verification = Some(duration);
+    }
+
+    pub fn set_state_transition_time(
+        &mut self,
+        block_root: Hash256,
+        slot: Slot,
+        duration: Duration,
+    ) {
+        self.timings_mut(block_root, slot).state_transition = Some(duration);
+    }
+
+    pub fn set_fork_choice_time(&mut self, block_root: Hash256, slot: Slot, duration: Duration) {
+        self.timings_mut(block_root, slot).fork_choice = Some(duration);
+    }
+
+    /// Returns the timings recorded for the block with `block_root`, if any.
+    pub fn get(&self, block_root: &Hash256) -> Option<BlockTimings> {
+        self.cache.get(block_root).map(|entry| entry.timings)
+    }
+
+    /// Adds `late_block` to the record, evicting the oldest record if it is full.
+    pub fn add_late_block(&mut self, late_block: LateBlock) {
+        if self.late_blocks.len() >= MAX_LATE_BLOCKS {
+            self.late_blocks.pop_front();
+        }
+        self.late_blocks.push_back(late_block);
+    }
+
+    /// Record the time taken to set the block with `block_root` as the head, if it is late.
+    pub fn set_head_update_time(&mut self, block_root: Hash256, duration: Duration) {
+        if let Some(late_block) = self
+            .late_blocks
+            .iter_mut()
+            .rev()
+            .find(|late_block| late_block.block_root == block_root)
+        {
+            late_block.head_update_ms = Some(duration.as_millis() as u64);
+        }
+    }
+
+    /// Returns the recorded late blocks, oldest first.
+    pub fn late_blocks(&self) -> Vec<LateBlock> {
+        self.late_blocks.iter().cloned().collect()
+    }
+
+    /// Drops the timings of all blocks which are too old to be relevant at `current_slot`.
+    pub fn prune(&mut self, current_slot: Slot) {
+        self.cache
+            .retain(|_, entry| entry.slot + RETENTION_SLOTS >= current_slot);
+    }
+
+    fn timings_mut(&mut self, block_root: Hash256, slot: Slot) -> &mut BlockTimings {
+        &mut self
+            .cache
+            .entry(block_root)
+            .or_insert_with(|| CacheEntry {
+                slot,
+                timings: BlockTimings::default(),
+            })
+            .timings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn late_block(slot: u64) -> LateBlock {
+        LateBlock {
+            slot: Slot::new(slot),
+            block_root: Hash256::from_low_u64_be(slot),
+            proposer_index: 0,
+            arrival_delay_ms: 5_000,
+            signature_verification_ms: None,
+            state_transition_ms: None,
+            fork_choice_ms: None,
+            head_update_ms: None,
+        }
+    }
+
+    #[test]
+    fn records_and_prunes_timings() {
+        let mut cache = BlockTimesCache::default();
+        let root = Hash256::from_low_u64_be(1);
+        let slot = Slot::new(1);
+
+        cache.set_time_observed(root, slot, Duration::from_secs(1));
+        cache.set_time_observed(root, slot, Duration::from_secs(2));
+        cache.set_signature_verification_time(root, slot, Duration::from_millis(3));
+        cache.set_state_transition_time(root, slot, Duration::from_millis(4));
+        cache.set_fork_choice_time(root, slot, Duration::from_millis(5));
+
+        assert_eq!(
+            cache.get(&root),
+            Some(BlockTimings {
+                observed: Some(Duration::from_secs(1)),
+                signature_verification: Some(Duration::from_millis(3)),
+                state_transition: Some(Duration::from_millis(4)),
+                fork_choice: Some(Duration::from_millis(5)),
+            })
+        );
+
+        cache.prune(slot + RETENTION_SLOTS);
+        assert!(cache.get(&root).is_some());
+        cache.prune(slot + RETENTION_SLOTS + 1);
+        assert!(cache.get(&root).is_none());
+    }
+
+    #[test]
+    fn bounded_late_blocks() {
+        let mut cache = BlockTimesCache::default();
+        for slot in 0..MAX_LATE_BLOCKS as u64 + 2 {
+            cache.add_late_block(late_block(slot));
+        }
+
+        let late_blocks = cache.late_blocks();
+        assert_eq!(late_blocks.len(), MAX_LATE_BLOCKS);
+        assert_eq!(late_blocks[0].slot, Slot::new(2));
+
+        let root = late_blocks[3].block_root;
+        cache.set_head_update_time(root, Duration::from_millis(7));
+        assert_eq!(cache.late_blocks()[3].head_update_ms, Some(7));
+    }
+}
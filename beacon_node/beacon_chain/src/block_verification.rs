@@ -63,6 +63,7 @@ use std::borrow::Cow;
 use std::convert::TryFrom;
 use std::fs;
 use std::io::Write;
+use std::time::{Duration, Instant};
 use store::{Error as DBError, HotColdDB, HotStateSummary, KeyValueStore, StoreOp};
 use tree_hash::TreeHash;
 use types::{
@@ -609,7 +610,16 @@ impl<T: BeaconChainTypes> SignatureVerifiedBlock<T> {
 
         signature_verifier.include_all_signatures(&block, Some(block_root))?;
 
-        if signature_verifier.verify().is_ok() {
+        let signature_timer = Instant::now();
+        let signatures_are_valid = signature_verifier.verify().is_ok();
+        observe_signature_verification_time(
+            chain,
+            block_root,
+            block.slot(),
+            signature_timer.elapsed(),
+        );
+
+        if signatures_are_valid {
             Ok(Self {
                 block,
                 block_root,
@@ -650,7 +660,16 @@ impl<T: BeaconChainTypes> SignatureVerifiedBlock<T> {
 
         signature_verifier.include_all_signatures_except_proposal(&block)?;
 
-        if signature_verifier.verify().is_ok() {
+        let signature_timer = Instant::now();
+        let signatures_are_valid = signature_verifier.verify().is_ok();
+        observe_signature_verification_time(
+            chain,
+            from.block_root,
+            block.slot(),
+            signature_timer.elapsed(),
+        );
+
+        if signatures_are_valid {
             Ok(Self {
                 block,
                 block_root: from.block_root,
@@ -761,6 +780,7 @@ impl<'a, T: BeaconChainTypes> FullyVerifiedBlock<'a, T> {
          * Advance the given `parent.beacon_state` to the slot of the given `block`.
          */
 
+        let state_transition_timer = Instant::now();
         let catchup_timer = metrics::start_timer(&metrics::BLOCK_PROCESSING_CATCHUP_STATE);
 
         // Stage a batch of operations to be completed atomically if this block is imported
@@ -884,6 +904,17 @@ impl<'a, T: BeaconChainTypes> FullyVerifiedBlock<'a, T> {
 
         metrics::stop_timer(state_root_timer);
 
+        let state_transition_time = state_transition_timer.elapsed();
+        metrics::observe(
+            &metrics::BLOCK_PROCESSING_STATE_TRANSITION,
+            state_transition_time.as_secs_f64(),
+        );
+        chain.block_times_cache.lock().set_state_transition_time(
+            block_root,
+            block.slot(),
+            state_transition_time,
+        );
+
         write_state(
             &format!("state_post_block_{}", block_root),
             &state,
@@ -1242,6 +1273,21 @@ fn verify_header_signature<T: BeaconChainTypes>(
     }
 }
 
+/// Exposes the time taken to verify the signatures of a block as a metric and records it in the
+/// `BlockTimesCache`.
+fn observe_signature_verification_time<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    block_root: Hash256,
+    slot: Slot,
+    duration: Duration,
+) {
+    metrics::observe(&metrics::BLOCK_PROCESSING_SIGNATURE, duration.as_secs_f64());
+    chain
+        .block_times_cache
+        .lock()
+        .set_signature_verification_time(block_root, slot, duration);
+}
+
 fn expose_participation_metrics(summaries: &[EpochProcessingSummary]) {
     if !cfg!(feature = "participation_metrics") {
         return;
@@ -12,6 +12,11 @@ pub struct ChainConfig {
     ///
     /// If `None`, there is no weak subjectivity verification.
     pub weak_subjectivity_checkpoint: Option<Checkpoint>,
+    /// The maximum number of gossip blocks and attestations for which to record timing
+    /// information in the `GossipTrace`.
+    ///
+    /// If `None`, gossip messages are not traced.
+    pub gossip_trace_capacity: Option<usize>,
 }
 
 impl Default for ChainConfig {
@@ -19,6 +24,7 @@ impl Default for ChainConfig {
         Self {
             import_max_skip_slots: None,
             weak_subjectivity_checkpoint: None,
+            gossip_trace_capacity: None,
         }
     }
 }
@@ -1,8 +1,10 @@
 use crate::metrics;
 use eth1::{Config as Eth1Config, Eth1Block, Service as HttpService};
-use eth2::lighthouse::Eth1SyncStatusData;
+use eth2::lighthouse::{
+    Eth1DataVoteTally, Eth1SyncStatusData, Eth1VoteReason, Eth1VotingStatusData,
+};
 use eth2_hashing::hash;
-use slog::{debug, error, trace, Logger};
+use slog::{debug, error, trace, warn, Logger};
 use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
 use state_processing::per_block_processing::get_new_eth1_data;
@@ -14,8 +16,8 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use store::{DBColumn, Error as StoreError, StoreItem};
 use task_executor::TaskExecutor;
 use types::{
-    BeaconState, BeaconStateError, ChainSpec, Deposit, Eth1Data, EthSpec, Hash256, Slot, Unsigned,
-    DEPOSIT_TREE_DEPTH,
+    BeaconState, BeaconStateError, ChainSpec, Deposit, DepositTreeSnapshot, Eth1Data, EthSpec,
+    Hash256, Slot, Unsigned, DEPOSIT_TREE_DEPTH,
 };
 
 type BlockNumber = u64;
@@ -167,6 +169,32 @@ fn get_sync_status<T: EthSpec>(
     })
 }
 
+/// The `Eth1Data` vote for a block being produced, along with the information used to choose it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Eth1Vote {
+    pub eth1_data: Eth1Data,
+    pub reason: Eth1VoteReason,
+    /// The candidate blocks for the voting period, in ascending order.
+    pub candidate_blocks: Vec<Eth1Block>,
+    /// The votes in the state, from most to least popular.
+    pub votes: Vec<Eth1DataVoteTally>,
+}
+
+impl Eth1Vote {
+    /// Returns the most popular vote in the state, if any.
+    pub fn leading_vote(&self) -> Option<&Eth1DataVoteTally> {
+        self.votes.first()
+    }
+
+    /// Returns the number of votes in the state for `self.eth1_data`.
+    pub fn our_vote_count(&self) -> u64 {
+        self.votes
+            .iter()
+            .find(|tally| tally.eth1_data == self.eth1_data)
+            .map_or(0, |tally| tally.votes)
+    }
+}
+
 #[derive(Encode, Decode, Clone)]
 pub struct SszEth1 {
     use_dummy_backend: bool,
@@ -235,6 +263,56 @@ where
         }
     }
 
+    /// Returns the `Eth1Data` vote that would be included in a block produced for the given
+    /// `state`, along with the reason it was chosen.
+    ///
+    /// Unlike `Self::eth1_data_for_block_production`, this function does not warn or update
+    /// metrics, so it is suitable for diagnostics.
+    pub fn eth1_vote(&self, state: &BeaconState<E>, spec: &ChainSpec) -> Result<Eth1Vote, Error> {
+        if self.use_dummy_backend {
+            let dummy_backend: DummyEth1ChainBackend<E> = DummyEth1ChainBackend::default();
+            dummy_backend.eth1_vote(state, spec)
+        } else {
+            self.backend.eth1_vote(state, spec)
+        }
+    }
+
+    /// Returns a summary of `Eth1Data` voting in the voting period of the given `state`.
+    pub fn eth1_voting_status(
+        &self,
+        state: &BeaconState<E>,
+        spec: &ChainSpec,
+    ) -> Result<Eth1VotingStatusData, Error> {
+        let vote = self.eth1_vote(state, spec)?;
+
+        let voting_period_start_slot = voting_period_start_slot::<E>(state.slot);
+        let voting_period_start_timestamp = voting_period_start_seconds(state, spec);
+        let follow_distance_seconds = spec
+            .seconds_per_eth1_block
+            .saturating_mul(spec.eth1_follow_distance);
+
+        let leading_vote = vote.leading_vote().map(|tally| tally.eth1_data.clone());
+        let diverges_from_leading_vote = leading_vote
+            .as_ref()
+            .map_or(false, |leading_vote| *leading_vote != vote.eth1_data);
+
+        Ok(Eth1VotingStatusData {
+            voting_period_start_slot,
+            voting_period_start_timestamp,
+            earliest_candidate_timestamp: voting_period_start_timestamp
+                .saturating_sub(follow_distance_seconds.saturating_mul(2)),
+            latest_candidate_timestamp: voting_period_start_timestamp
+                .saturating_sub(follow_distance_seconds),
+            candidate_blocks: vote.candidate_blocks,
+            votes: vote.votes,
+            votes_required: E::SlotsPerEth1VotingPeriod::to_u64() / 2 + 1,
+            our_vote: vote.eth1_data,
+            our_vote_reason: vote.reason,
+            leading_vote,
+            diverges_from_leading_vote,
+        })
+    }
+
     /// Returns a list of `Deposits` that may be included in a block.
     ///
     /// Including all of the returned `Deposits` in a block should _not_ cause it to become
@@ -273,6 +351,25 @@ where
         )
     }
 
+    /// Finalize the deposits included in `eth1_data`, pruning them from the eth1 cache.
+    ///
+    /// Must only be called with the `Eth1Data` of a finalized state whose deposits have all been
+    /// processed, since pruned deposits can no longer be included in blocks.
+    pub fn finalize_eth1_data(&self, eth1_data: Eth1Data) {
+        if !self.use_dummy_backend {
+            self.backend.finalize_eth1_data(eth1_data)
+        }
+    }
+
+    /// Returns a snapshot of the finalized deposit tree, if any deposits have been finalized.
+    pub fn get_deposit_snapshot(&self) -> Option<DepositTreeSnapshot> {
+        if self.use_dummy_backend {
+            None
+        } else {
+            self.backend.get_deposit_snapshot()
+        }
+    }
+
     /// Instantiate `Eth1Chain` from a persisted `SszEth1`.
     ///
     /// The `Eth1Chain` will have the same caches as the persisted `SszEth1`.
@@ -311,6 +408,13 @@ pub trait Eth1ChainBackend<T: EthSpec>: Sized + Send + Sync {
     fn eth1_data(&self, beacon_state: &BeaconState<T>, spec: &ChainSpec)
         -> Result<Eth1Data, Error>;
 
+    /// Returns the `Eth1Data` that would be included in a block being produced for the given
+    /// `state`, along with the reason it was chosen and the votes it was chosen from.
+    ///
+    /// Used for diagnostics as well as block production, so must not warn or update metrics.
+    fn eth1_vote(&self, beacon_state: &BeaconState<T>, spec: &ChainSpec)
+        -> Result<Eth1Vote, Error>;
+
     /// Returns all `Deposits` between `state.eth1_deposit_index` and
     /// `state.eth1_data.deposit_count`.
     ///
@@ -333,6 +437,12 @@ pub trait Eth1ChainBackend<T: EthSpec>: Sized + Send + Sync {
     /// an idea of how up-to-date the remote eth1 node is.
     fn head_block(&self) -> Option<Eth1Block>;
 
+    /// Finalize the deposits included in `eth1_data`, pruning them from the backend.
+    fn finalize_eth1_data(&self, eth1_data: Eth1Data);
+
+    /// Returns a snapshot of the finalized deposit tree, if any deposits have been finalized.
+    fn get_deposit_snapshot(&self) -> Option<DepositTreeSnapshot>;
+
     /// Encode the `Eth1ChainBackend` instance to bytes.
     fn as_bytes(&self) -> Vec<u8>;
 
@@ -369,6 +479,17 @@ impl<T: EthSpec> Eth1ChainBackend<T> for DummyEth1ChainBackend<T> {
         })
     }
 
+    /// The dummy back-end has no candidate blocks, so none of the votes in the state are
+    /// candidates.
+    fn eth1_vote(&self, state: &BeaconState<T>, spec: &ChainSpec) -> Result<Eth1Vote, Error> {
+        Ok(Eth1Vote {
+            eth1_data: self.eth1_data(state, spec)?,
+            reason: Eth1VoteReason::DummyBackend,
+            candidate_blocks: vec![],
+            votes: tally_votes(state, &HashMap::new()),
+        })
+    }
+
     /// The dummy back-end never produces deposits.
     fn queued_deposits(
         &self,
@@ -387,6 +508,13 @@ impl<T: EthSpec> Eth1ChainBackend<T> for DummyEth1ChainBackend<T> {
         None
     }
 
+    /// The dummy back-end has no deposits to finalize.
+    fn finalize_eth1_data(&self, _: Eth1Data) {}
+
+    fn get_deposit_snapshot(&self) -> Option<DepositTreeSnapshot> {
+        None
+    }
+
     /// Return empty Vec<u8> for dummy backend.
     fn as_bytes(&self) -> Vec<u8> {
         Vec::new()
@@ -450,16 +578,70 @@ impl<T: EthSpec> CachingEth1Backend<T> {
 
 impl<T: EthSpec> Eth1ChainBackend<T> for CachingEth1Backend<T> {
     fn eth1_data(&self, state: &BeaconState<T>, spec: &ChainSpec) -> Result<Eth1Data, Error> {
-        let period = T::SlotsPerEth1VotingPeriod::to_u64();
-        let voting_period_start_slot = (state.slot / period) * period;
-        let voting_period_start_seconds = slot_start_seconds::<T>(
-            state.genesis_time,
-            spec.milliseconds_per_slot,
-            voting_period_start_slot,
+        let vote = self.eth1_vote(state, spec)?;
+
+        match vote.reason {
+            Eth1VoteReason::MostVotedCandidate | Eth1VoteReason::DummyBackend => {}
+            // In this case, there are no valid votes available.
+            //
+            // The eth1_data corresponding to the latest block in our voting window was chosen.
+            Eth1VoteReason::LatestCandidate => debug!(
+                self.log,
+                "No valid eth1_data votes";
+                "outcome" => "Casting vote corresponding to last candidate eth1 block",
+                "vote" => ?vote.eth1_data
+            ),
+            // No candidate blocks exist, `state.eth1_data` was chosen as default vote.
+            Eth1VoteReason::NoCandidates => {
+                error!(
+                    self.log,
+                    "No valid eth1_data votes, `votes_to_consider` empty";
+                    "lowest_block_number" => self.core.lowest_block_number(),
+                    "earliest_block_timestamp" => self.core.earliest_block_timestamp(),
+                    "genesis_time" => state.genesis_time,
+                    "outcome" => "casting `state.eth1_data` as eth1 vote"
+                );
+                metrics::inc_counter(&metrics::DEFAULT_ETH1_VOTES);
+            }
+        }
+
+        metrics::inc_counter_vec(&metrics::ETH1_VOTES_BY_REASON, &[&vote.reason.to_string()]);
+
+        if let Some(leading_vote) = vote.leading_vote() {
+            if leading_vote.eth1_data != vote.eth1_data {
+                warn!(
+                    self.log,
+                    "Eth1 vote diverges from the leading vote";
+                    "info" => "the eth1 cache may be stale or the eth1 node may be on a different chain",
+                    "our_vote_reason" => %vote.reason,
+                    "our_votes" => vote.our_vote_count(),
+                    "leading_votes" => leading_vote.votes,
+                    "leading_vote_is_candidate" => leading_vote.candidate_block_number.is_some(),
+                    "leading_block_hash" => format!("{:?}", leading_vote.eth1_data.block_hash),
+                    "our_block_hash" => format!("{:?}", vote.eth1_data.block_hash),
+                );
+                metrics::inc_counter(&metrics::ETH1_VOTES_DIVERGING_FROM_LEADER);
+            }
+        }
+
+        debug!(
+            self.log,
+            "Produced vote for eth1 chain";
+            "deposit_root" => format!("{:?}", vote.eth1_data.deposit_root),
+            "deposit_count" => vote.eth1_data.deposit_count,
+            "block_hash" => format!("{:?}", vote.eth1_data.block_hash),
         );
 
+        Ok(vote.eth1_data)
+    }
+
+    fn eth1_vote(&self, state: &BeaconState<T>, spec: &ChainSpec) -> Result<Eth1Vote, Error> {
+        let voting_period_start_seconds = voting_period_start_seconds(state, spec);
+
         let blocks = self.core.blocks().read();
 
+        let candidate_blocks =
+            get_candidate_blocks(blocks.iter(), voting_period_start_seconds, spec);
         let votes_to_consider =
             get_votes_to_consider(blocks.iter(), voting_period_start_seconds, spec);
 
@@ -470,50 +652,23 @@ impl<T: EthSpec> Eth1ChainBackend<T> for CachingEth1Backend<T> {
         );
         let valid_votes = collect_valid_votes(state, &votes_to_consider);
 
-        let eth1_data = if let Some(eth1_data) = find_winning_vote(valid_votes) {
-            eth1_data
+        let (eth1_data, reason) = if let Some(eth1_data) = find_winning_vote(valid_votes) {
+            (eth1_data, Eth1VoteReason::MostVotedCandidate)
+        } else if let Some((eth1_data, _)) = votes_to_consider
+            .iter()
+            .max_by_key(|(_, block_number)| *block_number)
+        {
+            (eth1_data.clone(), Eth1VoteReason::LatestCandidate)
         } else {
-            // In this case, there are no valid votes available.
-            //
-            // Here we choose the eth1_data corresponding to the latest block in our voting window.
-            // If no votes exist, choose `state.eth1_data` as default vote.
-            votes_to_consider
-                .iter()
-                .max_by_key(|(_, block_number)| *block_number)
-                .map(|vote| {
-                    let vote = vote.0.clone();
-                    debug!(
-                        self.log,
-                        "No valid eth1_data votes";
-                        "outcome" => "Casting vote corresponding to last candidate eth1 block",
-                        "vote" => ?vote
-                    );
-                    vote
-                })
-                .unwrap_or_else(|| {
-                    let vote = state.eth1_data.clone();
-                    error!(
-                        self.log,
-                        "No valid eth1_data votes, `votes_to_consider` empty";
-                        "lowest_block_number" => self.core.lowest_block_number(),
-                        "earliest_block_timestamp" => self.core.earliest_block_timestamp(),
-                        "genesis_time" => state.genesis_time,
-                        "outcome" => "casting `state.eth1_data` as eth1 vote"
-                    );
-                    metrics::inc_counter(&metrics::DEFAULT_ETH1_VOTES);
-                    vote
-                })
+            (state.eth1_data.clone(), Eth1VoteReason::NoCandidates)
         };
 
-        debug!(
-            self.log,
-            "Produced vote for eth1 chain";
-            "deposit_root" => format!("{:?}", eth1_data.deposit_root),
-            "deposit_count" => eth1_data.deposit_count,
-            "block_hash" => format!("{:?}", eth1_data.block_hash),
-        );
-
-        Ok(eth1_data)
+        Ok(Eth1Vote {
+            eth1_data,
+            reason,
+            candidate_blocks: candidate_blocks.into_iter().rev().cloned().collect(),
+            votes: tally_votes(state, &votes_to_consider),
+        })
     }
 
     fn queued_deposits(
@@ -555,6 +710,15 @@ impl<T: EthSpec> Eth1ChainBackend<T> for CachingEth1Backend<T> {
         self.core.head_block()
     }
 
+    /// The deposits are finalized during the next update of the eth1 service.
+    fn finalize_eth1_data(&self, eth1_data: Eth1Data) {
+        self.core.set_to_finalize(Some(eth1_data))
+    }
+
+    fn get_deposit_snapshot(&self) -> Option<DepositTreeSnapshot> {
+        self.core.get_deposit_snapshot()
+    }
+
     /// Return encoded byte representation of the block and deposit caches.
     fn as_bytes(&self) -> Vec<u8> {
         self.core.as_bytes()
@@ -576,6 +740,24 @@ impl<T: EthSpec> Eth1ChainBackend<T> for CachingEth1Backend<T> {
     }
 }
 
+/// Get all eth1 blocks which are candidate blocks for the current eth1 voting period.
+///
+/// Returns the blocks in descending order.
+fn get_candidate_blocks<'a, I>(
+    blocks: I,
+    voting_period_start_seconds: u64,
+    spec: &ChainSpec,
+) -> Vec<&'a Eth1Block>
+where
+    I: DoubleEndedIterator<Item = &'a Eth1Block> + Clone,
+{
+    blocks
+        .rev()
+        .skip_while(|eth1_block| !is_candidate_block(eth1_block, voting_period_start_seconds, spec))
+        .take_while(|eth1_block| is_candidate_block(eth1_block, voting_period_start_seconds, spec))
+        .collect()
+}
+
 /// Get all votes from eth1 blocks which are in the list of candidate blocks for the
 /// current eth1 voting period.
 ///
@@ -588,10 +770,8 @@ fn get_votes_to_consider<'a, I>(
 where
     I: DoubleEndedIterator<Item = &'a Eth1Block> + Clone,
 {
-    blocks
-        .rev()
-        .skip_while(|eth1_block| !is_candidate_block(eth1_block, voting_period_start_seconds, spec))
-        .take_while(|eth1_block| is_candidate_block(eth1_block, voting_period_start_seconds, spec))
+    get_candidate_blocks(blocks, voting_period_start_seconds, spec)
+        .into_iter()
         .filter_map(|eth1_block| {
             eth1_block
                 .clone()
@@ -635,6 +815,39 @@ fn find_winning_vote(valid_votes: Eth1DataVoteCount) -> Option<Eth1Data> {
         .map(|((eth1_data, _), _)| eth1_data.clone())
 }
 
+/// Count all votes cast during the current voting period, including those which are not for
+/// candidates in `votes_to_consider`.
+///
+/// Returns the tallies from most to least popular. Ties are broken in favour of candidates with
+/// the highest block number, then by block hash so the order is deterministic.
+fn tally_votes<T: EthSpec>(
+    state: &BeaconState<T>,
+    votes_to_consider: &HashMap<Eth1Data, BlockNumber>,
+) -> Vec<Eth1DataVoteTally> {
+    let mut counts: HashMap<&Eth1Data, u64> = HashMap::new();
+    for vote in state.eth1_data_votes.iter() {
+        *counts.entry(vote).or_insert(0) += 1;
+    }
+
+    let mut tallies = counts
+        .into_iter()
+        .map(|(eth1_data, votes)| Eth1DataVoteTally {
+            eth1_data: eth1_data.clone(),
+            votes,
+            candidate_block_number: votes_to_consider.get(eth1_data).copied(),
+        })
+        .collect::<Vec<_>>();
+
+    tallies.sort_by(|a, b| {
+        b.votes
+            .cmp(&a.votes)
+            .then_with(|| b.candidate_block_number.cmp(&a.candidate_block_number))
+            .then_with(|| a.eth1_data.block_hash.cmp(&b.eth1_data.block_hash))
+    });
+
+    tallies
+}
+
 /// Returns `int` as little-endian bytes with a length of 32.
 fn int_to_bytes32(int: u64) -> Vec<u8> {
     let mut vec = int.to_le_bytes().to_vec();
@@ -642,6 +855,21 @@ fn int_to_bytes32(int: u64) -> Vec<u8> {
     vec
 }
 
+/// Returns the first slot of the eth1 voting period containing `slot`.
+fn voting_period_start_slot<T: EthSpec>(slot: Slot) -> Slot {
+    let period = T::SlotsPerEth1VotingPeriod::to_u64();
+    (slot / period) * period
+}
+
+/// Returns the unix-epoch seconds at the start of the eth1 voting period of `state`.
+fn voting_period_start_seconds<T: EthSpec>(state: &BeaconState<T>, spec: &ChainSpec) -> u64 {
+    slot_start_seconds::<T>(
+        state.genesis_time,
+        spec.milliseconds_per_slot,
+        voting_period_start_slot::<T>(state.slot),
+    )
+}
+
 /// Returns the unix-epoch seconds at the start of the given `slot`.
 fn slot_start_seconds<T: EthSpec>(
     genesis_unix_seconds: u64,
@@ -947,6 +1175,109 @@ mod test {
                 "default vote must correspond to last block in candidate blocks"
             );
         }
+
+        #[test]
+        fn voting_status() {
+            let spec = &E::default_spec();
+            let slots_per_eth1_voting_period = <E as EthSpec>::SlotsPerEth1VotingPeriod::to_u64();
+
+            let eth1_chain = get_eth1_chain();
+
+            let mut state: BeaconState<E> = BeaconState::new(0, get_eth1_data(0), &spec);
+            state.slot = Slot::from(slots_per_eth1_voting_period * 10 + 1);
+
+            let status = eth1_chain
+                .eth1_voting_status(&state, spec)
+                .expect("should get status with an empty cache");
+            assert_eq!(status.our_vote, state.eth1_data);
+            assert_eq!(status.our_vote_reason, Eth1VoteReason::NoCandidates);
+            assert!(status.candidate_blocks.is_empty());
+            assert_eq!(status.leading_vote, None);
+            assert!(!status.diverges_from_leading_vote);
+
+            let follow_distance_seconds = spec.eth1_follow_distance * spec.seconds_per_eth1_block;
+            let voting_period_start = get_voting_period_start_seconds(&state, &spec);
+            let start_eth1_block = voting_period_start - follow_distance_seconds * 2;
+            let end_eth1_block = voting_period_start - follow_distance_seconds;
+
+            // Include a block after the candidate range, which must be ignored.
+            let blocks = (start_eth1_block..=end_eth1_block + 1)
+                .map(|i| get_eth1_block(i, i))
+                .collect::<Vec<_>>();
+
+            blocks.iter().for_each(|block| {
+                eth1_chain
+                    .backend
+                    .core
+                    .blocks()
+                    .write()
+                    .insert_root_or_child(block.clone())
+                    .expect("should add blocks to cache");
+            });
+
+            let candidate_vote = blocks[1].clone().eth1_data().unwrap();
+            let unknown_vote = get_eth1_data(1);
+            state.eth1_data_votes = vec![
+                unknown_vote.clone(),
+                candidate_vote.clone(),
+                unknown_vote.clone(),
+            ]
+            .into();
+
+            let status = eth1_chain
+                .eth1_voting_status(&state, spec)
+                .expect("should get status");
+
+            assert_eq!(
+                status.voting_period_start_slot,
+                Slot::from(slots_per_eth1_voting_period * 10)
+            );
+            assert_eq!(status.voting_period_start_timestamp, voting_period_start);
+            assert_eq!(status.earliest_candidate_timestamp, start_eth1_block);
+            assert_eq!(status.latest_candidate_timestamp, end_eth1_block);
+            assert_eq!(
+                status.candidate_blocks,
+                blocks[..blocks.len() - 1].to_vec(),
+                "candidate blocks should be in ascending order"
+            );
+            assert_eq!(status.votes_required, slots_per_eth1_voting_period / 2 + 1);
+            assert_eq!(
+                status.votes,
+                vec![
+                    Eth1DataVoteTally {
+                        eth1_data: unknown_vote.clone(),
+                        votes: 2,
+                        candidate_block_number: None,
+                    },
+                    Eth1DataVoteTally {
+                        eth1_data: candidate_vote.clone(),
+                        votes: 1,
+                        candidate_block_number: Some(blocks[1].number),
+                    },
+                ]
+            );
+            assert_eq!(status.our_vote, candidate_vote);
+            assert_eq!(status.our_vote_reason, Eth1VoteReason::MostVotedCandidate);
+            assert_eq!(status.leading_vote, Some(unknown_vote));
+            assert!(status.diverges_from_leading_vote);
+
+            assert_eq!(
+                eth1_chain
+                    .eth1_data_for_block_production(&state, spec)
+                    .expect("should produce vote"),
+                status.our_vote,
+                "status should match the vote used for block production"
+            );
+
+            state.eth1_data_votes = vec![candidate_vote.clone()].into();
+
+            let status = eth1_chain
+                .eth1_voting_status(&state, spec)
+                .expect("should get status");
+            assert_eq!(status.our_vote, candidate_vote);
+            assert_eq!(status.leading_vote, Some(candidate_vote));
+            assert!(!status.diverges_from_leading_vote);
+        }
     }
 
     mod eth1_data_sets {
@@ -1,4 +1,5 @@
 use bus::Bus;
+pub use event_sink::EventSinkSender;
 use parking_lot::Mutex;
 use serde_derive::{Deserialize, Serialize};
 use slog::{error, Logger};
@@ -22,6 +23,20 @@ impl<T: EthSpec> EventHandler<T> for WebSocketSender<T> {
     }
 }
 
+impl<T: EthSpec> EventHandler<T> for EventSinkSender<T> {
+    fn register(&self, kind: EventKind<T>) -> Result<(), String> {
+        // Avoid serializing every event when no sinks are configured.
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        self.send_string(
+            serde_json::to_string(&kind)
+                .map_err(|e| format!("Unable to serialize event: {:?}", e))?,
+        )
+    }
+}
+
 pub struct ServerSentEvents<T: EthSpec> {
     // Bus<> is itself Sync + Send.  We use Mutex<> here only because of the surrounding code does
     // not enforce mutability statically (i.e. relies on interior mutability).
@@ -69,12 +84,13 @@ impl<T: EthSpec> EventHandler<T> for ServerSentEvents<T> {
     }
 }
 
-// An event handler that pushes events to both the websockets handler and the SSE handler.
-// Named after the unix `tee` command.  Meant as a temporary solution before ditching WebSockets
-// completely once SSE functions well enough.
+// An event handler that pushes events to the websockets handler, the SSE handler and any external
+// event sinks.  Named after the unix `tee` command.  Meant as a temporary solution before ditching
+// WebSockets completely once SSE functions well enough.
 pub struct TeeEventHandler<E: EthSpec> {
     websockets_handler: WebSocketSender<E>,
     sse_handler: ServerSentEvents<E>,
+    event_sink: EventSinkSender<E>,
 }
 
 impl<E: EthSpec> TeeEventHandler<E> {
@@ -82,11 +98,13 @@ impl<E: EthSpec> TeeEventHandler<E> {
     pub fn new(
         log: Logger,
         websockets_handler: WebSocketSender<E>,
+        event_sink: EventSinkSender<E>,
     ) -> Result<(Self, Arc<Mutex<Bus<SignedBeaconBlockHash>>>), String> {
         let (sse_handler, bus) = ServerSentEvents::new(log);
         let result = Self {
             websockets_handler,
             sse_handler,
+            event_sink,
         };
         Ok((result, bus))
     }
@@ -95,7 +113,8 @@ impl<E: EthSpec> TeeEventHandler<E> {
 impl<E: EthSpec> EventHandler<E> for TeeEventHandler<E> {
     fn register(&self, kind: EventKind<E>) -> Result<(), String> {
         self.websockets_handler.register(kind.clone())?;
-        self.sse_handler.register(kind)?;
+        self.sse_handler.register(kind.clone())?;
+        self.event_sink.register(kind)?;
         Ok(())
     }
 }
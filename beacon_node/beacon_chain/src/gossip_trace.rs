@@ -0,0 +1,215 @@
+//! Provides an optional, bounded, in-memory record of the timing of blocks and attestations
+//! received on gossip.
+//!
+//! Each message is identified by its gossipsub message id and records the peer it was received
+//! from, how long it waited in the `BeaconProcessor` queue and how long each of validation, import
+//! and fork choice took. When the trace is full, the least-recently received message is dropped.
+use lru::LruCache;
+use std::fmt;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use types::{Hash256, Slot};
+
+pub use eth2::lighthouse::{GossipMessageKind, GossipMessageTrace, GossipValidationOutcome};
+
+/// The default number of messages retained by the trace.
+pub const DEFAULT_GOSSIP_TRACE_CAPACITY: usize = 16_384;
+
+/// A traced message, plus the time at which its latest processing stage finished.
+struct TraceEntry {
+    trace: GossipMessageTrace,
+    checkpoint: Instant,
+}
+
+impl TraceEntry {
+    /// Returns the milliseconds since the previous stage finished and starts timing the next.
+    fn lap(&mut self) -> u64 {
+        let now = Instant::now();
+        let elapsed = now.saturating_duration_since(self.checkpoint);
+        self.checkpoint = now;
+        duration_millis(elapsed)
+    }
+}
+
+/// A bounded record of the processing of recent gossip messages.
+pub struct GossipTrace {
+    entries: LruCache<String, TraceEntry>,
+}
+
+impl GossipTrace {
+    /// Create a trace which retains, at most, the `capacity` most recently received messages.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: LruCache::new(capacity),
+        }
+    }
+
+    /// Start tracing a message which was received from the network at `seen_timestamp` (since the
+    /// UNIX epoch) and is about to be validated.
+    pub fn on_received(
+        &mut self,
+        kind: GossipMessageKind,
+        message_id: &impl fmt::Display,
+        peer_id: &impl fmt::Display,
+        seen_timestamp: Duration,
+        slot: Slot,
+        beacon_block_root: Hash256,
+    ) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_else(|_| Duration::from_secs(0));
+
+        let trace = GossipMessageTrace {
+            kind,
+            message_id: message_id.to_string(),
+            peer_id: peer_id.to_string(),
+            slot,
+            beacon_block_root,
+            seen_timestamp_ms: duration_millis(seen_timestamp),
+            queued_ms: duration_millis(now.checked_sub(seen_timestamp).unwrap_or_default()),
+            validation_outcome: None,
+            validation_ms: None,
+            import_ms: None,
+            import_error: None,
+            fork_choice_ms: None,
+        };
+
+        self.entries.put(
+            trace.message_id.clone(),
+            TraceEntry {
+                trace,
+                checkpoint: Instant::now(),
+            },
+        );
+    }
+
+    /// Record the result of validating the message with `message_id`.
+    ///
+    /// Only the first result for each message is recorded.
+    pub fn on_validation_result(
+        &mut self,
+        message_id: &impl fmt::Display,
+        outcome: GossipValidationOutcome,
+    ) {
+        if let Some(entry) = self.entry_mut(message_id) {
+            if entry.trace.validation_outcome.is_none() {
+                entry.trace.validation_ms = Some(entry.lap());
+                entry.trace.validation_outcome = Some(outcome);
+            }
+        }
+    }
+
+    /// Record that the message with `message_id` has been imported (i.e., added to the chain or
+    /// the relevant operation pool), or that the import failed with `result`.
+    pub fn on_import(&mut self, message_id: &impl fmt::Display, result: Result<(), String>) {
+        if let Some(entry) = self.entry_mut(message_id) {
+            entry.trace.import_ms = Some(entry.lap());
+            entry.trace.import_error = result.err();
+        }
+    }
+
+    /// Record that the message with `message_id` has been applied to (or has triggered a run of)
+    /// fork choice.
+    pub fn on_fork_choice(&mut self, message_id: &impl fmt::Display) {
+        if let Some(entry) = self.entry_mut(message_id) {
+            entry.trace.fork_choice_ms = Some(entry.lap());
+        }
+    }
+
+    /// Returns all traced messages, ordered by the time at which they were received.
+    pub fn traces(&self) -> Vec<GossipMessageTrace> {
+        let mut traces = self
+            .entries
+            .iter()
+            .map(|(_, entry)| entry.trace.clone())
+            .collect::<Vec<_>>();
+        traces.sort_by_key(|trace| trace.seen_timestamp_ms);
+        traces
+    }
+
+    /// Returns the number of traced messages.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no messages have been traced.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn entry_mut(&mut self, message_id: &impl fmt::Display) -> Option<&mut TraceEntry> {
+        self.entries.peek_mut(&message_id.to_string())
+    }
+}
+
+fn duration_millis(duration: Duration) -> u64 {
+    duration.as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn receive(trace: &mut GossipTrace, message_id: &str, seen_timestamp: Duration) {
+        trace.on_received(
+            GossipMessageKind::Attestation,
+            &message_id,
+            &"peer",
+            seen_timestamp,
+            Slot::new(1),
+            Hash256::zero(),
+        );
+    }
+
+    #[test]
+    fn records_each_stage() {
+        let mut trace = GossipTrace::new(4);
+        receive(&mut trace, "a", Duration::from_secs(1));
+
+        trace.on_validation_result(&"a", GossipValidationOutcome::Accept);
+        trace.on_validation_result(&"a", GossipValidationOutcome::Reject);
+        trace.on_fork_choice(&"a");
+        trace.on_import(&"a", Err("pool full".to_string()));
+
+        let traces = trace.traces();
+        assert_eq!(traces.len(), 1);
+
+        let a = &traces[0];
+        assert_eq!(a.message_id, "a");
+        assert_eq!(a.peer_id, "peer");
+        assert_eq!(a.seen_timestamp_ms, 1_000);
+        assert!(a.queued_ms > 0, "message was received long ago");
+        assert_eq!(
+            a.validation_outcome,
+            Some(GossipValidationOutcome::Accept),
+            "only the first result is recorded"
+        );
+        assert!(a.validation_ms.is_some());
+        assert!(a.fork_choice_ms.is_some());
+        assert!(a.import_ms.is_some());
+        assert_eq!(a.import_error, Some("pool full".to_string()));
+    }
+
+    #[test]
+    fn ignores_untraced_messages() {
+        let mut trace = GossipTrace::new(4);
+        trace.on_validation_result(&"a", GossipValidationOutcome::Ignore);
+        trace.on_import(&"a", Ok(()));
+        trace.on_fork_choice(&"a");
+        assert!(trace.is_empty());
+    }
+
+    #[test]
+    fn evicts_oldest_messages() {
+        let mut trace = GossipTrace::new(2);
+        for (i, id) in ["a", "b", "c"].iter().enumerate() {
+            receive(&mut trace, id, Duration::from_millis(i as u64));
+        }
+
+        let ids = trace
+            .traces()
+            .into_iter()
+            .map(|trace| trace.message_id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["b", "c"]);
+    }
+}
@@ -3,12 +3,14 @@ pub mod attestation_verification;
 mod beacon_chain;
 mod beacon_fork_choice_store;
 mod beacon_snapshot;
+pub mod block_times_cache;
 mod block_verification;
 pub mod builder;
 pub mod chain_config;
 mod errors;
 pub mod eth1_chain;
 pub mod events;
+pub mod gossip_trace;
 mod head_tracker;
 mod metrics;
 pub mod migrate;
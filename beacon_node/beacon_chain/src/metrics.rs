@@ -42,6 +42,10 @@ lazy_static! {
         "beacon_block_processing_core_seconds",
         "Time spent doing the core per_block_processing state processing."
     );
+    pub static ref BLOCK_PROCESSING_STATE_TRANSITION: Result<Histogram> = try_create_histogram(
+        "beacon_block_processing_state_transition_seconds",
+        "Time spent advancing the parent state, processing the block and computing the state root."
+    );
     pub static ref BLOCK_PROCESSING_STATE_ROOT: Result<Histogram> = try_create_histogram(
         "beacon_block_processing_state_root_seconds",
         "Time spent calculating the state root when processing a block."
@@ -55,6 +59,22 @@ lazy_static! {
         "Time spent hashing and remembering all the attestations in the block"
     );
 
+    /*
+     * Block Delays
+     */
+    pub static ref BLOCK_GOSSIP_ARRIVAL_DELAY: Result<Histogram> = try_create_histogram(
+        "beacon_block_gossip_arrival_delay_seconds",
+        "Duration between the start of the block's slot and its arrival on gossip"
+    );
+    pub static ref BLOCK_HEAD_UPDATE_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_block_head_update_seconds",
+        "Duration from the start of a fork choice run which changed the head until the new head was stored"
+    );
+    pub static ref LATE_BLOCKS: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_late_total",
+        "Count of imported blocks which arrived on gossip after the attestation deadline"
+    );
+
     /*
      * Block Production
      */
@@ -253,6 +273,15 @@ lazy_static! {
      */
     pub static ref DEFAULT_ETH1_VOTES: Result<IntCounter> =
         try_create_int_counter("beacon_eth1_default_votes", "Count of times we have voted default value for eth1 data");
+    pub static ref ETH1_VOTES_BY_REASON: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_eth1_votes_by_reason_total",
+        "Count of eth1 data votes produced, by the reason the vote was chosen",
+        &["reason"]
+    );
+    pub static ref ETH1_VOTES_DIVERGING_FROM_LEADER: Result<IntCounter> = try_create_int_counter(
+        "beacon_eth1_votes_diverging_from_leading_vote_total",
+        "Count of eth1 data votes produced which differ from the most popular vote in the state"
+    );
 
     /*
      * Chain Head
@@ -156,8 +156,8 @@ fn assert_chains_pretty_much_the_same<T: BeaconChainTypes>(a: &BeaconChain<T>, b
 
     let slot = a.slot().unwrap();
     assert!(
-        a.fork_choice.write().get_head(slot).unwrap()
-            == b.fork_choice.write().get_head(slot).unwrap(),
+        a.fork_choice.write().get_head(slot, &a.spec).unwrap()
+            == b.fork_choice.write().get_head(slot, &b.spec).unwrap(),
         "fork_choice heads should be equal"
     );
 }
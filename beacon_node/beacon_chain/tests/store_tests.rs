@@ -13,6 +13,7 @@ use std::collections::HashSet;
 use std::convert::TryInto;
 use std::sync::Arc;
 use store::{
+    hdiff::HierarchyConfig,
     iter::{BlockRootsIterator, StateRootsIterator},
     HotColdDB, LevelDB, StoreConfig,
 };
@@ -34,10 +35,16 @@ type E = MinimalEthSpec;
 type TestHarness = BeaconChainHarness<DiskHarnessType<E>>;
 
 fn get_store(db_path: &TempDir) -> Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>> {
+    get_store_with_config(db_path, StoreConfig::default())
+}
+
+fn get_store_with_config(
+    db_path: &TempDir,
+    config: StoreConfig,
+) -> Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>> {
     let spec = MinimalEthSpec::default_spec();
     let hot_path = db_path.path().join("hot_db");
     let cold_path = db_path.path().join("cold_db");
-    let config = StoreConfig::default();
     let log = test_logger();
 
     Arc::new(
@@ -78,6 +85,32 @@ fn full_participation_no_skips() {
     check_iterators(&harness);
 }
 
+#[test]
+fn full_participation_hierarchical_diffs() {
+    let num_blocks_produced = E::slots_per_epoch() * 12;
+    let db_path = tempdir().unwrap();
+    let config = StoreConfig {
+        slots_per_restore_point: E::slots_per_epoch(),
+        hierarchy_config: Some(HierarchyConfig {
+            exponents: vec![3, 4, 6],
+        }),
+        ..StoreConfig::default()
+    };
+    let store = get_store_with_config(&db_path, config);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness.extend_chain(
+        num_blocks_produced as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    check_finalization(&harness, num_blocks_produced);
+    check_split_slot(&harness, store);
+    check_chain_dump(&harness, num_blocks_produced + 1);
+    check_iterators(&harness);
+}
+
 #[test]
 fn randomised_skips() {
     let num_slots = E::slots_per_epoch() * 5;
@@ -13,6 +13,7 @@ use bus::Bus;
 use environment::RuntimeContext;
 use eth1::{Config as Eth1Config, Service as Eth1Service};
 use eth2_libp2p::NetworkGlobals;
+use event_sink::{Config as EventSinkConfig, EventSinkSender};
 use genesis::{interop_genesis_state, Eth1GenesisService};
 use network::{NetworkConfig, NetworkMessage, NetworkService};
 use parking_lot::Mutex;
@@ -565,10 +566,12 @@ where
     TColdStore: ItemStore<TEthSpec> + 'static,
 {
     #[allow(clippy::type_complexity)]
-    /// Specifies that the `BeaconChain` should publish events using the WebSocket server.
+    /// Specifies that the `BeaconChain` should publish events using the WebSocket server and any
+    /// configured event sinks.
     pub fn tee_event_handler(
         mut self,
         config: WebSocketConfig,
+        event_sink_config: EventSinkConfig,
     ) -> Result<(Self, Arc<Mutex<Bus<SignedBeaconBlockHash>>>), String> {
         let context = self
             .runtime_context
@@ -579,14 +582,21 @@ where
         let log = context.log().clone();
         let (sender, listening_addr): (WebSocketSender<TEthSpec>, Option<_>) = if config.enabled {
             let (sender, listening_addr) =
-                websocket_server::start_server(context.executor, &config)?;
+                websocket_server::start_server(context.executor.clone(), &config)?;
             (sender, Some(listening_addr))
         } else {
             (WebSocketSender::dummy(), None)
         };
 
+        let event_sink = if event_sink_config.targets.is_empty() {
+            EventSinkSender::dummy()
+        } else {
+            let executor = context.executor.clone_with_name("event_sink".into());
+            event_sink::start_sinks(executor, &event_sink_config)?
+        };
+
         self.websocket_listen_addr = listening_addr;
-        let (tee_event_handler, bus) = TeeEventHandler::new(log, sender)?;
+        let (tee_event_handler, bus) = TeeEventHandler::new(log, sender, event_sink)?;
         self.event_handler = Some(tee_event_handler);
         Ok((self, bus))
     }
@@ -697,12 +707,27 @@ where
                     )
                     .map(|chain| chain.into_backend())
                 })
-                .unwrap_or_else(|| {
-                    Ok(CachingEth1Backend::new(
+                .unwrap_or_else(|| match config.deposit_snapshot.clone() {
+                    Some(snapshot) => {
+                        info!(
+                            context.log(),
+                            "Initializing eth1 cache from deposit snapshot";
+                            "deposit_count" => snapshot.deposit_count,
+                            "eth1_block" => snapshot.execution_block_height,
+                        );
+                        Eth1Service::from_deposit_snapshot(
+                            config,
+                            context.log().clone(),
+                            spec.clone(),
+                            &snapshot,
+                        )
+                        .map(CachingEth1Backend::from_service)
+                    }
+                    None => Ok(CachingEth1Backend::new(
                         config,
                         context.log().clone(),
                         spec.clone(),
-                    ))
+                    )),
                 })?
         };
 
@@ -60,6 +60,7 @@ pub struct Config {
     pub network: network::NetworkConfig,
     pub chain: beacon_chain::ChainConfig,
     pub websocket_server: websocket_server::Config,
+    pub event_sink: event_sink::Config,
     pub eth1: eth1::Config,
     pub http_api: http_api::Config,
     pub http_metrics: http_metrics::Config,
@@ -78,6 +79,7 @@ impl Default for Config {
             network: NetworkConfig::default(),
             chain: <_>::default(),
             websocket_server: <_>::default(),
+            event_sink: <_>::default(),
             dummy_eth1_backend: false,
             sync_eth1_chain: false,
             eth1: <_>::default(),
@@ -1,5 +1,6 @@
 use ssz_derive::{Decode, Encode};
 use std::ops::RangeInclusive;
+use types::Hash256;
 
 pub use eth2::lighthouse::Eth1Block;
 
@@ -100,6 +101,11 @@ impl BlockCache {
         )
     }
 
+    /// Returns a block with the corresponding hash, if any.
+    pub fn block_by_hash(&self, block_hash: &Hash256) -> Option<&Eth1Block> {
+        self.blocks.iter().find(|block| block.hash == *block_hash)
+    }
+
     /// Insert an `Eth1Snapshot` into `self`, allowing future queries.
     ///
     /// Allows inserting either:
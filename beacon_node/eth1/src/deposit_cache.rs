@@ -1,9 +1,10 @@
-use crate::DepositLog;
+use crate::{DepositLog, Eth1Block};
+use ssz::legacy::four_byte_option;
 use ssz_derive::{Decode, Encode};
 use state_processing::common::DepositDataTree;
 use std::cmp::Ordering;
 use tree_hash::TreeHash;
-use types::{Deposit, Hash256, DEPOSIT_TREE_DEPTH};
+use types::{Deposit, DepositTreeSnapshot, FinalizedExecutionBlock, Hash256, DEPOSIT_TREE_DEPTH};
 
 #[derive(Debug, PartialEq)]
 pub enum Error {
@@ -27,16 +28,59 @@ pub enum Error {
     DepositCountInvalid { deposit_count: u64, range_end: u64 },
     /// Error with the merkle tree for deposits.
     DepositTreeError(merkle_proof::MerkleTreeError),
+    /// The requested deposits have been finalized and pruned from the cache.
+    DepositRangeFinalized {
+        requested: u64,
+        finalized_deposit_count: u64,
+    },
+    /// The block given for finalization does not have a known deposit root and count.
+    FinalizedBlockMissingDepositData(Hash256),
+    /// The block given for finalization contains more deposits than are in the cache.
+    FinalizedBlockDepositsUnknown {
+        deposit_count: u64,
+        known_deposits: usize,
+    },
+    /// The deposit root of the block given for finalization does not match the cache.
+    FinalizedDepositRootMismatch {
+        block_hash: Hash256,
+        expected: Hash256,
+        found: Hash256,
+    },
     /// An unexpected condition was encountered.
     InternalError(String),
 }
 
+/// The `SszDepositCache` layout used prior to the introduction of deposit tree snapshots.
+///
+/// Only used to decode caches persisted by earlier versions.
+#[derive(Encode, Decode, Clone)]
+pub struct SszDepositCacheV1 {
+    logs: Vec<DepositLog>,
+    leaves: Vec<Hash256>,
+    deposit_contract_deploy_block: u64,
+    deposit_roots: Vec<Hash256>,
+}
+
+impl From<SszDepositCacheV1> for SszDepositCache {
+    fn from(cache: SszDepositCacheV1) -> Self {
+        Self {
+            logs: cache.logs,
+            leaves: cache.leaves,
+            deposit_contract_deploy_block: cache.deposit_contract_deploy_block,
+            deposit_roots: cache.deposit_roots,
+            deposit_tree_snapshot: None,
+        }
+    }
+}
+
 #[derive(Encode, Decode, Clone)]
 pub struct SszDepositCache {
     logs: Vec<DepositLog>,
     leaves: Vec<Hash256>,
     deposit_contract_deploy_block: u64,
     deposit_roots: Vec<Hash256>,
+    #[ssz(with = "four_byte_option")]
+    deposit_tree_snapshot: Option<DepositTreeSnapshot>,
 }
 
 impl SszDepositCache {
@@ -46,12 +90,11 @@ impl SszDepositCache {
             leaves: cache.leaves.clone(),
             deposit_contract_deploy_block: cache.deposit_contract_deploy_block,
             deposit_roots: cache.deposit_roots.clone(),
+            deposit_tree_snapshot: cache.get_deposit_snapshot(),
         }
     }
 
     pub fn to_deposit_cache(&self) -> Result<DepositCache, String> {
-        let deposit_tree =
-            DepositDataTree::create(&self.leaves, self.leaves.len(), DEPOSIT_TREE_DEPTH);
         // Check for invalid SszDepositCache conditions
         if self.leaves.len() != self.logs.len() {
             return Err("Invalid SszDepositCache: logs and leaves should have equal length".into());
@@ -63,28 +106,62 @@ impl SszDepositCache {
                     .into(),
             );
         }
-        Ok(DepositCache {
-            logs: self.logs.clone(),
-            leaves: self.leaves.clone(),
-            deposit_contract_deploy_block: self.deposit_contract_deploy_block,
-            deposit_tree,
-            deposit_roots: self.deposit_roots.clone(),
-        })
+
+        let mut cache = match &self.deposit_tree_snapshot {
+            Some(snapshot) => {
+                DepositCache::from_deposit_snapshot(self.deposit_contract_deploy_block, snapshot)?
+            }
+            None => DepositCache::new(self.deposit_contract_deploy_block),
+        };
+
+        if self.deposit_roots.first() != cache.deposit_roots.first() {
+            return Err(
+                "Invalid SszDepositCache: deposit_roots does not start at the snapshot root".into(),
+            );
+        }
+
+        cache.deposit_tree = match &self.deposit_tree_snapshot {
+            Some(snapshot) => {
+                let mut deposit_tree = DepositDataTree::from_snapshot(snapshot, DEPOSIT_TREE_DEPTH)
+                    .map_err(|e| format!("Invalid SszDepositCache snapshot: {:?}", e))?;
+                for leaf in &self.leaves {
+                    deposit_tree
+                        .push_leaf(*leaf)
+                        .map_err(|e| format!("Invalid SszDepositCache leaves: {:?}", e))?;
+                }
+                deposit_tree
+            }
+            None => DepositDataTree::create(&self.leaves, self.leaves.len(), DEPOSIT_TREE_DEPTH),
+        };
+        cache.logs = self.logs.clone();
+        cache.leaves = self.leaves.clone();
+        cache.deposit_roots = self.deposit_roots.clone();
+
+        Ok(cache)
     }
 }
 
 /// Mirrors the merkle tree of deposits in the eth1 deposit contract.
 ///
 /// Provides `Deposit` objects with merkle proofs included.
+///
+/// Deposits that have been finalized are pruned from the cache, leaving only the branches of the
+/// deposit tree required to append new deposits. Such deposits can no longer be proven.
 pub struct DepositCache {
+    /// Logs for all deposits with an index of at least `finalized_deposit_count`.
     logs: Vec<DepositLog>,
+    /// Leaves for all deposits with an index of at least `finalized_deposit_count`.
     leaves: Vec<Hash256>,
     deposit_contract_deploy_block: u64,
+    /// The number of deposits that have been finalized and pruned.
+    finalized_deposit_count: u64,
+    /// The number of the eth1 block at which the deposits were finalized.
+    finalized_block_height: u64,
     /// An incremental merkle tree which represents the current state of the
     /// deposit contract tree.
     deposit_tree: DepositDataTree,
     /// Vector of deposit roots. `deposit_roots[i]` denotes `deposit_root` at
-    /// `deposit_index` `i`.
+    /// `deposit_index` `finalized_deposit_count + i`.
     deposit_roots: Vec<Hash256>,
 }
 
@@ -96,6 +173,8 @@ impl Default for DepositCache {
             logs: Vec::new(),
             leaves: Vec::new(),
             deposit_contract_deploy_block: 1,
+            finalized_deposit_count: 0,
+            finalized_block_height: 0,
             deposit_tree,
             deposit_roots,
         }
@@ -112,29 +191,132 @@ impl DepositCache {
         }
     }
 
-    /// Returns the number of deposits available in the cache.
+    /// Create a new `DepositCache` from a snapshot of the finalized deposit tree.
+    ///
+    /// The resulting cache only contains the snapshot; deposits made after it must be imported
+    /// with `Self::insert_log`.
+    pub fn from_deposit_snapshot(
+        deposit_contract_deploy_block: u64,
+        snapshot: &DepositTreeSnapshot,
+    ) -> Result<Self, String> {
+        if !snapshot.is_valid() {
+            return Err("Invalid deposit snapshot: deposit root does not match".into());
+        }
+
+        let deposit_tree = DepositDataTree::from_snapshot(snapshot, DEPOSIT_TREE_DEPTH)
+            .map_err(|e| format!("Invalid deposit snapshot: {:?}", e))?;
+
+        Ok(DepositCache {
+            logs: Vec::new(),
+            leaves: Vec::new(),
+            deposit_contract_deploy_block,
+            finalized_deposit_count: snapshot.deposit_count,
+            finalized_block_height: snapshot.execution_block_height,
+            deposit_roots: vec![deposit_tree.root()],
+            deposit_tree,
+        })
+    }
+
+    /// Returns a snapshot of the finalized deposit tree, or `None` if no deposits have been
+    /// finalized.
+    pub fn get_deposit_snapshot(&self) -> Option<DepositTreeSnapshot> {
+        self.deposit_tree.get_snapshot()
+    }
+
+    /// Returns the number of deposits that have been finalized and pruned from the cache.
+    pub fn finalized_deposit_count(&self) -> u64 {
+        self.finalized_deposit_count
+    }
+
+    /// Returns the number of deposits known to the cache, including finalized deposits.
     pub fn len(&self) -> usize {
-        self.logs.len()
+        self.finalized_deposit_count as usize + self.logs.len()
     }
 
     /// True if the cache does not store any blocks.
     pub fn is_empty(&self) -> bool {
-        self.logs.is_empty()
+        self.len() == 0
     }
 
     /// Returns the block number for the most recent deposit in the cache.
+    ///
+    /// If all known deposits have been finalized, returns the block at which they were finalized.
     pub fn latest_block_number(&self) -> Option<u64> {
-        self.logs.last().map(|log| log.block_number)
+        self.logs
+            .last()
+            .map(|log| log.block_number)
+            .or_else(|| Some(self.finalized_block_height).filter(|_| !self.is_empty()))
     }
 
-    /// Returns an iterator over all the logs in `self`.
+    /// Returns an iterator over all the logs in `self` which have not been finalized.
     pub fn iter(&self) -> impl Iterator<Item = &DepositLog> {
         self.logs.iter()
     }
 
-    /// Returns the i'th deposit log.
+    /// Returns the i'th deposit log, if it has not been finalized.
     pub fn get(&self, i: usize) -> Option<&DepositLog> {
-        self.logs.get(i)
+        self.logs
+            .get(i.checked_sub(self.finalized_deposit_count as usize)?)
+    }
+
+    /// Finalize the deposits included in `eth1_block`, pruning their logs and leaves from the
+    /// cache.
+    ///
+    /// Is a no-op if the deposits of `eth1_block` have already been finalized.
+    ///
+    /// ## Errors
+    ///
+    /// - If `eth1_block` does not have a deposit root and count.
+    /// - If `eth1_block` includes deposits that are not in the cache.
+    /// - If the deposit root of `eth1_block` does not match the root known to the cache.
+    pub fn finalize(&mut self, eth1_block: &Eth1Block) -> Result<(), Error> {
+        let (deposit_root, deposit_count) =
+            match (eth1_block.deposit_root, eth1_block.deposit_count) {
+                (Some(deposit_root), Some(deposit_count)) => (deposit_root, deposit_count),
+                _ => return Err(Error::FinalizedBlockMissingDepositData(eth1_block.hash)),
+            };
+
+        if deposit_count <= self.finalized_deposit_count {
+            return Ok(());
+        }
+
+        if deposit_count > self.len() as u64 {
+            return Err(Error::FinalizedBlockDepositsUnknown {
+                deposit_count,
+                known_deposits: self.len(),
+            });
+        }
+
+        let prune_count = (deposit_count - self.finalized_deposit_count) as usize;
+        let expected = *self
+            .deposit_roots
+            .get(prune_count)
+            .ok_or_else(|| Error::InternalError("Unable to get deposit root".into()))?;
+        if expected != deposit_root {
+            return Err(Error::FinalizedDepositRootMismatch {
+                block_hash: eth1_block.hash,
+                expected,
+                found: deposit_root,
+            });
+        }
+
+        self.deposit_tree
+            .finalize(FinalizedExecutionBlock {
+                deposit_root,
+                deposit_count,
+                block_hash: eth1_block.hash,
+                block_height: eth1_block.number,
+            })
+            .map_err(Error::DepositTreeError)?;
+
+        // Retain the root at `deposit_count`, so that `deposit_roots[0]` is the finalized root.
+        self.logs.drain(0..prune_count);
+        self.leaves.drain(0..prune_count);
+        self.deposit_roots.drain(0..prune_count);
+        self.finalized_deposit_count = deposit_count;
+        self.finalized_block_height = eth1_block.number;
+
+        Ok(())
     }
 
     /// Adds `log` to self.
@@ -147,7 +329,7 @@ impl DepositCache {
     /// - If a log with index `log.index - 1` is not already present in `self` (ignored when empty).
     /// - If a log with `log.index` is already known, but the given `log` is distinct to it.
     pub fn insert_log(&mut self, log: DepositLog) -> Result<(), Error> {
-        match log.index.cmp(&(self.logs.len() as u64)) {
+        match log.index.cmp(&(self.len() as u64)) {
             Ordering::Equal => {
                 let deposit = log.deposit_data.tree_hash_root();
                 self.leaves.push(deposit);
@@ -159,7 +341,10 @@ impl DepositCache {
                 Ok(())
             }
             Ordering::Less => {
-                if self.logs[log.index as usize] == log {
+                // Finalized logs have been pruned and cannot be compared.
+                if log.index < self.finalized_deposit_count
+                    || self.logs[(log.index - self.finalized_deposit_count) as usize] == log
+                {
                     Ok(())
                 } else {
                     Err(Error::DuplicateDistinctLog(log.index))
@@ -167,7 +352,7 @@ impl DepositCache {
             }
             Ordering::Greater => Err(Error::NonConsecutive {
                 log_index: log.index,
-                expected: self.logs.len(),
+                expected: self.len(),
             }),
         }
     }
@@ -183,6 +368,7 @@ impl DepositCache {
     ///
     /// - If `deposit_count` is larger than `end`.
     /// - There are not sufficient deposits in the tree to generate the proof.
+    /// - If `start` or `deposit_count` is less than the number of finalized deposits.
     pub fn get_deposits(
         &self,
         start: u64,
@@ -196,48 +382,71 @@ impl DepositCache {
                 deposit_count,
                 range_end: end,
             })
-        } else if end > self.logs.len() as u64 {
+        } else if end > self.len() as u64 {
             // The range of requested deposits exceeds the deposits stored locally.
             Err(Error::InsufficientDeposits {
                 requested: end,
-                known_deposits: self.logs.len(),
+                known_deposits: self.len(),
             })
-        } else if deposit_count > self.leaves.len() as u64 {
+        } else if deposit_count > self.len() as u64 {
             // There are not `deposit_count` known deposit roots, so we can't build the merkle tree
             // to prove into.
             Err(Error::InsufficientDeposits {
                 requested: deposit_count,
-                known_deposits: self.logs.len(),
+                known_deposits: self.len(),
+            })
+        } else if start < self.finalized_deposit_count {
+            // The requested deposits have been pruned, so they can no longer be proven.
+            Err(Error::DepositRangeFinalized {
+                requested: start,
+                finalized_deposit_count: self.finalized_deposit_count,
+            })
+        } else if deposit_count < self.finalized_deposit_count {
+            // The tree cannot be rewound to a size prior to finalization.
+            Err(Error::DepositRangeFinalized {
+                requested: deposit_count,
+                finalized_deposit_count: self.finalized_deposit_count,
             })
         } else {
+            let finalized_deposit_count = self.finalized_deposit_count as usize;
             let leaves = self
                 .leaves
-                .get(0..deposit_count as usize)
+                .get(0..deposit_count as usize - finalized_deposit_count)
                 .ok_or_else(|| Error::InternalError("Unable to get known leaves".into()))?;
 
-            // Note: there is likely a more optimal solution than recreating the `DepositDataTree`
-            // each time this function is called.
-            //
-            // Perhaps a base merkle tree could be maintained that contains all deposits up to the
-            // last finalized eth1 deposit count. Then, that tree could be cloned and extended for
-            // each of these calls.
-
-            let tree = DepositDataTree::create(leaves, deposit_count as usize, tree_depth);
+            // Rebuild the tree to `deposit_count`, starting from the finalized snapshot (if any)
+            // so that only the unfinalized leaves need to be hashed.
+            let tree = match self.deposit_tree.get_snapshot() {
+                Some(snapshot) => {
+                    let mut tree = DepositDataTree::from_snapshot(&snapshot, tree_depth)
+                        .map_err(Error::DepositTreeError)?;
+                    for leaf in leaves {
+                        tree.push_leaf(*leaf).map_err(Error::DepositTreeError)?;
+                    }
+                    tree
+                }
+                None => DepositDataTree::create(leaves, deposit_count as usize, tree_depth),
+            };
 
             let deposits = self
                 .logs
-                .get(start as usize..end as usize)
+                .get(
+                    start as usize - finalized_deposit_count
+                        ..end as usize - finalized_deposit_count,
+                )
                 .ok_or_else(|| Error::InternalError("Unable to get known log".into()))?
                 .iter()
                 .map(|deposit_log| {
-                    let (_leaf, proof) = tree.generate_proof(deposit_log.index as usize);
+                    let (_leaf, proof) = tree
+                        .generate_proof(deposit_log.index as usize)
+                        .map_err(Error::DepositTreeError)?;
 
-                    Deposit {
+                    Ok(Deposit {
                         proof: proof.into(),
                         data: deposit_log.deposit_data.clone(),
-                    }
+                    })
                 })
-                .collect();
+                .collect::<Result<_, _>>()?;
 
             Ok((tree.root(), deposits))
         }
@@ -246,9 +455,13 @@ impl DepositCache {
     /// Returns the number of deposits with valid signatures that have been observed up to and
     /// including the block at `block_number`.
     ///
-    /// Returns `None` if the `block_number` is zero or prior to contract deployment.
+    /// Returns `None` if the `block_number` is zero or prior to contract deployment, or if any
+    /// deposits have been finalized (their signatures are no longer known).
     pub fn get_valid_signature_count(&self, block_number: u64) -> Option<usize> {
-        if block_number == 0 || block_number < self.deposit_contract_deploy_block {
+        if block_number == 0
+            || block_number < self.deposit_contract_deploy_block
+            || self.finalized_deposit_count != 0
+        {
             None
         } else {
             Some(
@@ -264,16 +477,22 @@ impl DepositCache {
     /// Returns the number of deposits that have been observed up to and
     /// including the block at `block_number`.
     ///
-    /// Returns `None` if the `block_number` is zero or prior to contract deployment.
+    /// Returns `None` if the `block_number` is zero, prior to contract deployment or prior to the
+    /// block at which deposits were finalized.
     pub fn get_deposit_count_from_cache(&self, block_number: u64) -> Option<u64> {
-        if block_number == 0 || block_number < self.deposit_contract_deploy_block {
+        if block_number == 0
+            || block_number < self.deposit_contract_deploy_block
+            || block_number < self.finalized_block_height
+        {
             None
         } else {
             Some(
-                self.logs
-                    .iter()
-                    .take_while(|deposit| deposit.block_number <= block_number)
-                    .count() as u64,
+                self.finalized_deposit_count
+                    + self
+                        .logs
+                        .iter()
+                        .take_while(|deposit| deposit.block_number <= block_number)
+                        .count() as u64,
             )
         }
     }
@@ -283,7 +502,7 @@ impl DepositCache {
     /// Fetches the `deposit_count` on or just before the queried `block_number`
     /// and queries the `deposit_roots` map to get the corresponding `deposit_root`.
     pub fn get_deposit_root_from_cache(&self, block_number: u64) -> Option<Hash256> {
-        let index = self.get_deposit_count_from_cache(block_number)?;
+        let index = self.get_deposit_count_from_cache(block_number)? - self.finalized_deposit_count;
         Some(*self.deposit_roots.get(index as usize)?)
     }
 }
@@ -444,4 +663,207 @@ pub mod tests {
         // Range higher than count.
         assert!(tree.get_deposits(0, 4, 2, TREE_DEPTH).is_err());
     }
+
+    fn cache_with_deposits(n: u64) -> DepositCache {
+        let mut cache = DepositCache::default();
+
+        for i in 0..n {
+            let mut log = example_log();
+            log.index = i;
+            log.block_number = i + 1;
+            log.deposit_data.withdrawal_credentials = Hash256::from_low_u64_be(i);
+            cache.insert_log(log).expect("should add consecutive logs")
+        }
+
+        cache
+    }
+
+    /// Returns the eth1 block at `block_number`, as known to `cache`.
+    fn eth1_block(cache: &DepositCache, block_number: u64) -> Eth1Block {
+        Eth1Block {
+            hash: Hash256::from_low_u64_be(block_number),
+            timestamp: block_number,
+            number: block_number,
+            deposit_root: cache.get_deposit_root_from_cache(block_number),
+            deposit_count: cache.get_deposit_count_from_cache(block_number),
+        }
+    }
+
+    #[test]
+    fn finalize_and_get_deposits() {
+        let n = 64;
+        let reference = cache_with_deposits(n);
+        let mut cache = cache_with_deposits(n);
+
+        for finalized in &[1, 7, 8, 33] {
+            let block = eth1_block(&cache, *finalized);
+            cache.finalize(&block).expect("should finalize");
+
+            assert_eq!(cache.len(), n as usize);
+            assert_eq!(cache.finalized_deposit_count(), *finalized);
+            assert_eq!(cache.iter().count(), (n - finalized) as usize);
+            assert_eq!(cache.get(*finalized as usize).unwrap().index, *finalized);
+            if *finalized > 0 {
+                assert!(cache.get(*finalized as usize - 1).is_none());
+            }
+
+            for deposit_count in *finalized..=n {
+                assert_eq!(
+                    cache.get_deposits(*finalized, deposit_count, deposit_count, TREE_DEPTH),
+                    reference.get_deposits(*finalized, deposit_count, deposit_count, TREE_DEPTH),
+                    "{} finalized, {} deposits",
+                    finalized,
+                    deposit_count
+                );
+            }
+
+            for block_number in *finalized..=n {
+                assert_eq!(
+                    cache.get_deposit_root_from_cache(block_number),
+                    reference.get_deposit_root_from_cache(block_number)
+                );
+            }
+        }
+
+        // Finalized deposits can no longer be proven.
+        assert_eq!(
+            cache.get_deposits(32, 40, 40, TREE_DEPTH),
+            Err(Error::DepositRangeFinalized {
+                requested: 32,
+                finalized_deposit_count: 33
+            })
+        );
+        assert_eq!(
+            cache.get_deposits(33, 32, 32, TREE_DEPTH),
+            Err(Error::DepositRangeFinalized {
+                requested: 32,
+                finalized_deposit_count: 33
+            })
+        );
+        assert_eq!(cache.get_deposit_count_from_cache(32), None);
+        assert_eq!(cache.get_valid_signature_count(n), None);
+
+        // Re-inserting a finalized log is a no-op.
+        let mut log = example_log();
+        log.index = 0;
+        assert!(cache.insert_log(log).is_ok());
+
+        // Finalizing an earlier block is a no-op.
+        let block = eth1_block(&reference, 16);
+        assert!(cache.finalize(&block).is_ok());
+        assert_eq!(cache.finalized_deposit_count(), 33);
+    }
+
+    #[test]
+    fn finalize_invalid() {
+        let mut cache = cache_with_deposits(16);
+
+        let mut block = eth1_block(&cache, 8);
+        block.deposit_root = Some(Hash256::repeat_byte(1));
+        assert!(matches!(
+            cache.finalize(&block),
+            Err(Error::FinalizedDepositRootMismatch { .. })
+        ));
+
+        let mut block = eth1_block(&cache, 8);
+        block.deposit_count = Some(17);
+        assert!(matches!(
+            cache.finalize(&block),
+            Err(Error::FinalizedBlockDepositsUnknown { .. })
+        ));
+
+        let mut block = eth1_block(&cache, 8);
+        block.deposit_root = None;
+        assert!(matches!(
+            cache.finalize(&block),
+            Err(Error::FinalizedBlockMissingDepositData(_))
+        ));
+
+        assert_eq!(cache.finalized_deposit_count(), 0);
+        assert_eq!(cache.get_deposit_snapshot(), None);
+    }
+
+    #[test]
+    fn restore_from_snapshot() {
+        let n = 48;
+        let reference = cache_with_deposits(n);
+        let mut cache = cache_with_deposits(n);
+
+        let block = eth1_block(&cache, 21);
+        cache.finalize(&block).expect("should finalize");
+
+        let snapshot = cache.get_deposit_snapshot().expect("should have snapshot");
+        assert!(snapshot.is_valid());
+        assert_eq!(snapshot.deposit_count, 21);
+        assert_eq!(snapshot.execution_block_hash, block.hash);
+        assert_eq!(snapshot.execution_block_height, 21);
+
+        let mut restored = DepositCache::from_deposit_snapshot(1, &snapshot).unwrap();
+        assert_eq!(restored.len(), 21);
+        assert_eq!(restored.latest_block_number(), Some(21));
+        assert_eq!(restored.get_deposit_snapshot(), Some(snapshot.clone()));
+        for log in cache.iter() {
+            restored.insert_log(log.clone()).expect("should add log");
+        }
+        assert_eq!(
+            restored.get_deposits(21, n, n, TREE_DEPTH),
+            reference.get_deposits(21, n, n, TREE_DEPTH)
+        );
+
+        let mut invalid = snapshot;
+        invalid.deposit_root = Hash256::zero();
+        assert!(DepositCache::from_deposit_snapshot(1, &invalid).is_err());
+    }
+
+    #[test]
+    fn ssz_round_trip() {
+        use ssz::{Decode, Encode};
+
+        let n = 32;
+        let mut cache = cache_with_deposits(n);
+
+        for finalized in &[0, 13] {
+            if *finalized > 0 {
+                let block = eth1_block(&cache, *finalized);
+                cache.finalize(&block).expect("should finalize");
+            }
+
+            let bytes = SszDepositCache::from_deposit_cache(&cache).as_ssz_bytes();
+            let decoded = SszDepositCache::from_ssz_bytes(&bytes)
+                .unwrap()
+                .to_deposit_cache()
+                .unwrap();
+
+            assert_eq!(decoded.len(), cache.len());
+            assert_eq!(decoded.get_deposit_snapshot(), cache.get_deposit_snapshot());
+            assert_eq!(
+                decoded.get_deposits(*finalized, n, n, TREE_DEPTH),
+                cache.get_deposits(*finalized, n, n, TREE_DEPTH)
+            );
+        }
+    }
+
+    #[test]
+    fn decode_legacy_ssz() {
+        use ssz::{Decode, Encode};
+
+        let n = 8;
+        let cache = cache_with_deposits(n);
+        let legacy = SszDepositCacheV1 {
+            logs: cache.logs.clone(),
+            leaves: cache.leaves.clone(),
+            deposit_contract_deploy_block: cache.deposit_contract_deploy_block,
+            deposit_roots: cache.deposit_roots.clone(),
+        };
+        let bytes = legacy.as_ssz_bytes();
+
+        assert!(SszDepositCache::from_ssz_bytes(&bytes).is_err());
+        let decoded = SszDepositCache::from(SszDepositCacheV1::from_ssz_bytes(&bytes).unwrap())
+            .to_deposit_cache()
+            .unwrap();
+        assert_eq!(
+            decoded.get_deposits(0, n, n, TREE_DEPTH),
+            cache.get_deposits(0, n, n, TREE_DEPTH)
+        );
+    }
 }
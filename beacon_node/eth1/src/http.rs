@@ -1,19 +1,23 @@
 //! Provides a very minimal set of functions for interfacing with the eth2 deposit contract via an
-//! eth1 HTTP JSON-RPC endpoint.
+//! eth1 JSON-RPC endpoint.
 //!
 //! All remote functions return a future (i.e., are async).
 //!
-//! Does not use a web3 library, instead it uses `reqwest` (`hyper`) to call the remote endpoint
-//! and `serde` to decode the response.
+//! Does not use a web3 library, instead it uses `reqwest` (`hyper`) to call HTTP endpoints and
+//! `serde` to decode the response. WebSocket and IPC endpoints are supported via the `transport`
+//! module.
 //!
 //! ## Note
 //!
 //! There is no ABI parsing here, all function signatures and topics are hard-coded as constants.
 
+use crate::endpoint::Eth1Endpoint;
+use crate::transport::{send_ipc_request, send_ws_request, Transport};
 use futures::future::TryFutureExt;
 use reqwest::{header::CONTENT_TYPE, ClientBuilder, StatusCode};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::error::Error as StdError;
 use std::ops::Range;
 use std::str::FromStr;
 use std::time::Duration;
@@ -79,7 +83,7 @@ impl FromStr for Eth1Id {
 }
 
 /// Get the eth1 network id of the given endpoint.
-pub async fn get_network_id(endpoint: &str, timeout: Duration) -> Result<Eth1Id, String> {
+pub async fn get_network_id(endpoint: &Eth1Endpoint, timeout: Duration) -> Result<Eth1Id, String> {
     let response_body = send_rpc_request(endpoint, "net_version", json!([]), timeout).await?;
     Eth1Id::from_str(
         response_result(&response_body)?
@@ -90,7 +94,7 @@ pub async fn get_network_id(endpoint: &str, timeout: Duration) -> Result<Eth1Id,
 }
 
 /// Get the eth1 chain id of the given endpoint.
-pub async fn get_chain_id(endpoint: &str, timeout: Duration) -> Result<Eth1Id, String> {
+pub async fn get_chain_id(endpoint: &Eth1Endpoint, timeout: Duration) -> Result<Eth1Id, String> {
     let response_body = send_rpc_request(endpoint, "eth_chainId", json!([]), timeout).await?;
     hex_to_u64_be(
         response_result(&response_body)?
@@ -111,7 +115,7 @@ pub struct Block {
 /// Returns the current block number.
 ///
 /// Uses HTTP JSON RPC at `endpoint`. E.g., `http://localhost:8545`.
-pub async fn get_block_number(endpoint: &str, timeout: Duration) -> Result<u64, String> {
+pub async fn get_block_number(endpoint: &Eth1Endpoint, timeout: Duration) -> Result<u64, String> {
     let response_body = send_rpc_request(endpoint, "eth_blockNumber", json!([]), timeout).await?;
     hex_to_u64_be(
         response_result(&response_body)?
@@ -126,7 +130,7 @@ pub async fn get_block_number(endpoint: &str, timeout: Duration) -> Result<u64,
 ///
 /// Uses HTTP JSON RPC at `endpoint`. E.g., `http://localhost:8545`.
 pub async fn get_block(
-    endpoint: &str,
+    endpoint: &Eth1Endpoint,
     query: BlockQuery,
     timeout: Duration,
 ) -> Result<Block, String> {
@@ -191,7 +195,7 @@ pub async fn get_block(
 ///
 /// Uses HTTP JSON RPC at `endpoint`. E.g., `http://localhost:8545`.
 pub async fn get_deposit_count(
-    endpoint: &str,
+    endpoint: &Eth1Endpoint,
     address: &str,
     block_number: u64,
     timeout: Duration,
@@ -229,7 +233,7 @@ pub async fn get_deposit_count(
 ///
 /// Uses HTTP JSON RPC at `endpoint`. E.g., `http://localhost:8545`.
 pub async fn get_deposit_root(
-    endpoint: &str,
+    endpoint: &Eth1Endpoint,
     address: &str,
     block_number: u64,
     timeout: Duration,
@@ -266,7 +270,7 @@ pub async fn get_deposit_root(
 ///
 /// Uses HTTP JSON RPC at `endpoint`. E.g., `http://localhost:8545`.
 async fn call(
-    endpoint: &str,
+    endpoint: &Eth1Endpoint,
     address: &str,
     hex_data: &str,
     block_number: u64,
@@ -308,7 +312,7 @@ pub struct Log {
 ///
 /// Uses HTTP JSON RPC at `endpoint`. E.g., `http://localhost:8545`.
 pub async fn get_deposit_logs_in_range(
-    endpoint: &str,
+    endpoint: &Eth1Endpoint,
     address: &str,
     block_height_range: Range<u64>,
     timeout: Duration,
@@ -349,11 +353,13 @@ pub async fn get_deposit_logs_in_range(
         .map_err(|e| format!("Failed to get logs in range: {}", e))
 }
 
-/// Sends an RPC request to `endpoint`, using a POST with the given `body`.
+/// Sends an RPC request to `endpoint`, using the transport given by its scheme (see
+/// `Transport::from_endpoint`).
 ///
-/// Tries to receive the response and parse the body as a `String`.
+/// Tries to receive the response and parse the body as a `String`. Any occurrence of the endpoint
+/// URL in an error is redacted.
 pub async fn send_rpc_request(
-    endpoint: &str,
+    endpoint: &Eth1Endpoint,
     method: &str,
     params: Value,
     timeout: Duration,
@@ -366,6 +372,20 @@ pub async fn send_rpc_request(
     })
     .to_string();
 
+    match Transport::from_endpoint(&endpoint.url)? {
+        Transport::Http => send_http_request(endpoint, body, timeout).await,
+        Transport::Ws => send_ws_request(endpoint, body, timeout).await,
+        Transport::Ipc(path) => send_ipc_request(path, body, timeout).await,
+    }
+    .map_err(|e| endpoint.redact(&e))
+}
+
+/// Sends an RPC request to the HTTP `endpoint`, using a POST with the given `body`.
+async fn send_http_request(
+    endpoint: &Eth1Endpoint,
+    body: String,
+    timeout: Duration,
+) -> Result<String, String> {
     // Note: it is not ideal to create a new client for each request.
     //
     // A better solution would be to create some struct that contains a built client and pass it
@@ -374,11 +394,12 @@ pub async fn send_rpc_request(
         .timeout(timeout)
         .build()
         .expect("The builder should always build a client")
-        .post(endpoint)
+        .post(endpoint.request_url()?)
+        .headers(endpoint.auth_headers()?)
         .header(CONTENT_TYPE, "application/json")
         .body(body)
         .send()
-        .map_err(|e| format!("Request failed: {:?}", e))
+        .map_err(|e| format!("Request failed: {}", request_error_message(&e)))
         .await?;
     if response.status() != StatusCode::OK {
         return Err(format!(
@@ -396,7 +417,7 @@ pub async fn send_rpc_request(
 
     response
         .bytes()
-        .map_err(|e| format!("Failed to receive body: {:?}", e))
+        .map_err(|e| request_error_message(&e))
         .await
         .and_then(move |bytes| match encoding.as_str() {
             "application/json" => Ok(bytes),
@@ -407,6 +428,33 @@ pub async fn send_rpc_request(
         .map_err(|e| format!("Failed to receive body: {:?}", e))
 }
 
+/// Describes a `reqwest::Error` without including the URL of the request.
+///
+/// The `Debug` and `Display` implementations of `reqwest::Error` include the URL, which may contain
+/// an API key in its path or query. The URL is not a whole string in the `Debug` output, so it
+/// can't be removed by `Eth1Endpoint::redact`.
+fn request_error_message(e: &reqwest::Error) -> String {
+    let kind = if e.is_timeout() {
+        "timed out".to_string()
+    } else if let Some(status) = e.status() {
+        format!("HTTP status {}", status)
+    } else if e.is_connect() {
+        "connection failed".to_string()
+    } else if e.is_redirect() {
+        "redirect failed".to_string()
+    } else if e.is_body() || e.is_decode() {
+        "invalid body".to_string()
+    } else {
+        "request error".to_string()
+    };
+
+    // The source is the underlying `hyper` or `io` error, which never contains the URL.
+    match e.source() {
+        Some(source) => format!("{}: {}", kind, source),
+        None => kind,
+    }
+}
+
 /// Accepts an entire HTTP body (as a string) and returns the `result` field, as a serde `Value`.
 fn response_result(response: &str) -> Result<Option<Value>, String> {
     let json = serde_json::from_str::<Value>(&response)
@@ -449,3 +497,52 @@ fn strip_prefix(hex: &str) -> Result<&str, String> {
         Err("Hex string did not start with `0x`".to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use tokio_compat_02::FutureExt;
+
+    const SECRET_PATH: &str = "/v3/secret-api-key?token=secret-token";
+
+    async fn request_error(endpoint: &Eth1Endpoint, timeout: Duration) -> String {
+        send_rpc_request(endpoint, "eth_blockNumber", json!([]), timeout)
+            .compat()
+            .await
+            .expect_err("request should fail")
+    }
+
+    #[tokio::test]
+    async fn connection_error_omits_url() {
+        // Bind and then drop a listener to find a port which refuses connections.
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let endpoint = Eth1Endpoint::new(format!("http://127.0.0.1:{}{}", port, SECRET_PATH));
+
+        let error = request_error(&endpoint, Duration::from_secs(5)).await;
+
+        assert!(
+            error.starts_with("Request failed: connection failed"),
+            "{}",
+            error
+        );
+        assert!(!error.contains("secret"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn timeout_error_omits_url() {
+        // The listener never accepts, so the request hangs until it times out.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let endpoint = Eth1Endpoint::new(format!("http://127.0.0.1:{}{}", port, SECRET_PATH));
+
+        let error = request_error(&endpoint, Duration::from_millis(200)).await;
+
+        assert!(error.starts_with("Request failed: timed out"), "{}", error);
+        assert!(!error.contains("secret"), "{}", error);
+    }
+}
@@ -1,12 +1,12 @@
 use crate::Config;
 use crate::{
     block_cache::{BlockCache, Eth1Block},
-    deposit_cache::{DepositCache, SszDepositCache},
+    deposit_cache::{DepositCache, SszDepositCache, SszDepositCacheV1},
 };
 use parking_lot::RwLock;
-use ssz::{Decode, Encode};
+use ssz::{legacy::four_byte_option, Decode, Encode};
 use ssz_derive::{Decode, Encode};
-use types::ChainSpec;
+use types::{ChainSpec, Eth1Data};
 
 #[derive(Default)]
 pub struct DepositUpdater {
@@ -30,6 +30,9 @@ pub struct Inner {
     pub deposit_cache: RwLock<DepositUpdater>,
     pub config: RwLock<Config>,
     pub remote_head_block: RwLock<Option<Eth1Block>>,
+    /// The `Eth1Data` of the most recently finalized beacon state, whose deposits will be
+    /// finalized in the deposit cache during the next update.
+    pub to_finalize: RwLock<Option<Eth1Data>>,
     pub spec: ChainSpec,
 }
 
@@ -50,8 +53,15 @@ impl Inner {
     }
 
     /// Recover `Inner` given byte representation of eth1 deposit and block caches.
+    ///
+    /// Caches persisted prior to the introduction of deposit tree snapshots are also accepted.
     pub fn from_bytes(bytes: &[u8], config: Config, spec: ChainSpec) -> Result<Self, String> {
         let ssz_cache = SszEth1Cache::from_ssz_bytes(bytes)
+            .or_else(|e| {
+                SszEth1CacheV1::from_ssz_bytes(bytes)
+                    .map(Into::into)
+                    .map_err(|_| e)
+            })
             .map_err(|e| format!("Ssz decoding error: {:?}", e))?;
         Ok(ssz_cache.to_inner(config, spec)?)
     }
@@ -62,10 +72,30 @@ impl Inner {
     }
 }
 
+/// The `SszEth1Cache` layout used prior to the introduction of deposit tree snapshots.
+#[derive(Encode, Decode, Clone)]
+pub struct SszEth1CacheV1 {
+    block_cache: BlockCache,
+    deposit_cache: SszDepositCacheV1,
+    #[ssz(with = "four_byte_option")]
+    last_processed_block: Option<u64>,
+}
+
+impl From<SszEth1CacheV1> for SszEth1Cache {
+    fn from(cache: SszEth1CacheV1) -> Self {
+        Self {
+            block_cache: cache.block_cache,
+            deposit_cache: cache.deposit_cache.into(),
+            last_processed_block: cache.last_processed_block,
+        }
+    }
+}
+
 #[derive(Encode, Decode, Clone)]
 pub struct SszEth1Cache {
     block_cache: BlockCache,
     deposit_cache: SszDepositCache,
+    #[ssz(with = "four_byte_option")]
     last_processed_block: Option<u64>,
 }
 
@@ -90,6 +120,7 @@ impl SszEth1Cache {
             // Set the remote head_block zero when creating a new instance. We only care about
             // present and future eth1 nodes.
             remote_head_block: RwLock::new(None),
+            to_finalize: RwLock::new(None),
             config: RwLock::new(config),
             spec,
         })
@@ -4,14 +4,17 @@ extern crate lazy_static;
 mod block_cache;
 mod deposit_cache;
 mod deposit_log;
+mod endpoint;
 pub mod http;
 mod inner;
 mod metrics;
 mod service;
+mod transport;
 
 pub use block_cache::{BlockCache, Eth1Block};
 pub use deposit_cache::DepositCache;
 pub use deposit_log::DepositLog;
+pub use endpoint::Eth1Endpoint;
 pub use inner::SszEth1Cache;
 pub use service::{
     BlockCacheUpdateOutcome, Config, DepositCacheUpdateOutcome, Error, Service, DEFAULT_CHAIN_ID,
@@ -17,6 +17,14 @@ lazy_static! {
     pub static ref HIGHEST_PROCESSED_DEPOSIT_BLOCK: Result<IntGauge> =
         try_create_int_gauge("eth1_highest_processed_deposit_block", "Number of the last block checked for deposits");
 
+    /*
+     * Eth1 subscriptions
+     */
+    pub static ref SUBSCRIPTION_ACTIVE: Result<IntGauge> =
+        try_create_int_gauge("eth1_subscription_active", "Set to 1 whilst subscribed to new heads and deposit logs");
+    pub static ref SUBSCRIPTION_NOTIFICATIONS: Result<IntCounter> =
+        try_create_int_counter("eth1_subscription_notifications_total", "Count of notifications pushed by the eth1 node");
+
     /*
      * Eth1 endpoint errors
      */
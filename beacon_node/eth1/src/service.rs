@@ -2,25 +2,30 @@ use crate::metrics;
 use crate::{
     block_cache::{BlockCache, Error as BlockCacheError, Eth1Block},
     deposit_cache::Error as DepositCacheError,
+    endpoint::Eth1Endpoint,
     http::{
         get_block, get_block_number, get_chain_id, get_deposit_logs_in_range, get_network_id,
-        BlockQuery, Eth1Id,
+        BlockQuery, Eth1Id, DEPOSIT_EVENT_TOPIC,
     },
     inner::{DepositUpdater, Inner},
+    transport::{Subscription, Transport},
+    DepositCache,
 };
 use fallback::{Fallback, FallbackError};
 use futures::{future::TryFutureExt, StreamExt};
 use parking_lot::{RwLock, RwLockReadGuard};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use slog::{crit, debug, error, info, trace, warn, Logger};
 use std::fmt::Debug;
 use std::future::Future;
 use std::ops::{Range, RangeInclusive};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
-use tokio::sync::RwLock as TRwLock;
+use tokio::sync::{Notify, RwLock as TRwLock};
 use tokio::time::{interval_at, Duration, Instant};
-use types::{ChainSpec, EthSpec, Unsigned};
+use types::{ChainSpec, DepositTreeSnapshot, Eth1Data, EthSpec, Unsigned};
 
 /// Indicates the default eth1 network id we use for the deposit contract.
 pub const DEFAULT_NETWORK_ID: Eth1Id = Eth1Id::Goerli;
@@ -35,6 +40,10 @@ const BLOCK_NUMBER_TIMEOUT_MILLIS: u64 = STANDARD_TIMEOUT_MILLIS;
 const GET_BLOCK_TIMEOUT_MILLIS: u64 = STANDARD_TIMEOUT_MILLIS;
 /// Timeout when doing an eth_getLogs to read the deposit contract logs.
 const GET_DEPOSIT_LOG_TIMEOUT_MILLIS: u64 = 60_000;
+/// Timeout when doing an eth_subscribe call.
+const SUBSCRIBE_TIMEOUT_MILLIS: u64 = STANDARD_TIMEOUT_MILLIS;
+/// The time without any notification after which a subscription is assumed to have stalled.
+const SUBSCRIPTION_IDLE_TIMEOUT_MILLIS: u64 = 120_000;
 
 const WARNING_MSG: &str = "BLOCK PROPOSALS WILL FAIL WITHOUT VALID, SYNCED ETH1 CONNECTION";
 
@@ -51,7 +60,7 @@ pub enum EndpointError {
 
 type EndpointState = Result<(), EndpointError>;
 
-type EndpointWithState = (String, TRwLock<Option<EndpointState>>);
+type EndpointWithState = (Eth1Endpoint, TRwLock<Option<EndpointState>>);
 
 /// A cache structure to lazily check usability of endpoints. An endpoint is usable if it is
 /// reachable and has the correct network id and chain id. Emits a `WARN` log if a checked endpoint
@@ -74,7 +83,9 @@ impl EndpointsCache {
         if let Some(result) = *value {
             return result;
         }
-        crate::metrics::inc_counter_vec(&crate::metrics::ENDPOINT_REQUESTS, &[&endpoint.0]);
+        // Only the redacted endpoint is used as a label, since the URL may contain credentials.
+        let endpoint_label = endpoint.0.redacted();
+        crate::metrics::inc_counter_vec(&crate::metrics::ENDPOINT_REQUESTS, &[&endpoint_label]);
         let state = endpoint_state(
             &endpoint.0,
             &self.config_network_id,
@@ -84,7 +95,7 @@ impl EndpointsCache {
         .await;
         *value = Some(state);
         if state.is_err() {
-            crate::metrics::inc_counter_vec(&crate::metrics::ENDPOINT_ERRORS, &[&endpoint.0]);
+            crate::metrics::inc_counter_vec(&crate::metrics::ENDPOINT_ERRORS, &[&endpoint_label]);
         }
         state
     }
@@ -94,7 +105,7 @@ impl EndpointsCache {
         func: F,
     ) -> Result<O, FallbackError<SingleEndpointError>>
     where
-        F: Fn(&'a str) -> R,
+        F: Fn(&'a Eth1Endpoint) -> R,
         R: Future<Output = Result<O, SingleEndpointError>>,
     {
         let func = &func;
@@ -102,17 +113,17 @@ impl EndpointsCache {
             .first_success(|endpoint| async move {
                 match self.state(endpoint).await {
                     Ok(()) => {
-                        let endpoint_str = &endpoint.0;
+                        let endpoint_label = endpoint.0.redacted();
                         crate::metrics::inc_counter_vec(
                             &crate::metrics::ENDPOINT_REQUESTS,
-                            &[endpoint_str],
+                            &[&endpoint_label],
                         );
                         match func(&endpoint.0).await {
                             Ok(t) => Ok(t),
                             Err(t) => {
                                 crate::metrics::inc_counter_vec(
                                     &crate::metrics::ENDPOINT_ERRORS,
-                                    &[endpoint_str],
+                                    &[&endpoint_label],
                                 );
                                 if let SingleEndpointError::EndpointError(e) = &t {
                                     *endpoint.1.write().await = Some(Err(*e));
@@ -131,7 +142,7 @@ impl EndpointsCache {
 /// Returns `Ok` if the endpoint is usable, i.e. is reachable and has a correct network id and
 /// chain id. Otherwise it returns `Err`.
 async fn endpoint_state(
-    endpoint: &str,
+    endpoint: &Eth1Endpoint,
     config_network_id: &Eth1Id,
     config_chain_id: &Eth1Id,
     log: &Logger,
@@ -196,7 +207,7 @@ pub enum HeadType {
 /// Returns the head block and the new block ranges relevant for deposits and the block cache
 /// from the given endpoint.
 async fn get_remote_head_and_new_block_ranges(
-    endpoint: &str,
+    endpoint: &Eth1Endpoint,
     service: &Service,
     node_far_behind_seconds: u64,
 ) -> Result<
@@ -244,7 +255,7 @@ async fn get_remote_head_and_new_block_ranges(
 /// Returns the range of new block numbers to be considered for the given head type from the given
 /// endpoint.
 async fn relevant_new_block_numbers_from_endpoint(
-    endpoint: &str,
+    endpoint: &Eth1Endpoint,
     service: &Service,
     head_type: HeadType,
 ) -> Result<Option<RangeInclusive<u64>>, SingleEndpointError> {
@@ -310,8 +321,9 @@ pub struct DepositCacheUpdateOutcome {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
-    /// An Eth1 node (e.g., Geth) running a HTTP JSON-RPC endpoint.
-    pub endpoints: Vec<String>,
+    /// Eth1 nodes (e.g., Geth) running a JSON-RPC endpoint, over HTTP (`http://`), WebSocket
+    /// (`ws://`) or IPC (`ipc://`), along with the credentials used to authenticate with each.
+    pub endpoints: Vec<Eth1Endpoint>,
     /// The address the `BlockCache` and `DepositCache` should assume is the canonical deposit contract.
     pub deposit_contract_address: String,
     /// The eth1 network id where the deposit contract is deployed (Goerli/Mainnet).
@@ -343,6 +355,9 @@ pub struct Config {
     pub max_log_requests_per_update: Option<usize>,
     /// The maximum number of log requests per update.
     pub max_blocks_per_update: Option<usize>,
+    /// A snapshot of the finalized deposit tree, used to initialize an empty deposit cache
+    /// without downloading every deposit log.
+    pub deposit_snapshot: Option<DepositTreeSnapshot>,
 }
 
 impl Config {
@@ -386,10 +401,19 @@ impl Default for Config {
             blocks_per_log_query: 1_000,
             max_log_requests_per_update: Some(100),
             max_blocks_per_update: Some(8_192),
+            deposit_snapshot: None,
         }
     }
 }
 
+/// Allows the subscription task to trigger updates in the `auto_update` loop.
+#[derive(Default)]
+struct PushUpdates {
+    notify: Notify,
+    /// Set whilst a subscription is active, in which case polling is unnecessary.
+    subscribed: AtomicBool,
+}
+
 /// Provides a set of Eth1 caches and async functions to update them.
 ///
 /// Stores the following caches:
@@ -412,6 +436,7 @@ impl Service {
                     config.deposit_contract_deploy_block,
                 )),
                 remote_head_block: RwLock::new(None),
+                to_finalize: RwLock::new(None),
                 config: RwLock::new(config),
                 spec,
             }),
@@ -419,6 +444,48 @@ impl Service {
         }
     }
 
+    /// Creates a new service with a deposit cache initialized from `deposit_snapshot`. Does not
+    /// attempt to connect to the eth1 node.
+    ///
+    /// Deposit logs will only be downloaded for blocks after the snapshot.
+    pub fn from_deposit_snapshot(
+        config: Config,
+        log: Logger,
+        spec: ChainSpec,
+        deposit_snapshot: &DepositTreeSnapshot,
+    ) -> Result<Self, String> {
+        let deposit_cache = DepositCache::from_deposit_snapshot(
+            config.deposit_contract_deploy_block,
+            deposit_snapshot,
+        )?;
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                block_cache: <_>::default(),
+                deposit_cache: RwLock::new(DepositUpdater {
+                    cache: deposit_cache,
+                    last_processed_block: Some(deposit_snapshot.execution_block_height),
+                }),
+                remote_head_block: RwLock::new(None),
+                to_finalize: RwLock::new(None),
+                config: RwLock::new(config),
+                spec,
+            }),
+            log,
+        })
+    }
+
+    /// Sets the `Eth1Data` of the latest finalized beacon state. The deposits it includes will be
+    /// finalized and pruned from the deposit cache during the next update.
+    pub fn set_to_finalize(&self, eth1_data: Option<Eth1Data>) {
+        *self.inner.to_finalize.write() = eth1_data;
+    }
+
+    /// Returns a snapshot of the finalized deposit tree, if any deposits have been finalized.
+    pub fn get_deposit_snapshot(&self) -> Option<DepositTreeSnapshot> {
+        self.inner.deposit_cache.read().cache.get_deposit_snapshot()
+    }
+
     /// Returns the follow distance that has been shortened to accommodate for differences in the
     /// spacing between blocks.
     ///
@@ -690,12 +757,72 @@ impl Service {
         let (deposit_outcome, block_outcome) =
             futures::try_join!(update_deposit_cache, update_block_cache)?;
 
+        self.finalize_deposits();
+
         Ok((deposit_outcome, block_outcome))
     }
 
+    /// Finalizes the deposit cache up to the `Eth1Data` provided to `Self::set_to_finalize`.
+    ///
+    /// Finalization is retried on the next update if the corresponding eth1 block is not yet in
+    /// the block cache.
+    fn finalize_deposits(&self) {
+        let eth1_data = match self.inner.to_finalize.read().clone() {
+            Some(eth1_data) => eth1_data,
+            None => return,
+        };
+
+        let block = match self
+            .inner
+            .block_cache
+            .read()
+            .block_by_hash(&eth1_data.block_hash)
+            .cloned()
+        {
+            Some(block) => block,
+            None => {
+                debug!(
+                    self.log,
+                    "Finalized eth1 block not in block cache";
+                    "block_hash" => format!("{:?}", eth1_data.block_hash),
+                    "deposit_count" => eth1_data.deposit_count,
+                );
+                return;
+            }
+        };
+
+        // Don't clear a newer value set whilst we were finalizing.
+        {
+            let mut to_finalize = self.inner.to_finalize.write();
+            if to_finalize.as_ref() == Some(&eth1_data) {
+                *to_finalize = None;
+            }
+        }
+
+        match self.inner.deposit_cache.write().cache.finalize(&block) {
+            Ok(()) => debug!(
+                self.log,
+                "Finalized eth1 deposit cache";
+                "block_number" => block.number,
+                "deposit_count" => eth1_data.deposit_count,
+            ),
+            Err(e) => error!(
+                self.log,
+                "Failed to finalize eth1 deposit cache";
+                "error" => format!("{:?}", e),
+                "block_hash" => format!("{:?}", eth1_data.block_hash),
+                "deposit_count" => eth1_data.deposit_count,
+            ),
+        }
+    }
+
     /// A looping future that updates the cache, then waits `config.auto_update_interval` before
     /// updating it again.
     ///
+    /// If any endpoint supports subscriptions (i.e., WebSocket or IPC endpoints), the cache is
+    /// instead updated each time the eth1 node pushes a new head or deposit log. Polling resumes
+    /// whilst there is no active subscription.
+    ///
     /// ## Returns
     ///
     /// - Ok(_) if the update was successful (the cache may or may not have been modified).
@@ -706,9 +833,32 @@ impl Service {
         let update_interval = Duration::from_millis(self.config().auto_update_interval_millis);
 
         let mut interval = interval_at(Instant::now(), update_interval);
+        let push_updates = Arc::new(PushUpdates::default());
+
+        if self
+            .config()
+            .endpoints
+            .iter()
+            .any(|endpoint| supports_subscriptions(endpoint))
+        {
+            handle.spawn(
+                self.clone()
+                    .subscribe_to_updates(push_updates.clone(), update_interval),
+                "eth1_subscription",
+            );
+        }
 
         let update_future = async move {
-            while interval.next().await.is_some() {
+            loop {
+                tokio::select! {
+                    _ = interval.next() => {
+                        // There is no need to poll whilst the eth1 node is pushing updates.
+                        if push_updates.subscribed.load(Ordering::Relaxed) {
+                            continue;
+                        }
+                    }
+                    _ = push_updates.notify.notified() => {}
+                }
                 self.do_update(update_interval).await.ok();
             }
         };
@@ -716,6 +866,94 @@ impl Service {
         handle.spawn(update_future, "eth1");
     }
 
+    /// Maintains a subscription to new heads and deposit logs on the first endpoint that supports
+    /// subscriptions, notifying `push_updates` each time the eth1 node pushes a notification.
+    ///
+    /// Endpoints are tried in the configured order, falling back to the next one if a
+    /// subscription fails or stalls. Once all endpoints have been tried, waits `retry_interval`
+    /// before starting again.
+    async fn subscribe_to_updates(self, push_updates: Arc<PushUpdates>, retry_interval: Duration) {
+        let timeout = Duration::from_millis(SUBSCRIBE_TIMEOUT_MILLIS);
+        let idle_timeout = Duration::from_millis(SUBSCRIPTION_IDLE_TIMEOUT_MILLIS);
+
+        loop {
+            let (endpoints, deposit_contract_address) = {
+                let config = self.config();
+                (
+                    config.endpoints.clone(),
+                    config.deposit_contract_address.clone(),
+                )
+            };
+            let params = [
+                json!(["newHeads"]),
+                json!(["logs", {
+                    "address": deposit_contract_address,
+                    "topics": [DEPOSIT_EVENT_TOPIC],
+                }]),
+            ];
+
+            for endpoint in endpoints
+                .iter()
+                .filter(|endpoint| supports_subscriptions(endpoint))
+            {
+                let mut subscription = match Subscription::new(endpoint, &params, timeout).await {
+                    Ok(subscription) => subscription,
+                    Err(e) => {
+                        warn!(
+                            self.log,
+                            "Failed to subscribe to eth1 endpoint";
+                            "endpoint" => endpoint,
+                            "error" => e,
+                        );
+                        continue;
+                    }
+                };
+
+                info!(
+                    self.log,
+                    "Subscribed to eth1 endpoint";
+                    "endpoint" => endpoint,
+                );
+                push_updates.subscribed.store(true, Ordering::Relaxed);
+                metrics::set_gauge(&metrics::SUBSCRIPTION_ACTIVE, 1);
+                // Catch up on anything that happened before the subscription started.
+                push_updates.notify.notify_one();
+
+                let error = loop {
+                    match tokio::time::timeout(idle_timeout, subscription.next_notification()).await
+                    {
+                        Ok(Ok(notification)) => {
+                            trace!(
+                                self.log,
+                                "Eth1 subscription notification";
+                                "notification" => format!("{}", notification),
+                            );
+                            metrics::inc_counter(&metrics::SUBSCRIPTION_NOTIFICATIONS);
+                            push_updates.notify.notify_one();
+                        }
+                        Ok(Err(e)) => break e,
+                        Err(_) => {
+                            break format!("No notification for {} seconds", idle_timeout.as_secs())
+                        }
+                    }
+                };
+
+                push_updates.subscribed.store(false, Ordering::Relaxed);
+                metrics::set_gauge(&metrics::SUBSCRIPTION_ACTIVE, 0);
+                // Resume polling straight away, rather than after the next interval.
+                push_updates.notify.notify_one();
+                warn!(
+                    self.log,
+                    "Eth1 subscription ended, polling for updates";
+                    "endpoint" => endpoint,
+                    "error" => error,
+                );
+            }
+
+            tokio::time::sleep(retry_interval).await;
+        }
+    }
+
     async fn do_update(&self, update_interval: Duration) -> Result<(), ()> {
         let update_result = self.update().await;
         match update_result {
@@ -1116,6 +1354,13 @@ fn relevant_block_range(
     }
 }
 
+/// Returns `true` if `endpoint` uses a transport which supports `eth_subscribe`.
+fn supports_subscriptions(endpoint: &Eth1Endpoint) -> bool {
+    Transport::from_endpoint(&endpoint.url)
+        .map(|transport| transport.supports_subscriptions())
+        .unwrap_or(false)
+}
+
 /// Downloads the `(block, deposit_root, deposit_count)` tuple from an eth1 node for the given
 /// `block_number`.
 ///
@@ -1123,7 +1368,7 @@ fn relevant_block_range(
 ///
 /// Performs three async calls to an Eth1 HTTP JSON RPC endpoint.
 async fn download_eth1_block(
-    endpoint: &str,
+    endpoint: &Eth1Endpoint,
     cache: Arc<Inner>,
     block_number_opt: Option<u64>,
 ) -> Result<Eth1Block, SingleEndpointError> {
@@ -0,0 +1,492 @@
+//! Provides the WebSocket and IPC transports for eth1 JSON-RPC endpoints.
+//!
+//! The transport is selected by the scheme of the endpoint:
+//!
+//! - `http://` and `https://` use `reqwest` (see the `http` module).
+//! - `ws://` uses a WebSocket connection. `wss://` is not supported, so credentials are only
+//!   sent over `ws://` to the local machine (see `Eth1Endpoint::auth_headers`).
+//! - `ipc://` uses the Unix domain socket at the given path, e.g.,
+//!   `ipc:///home/user/.ethereum/geth.ipc`.
+//!
+//! Unlike HTTP, the WebSocket and IPC transports support `eth_subscribe`, which allows the eth1
+//! node to push new heads and deposit logs instead of having them polled.
+//!
+//! Errors from `tungstenite` are formatted with `Display` rather than `Debug`, so they describe
+//! the failure without echoing the handshake request or response, which may contain secrets.
+
+use crate::endpoint::Eth1Endpoint;
+use futures::{stream, SinkExt, Stream, StreamExt};
+use serde_json::{json, Value};
+use std::path::Path;
+use std::pin::Pin;
+use std::time::Duration;
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{
+        client::IntoClientRequest, handshake::client::Request, Error as WsError, Message,
+    },
+};
+
+/// The transport used to reach an eth1 endpoint.
+#[derive(Debug, PartialEq)]
+pub enum Transport<'a> {
+    Http,
+    Ws,
+    Ipc(&'a Path),
+}
+
+impl<'a> Transport<'a> {
+    /// Determines the transport from the scheme of `endpoint`.
+    pub fn from_endpoint(endpoint: &'a str) -> Result<Self, String> {
+        if endpoint.starts_with("http://") || endpoint.starts_with("https://") {
+            Ok(Transport::Http)
+        } else if endpoint.starts_with("ws://") {
+            Ok(Transport::Ws)
+        } else if endpoint.starts_with("wss://") {
+            Err("Secure WebSocket eth1 endpoints are not supported, use https:// instead".into())
+        } else if let Some(path) = endpoint.strip_prefix("ipc://") {
+            Ok(Transport::Ipc(Path::new(path)))
+        } else {
+            Err(format!("Unsupported eth1 endpoint scheme: {}", endpoint))
+        }
+    }
+
+    /// Returns `true` if the transport supports `eth_subscribe`.
+    pub fn supports_subscriptions(&self) -> bool {
+        match self {
+            Transport::Http => false,
+            Transport::Ws | Transport::Ipc(_) => true,
+        }
+    }
+}
+
+/// Sends the JSON-RPC request `body` to the WebSocket `endpoint` and returns the response.
+pub async fn send_ws_request(
+    endpoint: &Eth1Endpoint,
+    body: String,
+    timeout: Duration,
+) -> Result<String, String> {
+    let request = async {
+        let (mut socket, _) = connect_async(ws_handshake_request(endpoint)?)
+            .await
+            .map_err(|e| format!("WebSocket connection failed: {}", e))?;
+        socket
+            .send(Message::Text(body))
+            .await
+            .map_err(|e| format!("Failed to send WebSocket request: {}", e))?;
+        let response = next_ws_message(&mut socket).await?;
+        // The response has already been received, a failure to close cleanly is irrelevant.
+        let _ = SinkExt::close(&mut socket).await;
+        Ok(response)
+    };
+
+    tokio::time::timeout(timeout, request)
+        .await
+        .map_err(|_| "WebSocket request timed out".to_string())?
+}
+
+/// Sends the JSON-RPC request `body` to the Unix domain socket at `path` and returns the
+/// response.
+pub async fn send_ipc_request(
+    path: &Path,
+    body: String,
+    timeout: Duration,
+) -> Result<String, String> {
+    let request = async {
+        let mut stream = ipc::IpcStream::connect(path).await?;
+        stream.send(&body).await?;
+        stream.next_message().await
+    };
+
+    tokio::time::timeout(timeout, request)
+        .await
+        .map_err(|_| "IPC request timed out".to_string())?
+}
+
+/// Returns the WebSocket handshake request for `endpoint`, including its authentication headers.
+fn ws_handshake_request(endpoint: &Eth1Endpoint) -> Result<Request, String> {
+    let mut request = endpoint
+        .request_url()?
+        .as_str()
+        .into_client_request()
+        .map_err(|e| format!("Invalid WebSocket request: {}", e))?;
+    request.headers_mut().extend(endpoint.auth_headers()?);
+    Ok(request)
+}
+
+/// Reads the next text (or binary) message from a WebSocket, skipping control messages.
+async fn next_ws_message<S>(socket: &mut S) -> Result<String, String>
+where
+    S: Stream<Item = Result<Message, WsError>> + Unpin,
+{
+    while let Some(message) = socket.next().await {
+        match message.map_err(|e| format!("WebSocket error: {}", e))? {
+            Message::Text(text) => return Ok(text),
+            Message::Binary(bytes) => return Ok(String::from_utf8_lossy(&bytes).into_owned()),
+            Message::Close(_) => break,
+            // Pings are answered by `tungstenite` whilst reading.
+            Message::Ping(_) | Message::Pong(_) => {}
+        }
+    }
+    Err("WebSocket connection closed".to_string())
+}
+
+/// A connection to an eth1 node which receives `eth_subscription` notifications.
+pub struct Subscription {
+    messages: Pin<Box<dyn Stream<Item = Result<String, String>> + Send>>,
+}
+
+impl Subscription {
+    /// Connects to `endpoint` and calls `eth_subscribe` once for each of the given `params`,
+    /// e.g., `json!(["newHeads"])`.
+    ///
+    /// Returns an error if the transport of `endpoint` does not support subscriptions or if the
+    /// node rejects any of the subscriptions.
+    pub async fn new(
+        endpoint: &Eth1Endpoint,
+        params: &[Value],
+        timeout: Duration,
+    ) -> Result<Self, String> {
+        let subscribe = async {
+            let messages: Pin<Box<dyn Stream<Item = Result<String, String>> + Send>> =
+                match Transport::from_endpoint(&endpoint.url)? {
+                    Transport::Http => {
+                        return Err("HTTP eth1 endpoints do not support subscriptions".to_string())
+                    }
+                    Transport::Ws => {
+                        let (mut socket, _) = connect_async(ws_handshake_request(endpoint)?)
+                            .await
+                            .map_err(|e| format!("WebSocket connection failed: {}", e))?;
+                        for (id, params) in params.iter().enumerate() {
+                            socket
+                                .send(Message::Text(subscribe_request(id, params)))
+                                .await
+                                .map_err(|e| format!("Failed to send subscription: {}", e))?;
+                            while !is_subscribe_response(&next_ws_message(&mut socket).await?)? {}
+                        }
+                        Box::pin(stream::unfold(socket, |mut socket| async move {
+                            let message = next_ws_message(&mut socket).await;
+                            Some((message, socket))
+                        }))
+                    }
+                    Transport::Ipc(path) => {
+                        let mut stream = ipc::IpcStream::connect(path).await?;
+                        for (id, params) in params.iter().enumerate() {
+                            stream.send(&subscribe_request(id, params)).await?;
+                            while !is_subscribe_response(&stream.next_message().await?)? {}
+                        }
+                        Box::pin(stream::unfold(stream, |mut stream| async move {
+                            let message = stream.next_message().await;
+                            Some((message, stream))
+                        }))
+                    }
+                };
+            Ok(Self { messages })
+        };
+
+        tokio::time::timeout(timeout, subscribe)
+            .await
+            .map_err(|_| "Subscription request timed out".to_string())?
+            .map_err(|e: String| endpoint.redact(&e))
+    }
+
+    /// Waits for the next notification and returns its `result`.
+    ///
+    /// Returns an error once the connection has failed, after which the subscription is no longer
+    /// usable.
+    pub async fn next_notification(&mut self) -> Result<Value, String> {
+        loop {
+            let message = self
+                .messages
+                .next()
+                .await
+                .unwrap_or_else(|| Err("Subscription stream ended".to_string()))?;
+            let json = serde_json::from_str::<Value>(&message)
+                .map_err(|e| format!("Failed to parse notification: {:?}", e))?;
+
+            if json.get("method").and_then(Value::as_str) == Some("eth_subscription") {
+                return json
+                    .get("params")
+                    .and_then(|params| params.get("result"))
+                    .cloned()
+                    .ok_or_else(|| "Notification had no result".to_string());
+            }
+        }
+    }
+}
+
+/// Returns the body of an `eth_subscribe` request with the given `params`.
+fn subscribe_request(id: usize, params: &Value) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "eth_subscribe",
+        "params": params,
+        "id": id + 1,
+    })
+    .to_string()
+}
+
+/// Returns `true` if `message` is the response to an `eth_subscribe` request, or `false` if it is
+/// a notification for an earlier subscription.
+fn is_subscribe_response(message: &str) -> Result<bool, String> {
+    let json = serde_json::from_str::<Value>(message)
+        .map_err(|e| format!("Failed to parse subscription response: {:?}", e))?;
+
+    if let Some(error) = json.get("error") {
+        Err(format!("Eth1 node rejected subscription: {}", error))
+    } else {
+        Ok(json.get("id").is_some())
+    }
+}
+
+#[cfg(unix)]
+mod ipc {
+    use serde_json::{Deserializer, Value};
+    use std::path::Path;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::UnixStream;
+
+    /// Splits the byte stream of an IPC connection into individual JSON-RPC messages.
+    ///
+    /// Eth1 nodes do not delimit messages sent over IPC, so a message is complete once it parses
+    /// as a JSON value.
+    pub struct IpcStream {
+        stream: UnixStream,
+        buf: Vec<u8>,
+    }
+
+    impl IpcStream {
+        pub async fn connect(path: &Path) -> Result<Self, String> {
+            let stream = UnixStream::connect(path)
+                .await
+                .map_err(|e| format!("IPC connection to {:?} failed: {:?}", path, e))?;
+            Ok(Self {
+                stream,
+                buf: vec![],
+            })
+        }
+
+        pub async fn send(&mut self, body: &str) -> Result<(), String> {
+            self.stream
+                .write_all(body.as_bytes())
+                .await
+                .map_err(|e| format!("Failed to send IPC request: {:?}", e))
+        }
+
+        /// Reads from the socket until a complete JSON value has been received and returns it.
+        pub async fn next_message(&mut self) -> Result<String, String> {
+            let mut chunk = [0; 4_096];
+            loop {
+                if let Some(message) = self.take_message()? {
+                    return Ok(message);
+                }
+
+                let len = self
+                    .stream
+                    .read(&mut chunk)
+                    .await
+                    .map_err(|e| format!("Failed to read IPC response: {:?}", e))?;
+                if len == 0 {
+                    return Err("IPC connection closed".to_string());
+                }
+                self.buf.extend_from_slice(&chunk[..len]);
+            }
+        }
+
+        /// Removes the first complete JSON value from `self.buf`, if any.
+        fn take_message(&mut self) -> Result<Option<String>, String> {
+            let mut values = Deserializer::from_slice(&self.buf).into_iter::<Value>();
+            match values.next() {
+                Some(Ok(_)) => {
+                    let len = values.byte_offset();
+                    let message = String::from_utf8_lossy(&self.buf[..len]).into_owned();
+                    self.buf.drain(..len);
+                    Ok(Some(message))
+                }
+                Some(Err(e)) if e.is_eof() => Ok(None),
+                Some(Err(e)) => Err(format!("Invalid JSON from IPC endpoint: {:?}", e)),
+                None => Ok(None),
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod ipc {
+    use std::path::Path;
+
+    /// IPC endpoints are only supported via Unix domain sockets.
+    pub enum IpcStream {}
+
+    impl IpcStream {
+        pub async fn connect(_path: &Path) -> Result<Self, String> {
+            Err("IPC eth1 endpoints are only supported on Unix".to_string())
+        }
+
+        pub async fn send(&mut self, _body: &str) -> Result<(), String> {
+            match *self {}
+        }
+
+        pub async fn next_message(&mut self) -> Result<String, String> {
+            match *self {}
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::UnixListener;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn socket_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("eth1_{}_{}.ipc", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn transport_from_endpoint() {
+        assert_eq!(
+            Transport::from_endpoint("http://localhost:8545"),
+            Ok(Transport::Http)
+        );
+        assert_eq!(
+            Transport::from_endpoint("https://localhost:8545"),
+            Ok(Transport::Http)
+        );
+        assert_eq!(
+            Transport::from_endpoint("ws://localhost:8546"),
+            Ok(Transport::Ws)
+        );
+        assert_eq!(
+            Transport::from_endpoint("ipc:///tmp/geth.ipc"),
+            Ok(Transport::Ipc(Path::new("/tmp/geth.ipc")))
+        );
+        assert!(Transport::from_endpoint("wss://localhost:8546").is_err());
+        assert!(Transport::from_endpoint("localhost:8545").is_err());
+
+        assert!(!Transport::Http.supports_subscriptions());
+        assert!(Transport::Ws.supports_subscriptions());
+    }
+
+    #[tokio::test]
+    async fn ipc_request_split_across_reads() {
+        let path = socket_path("request");
+        let listener = UnixListener::bind(&path).unwrap();
+
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 1_024];
+            let len = stream.read(&mut buf).await.unwrap();
+            let request = serde_json::from_slice::<Value>(&buf[..len]).unwrap();
+            assert_eq!(request["method"], "eth_blockNumber");
+
+            stream.write_all(br#"{"jsonrpc":"2.0","#).await.unwrap();
+            stream.flush().await.unwrap();
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            stream
+                .write_all(br#""id":1,"result":"0x2a"}"#)
+                .await
+                .unwrap();
+        });
+
+        let body = json!({"jsonrpc": "2.0", "method": "eth_blockNumber", "params": [], "id": 1});
+        let response = send_ipc_request(&path, body.to_string(), TIMEOUT)
+            .await
+            .unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&response).unwrap()["result"],
+            "0x2a"
+        );
+
+        server.await.unwrap();
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn ipc_subscription() {
+        let path = socket_path("subscription");
+        let listener = UnixListener::bind(&path).unwrap();
+
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 1_024];
+            let len = stream.read(&mut buf).await.unwrap();
+            let request = serde_json::from_slice::<Value>(&buf[..len]).unwrap();
+            assert_eq!(request["method"], "eth_subscribe");
+            assert_eq!(request["params"], json!(["newHeads"]));
+
+            // The response and the first notification arrive in a single read.
+            stream
+                .write_all(
+                    concat!(
+                        r#"{"jsonrpc":"2.0","id":1,"result":"0xcd0c"}"#,
+                        r#"{"jsonrpc":"2.0","method":"eth_subscription","#,
+                        r#""params":{"subscription":"0xcd0c","result":{"number":"0x1"}}}"#,
+                    )
+                    .as_bytes(),
+                )
+                .await
+                .unwrap();
+            stream
+                .write_all(
+                    concat!(
+                        r#"{"jsonrpc":"2.0","method":"eth_subscription","#,
+                        r#""params":{"subscription":"0xcd0c","result":{"number":"0x2"}}}"#,
+                    )
+                    .as_bytes(),
+                )
+                .await
+                .unwrap();
+        });
+
+        let endpoint = Eth1Endpoint::from(format!("ipc://{}", path.display()));
+        let mut subscription = Subscription::new(&endpoint, &[json!(["newHeads"])], TIMEOUT)
+            .await
+            .unwrap();
+        assert_eq!(
+            subscription.next_notification().await.unwrap()["number"],
+            "0x1"
+        );
+        assert_eq!(
+            subscription.next_notification().await.unwrap()["number"],
+            "0x2"
+        );
+
+        server.await.unwrap();
+        // The server has hung up.
+        assert!(subscription.next_notification().await.is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn ipc_subscription_rejected() {
+        let path = socket_path("rejected");
+        let listener = UnixListener::bind(&path).unwrap();
+
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 1_024];
+            assert!(stream.read(&mut buf).await.unwrap() > 0);
+            stream
+                .write_all(
+                    br#"{"jsonrpc":"2.0","id":1,"error":{"code":-32601,"message":"notifications not supported"}}"#,
+                )
+                .await
+                .unwrap();
+        });
+
+        let endpoint = Eth1Endpoint::from(format!("ipc://{}", path.display()));
+        assert!(
+            Subscription::new(&endpoint, &[json!(["newHeads"])], TIMEOUT)
+                .await
+                .is_err()
+        );
+
+        server.await.unwrap();
+        let _ = std::fs::remove_file(&path);
+    }
+}
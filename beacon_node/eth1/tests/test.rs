@@ -54,7 +54,7 @@ fn random_deposit_data() -> DepositData {
 /// Blocking operation to get the deposit logs from the `deposit_contract`.
 async fn blocking_deposit_logs(eth1: &GanacheEth1Instance, range: Range<u64>) -> Vec<Log> {
     get_deposit_logs_in_range(
-        &eth1.endpoint(),
+        &eth1.endpoint().into(),
         &eth1.deposit_contract.address(),
         range,
         timeout(),
@@ -66,7 +66,7 @@ async fn blocking_deposit_logs(eth1: &GanacheEth1Instance, range: Range<u64>) ->
 /// Blocking operation to get the deposit root from the `deposit_contract`.
 async fn blocking_deposit_root(eth1: &GanacheEth1Instance, block_number: u64) -> Option<Hash256> {
     get_deposit_root(
-        &eth1.endpoint(),
+        &eth1.endpoint().into(),
         &eth1.deposit_contract.address(),
         block_number,
         timeout(),
@@ -78,7 +78,7 @@ async fn blocking_deposit_root(eth1: &GanacheEth1Instance, block_number: u64) ->
 /// Blocking operation to get the deposit count from the `deposit_contract`.
 async fn blocking_deposit_count(eth1: &GanacheEth1Instance, block_number: u64) -> Option<u64> {
     get_deposit_count(
-        &eth1.endpoint(),
+        &eth1.endpoint().into(),
         &eth1.deposit_contract.address(),
         block_number,
         timeout(),
@@ -120,7 +120,7 @@ mod eth1_cache {
 
                 let service = Service::new(
                     Config {
-                        endpoints: vec![eth1.endpoint()],
+                        endpoints: vec![eth1.endpoint().into()],
                         deposit_contract_address: deposit_contract.address(),
                         lowest_cached_block_number: initial_block_number,
                         follow_distance,
@@ -201,7 +201,7 @@ mod eth1_cache {
 
             let service = Service::new(
                 Config {
-                    endpoints: vec![eth1.endpoint()],
+                    endpoints: vec![eth1.endpoint().into()],
                     deposit_contract_address: deposit_contract.address(),
                     lowest_cached_block_number: get_block_number(&web3).await,
                     follow_distance: 0,
@@ -256,7 +256,7 @@ mod eth1_cache {
 
             let service = Service::new(
                 Config {
-                    endpoints: vec![eth1.endpoint()],
+                    endpoints: vec![eth1.endpoint().into()],
                     deposit_contract_address: deposit_contract.address(),
                     lowest_cached_block_number: get_block_number(&web3).await,
                     follow_distance: 0,
@@ -307,7 +307,7 @@ mod eth1_cache {
 
             let service = Service::new(
                 Config {
-                    endpoints: vec![eth1.endpoint()],
+                    endpoints: vec![eth1.endpoint().into()],
                     deposit_contract_address: deposit_contract.address(),
                     lowest_cached_block_number: get_block_number(&web3).await,
                     follow_distance: 0,
@@ -360,7 +360,7 @@ mod deposit_tree {
 
             let service = Service::new(
                 Config {
-                    endpoints: vec![eth1.endpoint()],
+                    endpoints: vec![eth1.endpoint().into()],
                     deposit_contract_address: deposit_contract.address(),
                     deposit_contract_deploy_block: start_block,
                     follow_distance: 0,
@@ -441,7 +441,7 @@ mod deposit_tree {
 
             let service = Service::new(
                 Config {
-                    endpoints: vec![eth1.endpoint()],
+                    endpoints: vec![eth1.endpoint().into()],
                     deposit_contract_address: deposit_contract.address(),
                     deposit_contract_deploy_block: start_block,
                     lowest_cached_block_number: start_block,
@@ -583,7 +583,7 @@ mod http {
 
     async fn get_block(eth1: &GanacheEth1Instance, block_number: u64) -> Block {
         eth1::http::get_block(
-            &eth1.endpoint(),
+            &eth1.endpoint().into(),
             BlockQuery::Number(block_number),
             timeout(),
         )
@@ -699,7 +699,7 @@ mod fast {
             let now = get_block_number(&web3).await;
             let service = Service::new(
                 Config {
-                    endpoints: vec![eth1.endpoint()],
+                    endpoints: vec![eth1.endpoint().into()],
                     deposit_contract_address: deposit_contract.address(),
                     deposit_contract_deploy_block: now,
                     lowest_cached_block_number: now,
@@ -776,7 +776,7 @@ mod persist {
 
             let now = get_block_number(&web3).await;
             let config = Config {
-                endpoints: vec![eth1.endpoint()],
+                endpoints: vec![eth1.endpoint().into()],
                 deposit_contract_address: deposit_contract.address(),
                 deposit_contract_deploy_block: now,
                 lowest_cached_block_number: now,
@@ -886,7 +886,7 @@ mod fallbacks {
 
             let service = Service::new(
                 Config {
-                    endpoints: vec![endpoint1.endpoint(), endpoint2.endpoint()],
+                    endpoints: vec![endpoint1.endpoint().into(), endpoint2.endpoint().into()],
                     deposit_contract_address: deposit_contract.address(),
                     lowest_cached_block_number: initial_block_number,
                     follow_distance: 0,
@@ -962,7 +962,7 @@ mod fallbacks {
 
             let service = Service::new(
                 Config {
-                    endpoints: vec![endpoint2.endpoint(), endpoint1.endpoint()],
+                    endpoints: vec![endpoint2.endpoint().into(), endpoint1.endpoint().into()],
                     deposit_contract_address: deposit_contract.address(),
                     lowest_cached_block_number: initial_block_number,
                     follow_distance: 0,
@@ -1029,7 +1029,7 @@ mod fallbacks {
 
             let service = Service::new(
                 Config {
-                    endpoints: vec![endpoint2.endpoint(), endpoint1.endpoint()],
+                    endpoints: vec![endpoint2.endpoint().into(), endpoint1.endpoint().into()],
                     deposit_contract_address: deposit_contract.address(),
                     lowest_cached_block_number: initial_block_number,
                     follow_distance: 0,
@@ -1082,7 +1082,7 @@ mod fallbacks {
 
             let service = Service::new(
                 Config {
-                    endpoints: vec![endpoint1.endpoint(), endpoint2.endpoint()],
+                    endpoints: vec![endpoint1.endpoint().into(), endpoint2.endpoint().into()],
                     deposit_contract_address: deposit_contract.address(),
                     lowest_cached_block_number: initial_block_number,
                     follow_distance: 0,
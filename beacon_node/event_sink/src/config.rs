@@ -0,0 +1,111 @@
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// The prefix of a `SinkTarget` which appends events to a file.
+pub const FILE_PREFIX: &str = "file:";
+/// The prefix of a `SinkTarget` which writes events to a Unix domain socket.
+pub const UNIX_PREFIX: &str = "unix:";
+
+/// An output to which events are published, one JSON object per line.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub enum SinkTarget {
+    /// An append-only newline-delimited JSON (NDJSON) file.
+    File(PathBuf),
+    /// A Unix domain socket in "stream" mode, reconnected whenever a write fails.
+    UnixSocket(PathBuf),
+    /// An HTTP(S) endpoint which receives each event as the body of a `POST` request.
+    ///
+    /// Only displayed in a redacted form, since the URL may contain credentials.
+    Webhook(String),
+}
+
+impl FromStr for SinkTarget {
+    type Err = String;
+
+    /// Parses `file:<path>`, `unix:<path>`, `http://<url>` or `https://<url>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(path) = strip_prefix(s, FILE_PREFIX) {
+            Ok(SinkTarget::File(PathBuf::from(path)))
+        } else if let Some(path) = strip_prefix(s, UNIX_PREFIX) {
+            Ok(SinkTarget::UnixSocket(PathBuf::from(path)))
+        } else if s.starts_with("http://") || s.starts_with("https://") {
+            Ok(SinkTarget::Webhook(s.to_string()))
+        } else {
+            Err(format!(
+                "Invalid event sink {:?}, expected a {}, {}, http:// or https:// prefix",
+                s, FILE_PREFIX, UNIX_PREFIX
+            ))
+        }
+    }
+}
+
+impl fmt::Display for SinkTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SinkTarget::File(path) => write!(f, "{}{}", FILE_PREFIX, path.display()),
+            SinkTarget::UnixSocket(path) => write!(f, "{}{}", UNIX_PREFIX, path.display()),
+            SinkTarget::Webhook(url) => write!(f, "{}", redact_url(url)),
+        }
+    }
+}
+
+/// Redacts webhook URLs, so that credentials are not leaked via `Debug` (e.g., when logging the
+/// client config).
+impl fmt::Debug for SinkTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SinkTarget::File(path) => f.debug_tuple("File").field(path).finish(),
+            SinkTarget::UnixSocket(path) => f.debug_tuple("UnixSocket").field(path).finish(),
+            SinkTarget::Webhook(url) => f.debug_tuple("Webhook").field(&redact_url(url)).finish(),
+        }
+    }
+}
+
+/// Returns the scheme, host and port of `url`, omitting any userinfo, path or query (which may
+/// contain credentials).
+pub(crate) fn redact_url(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(url) => match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}://{}:{}/", url.scheme(), host, port),
+            (Some(host), None) => format!("{}://{}/", url.scheme(), host),
+            (None, _) => format!("{}://", url.scheme()),
+        },
+        Err(_) => "<invalid URL>".to_string(),
+    }
+}
+
+fn strip_prefix<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    if s.starts_with(prefix) && s.len() > prefix.len() {
+        Some(&s[prefix.len()..])
+    } else {
+        None
+    }
+}
+
+/// Configuration for publishing chain events to external sinks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Config {
+    /// The outputs to which every event is published. No sinks are started if empty.
+    pub targets: Vec<SinkTarget>,
+    /// The maximum number of events buffered for each sink. Further events are dropped until the
+    /// sink catches up.
+    pub buffer_size: usize,
+    /// The number of times a failed write is retried before the event is discarded.
+    pub max_retries: usize,
+    /// The delay before the first retry. The `n`th retry waits `n` times this delay.
+    pub retry_delay: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            targets: vec![],
+            buffer_size: 1_024,
+            max_retries: 3,
+            retry_delay: Duration::from_millis(500),
+        }
+    }
+}
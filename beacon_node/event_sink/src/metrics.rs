@@ -0,0 +1,24 @@
+pub use lighthouse_metrics::*;
+
+lazy_static! {
+    pub static ref EVENTS_PUBLISHED: Result<IntCounterVec> = try_create_int_counter_vec(
+        "event_sink_events_published_total",
+        "Count of events successfully written to each kind of event sink",
+        &["sink"]
+    );
+    pub static ref EVENTS_DROPPED: Result<IntCounterVec> = try_create_int_counter_vec(
+        "event_sink_events_dropped_total",
+        "Count of events dropped because the buffer of an event sink was full",
+        &["sink"]
+    );
+    pub static ref EVENTS_FAILED: Result<IntCounterVec> = try_create_int_counter_vec(
+        "event_sink_events_failed_total",
+        "Count of events discarded after exhausting all retries for an event sink",
+        &["sink"]
+    );
+    pub static ref WRITE_ERRORS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "event_sink_write_errors_total",
+        "Count of failed attempts to write an event to an event sink, including retries",
+        &["sink"]
+    );
+}
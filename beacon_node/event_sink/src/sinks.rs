@@ -0,0 +1,183 @@
+use crate::config::{redact_url, SinkTarget};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+
+/// The maximum time to wait for a socket or webhook to accept a single event.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// An output which accepts serialized events, one at a time.
+///
+/// Sinks are driven by a single dedicated thread, so implementations may block.
+pub trait Sink: Send {
+    /// A short, static name for the kind of sink, used to label metrics.
+    fn kind(&self) -> &'static str;
+
+    /// Write a single serialized `event` to the sink.
+    ///
+    /// Implementations should leave the sink ready to retry the write if an error is returned.
+    fn write_event(&mut self, event: &str) -> Result<(), String>;
+}
+
+impl SinkTarget {
+    /// Open the sink described by `self`.
+    ///
+    /// Files are opened immediately, so that a misconfigured path is reported at startup.
+    /// Sockets and webhooks are connected lazily, when the first event is written.
+    pub fn open(&self) -> Result<Box<dyn Sink>, String> {
+        match self {
+            SinkTarget::File(path) => Ok(Box::new(FileSink::open(path.clone())?)),
+            #[cfg(unix)]
+            SinkTarget::UnixSocket(path) => Ok(Box::new(UnixSocketSink::new(path.clone()))),
+            #[cfg(not(unix))]
+            SinkTarget::UnixSocket(_) => {
+                Err("Unix socket event sinks are only supported on Unix".to_string())
+            }
+            SinkTarget::Webhook(url) => Ok(Box::new(WebhookSink::new(url.clone()))),
+        }
+    }
+}
+
+/// Appends events to a newline-delimited JSON file.
+pub struct FileSink {
+    path: PathBuf,
+    file: File,
+}
+
+impl FileSink {
+    pub fn open(path: PathBuf) -> Result<Self, String> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("Unable to open event sink file {:?}: {:?}", path, e))?;
+
+        Ok(Self { path, file })
+    }
+}
+
+impl Sink for FileSink {
+    fn kind(&self) -> &'static str {
+        "file"
+    }
+
+    fn write_event(&mut self, event: &str) -> Result<(), String> {
+        writeln!(self.file, "{}", event)
+            .and_then(|()| self.file.flush())
+            .map_err(|e| format!("Unable to write to {:?}: {:?}", self.path, e))
+    }
+}
+
+/// Writes newline-delimited events to a listener on a Unix domain socket.
+#[cfg(unix)]
+pub struct UnixSocketSink {
+    path: PathBuf,
+    stream: Option<UnixStream>,
+}
+
+#[cfg(unix)]
+impl UnixSocketSink {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, stream: None }
+    }
+
+    fn connect(&self) -> Result<UnixStream, String> {
+        let stream = UnixStream::connect(&self.path)
+            .map_err(|e| format!("Unable to connect to {:?}: {:?}", self.path, e))?;
+        stream
+            .set_write_timeout(Some(WRITE_TIMEOUT))
+            .map_err(|e| format!("Unable to set write timeout: {:?}", e))?;
+        Ok(stream)
+    }
+}
+
+#[cfg(unix)]
+impl Sink for UnixSocketSink {
+    fn kind(&self) -> &'static str {
+        "unix"
+    }
+
+    fn write_event(&mut self, event: &str) -> Result<(), String> {
+        let mut stream = match self.stream.take() {
+            Some(stream) => stream,
+            None => self.connect()?,
+        };
+
+        stream
+            .write_all(event.as_bytes())
+            .and_then(|()| stream.write_all(b"\n"))
+            .map_err(|e| format!("Unable to write to {:?}: {:?}", self.path, e))?;
+
+        // Only keep the connection if the write succeeded, so that a retry reconnects.
+        self.stream = Some(stream);
+        Ok(())
+    }
+}
+
+/// `POST`s each event as a JSON body to an HTTP endpoint.
+pub struct WebhookSink {
+    url: String,
+    /// The URL without credentials, for use in errors.
+    redacted_url: String,
+    /// Built on first use, since a blocking client cannot be created or dropped from within an
+    /// async context.
+    client: Option<reqwest::blocking::Client>,
+}
+
+impl WebhookSink {
+    pub fn new(url: String) -> Self {
+        Self {
+            redacted_url: redact_url(&url),
+            url,
+            client: None,
+        }
+    }
+}
+
+impl Sink for WebhookSink {
+    fn kind(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn write_event(&mut self, event: &str) -> Result<(), String> {
+        let client = match &self.client {
+            Some(client) => client,
+            None => {
+                let client = reqwest::blocking::Client::builder()
+                    .timeout(WRITE_TIMEOUT)
+                    .build()
+                    .map_err(|e| format!("Unable to build HTTP client: {:?}", e))?;
+                self.client.get_or_insert(client)
+            }
+        };
+
+        client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(event.to_string())
+            .send()
+            .and_then(|response| response.error_for_status())
+            .map(|_| ())
+            .map_err(|e| {
+                format!(
+                    "Unable to post event to {}: {}",
+                    self.redacted_url,
+                    webhook_error(&e)
+                )
+            })
+    }
+}
+
+/// Describes `e` without the request URL, which `reqwest` includes in its own messages.
+fn webhook_error(e: &reqwest::Error) -> String {
+    match (e.status(), std::error::Error::source(e)) {
+        (Some(status), _) => format!("HTTP status {}", status),
+        (None, Some(source)) => source.to_string(),
+        (None, None) if e.is_timeout() => "timed out".to_string(),
+        (None, None) => "request failed".to_string(),
+    }
+}
@@ -3,6 +3,7 @@ mod eth1_genesis_service;
 mod interop;
 
 pub use eth1::Config as Eth1Config;
+pub use eth1::Eth1Endpoint;
 pub use eth1_genesis_service::{Eth1GenesisService, Statistics};
 pub use interop::interop_genesis_state;
 pub use types::test_utils::generate_deterministic_keypairs;
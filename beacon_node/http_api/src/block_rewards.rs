@@ -0,0 +1,157 @@
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::BlockRewardsData;
+use state_processing::{
+    per_block_processing, per_block_processing::compute_block_reward, per_slot_processing,
+    BlockSignatureStrategy,
+};
+use types::{RelativeEpoch, SignedBeaconBlock, Slot};
+
+/// The maximum number of slots that may be requested at once, since every block in the range is
+/// replayed to compute its rewards.
+const MAX_BLOCK_REWARDS_SLOTS: u64 = 1024;
+
+/// Returns the rewards received by the proposers of each of the canonical blocks in the inclusive
+/// range `start_slot..=end_slot`.
+///
+/// The rewards are computed by replaying the blocks on top of the parent state of the first block
+/// in the range.
+pub fn block_rewards<T: BeaconChainTypes>(
+    start_slot: Slot,
+    end_slot: Slot,
+    chain: &BeaconChain<T>,
+) -> Result<Vec<BlockRewardsData>, warp::Rejection> {
+    if start_slot == 0 || start_slot > end_slot {
+        return Err(warp_utils::reject::custom_bad_request(format!(
+            "invalid range: start_slot must be non-zero and no greater than end_slot, got \
+             {}..={}",
+            start_slot, end_slot
+        )));
+    }
+    if end_slot - start_slot >= MAX_BLOCK_REWARDS_SLOTS {
+        return Err(warp_utils::reject::custom_bad_request(format!(
+            "invalid range: at most {} slots may be requested, got {}..={}",
+            MAX_BLOCK_REWARDS_SLOTS, start_slot, end_slot
+        )));
+    }
+
+    let blocks = canonical_blocks(start_slot, end_slot, chain)?;
+
+    let first_block = match blocks.first() {
+        Some(block) => block,
+        None => return Ok(vec![]),
+    };
+
+    let parent_root = first_block.parent_root();
+    let parent_block = chain
+        .get_block(&parent_root)
+        .map_err(warp_utils::reject::beacon_chain_error)?
+        .ok_or_else(|| {
+            warp_utils::reject::custom_server_error(format!(
+                "missing parent block {:?}",
+                parent_root
+            ))
+        })?;
+    let mut state_root = parent_block.state_root();
+    let mut state = chain
+        .get_state(&state_root, Some(parent_block.slot()))
+        .map_err(warp_utils::reject::beacon_chain_error)?
+        .ok_or_else(|| {
+            warp_utils::reject::custom_server_error(format!(
+                "missing parent state {:?}",
+                state_root
+            ))
+        })?;
+
+    let mut rewards = Vec::with_capacity(blocks.len());
+    for block in blocks {
+        // Supplying the known state root for the first slot avoids hashing the state. Any
+        // subsequent skipped slots must be hashed so the block roots recorded by the state remain
+        // correct.
+        let mut known_state_root = Some(state_root);
+        while state.slot < block.slot() {
+            per_slot_processing(&mut state, known_state_root.take(), &chain.spec).map_err(|e| {
+                warp_utils::reject::custom_server_error(format!("unable to advance state: {:?}", e))
+            })?;
+        }
+
+        state
+            .build_committee_cache(RelativeEpoch::Previous, &chain.spec)
+            .map_err(warp_utils::reject::beacon_state_error)?;
+        state
+            .build_committee_cache(RelativeEpoch::Current, &chain.spec)
+            .map_err(warp_utils::reject::beacon_state_error)?;
+
+        let reward = compute_block_reward(&state, &block.message, &chain.spec).map_err(|e| {
+            warp_utils::reject::custom_server_error(format!(
+                "unable to compute block reward: {:?}",
+                e
+            ))
+        })?;
+        let total = reward.total().map_err(|e| {
+            warp_utils::reject::custom_server_error(format!("block reward overflow: {:?}", e))
+        })?;
+
+        per_block_processing(
+            &mut state,
+            &block,
+            None,
+            BlockSignatureStrategy::NoVerification,
+            &chain.spec,
+        )
+        .map_err(|e| {
+            warp_utils::reject::custom_server_error(format!("unable to process block: {:?}", e))
+        })?;
+        state_root = block.state_root();
+
+        rewards.push(BlockRewardsData {
+            slot: block.slot(),
+            block_root: block.canonical_root(),
+            proposer_index: block.message.proposer_index,
+            total,
+            attestation_rewards: reward.attestation_rewards,
+            proposer_slashing_rewards: reward.proposer_slashing_rewards,
+            attester_slashing_rewards: reward.attester_slashing_rewards,
+        });
+    }
+
+    Ok(rewards)
+}
+
+/// Returns the canonical blocks in the inclusive range `start_slot..=end_slot`, in ascending order.
+fn canonical_blocks<T: BeaconChainTypes>(
+    start_slot: Slot,
+    end_slot: Slot,
+    chain: &BeaconChain<T>,
+) -> Result<Vec<SignedBeaconBlock<T::EthSpec>>, warp::Rejection> {
+    let mut block_roots = vec![];
+    for result in chain
+        .forwards_iter_block_roots(start_slot)
+        .map_err(warp_utils::reject::beacon_chain_error)?
+    {
+        let (block_root, slot) = result.map_err(warp_utils::reject::beacon_chain_error)?;
+        if slot > end_slot {
+            break;
+        }
+        // Skipped slots repeat the root of the most recent block.
+        if block_roots.last() != Some(&block_root) {
+            block_roots.push(block_root);
+        }
+    }
+
+    let mut blocks = Vec::with_capacity(block_roots.len());
+    for block_root in block_roots {
+        let block = chain
+            .get_block(&block_root)
+            .map_err(warp_utils::reject::beacon_chain_error)?
+            .ok_or_else(|| {
+                warp_utils::reject::custom_server_error(format!("missing block {:?}", block_root))
+            })?;
+
+        // The first root is from a block prior to the range if `start_slot` was skipped.
+        if block.slot() >= start_slot {
+            blocks.push(block);
+        }
+    }
+
+    Ok(blocks)
+}
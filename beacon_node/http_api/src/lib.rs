@@ -7,13 +7,15 @@
 
 mod beacon_proposer_cache;
 mod block_id;
+mod block_rewards;
 mod metrics;
 mod state_id;
 mod validator_inclusion;
+mod validator_rewards;
 
 use beacon_chain::{
     observed_operations::ObservationOutcome, AttestationError as AttnError, BeaconChain,
-    BeaconChainError, BeaconChainTypes,
+    BeaconChainError, BeaconChainTypes, StateSkipConfig,
 };
 use beacon_proposer_cache::BeaconProposerCache;
 use block_id::BlockId;
@@ -35,9 +37,10 @@ use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
 use std::sync::Arc;
 use tokio::sync::mpsc::UnboundedSender;
 use types::{
-    Attestation, AttestationDuty, AttesterSlashing, CloneConfig, CommitteeCache, Epoch, EthSpec,
-    Hash256, ProposerSlashing, PublicKey, PublicKeyBytes, RelativeEpoch, SignedAggregateAndProof,
-    SignedBeaconBlock, SignedVoluntaryExit, Slot, YamlConfig,
+    Attestation, AttestationDuty, AttesterSlashing, BeaconState, BeaconStateError, CloneConfig,
+    CommitteeCache, Epoch, EthSpec, Hash256, ProposerSlashing, PublicKey, PublicKeyBytes,
+    RelativeEpoch, SignedAggregateAndProof, SignedBeaconBlock, SignedVoluntaryExit, Slot,
+    YamlConfig,
 };
 use warp::http::StatusCode;
 use warp::{http::Response, Filter};
@@ -2064,6 +2067,40 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/validator_rewards/{epoch}?id
+    let get_lighthouse_validator_rewards = warp::path("lighthouse")
+        .and(warp::path("validator_rewards"))
+        .and(warp::path::param::<Epoch>())
+        .and(warp::path::end())
+        .and(warp::query::<eth2::lighthouse::ValidatorRewardsQuery>())
+        .and(chain_filter.clone())
+        .and_then(
+            |epoch: Epoch,
+             query: eth2::lighthouse::ValidatorRewardsQuery,
+             chain: Arc<BeaconChain<T>>| {
+                blocking_json_task(move || {
+                    let validator_ids = query.id.map(|ids| ids.0);
+                    validator_rewards::validator_rewards(epoch, validator_ids.as_deref(), &chain)
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // GET lighthouse/block_rewards?start_slot,end_slot
+    let get_lighthouse_block_rewards = warp::path("lighthouse")
+        .and(warp::path("block_rewards"))
+        .and(warp::path::end())
+        .and(warp::query::<eth2::lighthouse::BlockRewardsQuery>())
+        .and(chain_filter.clone())
+        .and_then(
+            |query: eth2::lighthouse::BlockRewardsQuery, chain: Arc<BeaconChain<T>>| {
+                blocking_json_task(move || {
+                    block_rewards::block_rewards(query.start_slot, query.end_slot, &chain)
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
     // GET lighthouse/eth1/syncing
     let get_lighthouse_eth1_syncing = warp::path("lighthouse")
         .and(warp::path("eth1"))
@@ -2097,6 +2134,41 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/eth1/voting
+    let get_lighthouse_eth1_voting = warp::path("lighthouse")
+        .and(warp::path("eth1"))
+        .and(warp::path("voting"))
+        .and(warp::path::end())
+        .and(chain_filter.clone())
+        .and_then(|chain: Arc<BeaconChain<T>>| {
+            blocking_json_task(move || {
+                let eth1_chain = chain.eth1_chain.as_ref().ok_or_else(|| {
+                    warp_utils::reject::custom_not_found(
+                        "Eth1 sync is disabled. See the --eth1 CLI flag.".to_string(),
+                    )
+                })?;
+
+                // Advance the head state to the current slot, as would happen during block
+                // production.
+                let current_slot = chain
+                    .slot()
+                    .map_err(warp_utils::reject::beacon_chain_error)?;
+                let state = chain
+                    .state_at_slot(current_slot, StateSkipConfig::WithoutStateRoots)
+                    .map_err(warp_utils::reject::beacon_chain_error)?;
+
+                eth1_chain
+                    .eth1_voting_status(&state, &chain.spec)
+                    .map(api_types::GenericResponse::from)
+                    .map_err(|e| {
+                        warp_utils::reject::custom_server_error(format!(
+                            "Unable to determine eth1 vote: {:?}",
+                            e
+                        ))
+                    })
+            })
+        });
+
     // GET lighthouse/eth1/block_cache
     let get_lighthouse_eth1_block_cache = warp::path("lighthouse")
         .and(warp::path("eth1"))
@@ -2121,7 +2193,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("eth1"))
         .and(warp::path("deposit_cache"))
         .and(warp::path::end())
-        .and(eth1_service_filter)
+        .and(eth1_service_filter.clone())
         .and_then(|eth1_service: eth1::Service| {
             blocking_json_task(move || {
                 Ok(api_types::GenericResponse::from(
@@ -2136,6 +2208,25 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/eth1/deposit_snapshot
+    let get_lighthouse_eth1_deposit_snapshot = warp::path("lighthouse")
+        .and(warp::path("eth1"))
+        .and(warp::path("deposit_snapshot"))
+        .and(warp::path::end())
+        .and(eth1_service_filter)
+        .and_then(|eth1_service: eth1::Service| {
+            blocking_json_task(move || {
+                eth1_service
+                    .get_deposit_snapshot()
+                    .map(api_types::GenericResponse::from)
+                    .ok_or_else(|| {
+                        warp_utils::reject::custom_not_found(
+                            "No deposits have been finalized".to_string(),
+                        )
+                    })
+            })
+        });
+
     // GET lighthouse/beacon/states/{state_id}/ssz
     let get_lighthouse_beacon_states_ssz = warp::path("lighthouse")
         .and(warp::path("beacon"))
@@ -2160,6 +2251,104 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/beacon/states/{state_id}/proof?gindex,path
+    let get_lighthouse_beacon_states_proof = warp::path("lighthouse")
+        .and(warp::path("beacon"))
+        .and(warp::path("states"))
+        .and(warp::path::param::<StateId>())
+        .and(warp::path("proof"))
+        .and(warp::path::end())
+        .and(warp::query::<eth2::lighthouse::StateProofQuery>())
+        .and(chain_filter.clone())
+        .and_then(
+            |state_id: StateId,
+             query: eth2::lighthouse::StateProofQuery,
+             chain: Arc<BeaconChain<T>>| {
+                blocking_json_task(move || {
+                    let generalized_index = match (query.gindex, query.path) {
+                        (Some(gindex), None) => gindex as usize,
+                        (None, Some(path)) => {
+                            BeaconState::<T::EthSpec>::generalized_index_for_path(
+                                &path.split('.').collect::<Vec<_>>(),
+                            )
+                            .map_err(|e| {
+                                warp_utils::reject::custom_bad_request(format!(
+                                    "invalid path: {:?}",
+                                    e
+                                ))
+                            })?
+                        }
+                        _ => {
+                            return Err(warp_utils::reject::custom_bad_request(
+                                "exactly one of gindex or path must be supplied".to_string(),
+                            ))
+                        }
+                    };
+
+                    let mut state = state_id.state(&chain)?;
+
+                    state
+                        .compute_merkle_proof(generalized_index)
+                        .map_err(|e| match e {
+                            BeaconStateError::InvalidGeneralizedIndex(_) => {
+                                warp_utils::reject::custom_bad_request(format!(
+                                    "unable to prove generalized index: {:?}",
+                                    e
+                                ))
+                            }
+                            e => warp_utils::reject::beacon_state_error(e),
+                        })
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // GET lighthouse/gossip/trace?kind,slot,peer_id
+    let get_lighthouse_gossip_trace = warp::path("lighthouse")
+        .and(warp::path("gossip"))
+        .and(warp::path("trace"))
+        .and(warp::path::end())
+        .and(warp::query::<eth2::lighthouse::GossipTraceQuery>())
+        .and(chain_filter.clone())
+        .and_then(
+            |query: eth2::lighthouse::GossipTraceQuery, chain: Arc<BeaconChain<T>>| {
+                blocking_json_task(move || {
+                    let gossip_trace = chain.gossip_trace.as_ref().ok_or_else(|| {
+                        warp_utils::reject::custom_not_found(
+                            "gossip tracing is not enabled, \
+                            see the --gossip-trace CLI flag"
+                                .to_string(),
+                        )
+                    })?;
+
+                    let traces = gossip_trace
+                        .lock()
+                        .traces()
+                        .into_iter()
+                        .filter(|trace| query.kind.map_or(true, |kind| trace.kind == kind))
+                        .filter(|trace| query.slot.map_or(true, |slot| trace.slot == slot))
+                        .filter(|trace| {
+                            query
+                                .peer_id
+                                .as_ref()
+                                .map_or(true, |peer_id| trace.peer_id == *peer_id)
+                        })
+                        .collect::<Vec<_>>();
+
+                    Ok(api_types::GenericResponse::from(traces))
+                })
+            },
+        );
+
+    // GET lighthouse/late_blocks
+    let get_lighthouse_late_blocks = warp::path("lighthouse")
+        .and(warp::path("late_blocks"))
+        .and(warp::path::end())
+        .and(chain_filter.clone())
+        .and_then(|chain: Arc<BeaconChain<T>>| {
+            blocking_json_task(move || Ok(api_types::GenericResponse::from(chain.late_blocks())))
+        });
+
     // GET lighthouse/staking
     let get_lighthouse_staking = warp::path("lighthouse")
         .and(warp::path("staking"))
@@ -2223,10 +2412,17 @@ pub fn serve<T: BeaconChainTypes>(
                 .or(get_lighthouse_proto_array.boxed())
                 .or(get_lighthouse_validator_inclusion_global.boxed())
                 .or(get_lighthouse_validator_inclusion.boxed())
+                .or(get_lighthouse_validator_rewards.boxed())
+                .or(get_lighthouse_block_rewards.boxed())
                 .or(get_lighthouse_eth1_syncing.boxed())
+                .or(get_lighthouse_eth1_voting.boxed())
                 .or(get_lighthouse_eth1_block_cache.boxed())
                 .or(get_lighthouse_eth1_deposit_cache.boxed())
+                .or(get_lighthouse_eth1_deposit_snapshot.boxed())
                 .or(get_lighthouse_beacon_states_ssz.boxed())
+                .or(get_lighthouse_beacon_states_proof.boxed())
+                .or(get_lighthouse_gossip_trace.boxed())
+                .or(get_lighthouse_late_blocks.boxed())
                 .or(get_lighthouse_staking.boxed()),
         )
         .or(warp::post().and(
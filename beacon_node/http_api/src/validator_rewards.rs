@@ -0,0 +1,94 @@
+use crate::state_id::StateId;
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::{
+    lighthouse::{RewardsAndPenalties, ValidatorRewardsData},
+    types::ValidatorId,
+};
+use state_processing::per_epoch_processing::{
+    get_attestation_deltas, process_justification_and_finalization, Delta, ValidatorStatuses,
+};
+use types::{Epoch, EthSpec, RelativeEpoch};
+
+/// Returns the rewards and penalties received by the given validators (or all validators, if
+/// `validator_ids` is `None`) for their attestations during `epoch`.
+///
+/// Unknown validators are omitted from the result.
+pub fn validator_rewards<T: BeaconChainTypes>(
+    epoch: Epoch,
+    validator_ids: Option<&[ValidatorId]>,
+    chain: &BeaconChain<T>,
+) -> Result<Vec<ValidatorRewardsData>, warp::Rejection> {
+    // The rewards for `epoch` are applied by the epoch transition at the end of the following
+    // epoch, when `epoch` is the previous epoch of the state.
+    let target_slot = (epoch + 1).end_slot(T::EthSpec::slots_per_epoch());
+
+    let mut state = StateId::slot(target_slot).state(chain)?;
+
+    state
+        .build_committee_cache(RelativeEpoch::Previous, &chain.spec)
+        .map_err(warp_utils::reject::beacon_state_error)?;
+    state
+        .build_committee_cache(RelativeEpoch::Current, &chain.spec)
+        .map_err(warp_utils::reject::beacon_state_error)?;
+
+    let mut validator_statuses = ValidatorStatuses::new(&state, &chain.spec)
+        .map_err(warp_utils::reject::beacon_state_error)?;
+    validator_statuses
+        .process_attestations(&state, &chain.spec)
+        .map_err(warp_utils::reject::beacon_state_error)?;
+
+    // The finality delay used to compute the rewards depends on the outcome of justification and
+    // finalization, which happens first during the epoch transition.
+    let deltas =
+        process_justification_and_finalization(&mut state, &validator_statuses.total_balances)
+            .and_then(|()| get_attestation_deltas(&state, &validator_statuses, &chain.spec))
+            .map_err(|e| {
+                warp_utils::reject::custom_server_error(format!(
+                    "unable to compute rewards: {:?}",
+                    e
+                ))
+            })?;
+
+    let validator_indices = if let Some(validator_ids) = validator_ids {
+        state
+            .update_pubkey_cache()
+            .map_err(warp_utils::reject::beacon_state_error)?;
+
+        validator_ids
+            .iter()
+            .map(|validator_id| match validator_id {
+                ValidatorId::Index(index) => Ok(Some(*index as usize)),
+                ValidatorId::PublicKey(pubkey) => state
+                    .get_validator_index(pubkey)
+                    .map_err(warp_utils::reject::beacon_state_error),
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+    } else {
+        (0..deltas.len()).collect()
+    };
+
+    Ok(validator_indices
+        .into_iter()
+        .filter_map(|index| {
+            let delta = deltas.get(index)?;
+            Some(ValidatorRewardsData {
+                validator_index: index as u64,
+                source: rewards_and_penalties(&delta.source_delta),
+                target: rewards_and_penalties(&delta.target_delta),
+                head: rewards_and_penalties(&delta.head_delta),
+                inclusion_delay: rewards_and_penalties(&delta.inclusion_delay_delta),
+                inactivity_penalty: rewards_and_penalties(&delta.inactivity_penalty_delta),
+            })
+        })
+        .collect())
+}
+
+fn rewards_and_penalties(delta: &Delta) -> RewardsAndPenalties {
+    RewardsAndPenalties {
+        rewards: delta.rewards,
+        penalties: delta.penalties,
+    }
+}
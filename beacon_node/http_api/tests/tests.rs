@@ -8,7 +8,7 @@ use discv5::enr::{CombinedKey, EnrBuilder};
 use environment::null_logger;
 use eth2::Error;
 use eth2::StatusCode;
-use eth2::{types::*, BeaconNodeHttpClient, Url};
+use eth2::{lighthouse::Eth1VoteReason, types::*, BeaconNodeHttpClient, Url};
 use eth2_libp2p::{
     rpc::methods::MetaData,
     types::{EnrBitfield, SyncState},
@@ -1774,12 +1774,135 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_validator_rewards(self) -> Self {
+        let epoch = self.chain.epoch().unwrap() - 2;
+
+        let all = self
+            .client
+            .get_lighthouse_validator_rewards(epoch, None)
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(all.len(), VALIDATOR_COUNT);
+        for (i, rewards) in all.iter().enumerate() {
+            assert_eq!(rewards.validator_index, i as u64);
+        }
+
+        let pubkey = self.chain.head().unwrap().beacon_state.validators[1]
+            .pubkey
+            .clone();
+        let selected = self
+            .client
+            .get_lighthouse_validator_rewards(
+                epoch,
+                Some(&[
+                    ValidatorId::Index(0),
+                    ValidatorId::PublicKey(pubkey),
+                    ValidatorId::Index(VALIDATOR_COUNT as u64),
+                ]),
+            )
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(
+            selected,
+            all[0..2].to_vec(),
+            "unknown validators are omitted"
+        );
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_block_rewards(self) -> Self {
+        let head_slot = self.chain.head_info().unwrap().slot;
+        let start_slot = Slot::new(1);
+
+        let rewards = self
+            .client
+            .get_lighthouse_block_rewards(start_slot, head_slot)
+            .await
+            .unwrap()
+            .data;
+
+        let mut expected_roots = vec![];
+        for slot in start_slot.as_u64()..=head_slot.as_u64() {
+            let block_root = self.chain.block_root_at_slot(Slot::new(slot)).unwrap();
+            let block = block_root.and_then(|root| self.chain.get_block(&root).unwrap());
+            // Skipped slots repeat the root of the most recent block.
+            if let Some(block) = block.filter(|block| block.slot() == slot) {
+                expected_roots.push(block.canonical_root());
+            }
+        }
+        assert_eq!(
+            rewards
+                .iter()
+                .map(|reward| reward.block_root)
+                .collect::<Vec<_>>(),
+            expected_roots
+        );
+
+        for reward in &rewards {
+            let sum = reward
+                .attestation_rewards
+                .iter()
+                .chain(&reward.proposer_slashing_rewards)
+                .chain(&reward.attester_slashing_rewards)
+                .sum::<u64>();
+            assert_eq!(reward.total, sum);
+        }
+        assert!(
+            rewards.iter().any(|reward| reward.total > 0),
+            "attestations should earn rewards"
+        );
+
+        // The rewards for a sub-range match those computed for the entire range.
+        let middle_slot = rewards[rewards.len() / 2].slot;
+        let tail = self
+            .client
+            .get_lighthouse_block_rewards(middle_slot, head_slot)
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(tail, rewards[rewards.len() / 2..].to_vec());
+
+        assert!(self
+            .client
+            .get_lighthouse_block_rewards(head_slot, start_slot)
+            .await
+            .is_err());
+
+        // Ranges longer than the limit are rejected rather than replayed.
+        let result = self
+            .client
+            .get_lighthouse_block_rewards(start_slot, start_slot + 1024)
+            .await;
+        assert_eq!(result.unwrap_err().status(), Some(StatusCode::BAD_REQUEST));
+
+        self
+    }
+
     pub async fn test_get_lighthouse_eth1_syncing(self) -> Self {
         self.client.get_lighthouse_eth1_syncing().await.unwrap();
 
         self
     }
 
+    pub async fn test_get_lighthouse_eth1_voting(self) -> Self {
+        let status = self.client.get_lighthouse_eth1_voting().await.unwrap().data;
+
+        // The test harness uses the dummy eth1 backend for block production, so every vote in the
+        // state should agree with ours.
+        assert_eq!(status.our_vote_reason, Eth1VoteReason::DummyBackend);
+        assert!(status.candidate_blocks.is_empty());
+        assert!(status
+            .votes
+            .iter()
+            .all(|tally| tally.eth1_data == status.our_vote));
+        assert!(!status.diverges_from_leading_vote);
+
+        self
+    }
+
     pub async fn test_get_lighthouse_eth1_block_cache(self) -> Self {
         let blocks = self.client.get_lighthouse_eth1_block_cache().await.unwrap();
 
@@ -1800,6 +1923,19 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_eth1_deposit_snapshot(self) -> Self {
+        let snapshot = self
+            .client
+            .get_lighthouse_eth1_deposit_snapshot()
+            .await
+            .unwrap();
+
+        // The eth1 service is never updated, so no deposits are finalized.
+        assert!(snapshot.is_none());
+
+        self
+    }
+
     pub async fn test_get_lighthouse_beacon_states_ssz(self) -> Self {
         for state_id in self.interesting_state_ids() {
             let result = self
@@ -1817,6 +1953,84 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_beacon_states_proof(self) -> Self {
+        for state_id in self.interesting_state_ids() {
+            for path in &["slot", "validators.3.effective_balance", "balances.5"] {
+                let result = self
+                    .client
+                    .get_lighthouse_beacon_states_proof(state_id, None, Some(path))
+                    .await
+                    .unwrap()
+                    .map(|res| res.data);
+
+                let expected_root = self.get_state(state_id).map(|state| state.tree_hash_root());
+
+                assert_eq!(
+                    result.as_ref().map(|proof| proof.state_root),
+                    expected_root,
+                    "{:?} {}",
+                    state_id,
+                    path
+                );
+
+                if let Some(proof) = result {
+                    assert!(proof.verify(), "{:?} {}", state_id, path);
+
+                    let by_gindex = self
+                        .client
+                        .get_lighthouse_beacon_states_proof(
+                            state_id,
+                            Some(proof.generalized_index),
+                            None,
+                        )
+                        .await
+                        .unwrap()
+                        .unwrap()
+                        .data;
+                    assert_eq!(by_gindex, proof);
+                }
+            }
+        }
+
+        // Invalid queries should be rejected.
+        for (gindex, path) in &[
+            (None, None),
+            (Some(32), Some("slot")),
+            (Some(1), None),
+            (None, Some("validators.unknown")),
+        ] {
+            let result = self
+                .client
+                .get_lighthouse_beacon_states_proof(StateId::Head, *gindex, *path)
+                .await;
+            assert_eq!(result.unwrap_err().status(), Some(StatusCode::BAD_REQUEST));
+        }
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_gossip_trace(self) -> Self {
+        // Gossip tracing is disabled by default.
+        let result = self
+            .client
+            .get_lighthouse_gossip_trace(&<_>::default())
+            .await
+            .unwrap();
+        assert_eq!(result, None);
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_late_blocks(self) -> Self {
+        let result = self.client.get_lighthouse_late_blocks().await.unwrap().data;
+
+        // Blocks are not received on gossip by the harness, so none of them can be late.
+        assert!(result.is_empty());
+        assert_eq!(result, self.chain.late_blocks());
+
+        self
+    }
+
     pub async fn test_get_lighthouse_staking(self) -> Self {
         let result = self.client.get_lighthouse_staking().await.unwrap();
 
@@ -2144,18 +2358,39 @@ async fn lighthouse_endpoints() {
         .test_get_lighthouse_validator_inclusion_global()
         .compat()
         .await
+        .test_get_lighthouse_validator_rewards()
+        .compat()
+        .await
+        .test_get_lighthouse_block_rewards()
+        .compat()
+        .await
         .test_get_lighthouse_eth1_syncing()
         .compat()
         .await
+        .test_get_lighthouse_eth1_voting()
+        .compat()
+        .await
         .test_get_lighthouse_eth1_block_cache()
         .compat()
         .await
         .test_get_lighthouse_eth1_deposit_cache()
         .compat()
         .await
+        .test_get_lighthouse_eth1_deposit_snapshot()
+        .compat()
+        .await
         .test_get_lighthouse_beacon_states_ssz()
         .compat()
         .await
+        .test_get_lighthouse_beacon_states_proof()
+        .compat()
+        .await
+        .test_get_lighthouse_gossip_trace()
+        .compat()
+        .await
+        .test_get_lighthouse_late_blocks()
+        .compat()
+        .await
         .test_get_lighthouse_staking()
         .compat()
         .await;
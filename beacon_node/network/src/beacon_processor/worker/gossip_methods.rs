@@ -1,11 +1,14 @@
 use crate::{metrics, service::NetworkMessage, sync::SyncMessage};
 use beacon_chain::{
-    attestation_verification::Error as AttnError, observed_operations::ObservationOutcome,
+    attestation_verification::Error as AttnError,
+    gossip_trace::{GossipMessageKind, GossipTrace, GossipValidationOutcome},
+    observed_operations::ObservationOutcome,
     BeaconChainError, BeaconChainTypes, BlockError, ForkChoiceError,
 };
 use eth2_libp2p::{MessageAcceptance, MessageId, PeerAction, PeerId};
 use slog::{debug, error, info, trace, warn};
 use ssz::Encode;
+use std::time::Duration;
 use types::{
     Attestation, AttesterSlashing, Hash256, ProposerSlashing, SignedAggregateAndProof,
     SignedBeaconBlock, SignedVoluntaryExit, SubnetId,
@@ -33,6 +36,15 @@ impl<T: BeaconChainTypes> Worker<T> {
         propagation_source: PeerId,
         validation_result: MessageAcceptance,
     ) {
+        self.update_gossip_trace(|trace| {
+            let outcome = match validation_result {
+                MessageAcceptance::Accept => GossipValidationOutcome::Accept,
+                MessageAcceptance::Ignore => GossipValidationOutcome::Ignore,
+                MessageAcceptance::Reject => GossipValidationOutcome::Reject,
+            };
+            trace.on_validation_result(&message_id, outcome)
+        });
+
         self.send_network_message(NetworkMessage::ValidationResult {
             propagation_source,
             message_id,
@@ -40,6 +52,13 @@ impl<T: BeaconChainTypes> Worker<T> {
         })
     }
 
+    /// Applies `update` to the gossip trace, if gossip tracing is enabled.
+    fn update_gossip_trace<F: FnOnce(&mut GossipTrace)>(&self, update: F) {
+        if let Some(gossip_trace) = &self.chain.gossip_trace {
+            update(&mut gossip_trace.lock())
+        }
+    }
+
     /* Processing functions */
 
     /// Process the unaggregated attestation received from the gossip network and:
@@ -49,6 +68,7 @@ impl<T: BeaconChainTypes> Worker<T> {
     /// - Attempt to add it to the naive aggregation pool.
     ///
     /// Raises a log if there are errors.
+    #[allow(clippy::too_many_arguments)]
     pub fn process_gossip_attestation(
         self,
         message_id: MessageId,
@@ -56,9 +76,21 @@ impl<T: BeaconChainTypes> Worker<T> {
         attestation: Attestation<T::EthSpec>,
         subnet_id: SubnetId,
         should_import: bool,
+        seen_timestamp: Duration,
     ) {
         let beacon_block_root = attestation.data.beacon_block_root;
 
+        self.update_gossip_trace(|trace| {
+            trace.on_received(
+                GossipMessageKind::Attestation,
+                &message_id,
+                &peer_id,
+                seen_timestamp,
+                attestation.data.slot,
+                beacon_block_root,
+            )
+        });
+
         let attestation = match self
             .chain
             .verify_unaggregated_attestation_for_gossip(attestation, Some(subnet_id))
@@ -78,7 +110,11 @@ impl<T: BeaconChainTypes> Worker<T> {
 
         // Indicate to the `Network` service that this message is valid and can be
         // propagated on the gossip network.
-        self.propagate_validation_result(message_id, peer_id.clone(), MessageAcceptance::Accept);
+        self.propagate_validation_result(
+            message_id.clone(),
+            peer_id.clone(),
+            MessageAcceptance::Accept,
+        );
 
         if !should_import {
             return;
@@ -106,8 +142,15 @@ impl<T: BeaconChainTypes> Worker<T> {
                 ),
             }
         }
+        self.update_gossip_trace(|trace| trace.on_fork_choice(&message_id));
 
-        if let Err(e) = self.chain.add_to_naive_aggregation_pool(attestation) {
+        let result = self.chain.add_to_naive_aggregation_pool(attestation);
+        self.update_gossip_trace(|trace| {
+            let result = result.as_ref().map(|_| ()).map_err(|e| format!("{:?}", e));
+            trace.on_import(&message_id, result)
+        });
+
+        if let Err(e) = result {
             debug!(
                 self.log,
                 "Attestation invalid for agg pool";
@@ -132,9 +175,21 @@ impl<T: BeaconChainTypes> Worker<T> {
         message_id: MessageId,
         peer_id: PeerId,
         aggregate: SignedAggregateAndProof<T::EthSpec>,
+        seen_timestamp: Duration,
     ) {
         let beacon_block_root = aggregate.message.aggregate.data.beacon_block_root;
 
+        self.update_gossip_trace(|trace| {
+            trace.on_received(
+                GossipMessageKind::Aggregate,
+                &message_id,
+                &peer_id,
+                seen_timestamp,
+                aggregate.message.aggregate.data.slot,
+                beacon_block_root,
+            )
+        });
+
         let aggregate = match self
             .chain
             .verify_aggregated_attestation_for_gossip(aggregate)
@@ -155,7 +210,11 @@ impl<T: BeaconChainTypes> Worker<T> {
 
         // Indicate to the `Network` service that this message is valid and can be
         // propagated on the gossip network.
-        self.propagate_validation_result(message_id, peer_id.clone(), MessageAcceptance::Accept);
+        self.propagate_validation_result(
+            message_id.clone(),
+            peer_id.clone(),
+            MessageAcceptance::Accept,
+        );
 
         metrics::inc_counter(&metrics::BEACON_PROCESSOR_AGGREGATED_ATTESTATION_VERIFIED_TOTAL);
 
@@ -179,8 +238,15 @@ impl<T: BeaconChainTypes> Worker<T> {
                 ),
             }
         }
+        self.update_gossip_trace(|trace| trace.on_fork_choice(&message_id));
+
+        let result = self.chain.add_to_block_inclusion_pool(aggregate);
+        self.update_gossip_trace(|trace| {
+            let result = result.as_ref().map(|_| ()).map_err(|e| format!("{:?}", e));
+            trace.on_import(&message_id, result)
+        });
 
-        if let Err(e) = self.chain.add_to_block_inclusion_pool(aggregate) {
+        if let Err(e) = result {
             debug!(
                 self.log,
                 "Attestation invalid for op pool";
@@ -205,7 +271,19 @@ impl<T: BeaconChainTypes> Worker<T> {
         message_id: MessageId,
         peer_id: PeerId,
         block: SignedBeaconBlock<T::EthSpec>,
+        seen_timestamp: Duration,
     ) {
+        self.update_gossip_trace(|trace| {
+            trace.on_received(
+                GossipMessageKind::Block,
+                &message_id,
+                &peer_id,
+                seen_timestamp,
+                block.slot(),
+                block.canonical_root(),
+            )
+        });
+
         let verified_block = match self.chain.verify_block_for_gossip(block) {
             Ok(verified_block) => {
                 info!(
@@ -215,7 +293,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                     "hash" => %verified_block.block_root
                 );
                 self.propagate_validation_result(
-                    message_id,
+                    message_id.clone(),
                     peer_id.clone(),
                     MessageAcceptance::Accept,
                 );
@@ -265,8 +343,20 @@ impl<T: BeaconChainTypes> Worker<T> {
 
         metrics::inc_counter(&metrics::BEACON_PROCESSOR_GOSSIP_BLOCK_VERIFIED_TOTAL);
 
+        self.chain.observe_gossip_block_arrival(
+            verified_block.block_root,
+            verified_block.block.slot(),
+            seen_timestamp,
+        );
+
         let block = Box::new(verified_block.block.clone());
-        match self.chain.process_block(verified_block) {
+        let result = self.chain.process_block(verified_block);
+        self.update_gossip_trace(|trace| {
+            let result = result.as_ref().map(|_| ()).map_err(|e| format!("{:?}", e));
+            trace.on_import(&message_id, result)
+        });
+
+        match result {
             Ok(_block_root) => {
                 metrics::inc_counter(&metrics::BEACON_PROCESSOR_GOSSIP_BLOCK_IMPORTED_TOTAL);
 
@@ -292,6 +382,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                         "location" => "block gossip"
                     ),
                 }
+                self.update_gossip_trace(|trace| trace.on_fork_choice(&message_id));
             }
             Err(BlockError::ParentUnknown { .. }) => {
                 // Inform the sync manager to find parents for this block
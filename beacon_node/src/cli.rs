@@ -255,6 +255,46 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("5053")
                 .takes_value(true),
         )
+        /* Event sink related arguments */
+        .arg(
+            Arg::with_name("event-sink")
+                .long("event-sink")
+                .value_name("SINK")
+                .help("Publish every chain event as a line of JSON to SINK. May be supplied \
+                    multiple times. SINK is one of file:<PATH> (an append-only NDJSON file), \
+                    unix:<PATH> (a Unix domain socket) or an http:// or https:// URL which \
+                    receives each event as a POST request.")
+                .multiple(true)
+                .number_of_values(1)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("event-sink-buffer-size")
+                .long("event-sink-buffer-size")
+                .value_name("EVENTS")
+                .help("The maximum number of events buffered for each event sink. Events are \
+                    dropped while a sink's buffer is full.")
+                .default_value("1024")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("event-sink-max-retries")
+                .long("event-sink-max-retries")
+                .value_name("RETRIES")
+                .help("The number of times a failed write to an event sink is retried before \
+                    the event is discarded.")
+                .default_value("3")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("event-sink-retry-delay")
+                .long("event-sink-retry-delay")
+                .value_name("MILLIS")
+                .help("The delay in milliseconds before the first retry of a failed write to an \
+                    event sink. Each subsequent retry waits an additional MILLIS.")
+                .default_value("500")
+                .takes_value(true),
+        )
 
         /*
          * Standard staking flags
@@ -296,14 +336,49 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         .arg(
             Arg::with_name("eth1-endpoints")
                 .long("eth1-endpoints")
-                .value_name("HTTP-ENDPOINTS")
+                .value_name("ENDPOINTS")
                 .conflicts_with("eth1-endpoint")
                 .help("One or more comma-delimited server endpoints for web3 connection. \
                        If multiple endpoints are given the endpoints are used as fallback in the \
-                       given order. Also enables the --eth1 flag. \
+                       given order. HTTP (http://), WebSocket (ws://) and IPC (ipc:///path) \
+                       endpoints are supported. WebSocket and IPC endpoints push new blocks \
+                       instead of being polled. Secure WebSocket (wss://) is not supported, so \
+                       credentials are refused for ws:// endpoints other than localhost. Also \
+                       enables the --eth1 flag. \
                        Defaults to http://127.0.0.1:8545.")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("eth1-jwt-secrets")
+                .long("eth1-jwt-secrets")
+                .value_name("PATHS")
+                .help("One or more comma-delimited paths to files containing hex-encoded secrets, \
+                       in the same order as the eth1 endpoints. Each secret is used to sign an \
+                       HS256 JWT which is sent as a bearer token with every request to its \
+                       endpoint. Leave an entry empty for endpoints that do not use a JWT.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("eth1-headers")
+                .long("eth1-headers")
+                .value_name("HEADERS")
+                .help("One or more comma-delimited lists of HTTP headers, in the same order as \
+                       the eth1 endpoints. Each list is semicolon-delimited, with headers of the \
+                       form `Name: value`, e.g., `Authorization: Bearer TOKEN;X-Api-Key: KEY`. \
+                       Credentials may also be given as userinfo in the endpoint URL.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("eth1-headers-files")
+                .long("eth1-headers-files")
+                .value_name("PATHS")
+                .help("One or more comma-delimited paths to files containing HTTP headers, in \
+                       the same order as the eth1 endpoints. Each file has one header of the form \
+                       `Name: value` per line, and is read for every request so that the headers \
+                       may be rotated without a restart. These headers take precedence over all \
+                       others. Leave an entry empty for endpoints without a headers file.")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("eth1-blocks-per-log-query")
                 .long("eth1-blocks-per-log-query")
@@ -313,6 +388,16 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("1000")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("eth1-deposit-snapshot")
+                .long("eth1-deposit-snapshot")
+                .value_name("PATH")
+                .help("A JSON file containing a snapshot of the finalized deposit tree, as \
+                       served by /lighthouse/eth1/deposit_snapshot. When the eth1 cache is empty, \
+                       it is initialized from the snapshot and only deposit logs after the \
+                       snapshot are downloaded.")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("slots-per-restore-point")
                 .long("slots-per-restore-point")
@@ -322,6 +407,18 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                        [default: 2048 (mainnet) or 64 (minimal)]")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("hierarchy-exponents")
+                .long("hierarchy-exponents")
+                .value_name("EXPONENTS")
+                .help("Store freezer DB restore points as layered diffs rather than full states. \
+                       Takes a comma-separated list of exponents, with each layer storing a state \
+                       every 2^exponent slots and the first exponent setting the slots per restore \
+                       point. Cannot be changed after initialization. \
+                       Recommended: 5,9,11,13,16,18,21")
+                .conflicts_with("slots-per-restore-point")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("block-cache-size")
                 .long("block-cache-size")
@@ -376,6 +473,27 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .value_name("NUM_SLOTS")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("gossip-trace")
+                .long("gossip-trace")
+                .help(
+                    "Record when each block and attestation was received on gossip, from which \
+                    peer, and how long it took to validate and import. The trace is held in \
+                    memory and served by the /lighthouse/gossip/trace HTTP API endpoint."
+                )
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("gossip-trace-size")
+                .long("gossip-trace-size")
+                .help(
+                    "The maximum number of messages retained by --gossip-trace. When full, the \
+                    oldest messages are dropped. [default: 16384]"
+                )
+                .value_name("NUM_MESSAGES")
+                .requires("gossip-trace")
+                .takes_value(true)
+        )
         /*
          * Slasher.
          */
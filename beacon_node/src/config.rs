@@ -1,18 +1,23 @@
 use beacon_chain::builder::PUBKEY_CACHE_FILENAME;
+use beacon_chain::gossip_trace::DEFAULT_GOSSIP_TRACE_CAPACITY;
 use clap::ArgMatches;
 use clap_utils::BAD_TESTNET_DIR_MESSAGE;
 use client::{ClientConfig, ClientGenesis};
 use directory::{DEFAULT_BEACON_NODE_DIR, DEFAULT_NETWORK_DIR, DEFAULT_ROOT_DIR};
 use eth2_libp2p::{multiaddr::Protocol, Enr, Multiaddr, NetworkConfig, PeerIdSerialized};
 use eth2_testnet_config::Eth2TestnetConfig;
+use genesis::Eth1Endpoint;
 use slog::{info, warn, Logger};
 use std::cmp;
 use std::cmp::max;
 use std::fs;
 use std::net::{IpAddr, Ipv4Addr, ToSocketAddrs};
 use std::net::{TcpListener, UdpSocket};
-use std::path::PathBuf;
-use types::{ChainSpec, Checkpoint, Epoch, EthSpec, Hash256, GRAFFITI_BYTES_LEN};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use types::{
+    ChainSpec, Checkpoint, DepositTreeSnapshot, Epoch, EthSpec, Hash256, GRAFFITI_BYTES_LEN,
+};
 
 /// Gets the fully-initialized global client.
 ///
@@ -174,6 +179,34 @@ pub fn get_config<E: EthSpec>(
             .map_err(|_| "ws-port is not a valid u16.")?;
     }
 
+    /*
+     * Event sinks
+     */
+
+    if let Some(sinks) = cli_args.values_of("event-sink") {
+        client_config.event_sink.targets = sinks.map(str::parse).collect::<Result<_, _>>()?;
+    }
+
+    if let Some(buffer_size) = cli_args.value_of("event-sink-buffer-size") {
+        client_config.event_sink.buffer_size = buffer_size
+            .parse::<usize>()
+            .map_err(|_| "event-sink-buffer-size is not a valid integer.")?;
+    }
+
+    if let Some(max_retries) = cli_args.value_of("event-sink-max-retries") {
+        client_config.event_sink.max_retries = max_retries
+            .parse::<usize>()
+            .map_err(|_| "event-sink-max-retries is not a valid integer.")?;
+    }
+
+    if let Some(retry_delay) = cli_args.value_of("event-sink-retry-delay") {
+        client_config.event_sink.retry_delay = Duration::from_millis(
+            retry_delay
+                .parse::<u64>()
+                .map_err(|_| "event-sink-retry-delay is not a valid integer.")?,
+        );
+    }
+
     /*
      * Eth1
      */
@@ -195,10 +228,40 @@ pub fn get_config<E: EthSpec>(
     // Defines the URL to reach the eth1 node.
     if let Some(val) = cli_args.value_of("eth1-endpoint") {
         client_config.sync_eth1_chain = true;
-        client_config.eth1.endpoints = vec![val.to_string()];
+        client_config.eth1.endpoints = vec![val.into()];
     } else if let Some(val) = cli_args.value_of("eth1-endpoints") {
         client_config.sync_eth1_chain = true;
-        client_config.eth1.endpoints = val.split(',').map(String::from).collect();
+        client_config.eth1.endpoints = val.split(',').map(Into::into).collect();
+    }
+
+    // Each of the following takes a comma-delimited list, in the same order as the endpoints.
+    if let Some(val) = cli_args.value_of("eth1-jwt-secrets") {
+        for (endpoint, path) in per_endpoint_values(&mut client_config.eth1.endpoints, val)? {
+            if !path.is_empty() {
+                endpoint.jwt_secret_path = Some(PathBuf::from(path));
+            }
+        }
+    }
+
+    if let Some(val) = cli_args.value_of("eth1-headers") {
+        for (endpoint, headers) in per_endpoint_values(&mut client_config.eth1.endpoints, val)? {
+            endpoint.headers = parse_headers(headers)?;
+        }
+    }
+
+    if let Some(val) = cli_args.value_of("eth1-headers-files") {
+        for (endpoint, path) in per_endpoint_values(&mut client_config.eth1.endpoints, val)? {
+            if !path.is_empty() {
+                endpoint.headers_path = Some(PathBuf::from(path));
+            }
+        }
+    }
+
+    // Fail early (rather than on every request) if the authentication is invalid.
+    for endpoint in &client_config.eth1.endpoints {
+        endpoint
+            .auth_headers()
+            .map_err(|e| format!("Invalid eth1 endpoint authentication: {}", e))?;
     }
 
     if let Some(val) = cli_args.value_of("eth1-blocks-per-log-query") {
@@ -207,11 +270,19 @@ pub fn get_config<E: EthSpec>(
             .map_err(|_| "eth1-blocks-per-log-query is not a valid integer".to_string())?;
     }
 
+    if let Some(path) = cli_args.value_of("eth1-deposit-snapshot") {
+        client_config.eth1.deposit_snapshot = Some(load_deposit_snapshot(Path::new(path))?);
+    }
+
     if let Some(freezer_dir) = cli_args.value_of("freezer-dir") {
         client_config.freezer_db_path = Some(PathBuf::from(freezer_dir));
     }
 
-    if let Some(slots_per_restore_point) = cli_args.value_of("slots-per-restore-point") {
+    if let Some(hierarchy_exponents) = cli_args.value_of("hierarchy-exponents") {
+        let hierarchy_config: store::hdiff::HierarchyConfig = hierarchy_exponents.parse()?;
+        client_config.store.slots_per_restore_point = 1 << hierarchy_config.exponents[0];
+        client_config.store.hierarchy_config = Some(hierarchy_config);
+    } else if let Some(slots_per_restore_point) = cli_args.value_of("slots-per-restore-point") {
         client_config.store.slots_per_restore_point = slots_per_restore_point
             .parse()
             .map_err(|_| "slots-per-restore-point is not a valid integer".to_string())?;
@@ -360,6 +431,18 @@ pub fn get_config<E: EthSpec>(
         };
     }
 
+    if cli_args.is_present("gossip-trace") {
+        let capacity = match cli_args.value_of("gossip-trace-size") {
+            Some(size) => size
+                .parse::<usize>()
+                .ok()
+                .filter(|size| *size > 0)
+                .ok_or_else(|| "gossip-trace-size must be a positive integer".to_string())?,
+            None => DEFAULT_GOSSIP_TRACE_CAPACITY,
+        };
+        client_config.chain.gossip_trace_capacity = Some(capacity);
+    }
+
     if cli_args.is_present("slasher") {
         let slasher_dir = if let Some(slasher_dir) = cli_args.value_of("slasher-dir") {
             PathBuf::from(slasher_dir)
@@ -606,6 +689,58 @@ pub fn get_data_dir(cli_args: &ArgMatches) -> PathBuf {
         .unwrap_or_else(|| PathBuf::from("."))
 }
 
+/// Pairs each endpoint with its entry in the comma-delimited `values`.
+///
+/// Returns an error if there are more values than endpoints.
+fn per_endpoint_values<'a>(
+    endpoints: &'a mut [Eth1Endpoint],
+    values: &'a str,
+) -> Result<impl Iterator<Item = (&'a mut Eth1Endpoint, &'a str)>, String> {
+    let values = values.split(',').collect::<Vec<_>>();
+    if values.len() > endpoints.len() {
+        return Err(format!(
+            "{} values were given for {} eth1 endpoints",
+            values.len(),
+            endpoints.len()
+        ));
+    }
+    Ok(endpoints.iter_mut().zip(values))
+}
+
+/// Parses a `;`-delimited list of headers, each of the form `Name: value`.
+fn parse_headers(headers: &str) -> Result<Vec<(String, String)>, String> {
+    headers
+        .split(';')
+        .filter(|header| !header.trim().is_empty())
+        .map(Eth1Endpoint::parse_header)
+        .collect()
+}
+
+/// Reads a `DepositTreeSnapshot` from the JSON file at `path`.
+///
+/// Accepts either the snapshot itself or the response of the
+/// `/lighthouse/eth1/deposit_snapshot` endpoint.
+fn load_deposit_snapshot(path: &Path) -> Result<DepositTreeSnapshot, String> {
+    let file = fs::File::open(path)
+        .map_err(|e| format!("Unable to open deposit snapshot {:?}: {:?}", path, e))?;
+    let mut json: serde_json::Value = serde_json::from_reader(file)
+        .map_err(|e| format!("Unable to parse deposit snapshot {:?}: {:?}", path, e))?;
+    if let Some(data) = json.get_mut("data") {
+        json = data.take();
+    }
+
+    let snapshot: DepositTreeSnapshot = serde_json::from_value(json)
+        .map_err(|e| format!("Unable to parse deposit snapshot {:?}: {:?}", path, e))?;
+    if !snapshot.is_valid() {
+        return Err(format!(
+            "Invalid deposit snapshot {:?}: deposit root does not match",
+            path
+        ));
+    }
+
+    Ok(snapshot)
+}
+
 /// Try to parse the eth2 testnet config from the `network`, `testnet-dir` flags in that order.
 /// Returns the default hardcoded testnet if neither flags are set.
 pub fn get_eth2_testnet_config(cli_args: &ArgMatches) -> Result<Eth2TestnetConfig, String> {
@@ -121,9 +121,10 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
             builder.no_eth1_backend()?
         };
 
-        let (builder, _events) = builder
-            .system_time_slot_clock()?
-            .tee_event_handler(client_config.websocket_server.clone())?;
+        let (builder, _events) = builder.system_time_slot_clock()?.tee_event_handler(
+            client_config.websocket_server.clone(),
+            client_config.event_sink.clone(),
+        )?;
 
         // Inject the executor into the discv5 network config.
         let discv5_executor = Discv5Executor(executor);
@@ -1,3 +1,4 @@
+use crate::hdiff::HierarchyConfig;
 use crate::{DBColumn, Error, StoreItem};
 use serde_derive::{Deserialize, Serialize};
 use ssz::{Decode, Encode};
@@ -18,6 +19,10 @@ pub struct StoreConfig {
     pub compact_on_init: bool,
     /// Whether to compact the database during database pruning.
     pub compact_on_prune: bool,
+    /// Layout of the hierarchical state diffs in the freezer database, if enabled.
+    ///
+    /// The frequency of the finest layer must equal `slots_per_restore_point`.
+    pub hierarchy_config: Option<HierarchyConfig>,
 }
 
 /// Variant of `StoreConfig` that gets written to disk. Contains immutable configuration params.
@@ -26,11 +31,21 @@ pub struct OnDiskStoreConfig {
     pub slots_per_restore_point: u64,
     // NOTE: redundant, see https://github.com/sigp/lighthouse/issues/1784
     pub _block_cache_size: usize,
+    /// Exponents of the freezer hierarchy, or empty if restore points are stored in full.
+    pub hierarchy_exponents: Vec<u8>,
+}
+
+/// Variant of `OnDiskStoreConfig` written by schema v2 and earlier.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct OnDiskStoreConfigV2 {
+    pub slots_per_restore_point: u64,
+    pub _block_cache_size: usize,
 }
 
 #[derive(Debug, Clone)]
 pub enum StoreConfigError {
     MismatchedSlotsPerRestorePoint { config: u64, on_disk: u64 },
+    MismatchedHierarchyExponents { config: Vec<u8>, on_disk: Vec<u8> },
 }
 
 impl Default for StoreConfig {
@@ -41,6 +56,7 @@ impl Default for StoreConfig {
             block_cache_size: DEFAULT_BLOCK_CACHE_SIZE,
             compact_on_init: false,
             compact_on_prune: true,
+            hierarchy_config: None,
         }
     }
 }
@@ -50,9 +66,18 @@ impl StoreConfig {
         OnDiskStoreConfig {
             slots_per_restore_point: self.slots_per_restore_point,
             _block_cache_size: DEFAULT_BLOCK_CACHE_SIZE,
+            hierarchy_exponents: self.hierarchy_exponents(),
         }
     }
 
+    /// The exponents of the freezer hierarchy, or an empty list if it is disabled.
+    pub fn hierarchy_exponents(&self) -> Vec<u8> {
+        self.hierarchy_config
+            .as_ref()
+            .map(|config| config.exponents.clone())
+            .unwrap_or_default()
+    }
+
     pub fn check_compatibility(
         &self,
         on_disk_config: &OnDiskStoreConfig,
@@ -63,6 +88,12 @@ impl StoreConfig {
                 on_disk: on_disk_config.slots_per_restore_point,
             });
         }
+        if self.hierarchy_exponents() != on_disk_config.hierarchy_exponents {
+            return Err(StoreConfigError::MismatchedHierarchyExponents {
+                config: self.hierarchy_exponents(),
+                on_disk: on_disk_config.hierarchy_exponents.clone(),
+            });
+        }
         Ok(())
     }
 }
@@ -80,3 +111,17 @@ impl StoreItem for OnDiskStoreConfig {
         Ok(Self::from_ssz_bytes(bytes)?)
     }
 }
+
+impl StoreItem for OnDiskStoreConfigV2 {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconMeta
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self::from_ssz_bytes(bytes)?)
+    }
+}
@@ -1,5 +1,6 @@
 use crate::chunked_vector::ChunkError;
 use crate::config::StoreConfigError;
+use crate::hdiff;
 use crate::hot_cold_store::HotColdDBError;
 use ssz::DecodeError;
 use types::{BeaconStateError, Hash256, Slot};
@@ -19,6 +20,7 @@ pub enum Error {
     NoContinuationData,
     SplitPointModified(Slot, Slot),
     ConfigError(StoreConfigError),
+    HierarchyError(hdiff::Error),
 }
 
 impl From<DecodeError> for Error {
@@ -57,6 +59,12 @@ impl From<StoreConfigError> for Error {
     }
 }
 
+impl From<hdiff::Error> for Error {
+    fn from(e: hdiff::Error) -> Error {
+        Error::HierarchyError(e)
+    }
+}
+
 #[derive(Debug)]
 pub struct DBError {
     pub message: String,
@@ -0,0 +1,619 @@
+//! Hierarchical diffs between `BeaconState`s, for storage in the freezer database.
+//!
+//! The same diffs are used to store epoch boundary states in the hot database against the split
+//! state, see `HotColdDB::store_hot_state`.
+//!
+//! Restore points are arranged into layers, each layer storing a state every `2^exponent` slots.
+//! States in the coarsest layer are stored as full snapshots, while states in every other layer
+//! are stored as a diff against the nearest state of the next coarsest layer. A state is
+//! reconstructed by applying at most one diff per layer to a snapshot, rather than by replaying
+//! blocks.
+//!
+//! The diffs operate on an `HDiffBuffer`, which is the SSZ encoding of a `PartialBeaconState` with
+//! the balances stored separately. The balances change at every epoch for almost all validators,
+//! so they are diffed numerically, whilst the rest of the state is diffed byte-by-byte.
+use crate::impls::beacon_state::StorageContainer;
+use crate::{DBColumn, Error as StoreError, PartialBeaconState, StoreItem};
+use serde_derive::{Deserialize, Serialize};
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use std::convert::TryInto;
+use std::str::FromStr;
+use types::{BeaconState, EthSpec, RegistryList, Slot};
+
+/// Patches are merged if they are separated by fewer than this many unchanged bytes, as the SSZ
+/// overhead of an additional patch is larger than the unchanged bytes.
+const MIN_PATCH_GAP: usize = 16;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// The exponents are empty, not strictly increasing or too large.
+    InvalidHierarchy,
+    /// A patch extends beyond the end of the target bytes.
+    InvalidBytesPatch {
+        offset: u64,
+        len: u64,
+        target_len: u64,
+    },
+    /// The balance deltas ended before all balances were updated.
+    BalancesDiffTooShort,
+    /// There were balance deltas left over after all balances were updated.
+    BalancesDiffTooLong,
+    /// A balance delta was encoded with more than 64 bits.
+    InvalidBalanceDelta,
+    /// The balances of a buffer exceed the validator registry limit.
+    InvalidBalancesLength,
+}
+
+/// The layout of the layers of the freezer database.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HierarchyConfig {
+    /// Each layer stores a state every `2^exponent` slots, from the finest layer to the coarsest.
+    pub exponents: Vec<u8>,
+}
+
+impl Default for HierarchyConfig {
+    /// Restore points every 32 slots, with layers from an epoch through to roughly a year of
+    /// mainnet slots.
+    fn default() -> Self {
+        HierarchyConfig {
+            exponents: vec![5, 9, 11, 13, 16, 18, 21],
+        }
+    }
+}
+
+impl FromStr for HierarchyConfig {
+    type Err = String;
+
+    /// Parse a comma-separated list of exponents, e.g. `5,9,11,13,16,18,21`.
+    fn from_str(s: &str) -> Result<Self, String> {
+        let exponents = s
+            .split(',')
+            .map(|exponent| {
+                exponent
+                    .trim()
+                    .parse()
+                    .map_err(|e| format!("invalid hierarchy exponent {:?}: {:?}", exponent, e))
+            })
+            .collect::<Result<Vec<u8>, _>>()?;
+
+        let config = HierarchyConfig { exponents };
+        config
+            .to_moduli()
+            .map_err(|e| format!("invalid hierarchy exponents: {:?}", e))?;
+        Ok(config)
+    }
+}
+
+impl HierarchyConfig {
+    /// Check the exponents and convert them to the slot frequency of each layer.
+    pub fn to_moduli(&self) -> Result<HierarchyModuli, Error> {
+        let valid = !self.exponents.is_empty()
+            && self.exponents.windows(2).all(|pair| pair[0] < pair[1])
+            && self.exponents.iter().all(|exponent| *exponent < 64);
+
+        if valid {
+            Ok(HierarchyModuli {
+                moduli: self
+                    .exponents
+                    .iter()
+                    .map(|exponent| 1 << exponent)
+                    .collect(),
+            })
+        } else {
+            Err(Error::InvalidHierarchy)
+        }
+    }
+}
+
+/// The slot frequency of each layer, from the finest layer to the coarsest.
+///
+/// Each modulus is a power of two and divides all the moduli that follow it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HierarchyModuli {
+    moduli: Vec<u64>,
+}
+
+/// How the state at a given slot is stored in the freezer database.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StorageStrategy {
+    /// Stored in full.
+    Snapshot,
+    /// Stored as a diff against the state at the given slot.
+    DiffFrom(Slot),
+    /// Not stored, and must be reconstructed by replaying blocks on the state at the given slot.
+    ReplayFrom(Slot),
+}
+
+impl HierarchyModuli {
+    /// The frequency of the finest layer, which is the frequency at which restore points are
+    /// stored.
+    pub fn restore_point_frequency(&self) -> u64 {
+        self.moduli[0]
+    }
+
+    pub fn num_layers(&self) -> usize {
+        self.moduli.len()
+    }
+
+    /// Determine how the state at `slot` is stored.
+    pub fn storage_strategy(&self, slot: Slot) -> StorageStrategy {
+        let slot = slot.as_u64();
+        let finest = self.moduli[0];
+        let coarsest = self.moduli[self.moduli.len() - 1];
+
+        if slot % coarsest == 0 {
+            StorageStrategy::Snapshot
+        } else if slot % finest != 0 {
+            StorageStrategy::ReplayFrom(Slot::new(slot / finest * finest))
+        } else {
+            // The state lies in the coarsest layer whose modulus divides its slot, and is diffed
+            // against the nearest prior state from the next coarsest layer.
+            let layer = self
+                .moduli
+                .iter()
+                .rposition(|modulus| slot % modulus == 0)
+                .unwrap_or(0);
+            let next_modulus = self.moduli[layer + 1];
+            StorageStrategy::DiffFrom(Slot::new(slot / next_modulus * next_modulus))
+        }
+    }
+}
+
+/// A state in the form which diffs are computed against and applied to.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct HDiffBuffer {
+    /// The SSZ bytes of a `PartialBeaconState` with empty balances, or of a `StorageContainer`
+    /// with empty balances for buffers of hot states.
+    state: Vec<u8>,
+    balances: Vec<u64>,
+}
+
+impl HDiffBuffer {
+    pub fn from_state<E: EthSpec>(state: &BeaconState<E>) -> Self {
+        let mut partial_state = PartialBeaconState::from_state_forgetful(state);
+        let balances = std::mem::take(&mut partial_state.balances).into();
+
+        HDiffBuffer {
+            state: partial_state.as_ssz_bytes(),
+            balances,
+        }
+    }
+
+    /// Convert the buffer into a `PartialBeaconState`, without its vector fields.
+    pub fn into_partial_state<E: EthSpec>(self) -> Result<PartialBeaconState<E>, StoreError> {
+        let mut partial_state = PartialBeaconState::from_ssz_bytes(&self.state)?;
+        partial_state.balances =
+            RegistryList::new(self.balances).map_err(|_| Error::InvalidBalancesLength)?;
+        Ok(partial_state)
+    }
+
+    /// Create a buffer from a full state, including its vector fields and committee caches.
+    pub fn from_hot_state<E: EthSpec>(state: &BeaconState<E>) -> Self {
+        let mut container = StorageContainer::new(state);
+        let balances = std::mem::take(&mut container.state.balances).into();
+
+        HDiffBuffer {
+            state: container.as_ssz_bytes(),
+            balances,
+        }
+    }
+
+    /// Convert a buffer created by `from_hot_state` back into a full state.
+    pub fn into_hot_state<E: EthSpec>(self) -> Result<BeaconState<E>, StoreError> {
+        let mut container = StorageContainer::<E>::from_ssz_bytes(&self.state)?;
+        container.state.balances =
+            RegistryList::new(self.balances).map_err(|_| Error::InvalidBalancesLength)?;
+        container.try_into()
+    }
+}
+
+/// The difference between two `HDiffBuffer`s.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct HDiff {
+    state_diff: BytesDiff,
+    balances_diff: BalancesDiff,
+}
+
+impl HDiff {
+    /// Compute the diff which transforms `source` into `target`.
+    pub fn compute(source: &HDiffBuffer, target: &HDiffBuffer) -> Self {
+        HDiff {
+            state_diff: BytesDiff::compute(&source.state, &target.state),
+            balances_diff: BalancesDiff::compute(&source.balances, &target.balances),
+        }
+    }
+
+    /// Transform `source` into the `target` this diff was computed with.
+    pub fn apply(&self, source: &mut HDiffBuffer) -> Result<(), Error> {
+        self.state_diff.apply(&mut source.state)?;
+        self.balances_diff.apply(&mut source.balances)
+    }
+}
+
+/// A list of byte ranges in which the target differs from the source.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct BytesDiff {
+    target_len: u64,
+    patches: Vec<BytesPatch>,
+}
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+struct BytesPatch {
+    offset: u64,
+    bytes: Vec<u8>,
+}
+
+impl BytesDiff {
+    pub fn compute(source: &[u8], target: &[u8]) -> Self {
+        let is_changed = |i: usize| source.get(i) != Some(&target[i]);
+        let mut patches = vec![];
+
+        let mut i = 0;
+        while i < target.len() {
+            if !is_changed(i) {
+                i += 1;
+                continue;
+            }
+
+            // Extend the patch until there is a long enough run of unchanged bytes.
+            let start = i;
+            let mut end = i + 1;
+            i += 1;
+            while i < target.len() && i - end < MIN_PATCH_GAP {
+                if is_changed(i) {
+                    end = i + 1;
+                }
+                i += 1;
+            }
+
+            patches.push(BytesPatch {
+                offset: start as u64,
+                bytes: target[start..end].to_vec(),
+            });
+        }
+
+        BytesDiff {
+            target_len: target.len() as u64,
+            patches,
+        }
+    }
+
+    pub fn apply(&self, source: &mut Vec<u8>) -> Result<(), Error> {
+        source.resize(self.target_len as usize, 0);
+
+        for patch in &self.patches {
+            let len = patch.bytes.len() as u64;
+            let range = patch
+                .offset
+                .checked_add(len)
+                .filter(|end| *end <= self.target_len)
+                .map(|end| patch.offset as usize..end as usize)
+                .ok_or(Error::InvalidBytesPatch {
+                    offset: patch.offset,
+                    len,
+                    target_len: self.target_len,
+                })?;
+            source[range].copy_from_slice(&patch.bytes);
+        }
+
+        Ok(())
+    }
+}
+
+/// The change in each balance between the source and the target.
+///
+/// Validators absent from the source are treated as having a zero balance. Each change is stored
+/// as a zig-zag LEB128 integer, as most changes are small relative to the balances themselves.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct BalancesDiff {
+    target_len: u64,
+    deltas: Vec<u8>,
+}
+
+impl BalancesDiff {
+    pub fn compute(source: &[u64], target: &[u64]) -> Self {
+        let mut deltas = vec![];
+
+        for (i, balance) in target.iter().enumerate() {
+            let source_balance = source.get(i).copied().unwrap_or(0);
+            let delta = balance.wrapping_sub(source_balance) as i64;
+
+            // Zig-zag encoding maps small negative numbers to small positive numbers.
+            let mut value = ((delta << 1) ^ (delta >> 63)) as u64;
+            loop {
+                let byte = (value & 0x7f) as u8;
+                value >>= 7;
+                if value == 0 {
+                    deltas.push(byte);
+                    break;
+                } else {
+                    deltas.push(byte | 0x80);
+                }
+            }
+        }
+
+        BalancesDiff {
+            target_len: target.len() as u64,
+            deltas,
+        }
+    }
+
+    pub fn apply(&self, source: &mut Vec<u64>) -> Result<(), Error> {
+        source.resize(self.target_len as usize, 0);
+        let mut bytes = self.deltas.iter();
+
+        for balance in source.iter_mut() {
+            let mut value = 0u64;
+            let mut shift = 0;
+            loop {
+                let byte = *bytes.next().ok_or(Error::BalancesDiffTooShort)?;
+                if shift >= 64 {
+                    return Err(Error::InvalidBalanceDelta);
+                }
+                value |= u64::from(byte & 0x7f) << shift;
+                shift += 7;
+                if byte & 0x80 == 0 {
+                    break;
+                }
+            }
+
+            let delta = ((value >> 1) as i64) ^ -((value & 1) as i64);
+            *balance = balance.wrapping_add(delta as u64);
+        }
+
+        if bytes.next().is_some() {
+            return Err(Error::BalancesDiffTooLong);
+        }
+
+        Ok(())
+    }
+}
+
+impl StoreItem for HDiffBuffer {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconStateSnapshot
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        Ok(Self::from_ssz_bytes(bytes)?)
+    }
+}
+
+impl StoreItem for HDiff {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconStateDiff
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        Ok(Self::from_ssz_bytes(bytes)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::test_utils::{RngCore, SeedableRng, TestRandom, XorShiftRng};
+    use types::MinimalEthSpec;
+
+    fn strategies(config: &HierarchyConfig, slots: &[u64]) -> Vec<StorageStrategy> {
+        let moduli = config.to_moduli().unwrap();
+        slots
+            .iter()
+            .map(|slot| moduli.storage_strategy(Slot::new(*slot)))
+            .collect()
+    }
+
+    #[test]
+    fn invalid_hierarchies() {
+        for exponents in vec![vec![], vec![5, 5], vec![9, 5], vec![5, 64]] {
+            assert_eq!(
+                HierarchyConfig { exponents }.to_moduli(),
+                Err(Error::InvalidHierarchy)
+            );
+        }
+        assert!("5,9,x".parse::<HierarchyConfig>().is_err());
+        assert!("9,5".parse::<HierarchyConfig>().is_err());
+        assert_eq!(
+            "5, 9,11".parse::<HierarchyConfig>(),
+            Ok(HierarchyConfig {
+                exponents: vec![5, 9, 11]
+            })
+        );
+    }
+
+    #[test]
+    fn storage_strategies() {
+        use StorageStrategy::*;
+
+        let config = HierarchyConfig {
+            exponents: vec![1, 3, 5],
+        };
+        assert_eq!(
+            strategies(
+                &config,
+                &[0, 1, 2, 3, 4, 6, 8, 10, 14, 16, 24, 30, 32, 33, 34]
+            ),
+            vec![
+                Snapshot,
+                ReplayFrom(Slot::new(0)),
+                DiffFrom(Slot::new(0)),
+                ReplayFrom(Slot::new(2)),
+                DiffFrom(Slot::new(0)),
+                DiffFrom(Slot::new(0)),
+                DiffFrom(Slot::new(0)),
+                DiffFrom(Slot::new(8)),
+                DiffFrom(Slot::new(8)),
+                DiffFrom(Slot::new(0)),
+                DiffFrom(Slot::new(0)),
+                DiffFrom(Slot::new(24)),
+                Snapshot,
+                ReplayFrom(Slot::new(32)),
+                DiffFrom(Slot::new(32)),
+            ]
+        );
+
+        // A single layer stores only snapshots.
+        let config = HierarchyConfig { exponents: vec![3] };
+        assert_eq!(
+            strategies(&config, &[0, 4, 8, 12]),
+            vec![
+                Snapshot,
+                ReplayFrom(Slot::new(0)),
+                Snapshot,
+                ReplayFrom(Slot::new(8))
+            ]
+        );
+    }
+
+    #[test]
+    fn bytes_diff_round_trip() {
+        let mut rng = XorShiftRng::from_seed([42; 16]);
+        let mut source = vec![0; 1024];
+        rng.fill_bytes(&mut source);
+
+        let mut changed = source.clone();
+        changed[0] ^= 1;
+        changed[100] ^= 1;
+        changed[110] ^= 1;
+        changed[500] ^= 1;
+
+        let mut longer = changed.clone();
+        longer.extend_from_slice(&[1, 2, 3]);
+
+        for target in vec![
+            source.clone(),
+            changed,
+            longer,
+            source[..512].to_vec(),
+            vec![],
+        ] {
+            let diff = BytesDiff::compute(&source, &target);
+            let decoded = BytesDiff::from_ssz_bytes(&diff.as_ssz_bytes()).unwrap();
+            let mut buffer = source.clone();
+            decoded.apply(&mut buffer).unwrap();
+            assert_eq!(buffer, target);
+        }
+
+        // Nearby changes share a patch.
+        let mut target = source.clone();
+        target[100] ^= 1;
+        target[110] ^= 1;
+        assert_eq!(BytesDiff::compute(&source, &target).patches.len(), 1);
+    }
+
+    #[test]
+    fn invalid_bytes_patch() {
+        let diff = BytesDiff {
+            target_len: 4,
+            patches: vec![BytesPatch {
+                offset: 2,
+                bytes: vec![1, 2, 3],
+            }],
+        };
+        assert_eq!(
+            diff.apply(&mut vec![0; 4]),
+            Err(Error::InvalidBytesPatch {
+                offset: 2,
+                len: 3,
+                target_len: 4
+            })
+        );
+    }
+
+    #[test]
+    fn balances_diff_round_trip() {
+        let source = vec![32_000_000_000, 31_999_999_000, 0, u64::max_value(), 1];
+        let targets = vec![
+            source.clone(),
+            vec![32_000_001_000, 31_000_000_000, 1, 0, u64::max_value()],
+            vec![
+                32_000_000_000,
+                31_999_999_000,
+                0,
+                u64::max_value(),
+                1,
+                32_000_000_000,
+            ],
+            vec![16_000_000_000],
+            vec![],
+        ];
+
+        for target in targets {
+            let diff = BalancesDiff::compute(&source, &target);
+            let decoded = BalancesDiff::from_ssz_bytes(&diff.as_ssz_bytes()).unwrap();
+            let mut buffer = source.clone();
+            decoded.apply(&mut buffer).unwrap();
+            assert_eq!(buffer, target);
+        }
+
+        // Small changes are encoded compactly.
+        let target = source
+            .iter()
+            .map(|balance| balance.wrapping_add(10))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            BalancesDiff::compute(&source, &target).deltas.len(),
+            source.len()
+        );
+    }
+
+    #[test]
+    fn invalid_balances_diff() {
+        let diff = BalancesDiff {
+            target_len: 2,
+            deltas: vec![2],
+        };
+        assert_eq!(diff.apply(&mut vec![]), Err(Error::BalancesDiffTooShort));
+
+        let diff = BalancesDiff {
+            target_len: 1,
+            deltas: vec![2, 2],
+        };
+        assert_eq!(diff.apply(&mut vec![]), Err(Error::BalancesDiffTooLong));
+
+        let diff = BalancesDiff {
+            target_len: 1,
+            deltas: vec![0xff; 11],
+        };
+        assert_eq!(diff.apply(&mut vec![]), Err(Error::InvalidBalanceDelta));
+    }
+
+    #[test]
+    fn state_diff_round_trip() {
+        let mut rng = XorShiftRng::from_seed([42; 16]);
+        let source_state = BeaconState::<MinimalEthSpec>::random_for_test(&mut rng);
+
+        let mut target_state = source_state.clone();
+        target_state.slot += 1;
+        for balance in target_state.balances.iter_mut() {
+            *balance = balance.wrapping_add(1000);
+        }
+        target_state
+            .balances
+            .push(32_000_000_000)
+            .expect("should push balance");
+
+        let source = HDiffBuffer::from_state(&source_state);
+        let target = HDiffBuffer::from_state(&target_state);
+
+        let diff = HDiff::compute(&source, &target);
+        let decoded = HDiff::from_store_bytes(&diff.as_store_bytes()).unwrap();
+        let mut buffer = source;
+        decoded.apply(&mut buffer).unwrap();
+
+        assert_eq!(buffer, target);
+        assert_eq!(
+            buffer.into_partial_state::<MinimalEthSpec>().unwrap(),
+            PartialBeaconState::from_state_forgetful(&target_state)
+        );
+    }
+}
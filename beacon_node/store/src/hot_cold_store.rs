@@ -3,6 +3,7 @@ use crate::chunked_vector::{
 };
 use crate::config::{OnDiskStoreConfig, StoreConfig};
 use crate::forwards_iter::HybridForwardsBlockRootsIterator;
+use crate::hdiff::{HDiff, HDiffBuffer, HierarchyModuli, StorageStrategy};
 use crate::impls::beacon_state::{get_full_state, store_full_state};
 use crate::iter::{ParentRootBlockIterator, StateRootsIterator};
 use crate::leveldb_store::BytesKey;
@@ -10,14 +11,14 @@ use crate::leveldb_store::LevelDB;
 use crate::memory_store::MemoryStore;
 use crate::metadata::{
     CompactionTimestamp, PruningCheckpoint, SchemaVersion, COMPACTION_TIMESTAMP_KEY, CONFIG_KEY,
-    CURRENT_SCHEMA_VERSION, PRUNING_CHECKPOINT_KEY, SCHEMA_VERSION_KEY, SPLIT_KEY,
+    CURRENT_SCHEMA_VERSION, HOT_DIFF_BASES_KEY, PRUNING_CHECKPOINT_KEY, SCHEMA_VERSION_KEY,
+    SPLIT_KEY,
 };
 use crate::metrics;
 use crate::{
-    get_key_for_col, DBColumn, Error, ItemStore, KeyValueStoreOp, PartialBeaconState, StoreItem,
-    StoreOp,
+    get_key_for_col, ColumnKeyIter, DBColumn, Error, ItemStore, KeyValueStore, KeyValueStoreOp,
+    PartialBeaconState, StoreItem, StoreOp,
 };
-use leveldb::iterator::LevelDBIterator;
 use lru::LruCache;
 use parking_lot::{Mutex, RwLock};
 use slog::{debug, error, info, trace, warn, Logger};
@@ -27,6 +28,7 @@ use state_processing::{
     per_block_processing, per_slot_processing, BlockProcessingError, BlockSignatureStrategy,
     SlotProcessingError,
 };
+use std::collections::HashSet;
 use std::convert::TryInto;
 use std::marker::PhantomData;
 use std::path::Path;
@@ -56,6 +58,8 @@ pub struct HotColdDB<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> {
     /// greater than or equal are in the hot DB.
     split: RwLock<Split>,
     config: StoreConfig,
+    /// Slot frequencies of the layers of the freezer hierarchy, if enabled.
+    hierarchy: Option<HierarchyModuli>,
     /// Cold database containing compact historical data.
     pub cold_db: Cold,
     /// Hot database containing duplicated but quick-to-access recent data.
@@ -64,6 +68,15 @@ pub struct HotColdDB<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> {
     pub hot_db: Hot,
     /// LRU cache of deserialized blocks. Updated whenever a block is loaded.
     block_cache: Mutex<LruCache<Hash256, SignedBeaconBlock<E>>>,
+    /// LRU cache of freezer hierarchy buffers, keyed by slot.
+    ///
+    /// Holds one buffer per layer so that consecutive restore points can be diffed without
+    /// reconstructing their base states.
+    diff_buffer_cache: Mutex<LruCache<Slot, HDiffBuffer>>,
+    /// Buffer of the most recently used base of the hot state diffs, keyed by state root.
+    ///
+    /// This is almost always the split state, which new hot state diffs are computed against.
+    hot_diff_base_cache: Mutex<Option<(Hash256, Arc<HDiffBuffer>)>>,
     /// Chain spec.
     spec: ChainSpec,
     /// Logger.
@@ -88,9 +101,15 @@ pub enum HotColdDBError {
     MissingStateToFreeze(Hash256),
     MissingRestorePointHash(u64),
     MissingRestorePoint(Hash256),
+    MissingStateSnapshot(Slot),
+    MissingStateDiff(Slot),
+    /// A state which should be reconstructed by replaying blocks was treated as a restore point.
+    NotAHierarchyRestorePoint(Slot),
     MissingColdStateSummary(Hash256),
     MissingHotStateSummary(Hash256),
     MissingEpochBoundaryState(Hash256),
+    MissingHotStateDiff(Hash256),
+    MissingHotDiffBase(Hash256),
     MissingSplitState(Hash256, Slot),
     HotStateSummaryError(BeaconStateError),
     RestorePointDecodeError(ssz::DecodeError),
@@ -102,6 +121,10 @@ pub enum HotColdDBError {
         slots_per_historical_root: u64,
         slots_per_epoch: u64,
     },
+    InvalidHierarchyRestorePoint {
+        slots_per_restore_point: u64,
+        hierarchy_restore_point: u64,
+    },
     RestorePointBlockHashError(BeaconStateError),
     IterationError {
         unexpected_key: BytesKey,
@@ -115,13 +138,17 @@ impl<E: EthSpec> HotColdDB<E, MemoryStore<E>, MemoryStore<E>> {
         log: Logger,
     ) -> Result<HotColdDB<E, MemoryStore<E>, MemoryStore<E>>, Error> {
         Self::verify_slots_per_restore_point(config.slots_per_restore_point)?;
+        let hierarchy = Self::verify_hierarchy_config(&config)?;
 
         let db = HotColdDB {
             split: RwLock::new(Split::default()),
             cold_db: MemoryStore::open(),
             hot_db: MemoryStore::open(),
             block_cache: Mutex::new(LruCache::new(config.block_cache_size)),
+            diff_buffer_cache: Mutex::new(LruCache::new(Self::diff_buffer_cache_size(&hierarchy))),
+            hot_diff_base_cache: Mutex::new(None),
             config,
+            hierarchy,
             spec,
             log,
             _phantom: PhantomData,
@@ -143,13 +170,17 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
         log: Logger,
     ) -> Result<HotColdDB<E, LevelDB<E>, LevelDB<E>>, Error> {
         Self::verify_slots_per_restore_point(config.slots_per_restore_point)?;
+        let hierarchy = Self::verify_hierarchy_config(&config)?;
 
         let db = HotColdDB {
             split: RwLock::new(Split::default()),
             cold_db: LevelDB::open(cold_path)?,
             hot_db: LevelDB::open(hot_path)?,
             block_cache: Mutex::new(LruCache::new(config.block_cache_size)),
+            diff_buffer_cache: Mutex::new(LruCache::new(Self::diff_buffer_cache_size(&hierarchy))),
+            hot_diff_base_cache: Mutex::new(None),
             config,
+            hierarchy,
             spec,
             log,
             _phantom: PhantomData,
@@ -202,26 +233,8 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
     }
 
     /// Return an iterator over the state roots of all temporary states.
-    pub fn iter_temporary_state_roots<'a>(
-        &'a self,
-    ) -> impl Iterator<Item = Result<Hash256, Error>> + 'a {
-        let column = DBColumn::BeaconStateTemporary;
-        let start_key =
-            BytesKey::from_vec(get_key_for_col(column.into(), Hash256::zero().as_bytes()));
-
-        let keys_iter = self.hot_db.keys_iter();
-        keys_iter.seek(&start_key);
-
-        keys_iter
-            .take_while(move |key| key.matches_column(column))
-            .map(move |bytes_key| {
-                bytes_key.remove_column(column).ok_or_else(|| {
-                    HotColdDBError::IterationError {
-                        unexpected_key: bytes_key,
-                    }
-                    .into()
-                })
-            })
+    pub fn iter_temporary_state_roots(&self) -> ColumnKeyIter {
+        self.hot_db.iter_column_keys(DBColumn::BeaconStateTemporary)
     }
 }
 
@@ -283,6 +296,9 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             self.store_cold_state(state_root, &state, &mut ops)?;
             self.cold_db.do_atomically(ops)
         } else {
+            // Hold the transaction lock until the state is committed, as required by
+            // `store_hot_state`.
+            let _txn_lock = self.hot_db.begin_rw_transaction();
             let mut ops: Vec<KeyValueStoreOp> = Vec::new();
             self.store_hot_state(state_root, state, &mut ops)?;
             self.hot_db.do_atomically(ops)
@@ -357,10 +373,12 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         self.hot_db
             .key_delete(DBColumn::BeaconStateSummary.into(), state_root.as_bytes())?;
 
-        // Delete the full state if it lies on an epoch boundary.
+        // Delete the full state or its diff if it lies on an epoch boundary.
         if slot % E::slots_per_epoch() == 0 {
             self.hot_db
                 .key_delete(DBColumn::BeaconState.into(), state_root.as_bytes())?;
+            self.hot_db
+                .key_delete(DBColumn::BeaconStateHotDiff.into(), state_root.as_bytes())?;
         }
 
         Ok(())
@@ -464,6 +482,12 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
                         let state_key =
                             get_key_for_col(DBColumn::BeaconState.into(), state_root.as_bytes());
                         key_value_batch.push(KeyValueStoreOp::DeleteKey(state_key));
+
+                        let diff_key = get_key_for_col(
+                            DBColumn::BeaconStateHotDiff.into(),
+                            state_root.as_bytes(),
+                        );
+                        key_value_batch.push(KeyValueStoreOp::DeleteKey(diff_key));
                     }
                 }
             }
@@ -504,21 +528,43 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     ///
     /// On an epoch boundary, store a full state. On an intermediate slot, store
     /// just a backpointer to the nearest epoch boundary.
+    ///
+    /// With the `tree-states` feature, epoch boundary states are instead stored as diffs against
+    /// the split state, which is always stored in full. The diff is computed against the split
+    /// state at the time of the call, so the caller must hold the hot database's
+    /// `begin_rw_transaction` lock until `ops` are committed, to prevent the split state being
+    /// deleted by a concurrent `migrate_database` in the meantime.
     pub fn store_hot_state(
         &self,
         state_root: &Hash256,
         state: &BeaconState<E>,
         ops: &mut Vec<KeyValueStoreOp>,
     ) -> Result<(), Error> {
-        // On the epoch boundary, store the full state.
+        // On the epoch boundary, store the full state or its diff.
         if state.slot % E::slots_per_epoch() == 0 {
-            trace!(
-                self.log,
-                "Storing full state on epoch boundary";
-                "slot" => state.slot.as_u64(),
-                "state_root" => format!("{:?}", state_root)
-            );
-            store_full_state(state_root, &state, ops)?;
+            if let Some((base_state_root, base_buffer)) = self.hot_diff_base(state_root)? {
+                trace!(
+                    self.log,
+                    "Storing state diff on epoch boundary";
+                    "slot" => state.slot.as_u64(),
+                    "state_root" => format!("{:?}", state_root),
+                    "base_state_root" => format!("{:?}", base_state_root)
+                );
+                let diff = HDiff::compute(&base_buffer, &HDiffBuffer::from_hot_state(state));
+                let hot_state_diff = HotStateDiff {
+                    base_state_root,
+                    diff,
+                };
+                ops.push(hot_state_diff.as_kv_store_op(*state_root));
+            } else {
+                trace!(
+                    self.log,
+                    "Storing full state on epoch boundary";
+                    "slot" => state.slot.as_u64(),
+                    "state_root" => format!("{:?}", state_root)
+                );
+                store_full_state(state_root, &state, ops)?;
+            }
         }
 
         // Store a summary of the state.
@@ -553,7 +599,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             epoch_boundary_state_root,
         }) = self.load_hot_state_summary(state_root)?
         {
-            let boundary_state = get_full_state(&self.hot_db, &epoch_boundary_state_root)?
+            let boundary_state = self
+                .load_hot_full_state(&epoch_boundary_state_root)?
                 .ok_or_else(|| {
                     HotColdDBError::MissingEpochBoundaryState(epoch_boundary_state_root)
                 })?;
@@ -574,6 +621,150 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         }
     }
 
+    /// Load an epoch boundary state from the hot database, whether it is stored in full or as a
+    /// diff.
+    fn load_hot_full_state(&self, state_root: &Hash256) -> Result<Option<BeaconState<E>>, Error> {
+        if let Some(state) = get_full_state(&self.hot_db, state_root)? {
+            return Ok(Some(state));
+        }
+
+        match self.hot_db.get::<HotStateDiff>(state_root)? {
+            Some(hot_state_diff) => self
+                .apply_hot_state_diff(&hot_state_diff)?
+                .into_hot_state()
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Apply a hot state diff to the buffer of its base state.
+    fn apply_hot_state_diff(&self, hot_state_diff: &HotStateDiff) -> Result<HDiffBuffer, Error> {
+        let base_state_root = hot_state_diff.base_state_root;
+        let base_buffer = self
+            .load_hot_diff_base(&base_state_root)?
+            .ok_or(HotColdDBError::MissingHotDiffBase(base_state_root))?;
+        let mut buffer = HDiffBuffer::clone(&base_buffer);
+        hot_state_diff.diff.apply(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Return the split state and its buffer if the epoch boundary state with `state_root` should
+    /// be stored as a diff against it.
+    ///
+    /// Diffs are only stored with the `tree-states` feature, and only against a split state which
+    /// is stored in full.
+    fn hot_diff_base(
+        &self,
+        state_root: &Hash256,
+    ) -> Result<Option<(Hash256, Arc<HDiffBuffer>)>, Error> {
+        let split_state_root = self.split.read().state_root;
+
+        if !TREE_STATES || split_state_root == *state_root {
+            return Ok(None);
+        }
+
+        Ok(self
+            .load_hot_diff_base(&split_state_root)?
+            .map(|buffer| (split_state_root, buffer)))
+    }
+
+    /// Load the buffer of a state which is stored in full in the hot database.
+    fn load_hot_diff_base(&self, state_root: &Hash256) -> Result<Option<Arc<HDiffBuffer>>, Error> {
+        if let Some((cached_state_root, buffer)) = &*self.hot_diff_base_cache.lock() {
+            if cached_state_root == state_root {
+                return Ok(Some(buffer.clone()));
+            }
+        }
+
+        let buffer = match get_full_state(&self.hot_db, state_root)? {
+            Some(state) => Arc::new(HDiffBuffer::from_hot_state(&state)),
+            None => return Ok(None),
+        };
+        *self.hot_diff_base_cache.lock() = Some((*state_root, buffer.clone()));
+
+        Ok(Some(buffer))
+    }
+
+    /// Store `new_split`, which is about to become the split state, in full if it is stored as a
+    /// diff, so that new hot state diffs can be computed against it.
+    fn store_hot_diff_base(
+        &self,
+        new_split_root: Hash256,
+        new_split: &BeaconState<E>,
+    ) -> Result<(), Error> {
+        if !self.hot_db.exists::<HotStateDiff>(&new_split_root)? {
+            return Ok(());
+        }
+
+        let mut ops = vec![];
+        store_full_state(&new_split_root, new_split, &mut ops)?;
+        let diff_key = get_key_for_col(
+            DBColumn::BeaconStateHotDiff.into(),
+            new_split_root.as_bytes(),
+        );
+        ops.push(KeyValueStoreOp::DeleteKey(diff_key));
+        self.hot_db.do_atomically(ops)
+    }
+
+    /// Determine which former split states must be kept in full after the split moves from
+    /// `old_split_root` to `new_split_root`, because they are still the base of a hot state diff.
+    ///
+    /// Diffs are never recomputed: a former split state is kept until the last diff against it is
+    /// deleted. The diffs of the states in `deleted_states` are ignored, as they are about to be
+    /// deleted. Deletions of the full states of the bases which are no longer needed are pushed to
+    /// `ops`.
+    fn prune_hot_diff_bases(
+        &self,
+        old_split_root: Hash256,
+        new_split_root: Hash256,
+        deleted_states: &HashSet<Hash256>,
+        ops: &mut Vec<KeyValueStoreOp>,
+    ) -> Result<HotDiffBases, Error> {
+        let mut referenced_bases = HashSet::new();
+        for state_root in self.hot_db.iter_column_keys(DBColumn::BeaconStateHotDiff) {
+            let state_root = state_root?;
+            if state_root == new_split_root || deleted_states.contains(&state_root) {
+                continue;
+            }
+
+            // The base state root is the first field of the diff, so there is no need to decode
+            // the diff itself.
+            let bytes = self
+                .hot_db
+                .get_bytes(DBColumn::BeaconStateHotDiff.into(), state_root.as_bytes())?
+                .ok_or(HotColdDBError::MissingHotStateDiff(state_root))?;
+            let base_state_root = bytes
+                .get(..Hash256::len_bytes())
+                .map(Hash256::from_slice)
+                .ok_or(HotColdDBError::MissingHotStateDiff(state_root))?;
+            referenced_bases.insert(base_state_root);
+        }
+
+        let mut bases = self.load_hot_diff_bases()?.unwrap_or_default();
+        bases.state_roots.push(old_split_root);
+        let (retained, pruned): (Vec<_>, Vec<_>) = bases
+            .state_roots
+            .into_iter()
+            .filter(|state_root| *state_root != new_split_root)
+            .partition(|state_root| referenced_bases.contains(state_root));
+
+        for state_root in pruned {
+            let state_key = get_key_for_col(DBColumn::BeaconState.into(), state_root.as_bytes());
+            ops.push(KeyValueStoreOp::DeleteKey(state_key));
+        }
+
+        debug!(
+            self.log,
+            "Pruning hot state diff bases";
+            "retained" => retained.len(),
+            "diff_count" => referenced_bases.len(),
+        );
+
+        Ok(HotDiffBases {
+            state_roots: retained,
+        })
+    }
+
     /// Store a pre-finalization state in the freezer database.
     ///
     /// Will log a warning and not store anything if the state does not lie on a restore point
@@ -601,10 +792,15 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             "state_root" => format!("{:?}", state_root)
         );
 
-        // 1. Convert to PartialBeaconState and store that in the DB.
-        let partial_state = PartialBeaconState::from_state_forgetful(state);
-        let op = partial_state.as_kv_store_op(*state_root);
-        ops.push(op);
+        // 1. Store the state as a diff or snapshot if the hierarchy is enabled, otherwise convert
+        // to PartialBeaconState and store that in the DB.
+        if let Some(hierarchy) = &self.hierarchy {
+            self.store_hierarchy_state(hierarchy, state, ops)?;
+        } else {
+            let partial_state = PartialBeaconState::from_state_forgetful(state);
+            let op = partial_state.as_kv_store_op(*state_root);
+            ops.push(op);
+        }
 
         // 2. Store updated vector entries.
         let db = &self.cold_db;
@@ -642,14 +838,88 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         }
     }
 
+    /// Store a restore point state as a snapshot or a diff, according to its layer.
+    fn store_hierarchy_state(
+        &self,
+        hierarchy: &HierarchyModuli,
+        state: &BeaconState<E>,
+        ops: &mut Vec<KeyValueStoreOp>,
+    ) -> Result<(), Error> {
+        let buffer = HDiffBuffer::from_state(state);
+        let key = Self::hierarchy_key(state.slot);
+
+        match hierarchy.storage_strategy(state.slot) {
+            StorageStrategy::Snapshot => {
+                ops.push(buffer.as_kv_store_op(key));
+            }
+            StorageStrategy::DiffFrom(from_slot) => {
+                let from_buffer = self.load_hierarchy_buffer(hierarchy, from_slot)?;
+                let diff = HDiff::compute(&from_buffer, &buffer);
+                ops.push(diff.as_kv_store_op(key));
+            }
+            StorageStrategy::ReplayFrom(_) => {
+                return Err(HotColdDBError::NotAHierarchyRestorePoint(state.slot).into());
+            }
+        }
+
+        self.diff_buffer_cache.lock().put(state.slot, buffer);
+
+        Ok(())
+    }
+
+    /// Load the buffer of a restore point state by applying diffs to its snapshot.
+    ///
+    /// Diffs for the states between the restore point and its snapshot are applied in turn, one
+    /// per layer of the hierarchy.
+    fn load_hierarchy_buffer(
+        &self,
+        hierarchy: &HierarchyModuli,
+        slot: Slot,
+    ) -> Result<HDiffBuffer, Error> {
+        if let Some(buffer) = self.diff_buffer_cache.lock().get(&slot) {
+            return Ok(buffer.clone());
+        }
+
+        let key = Self::hierarchy_key(slot);
+        let buffer = match hierarchy.storage_strategy(slot) {
+            StorageStrategy::Snapshot => self
+                .cold_db
+                .get(&key)?
+                .ok_or(HotColdDBError::MissingStateSnapshot(slot))?,
+            StorageStrategy::DiffFrom(from_slot) => {
+                let mut buffer = self.load_hierarchy_buffer(hierarchy, from_slot)?;
+                let diff: HDiff = self
+                    .cold_db
+                    .get(&key)?
+                    .ok_or(HotColdDBError::MissingStateDiff(slot))?;
+                diff.apply(&mut buffer)?;
+                buffer
+            }
+            StorageStrategy::ReplayFrom(_) => {
+                return Err(HotColdDBError::NotAHierarchyRestorePoint(slot).into());
+            }
+        };
+
+        self.diff_buffer_cache.lock().put(slot, buffer.clone());
+
+        Ok(buffer)
+    }
+
     /// Load a restore point state by its `state_root`.
     fn load_restore_point(&self, state_root: &Hash256) -> Result<BeaconState<E>, Error> {
-        let mut partial_state: PartialBeaconState<E> = self
+        let partial_state: PartialBeaconState<E> = self
             .cold_db
             .get(state_root)?
             .ok_or_else(|| HotColdDBError::MissingRestorePoint(*state_root))?;
 
-        // Fill in the fields of the partial state.
+        self.complete_restore_point(partial_state)
+    }
+
+    /// Fill in the vector fields of a restore point state from the freezer database.
+    fn complete_restore_point(
+        &self,
+        mut partial_state: PartialBeaconState<E>,
+    ) -> Result<BeaconState<E>, Error> {
         partial_state.load_block_roots(&self.cold_db, &self.spec)?;
         partial_state.load_state_roots(&self.cold_db, &self.spec)?;
         partial_state.load_historical_roots(&self.cold_db, &self.spec)?;
@@ -663,8 +933,16 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         &self,
         restore_point_index: u64,
     ) -> Result<BeaconState<E>, Error> {
-        let state_root = self.load_restore_point_hash(restore_point_index)?;
-        self.load_restore_point(&state_root)
+        if let Some(hierarchy) = &self.hierarchy {
+            let slot = Slot::new(restore_point_index * self.config.slots_per_restore_point);
+            let partial_state = self
+                .load_hierarchy_buffer(hierarchy, slot)?
+                .into_partial_state()?;
+            self.complete_restore_point(partial_state)
+        } else {
+            let state_root = self.load_restore_point_hash(restore_point_index)?;
+            self.load_restore_point(&state_root)
+        }
     }
 
     /// Load a frozen state that lies between restore points.
@@ -847,6 +1125,11 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     }
 
     /// Load the split point from disk.
+    /// Load the former split states which are kept as the bases of hot state diffs.
+    fn load_hot_diff_bases(&self) -> Result<Option<HotDiffBases>, Error> {
+        self.hot_db.get(&HOT_DIFF_BASES_KEY)
+    }
+
     fn load_split(&self) -> Result<Option<Split>, Error> {
         self.hot_db.get(&SPLIT_KEY)
     }
@@ -877,6 +1160,11 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         Hash256::from_low_u64_be(restore_point_index)
     }
 
+    /// Convert the slot of a freezer hierarchy snapshot or diff into a database key.
+    fn hierarchy_key(slot: Slot) -> Hash256 {
+        Hash256::from_low_u64_be(slot.as_u64())
+    }
+
     /// Load a frozen state's slot, given its root.
     fn load_cold_state_slot(&self, state_root: &Hash256) -> Result<Option<Slot>, Error> {
         Ok(self
@@ -934,6 +1222,32 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         }
     }
 
+    /// Check that the freezer hierarchy, if enabled, is valid and stores a restore point every
+    /// `slots_per_restore_point` slots.
+    fn verify_hierarchy_config(config: &StoreConfig) -> Result<Option<HierarchyModuli>, Error> {
+        config
+            .hierarchy_config
+            .as_ref()
+            .map(|hierarchy_config| {
+                let hierarchy = hierarchy_config.to_moduli()?;
+                if hierarchy.restore_point_frequency() == config.slots_per_restore_point {
+                    Ok(hierarchy)
+                } else {
+                    Err(HotColdDBError::InvalidHierarchyRestorePoint {
+                        slots_per_restore_point: config.slots_per_restore_point,
+                        hierarchy_restore_point: hierarchy.restore_point_frequency(),
+                    }
+                    .into())
+                }
+            })
+            .transpose()
+    }
+
+    /// The number of buffers to cache: one per layer of the freezer hierarchy.
+    fn diff_buffer_cache_size(hierarchy: &Option<HierarchyModuli>) -> usize {
+        hierarchy.as_ref().map_or(1, HierarchyModuli::num_layers)
+    }
+
     /// Run a compaction pass to free up space used by deleted states.
     pub fn compact(&self) -> Result<(), Error> {
         self.hot_db.compact()?;
@@ -1007,18 +1321,27 @@ pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
     let mut hot_db_ops: Vec<StoreOp<E>> = Vec::new();
 
     // 1. Copy all of the states between the head and the split slot, from the hot DB
-    // to the cold DB.
+    // to the cold DB. States are copied in ascending slot order, as hierarchical diffs are
+    // computed against earlier restore points.
     let state_root_iter = StateRootsIterator::new(store.clone(), frozen_head);
-    for maybe_pair in state_root_iter.take_while(|result| match result {
-        Ok((_, slot)) => slot >= &current_split_slot,
-        Err(_) => true,
-    }) {
-        let (state_root, slot) = maybe_pair?;
+    let state_roots = state_root_iter
+        .take_while(|result| match result {
+            Ok((_, slot)) => slot >= &current_split_slot,
+            Err(_) => true,
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let deleted_states = state_roots
+        .iter()
+        .map(|(state_root, _)| *state_root)
+        .collect::<HashSet<_>>();
 
+    for (state_root, slot) in state_roots.into_iter().rev() {
         let mut cold_db_ops: Vec<KeyValueStoreOp> = Vec::new();
 
         if slot % store.config.slots_per_restore_point == 0 {
-            let state: BeaconState<E> = get_full_state(&store.hot_db, &state_root)?
+            let state: BeaconState<E> = store
+                .load_hot_full_state(&state_root)?
                 .ok_or_else(|| HotColdDBError::MissingStateToFreeze(state_root))?;
 
             store.store_cold_state(&state_root, &state, &mut cold_db_ops)?;
@@ -1051,6 +1374,15 @@ pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
     // Flush to disk all the states that have just been migrated to the cold store.
     store.cold_db.sync()?;
 
+    // Hold the transaction lock until the old split state has been deleted, so that no hot state
+    // diff can be stored against it after its bases are pruned below (see `store_hot_state`).
+    let txn_lock = store.hot_db.begin_rw_transaction();
+
+    // Store the new split state in full before it becomes the base of new hot state diffs. Each
+    // diff records its own base, so they remain loadable if the process dies at any point.
+    store.store_hot_diff_base(frozen_head_root, frozen_head)?;
+    let old_split_root = store.split.read().state_root;
+
     {
         let mut split_guard = store.split.write();
         let latest_split_slot = split_guard.slot;
@@ -1086,8 +1418,27 @@ pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
         *split_guard = split;
     }
 
-    // Delete the states from the hot database if we got this far.
-    store.do_atomically(hot_db_ops)?;
+    // Delete the states from the hot database if we got this far, except for the former split
+    // states which are still the base of a hot state diff.
+    let mut hot_kv_ops = store.convert_to_kv_batch(&hot_db_ops)?;
+    let hot_diff_bases = store.prune_hot_diff_bases(
+        old_split_root,
+        frozen_head_root,
+        &deleted_states,
+        &mut hot_kv_ops,
+    )?;
+    let retained_keys = hot_diff_bases
+        .state_roots
+        .iter()
+        .map(|state_root| get_key_for_col(DBColumn::BeaconState.into(), state_root.as_bytes()))
+        .collect::<HashSet<_>>();
+    hot_kv_ops.retain(|op| match op {
+        KeyValueStoreOp::DeleteKey(key) => !retained_keys.contains(key),
+        KeyValueStoreOp::PutKeyValue(..) => true,
+    });
+    hot_kv_ops.push(hot_diff_bases.as_kv_store_op(HOT_DIFF_BASES_KEY));
+    store.hot_db.do_atomically(hot_kv_ops)?;
+    drop(txn_lock);
 
     debug!(
         store.log,
@@ -1166,6 +1517,49 @@ impl HotStateSummary {
     }
 }
 
+/// Struct for storing an epoch boundary state in the hot database as a diff against a state
+/// which is stored in full.
+#[derive(Debug, Clone, Encode, Decode)]
+struct HotStateDiff {
+    base_state_root: Hash256,
+    diff: HDiff,
+}
+
+impl StoreItem for HotStateDiff {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconStateHotDiff
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self::from_ssz_bytes(bytes)?)
+    }
+}
+
+/// Struct for storing the former split states which are kept in the hot database in full, as the
+/// bases of hot state diffs.
+#[derive(Debug, Clone, Default, Encode, Decode)]
+struct HotDiffBases {
+    state_roots: Vec<Hash256>,
+}
+
+impl StoreItem for HotDiffBases {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconMeta
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self::from_ssz_bytes(bytes)?)
+    }
+}
+
 /// Struct for summarising a state in the freezer database.
 #[derive(Debug, Clone, Copy, Default, Encode, Decode)]
 struct ColdStateSummary {
@@ -1222,3 +1616,157 @@ impl StoreItem for TemporaryFlag {
         Ok(TemporaryFlag)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sloggers::{null::NullLoggerBuilder, Build};
+    use types::test_utils::TestingBeaconStateBuilder;
+
+    type E = MinimalEthSpec;
+    type Store = HotColdDB<E, MemoryStore<E>, MemoryStore<E>>;
+
+    fn get_store() -> Arc<Store> {
+        let log = NullLoggerBuilder.build().unwrap();
+        Arc::new(
+            HotColdDB::open_ephemeral(StoreConfig::default(), ChainSpec::minimal(), log).unwrap(),
+        )
+    }
+
+    /// A state at `slot` whose balances depend on the slot, so that states differ.
+    fn get_state(slot: u64) -> BeaconState<E> {
+        let builder =
+            TestingBeaconStateBuilder::from_deterministic_keypairs(16, &E::default_spec());
+        let (mut state, _keypairs) = builder.build();
+        state.slot = Slot::new(slot);
+        for (i, balance) in state.balances.iter_mut().enumerate() {
+            *balance += slot * i as u64;
+        }
+        state
+    }
+
+    fn is_stored_as_diff(store: &Store, state_root: &Hash256) -> bool {
+        store.hot_db.exists::<HotStateDiff>(state_root).unwrap()
+    }
+
+    fn load_uncached(store: &Store, state_root: &Hash256) -> BeaconState<E> {
+        *store.hot_diff_base_cache.lock() = None;
+        store
+            .load_hot_state(state_root, BlockReplay::Accurate)
+            .unwrap()
+            .expect("state should be in the hot database")
+    }
+
+    #[test]
+    fn hot_state_diffs_against_split() {
+        let store = get_store();
+
+        let split_state = get_state(8);
+        let split_root = Hash256::from_low_u64_be(8);
+        store.put_state(&split_root, &split_state).unwrap();
+        *store.split.write() = Split {
+            slot: split_state.slot,
+            state_root: split_root,
+        };
+
+        let state = get_state(16);
+        let state_root = Hash256::from_low_u64_be(16);
+        store.put_state(&state_root, &state).unwrap();
+
+        assert!(!is_stored_as_diff(&store, &split_root));
+        assert_eq!(is_stored_as_diff(&store, &state_root), TREE_STATES);
+        assert_eq!(
+            load_uncached(&store, &state_root).canonical_root(),
+            state.canonical_root()
+        );
+
+        store.delete_state(&state_root, state.slot).unwrap();
+        assert!(!is_stored_as_diff(&store, &state_root));
+        assert!(store
+            .load_hot_state(&state_root, BlockReplay::Accurate)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn migration_retains_hot_state_diff_bases() {
+        let store = get_store();
+        let mut states = vec![];
+
+        // The split state, a finalized state and a state descending from it.
+        for slot in &[8, 16, 24] {
+            let mut state = get_state(*slot);
+            for prev_slot in 0..*slot {
+                state
+                    .set_state_root(Slot::new(prev_slot), Hash256::from_low_u64_be(prev_slot))
+                    .unwrap();
+            }
+            let state_root = Hash256::from_low_u64_be(*slot);
+            store.put_state(&state_root, &state).unwrap();
+            if *slot == 8 {
+                *store.split.write() = Split {
+                    slot: state.slot,
+                    state_root,
+                };
+            }
+            states.push((state_root, state));
+        }
+
+        let (old_split_root, _) = &states[0];
+        let (finalized_root, finalized_state) = &states[1];
+        let (head_root, head_state) = &states[2];
+        assert_eq!(is_stored_as_diff(&store, finalized_root), TREE_STATES);
+        assert_eq!(is_stored_as_diff(&store, head_root), TREE_STATES);
+
+        migrate_database(store.clone(), *finalized_root, finalized_state).unwrap();
+
+        // The new split state is stored in full. The old split state is no longer loadable, but
+        // is kept in full for as long as a diff is computed against it.
+        assert!(store
+            .load_hot_state(old_split_root, BlockReplay::Accurate)
+            .unwrap()
+            .is_none());
+        assert!(!is_stored_as_diff(&store, finalized_root));
+        assert_eq!(
+            get_full_state::<_, E>(&store.hot_db, old_split_root)
+                .unwrap()
+                .is_some(),
+            TREE_STATES
+        );
+        if TREE_STATES {
+            let hot_state_diff = store
+                .hot_db
+                .get::<HotStateDiff>(head_root)
+                .unwrap()
+                .unwrap();
+            assert_eq!(hot_state_diff.base_state_root, *old_split_root);
+        }
+
+        assert_eq!(
+            load_uncached(&store, finalized_root).canonical_root(),
+            finalized_state.canonical_root()
+        );
+        assert_eq!(
+            load_uncached(&store, head_root).canonical_root(),
+            head_state.canonical_root()
+        );
+
+        // Once the last diff against it is gone, the old split state is deleted.
+        migrate_database(store.clone(), *head_root, head_state).unwrap();
+
+        assert!(!is_stored_as_diff(&store, head_root));
+        assert!(get_full_state::<_, E>(&store.hot_db, old_split_root)
+            .unwrap()
+            .is_none());
+        assert!(store
+            .load_hot_diff_bases()
+            .unwrap()
+            .unwrap()
+            .state_roots
+            .is_empty());
+        assert_eq!(
+            load_uncached(&store, head_root).canonical_root(),
+            head_state.canonical_root()
+        );
+    }
+}
@@ -46,7 +46,7 @@ pub fn get_full_state<KV: KeyValueStore<E>, E: EthSpec>(
 // TODO: would be more space efficient with the caches stored separately and referenced by hash
 #[derive(Encode, Decode)]
 pub struct StorageContainer<T: EthSpec> {
-    state: BeaconState<T>,
+    pub(crate) state: BeaconState<T>,
     committee_caches: Vec<CommitteeCache>,
 }
 
@@ -16,6 +16,7 @@ pub mod config;
 pub mod errors;
 mod forwards_iter;
 mod garbage_collection;
+pub mod hdiff;
 pub mod hot_cold_store;
 mod impls;
 mod leveldb_store;
@@ -70,8 +71,16 @@ pub trait KeyValueStore<E: EthSpec>: Sync + Send + Sized + 'static {
 
     /// Compact the database, freeing space used by deleted items.
     fn compact(&self) -> Result<(), Error>;
+
+    /// Iterate over the keys of all items stored in `column`.
+    ///
+    /// The keys of `column` must all be `Hash256`s.
+    fn iter_column_keys(&self, column: DBColumn) -> ColumnKeyIter;
 }
 
+/// An iterator over the keys of a column, returned by `KeyValueStore::iter_column_keys`.
+pub type ColumnKeyIter<'a> = Box<dyn Iterator<Item = Result<Hash256, Error>> + 'a>;
+
 pub fn get_key_for_col(column: &str, key: &[u8]) -> Vec<u8> {
     let mut result = column.as_bytes().to_vec();
     result.extend_from_slice(key);
@@ -164,6 +173,12 @@ pub enum DBColumn {
     BeaconStateRoots,
     BeaconHistoricalRoots,
     BeaconRandaoMixes,
+    /// For full states in the coarsest layer of the freezer hierarchy.
+    BeaconStateSnapshot,
+    /// For states in the finer layers of the freezer hierarchy, stored as diffs.
+    BeaconStateDiff,
+    /// For epoch boundary states in the hot database, stored as diffs against the split state.
+    BeaconStateHotDiff,
     DhtEnrs,
 }
 
@@ -185,6 +200,9 @@ impl Into<&'static str> for DBColumn {
             DBColumn::BeaconStateRoots => "bsr",
             DBColumn::BeaconHistoricalRoots => "bhr",
             DBColumn::BeaconRandaoMixes => "brm",
+            DBColumn::BeaconStateSnapshot => "bsn",
+            DBColumn::BeaconStateDiff => "bsd",
+            DBColumn::BeaconStateHotDiff => "bhd",
             DBColumn::DhtEnrs => "dht",
         }
     }
@@ -1,4 +1,4 @@
-use super::{Error, ItemStore, KeyValueStore, KeyValueStoreOp};
+use super::{ColumnKeyIter, DBColumn, Error, ItemStore, KeyValueStore, KeyValueStoreOp};
 use parking_lot::{Mutex, MutexGuard, RwLock};
 use std::collections::HashMap;
 use std::marker::PhantomData;
@@ -88,6 +88,19 @@ impl<E: EthSpec> KeyValueStore<E> for MemoryStore<E> {
     fn compact(&self) -> Result<(), Error> {
         Ok(())
     }
+
+    fn iter_column_keys(&self, column: DBColumn) -> ColumnKeyIter {
+        let col = column.as_bytes();
+        let keys = self
+            .db
+            .read()
+            .keys()
+            .filter(|key| key.starts_with(col))
+            .map(|key| Ok(Hash256::from_slice(&key[col.len()..])))
+            .collect::<Vec<_>>();
+
+        Box::new(keys.into_iter())
+    }
 }
 
 impl<E: EthSpec> ItemStore<E> for MemoryStore<E> {}
@@ -43,8 +43,8 @@ where
     pub eth1_deposit_index: u64,
 
     // Registry
-    pub validators: VariableList<Validator, T::ValidatorRegistryLimit>,
-    pub balances: VariableList<u64, T::ValidatorRegistryLimit>,
+    pub validators: RegistryList<Validator, T::ValidatorRegistryLimit>,
+    pub balances: RegistryList<u64, T::ValidatorRegistryLimit>,
 
     // Shuffling
     /// Randao value from the current slot, for patching into the per-epoch randao vector.
@@ -1,6 +1,7 @@
 //! Utilities for managing database schema changes.
+use crate::config::{OnDiskStoreConfig, OnDiskStoreConfigV2};
 use crate::hot_cold_store::{HotColdDB, HotColdDBError};
-use crate::metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION};
+use crate::metadata::{SchemaVersion, CONFIG_KEY, CURRENT_SCHEMA_VERSION};
 use crate::{Error, ItemStore};
 use types::EthSpec;
 
@@ -19,6 +20,30 @@ where
                 self.store_schema_version(to)?;
                 Ok(())
             }
+            // Migration from v2 to v3, adding the freezer hierarchy exponents to the on-disk
+            // config. Existing databases store their restore points in full.
+            (SchemaVersion(2), SchemaVersion(3)) => {
+                if let Some(OnDiskStoreConfigV2 {
+                    slots_per_restore_point,
+                    _block_cache_size,
+                }) = self.hot_db.get(&CONFIG_KEY)?
+                {
+                    let config = OnDiskStoreConfig {
+                        slots_per_restore_point,
+                        _block_cache_size,
+                        hierarchy_exponents: vec![],
+                    };
+                    self.hot_db.put(&CONFIG_KEY, &config)?;
+                }
+                self.store_schema_version(to)?;
+                Ok(())
+            }
+            // Migrations spanning several versions are applied one version at a time.
+            (_, _) if from < to && to <= CURRENT_SCHEMA_VERSION => {
+                let next = SchemaVersion(from.as_u64() + 1);
+                self.migrate_schema(from, next)?;
+                self.migrate_schema(next, to)
+            }
             // Migrating from the current schema version to iself is always OK, a no-op.
             (_, _) if from == to && to == CURRENT_SCHEMA_VERSION => Ok(()),
             // Anything else is an error.
@@ -0,0 +1,179 @@
+//! Provides a file format which describes a share of a voting key split by
+//! `lighthouse account validator split`, written alongside the keystore of the share.
+//!
+//! The identifier of each share must be known to combine signatures from the shares, so it is
+//! recorded here rather than only in the (free-form) keystore description.
+
+use serde_derive::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::Path;
+use types::{PublicKey, Signature};
+
+/// The file name for the serialized `KeyShare` struct.
+pub const KEY_SHARE_FILENAME: &str = "key_share.yml";
+
+#[derive(Debug)]
+pub enum Error {
+    /// The file could not be opened.
+    UnableToOpenFile(io::Error),
+    /// The file could not be parsed as YAML.
+    UnableToParseFile(serde_yaml::Error),
+    /// The file could not be created.
+    UnableToCreateFile(io::Error),
+    /// The `KeyShare` could not be written to the file.
+    UnableToWriteFile(serde_yaml::Error),
+    /// The shares were not all split from the same key with the same threshold.
+    MismatchedShares,
+    /// Fewer signature shares than the threshold were supplied.
+    InsufficientShares { got: usize, threshold: u64 },
+    /// The signature shares could not be combined.
+    UnableToCombineShares(bls::Error),
+}
+
+/// Describes one share of a voting key which has been split into `shares` shares, any `threshold`
+/// of which are required to produce a signature from `validator_public_key`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyShare {
+    /// The identifier of the share, which is its evaluation point on the secret polynomial.
+    pub id: u64,
+    pub threshold: u64,
+    pub shares: u64,
+    /// The public key of the key which was split.
+    pub validator_public_key: PublicKey,
+    /// The public key of this share.
+    pub share_public_key: PublicKey,
+}
+
+impl KeyShare {
+    /// Reads `Self` from the `KEY_SHARE_FILENAME` file in `share_dir`.
+    pub fn open<P: AsRef<Path>>(share_dir: P) -> Result<Self, Error> {
+        let file = File::open(share_dir.as_ref().join(KEY_SHARE_FILENAME))
+            .map_err(Error::UnableToOpenFile)?;
+        serde_yaml::from_reader(file).map_err(Error::UnableToParseFile)
+    }
+
+    /// Writes `self` to a new `KEY_SHARE_FILENAME` file in `share_dir`, failing if the file
+    /// already exists.
+    pub fn create<P: AsRef<Path>>(&self, share_dir: P) -> Result<(), Error> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(share_dir.as_ref().join(KEY_SHARE_FILENAME))
+            .map_err(Error::UnableToCreateFile)?;
+        serde_yaml::to_writer(file, self).map_err(Error::UnableToWriteFile)
+    }
+}
+
+/// Combines signatures across the same message from at least `threshold` shares of the same key
+/// into a signature from that key, using the identifier recorded in each `KeyShare`.
+pub fn combine_signature_shares(shares: &[(&KeyShare, &Signature)]) -> Result<Signature, Error> {
+    let first = shares
+        .first()
+        .map(|(share, _)| share)
+        .ok_or(Error::InsufficientShares {
+            got: 0,
+            threshold: 1,
+        })?;
+
+    if shares.iter().any(|(share, _)| {
+        share.validator_public_key != first.validator_public_key
+            || share.threshold != first.threshold
+            || share.shares != first.shares
+    }) {
+        return Err(Error::MismatchedShares);
+    }
+
+    if (shares.len() as u64) < first.threshold {
+        return Err(Error::InsufficientShares {
+            got: shares.len(),
+            threshold: first.threshold,
+        });
+    }
+
+    let signatures = shares
+        .iter()
+        .map(|(share, signature)| (share.id, *signature))
+        .collect::<Vec<_>>();
+
+    Signature::combine_shares(&signatures).map_err(Error::UnableToCombineShares)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    use types::{Hash256, SecretKey};
+
+    fn split(threshold: u64, shares: u64) -> (SecretKey, Vec<(KeyShare, SecretKey)>) {
+        let sk = SecretKey::random();
+        let key_shares = sk
+            .split(threshold, shares)
+            .unwrap()
+            .into_iter()
+            .map(|(id, share_sk)| {
+                let key_share = KeyShare {
+                    id,
+                    threshold,
+                    shares,
+                    validator_public_key: sk.public_key(),
+                    share_public_key: share_sk.public_key(),
+                };
+                (key_share, share_sk)
+            })
+            .collect();
+        (sk, key_shares)
+    }
+
+    #[test]
+    fn key_share_round_trip() {
+        let dir = tempdir().unwrap();
+        let (_, key_shares) = split(2, 3);
+        let key_share = &key_shares[1].0;
+
+        key_share.create(dir.path()).unwrap();
+        assert_eq!(&KeyShare::open(dir.path()).unwrap(), key_share);
+        assert!(matches!(
+            key_share.create(dir.path()),
+            Err(Error::UnableToCreateFile(_))
+        ));
+    }
+
+    #[test]
+    fn combine_signature_shares_by_id() {
+        let msg = Hash256::repeat_byte(42);
+        let (sk, key_shares) = split(2, 3);
+        let signatures = key_shares
+            .iter()
+            .map(|(key_share, share_sk)| (key_share, share_sk.sign(msg)))
+            .collect::<Vec<_>>();
+        let shares = |indices: &[usize]| {
+            indices
+                .iter()
+                .map(|&i| (signatures[i].0, &signatures[i].1))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            combine_signature_shares(&shares(&[2, 0])).unwrap(),
+            sk.sign(msg)
+        );
+        assert!(matches!(
+            combine_signature_shares(&shares(&[1])),
+            Err(Error::InsufficientShares {
+                got: 1,
+                threshold: 2
+            })
+        ));
+
+        let (_, other_key_shares) = split(2, 3);
+        let other_signature = other_key_shares[0].1.sign(msg);
+        assert!(matches!(
+            combine_signature_shares(&[
+                (signatures[0].0, &signatures[0].1),
+                (&other_key_shares[1].0, &other_signature),
+            ]),
+            Err(Error::MismatchedShares)
+        ));
+    }
+}
@@ -1,20 +1,23 @@
 //! Provides functions that are used for key/account management across multiple crates in the
 //! Lighthouse project.
 
-use eth2_keystore::Keystore;
+use eth2_keystore::{keypair_from_secret, Keystore};
 use eth2_wallet::{
     bip39::{Language, Mnemonic, MnemonicType},
-    Wallet,
+    DerivedKey, KeyType, ValidatorPathTemplate, Wallet,
 };
 use rand::{distributions::Alphanumeric, Rng};
 use serde_derive::{Deserialize, Serialize};
 use std::fs::{self, File};
 use std::io;
 use std::io::prelude::*;
+use std::ops::Range;
 use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
+use types::PublicKey;
 use zeroize::Zeroize;
 
+pub mod key_share;
 pub mod validator_definitions;
 
 pub use eth2_keystore;
@@ -29,6 +32,17 @@ pub const MINIMUM_PASSWORD_LEN: usize = 12;
 /// 62**48 is greater than 255**32, therefore this password has more bits of entropy than a byte
 /// array of length 32.
 const DEFAULT_PASSWORD_LEN: usize = 48;
+/// The BIP-39 wordlists that may be used for mnemonics, alongside their BIP-39 language codes.
+pub const MNEMONIC_LANGUAGES: &[(&str, Language)] = &[
+    ("en", Language::English),
+    ("zh-hans", Language::ChineseSimplified),
+    ("zh-hant", Language::ChineseTraditional),
+    ("fr", Language::French),
+    ("it", Language::Italian),
+    ("ja", Language::Japanese),
+    ("ko", Language::Korean),
+    ("es", Language::Spanish),
+];
 
 /// Returns the "default" path where a wallet should store its password file.
 pub fn default_wallet_password_path<P: AsRef<Path>>(wallet_name: &str, secrets_dir: P) -> PathBuf {
@@ -170,6 +184,96 @@ pub fn mnemonic_from_phrase(phrase: &str) -> Result<Mnemonic, String> {
     Mnemonic::from_phrase(phrase, Language::English).map_err(|e| e.to_string())
 }
 
+/// Returns the BIP-39 language with the given code from `MNEMONIC_LANGUAGES` (e.g., `en`).
+pub fn mnemonic_language_from_code(code: &str) -> Result<Language, String> {
+    MNEMONIC_LANGUAGES
+        .iter()
+        .find(|(language_code, _)| *language_code == code)
+        .map(|(_, language)| *language)
+        .ok_or_else(|| format!("Unknown mnemonic language: {}", code))
+}
+
+/// Attempts to parse a mnemonic phrase, detecting its language from `MNEMONIC_LANGUAGES`.
+///
+/// Some words appear in more than one word list, so a phrase may be valid in more than one
+/// language. In that case the first matching language in `MNEMONIC_LANGUAGES` is used.
+pub fn mnemonic_from_phrase_any_language(phrase: &str) -> Result<Mnemonic, String> {
+    MNEMONIC_LANGUAGES
+        .iter()
+        .find_map(|(_, language)| Mnemonic::from_phrase(phrase, *language).ok())
+        .ok_or_else(|| {
+            format!(
+                "Mnemonic is not valid in any supported language ({})",
+                MNEMONIC_LANGUAGES
+                    .iter()
+                    .map(|(code, _)| *code)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })
+}
+
+/// Searches the `indices` of `template` for the voting keys of `voting_pubkeys`, deriving each
+/// key from the BIP-39 `seed`.
+///
+/// Returns the index of each voting key, in the same order as `voting_pubkeys`. Returns an error
+/// if any of the keys is not found within `indices`.
+pub fn find_voting_key_indices(
+    seed: &[u8],
+    template: &ValidatorPathTemplate,
+    indices: Range<u32>,
+    voting_pubkeys: &[PublicKey],
+) -> Result<Vec<u32>, String> {
+    let (first_index, last_index) = (indices.start, indices.end);
+    let mut found: Vec<Option<u32>> = vec![None; voting_pubkeys.len()];
+
+    // Derive the nodes shared by every index once, rather than once per index.
+    let shared_len = template.shared_prefix_len();
+    let master = DerivedKey::from_seed(seed)
+        .map_err(|()| "Unable to derive keys from an empty seed".to_string())?;
+    let shared = template
+        .path(first_index, KeyType::Voting)
+        .iter_nodes()
+        .take(shared_len)
+        .fold(master, |dk, node| dk.child(*node));
+
+    for index in indices {
+        if found.iter().all(Option::is_some) {
+            break;
+        }
+
+        let path = template.path(index, KeyType::Voting);
+        let secret = path
+            .iter_nodes()
+            .skip(shared_len)
+            .fold(None, |dk: Option<DerivedKey>, node| {
+                Some(dk.as_ref().unwrap_or(&shared).child(*node))
+            })
+            .expect("voting key paths are longer than the shared prefix");
+        let keypair = keypair_from_secret(secret.secret())
+            .map_err(|e| format!("Unable to derive keypair at {}: {:?}", path, e))?;
+
+        for (voting_pubkey, found_index) in voting_pubkeys.iter().zip(found.iter_mut()) {
+            if found_index.is_none() && *voting_pubkey == keypair.pk {
+                *found_index = Some(index);
+            }
+        }
+    }
+
+    voting_pubkeys
+        .iter()
+        .zip(found)
+        .map(|(voting_pubkey, found_index)| {
+            found_index.ok_or_else(|| {
+                format!(
+                    "Voting key {} not found at indices {}..{} of {}",
+                    voting_pubkey, first_index, last_index, template
+                )
+            })
+        })
+        .collect()
+}
+
 /// Provides a new-type wrapper around `String` that is zeroized on `Drop`.
 ///
 /// Useful for ensuring that password memory is zeroed-out on drop.
@@ -199,6 +303,7 @@ impl AsRef<[u8]> for ZeroizeString {
 #[cfg(test)]
 mod test {
     use super::*;
+    use eth2_wallet::recover_validator_secret_from_path;
 
     #[test]
     fn test_strip_off() {
@@ -234,6 +339,63 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_mnemonic_language_detection() {
+        for (code, language) in MNEMONIC_LANGUAGES {
+            let mnemonic = Mnemonic::new(MnemonicType::Words24, *language);
+            let detected = mnemonic_from_phrase_any_language(mnemonic.phrase()).unwrap();
+
+            // The first language in which the phrase is valid is used, even if it is also valid
+            // in a later one.
+            let first_valid = MNEMONIC_LANGUAGES
+                .iter()
+                .map(|(_, language)| *language)
+                .find(|language| Mnemonic::validate(mnemonic.phrase(), *language).is_ok())
+                .unwrap();
+            assert_eq!(detected.language(), first_valid, "{}", code);
+            assert_eq!(detected.phrase(), mnemonic.phrase(), "{}", code);
+            assert_eq!(
+                mnemonic_language_from_code(code).unwrap(),
+                *language,
+                "{}",
+                code
+            );
+        }
+
+        assert!(mnemonic_from_phrase_any_language("not a mnemonic").is_err());
+        assert!(mnemonic_language_from_code("xx").is_err());
+    }
+
+    #[test]
+    fn test_find_voting_key_indices() {
+        let seed = [42; 32];
+
+        for template in &["m/12381/3600/i/0/0", "m/12381/3600/0/i", "m/i/0"] {
+            let template: ValidatorPathTemplate = template.parse().unwrap();
+            let voting_pubkey = |index| {
+                let path = template.path(index, KeyType::Voting);
+                let secret = recover_validator_secret_from_path(&seed, &path).unwrap();
+                keypair_from_secret(secret.as_bytes()).unwrap().pk
+            };
+            let voting_pubkeys = vec![voting_pubkey(3), voting_pubkey(1)];
+
+            assert_eq!(
+                find_voting_key_indices(&seed, &template, 0..4, &voting_pubkeys).unwrap(),
+                vec![3, 1]
+            );
+            assert!(find_voting_key_indices(&seed, &template, 0..3, &voting_pubkeys).is_err());
+        }
+
+        let template: ValidatorPathTemplate = "m/12381/60/1/0".parse().unwrap();
+        let secret =
+            recover_validator_secret_from_path(&seed, &template.path(0, KeyType::Voting)).unwrap();
+        let voting_pubkeys = vec![keypair_from_secret(secret.as_bytes()).unwrap().pk];
+        assert_eq!(
+            find_voting_key_indices(&seed, &template, 0..1, &voting_pubkeys).unwrap(),
+            vec![0]
+        );
+    }
+
     #[test]
     fn test_password_over_min_length() {
         is_password_sufficiently_complex(b"TestPasswordLong").unwrap();
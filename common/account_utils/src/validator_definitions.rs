@@ -3,23 +3,26 @@
 //! Serves as the source-of-truth of which validators this validator client should attempt (or not
 //! attempt) to load into the `crate::intialized_validators::InitializedValidators` struct.
 
-use crate::{create_with_600_perms, default_keystore_password_path, ZeroizeString};
+use crate::{create_with_600_perms, default_keystore_password_path, read_password, ZeroizeString};
 use directory::ensure_dir_exists;
-use eth2_keystore::Keystore;
+use eth2_keystore::{json_keystore::Kdf, Keystore, KeystoreBuilder, Uuid};
 use regex::Regex;
 use serde_derive::{Deserialize, Serialize};
 use slog::{error, Logger};
 use std::collections::HashSet;
-use std::fs::{self, OpenOptions};
+use std::fs::{self, File, OpenOptions};
 use std::io;
 use std::iter::FromIterator;
 use std::path::{Path, PathBuf};
-use types::PublicKey;
+use types::{Keypair, PublicKey};
 use validator_dir::VOTING_KEYSTORE_FILE;
 
 /// The file name for the serialized `ValidatorDefinitions` struct.
 pub const CONFIG_FILENAME: &str = "validator_definitions.yml";
 
+/// The file name of the validator client's encrypted cache of voting keypairs.
+pub const KEY_CACHE_FILENAME: &str = "validator_key_cache.json";
+
 #[derive(Debug)]
 pub enum Error {
     /// The config file could not be opened.
@@ -38,6 +41,17 @@ pub enum Error {
     UnableToOpenKeystore(eth2_keystore::Error),
     /// The validator directory could not be created.
     UnableToCreateValidatorDir(PathBuf),
+    /// The voting keystore password could not be read from its file.
+    UnableToReadKeystorePassword(io::Error),
+    /// The definition does not contain a voting keystore password, or a path to one.
+    MissingKeystorePassword,
+    /// The voting keystore could not be decrypted or re-encrypted.
+    UnableToReencryptKeystore(eth2_keystore::Error),
+    /// The re-encrypted voting keystore or its password could not be written to the filesystem.
+    UnableToWriteKeystore(io::Error),
+    /// The definition of a re-encrypted voting keystore was changed or removed before the
+    /// keystore could be replaced.
+    ReencryptedDefinitionChanged,
 }
 
 /// Defines how the validator client should attempt to sign messages for this validator.
@@ -101,6 +115,92 @@ impl ValidatorDefinition {
             },
         })
     }
+
+    /// Decrypts the voting keystore and re-encrypts it with `new_password`, using `kdf`.
+    ///
+    /// ## Notes
+    ///
+    /// Nothing is written to disk. The result must be passed to
+    /// `ValidatorDefinitions::commit_reencrypted_voting_keystore` to replace the keystore, its
+    /// password and the definition.
+    pub fn reencrypt_voting_keystore(
+        &self,
+        new_password: ZeroizeString,
+        kdf: Kdf,
+    ) -> Result<ReencryptedVotingKeystore, Error> {
+        match &self.signing_definition {
+            SigningDefinition::LocalKeystore {
+                voting_keystore_path,
+                voting_keystore_password_path,
+                voting_keystore_password,
+            } => {
+                let keystore = Keystore::from_json_file(&voting_keystore_path)
+                    .map_err(Error::UnableToOpenKeystore)?;
+
+                let password = match (
+                    voting_keystore_password_path.as_ref(),
+                    voting_keystore_password.as_ref(),
+                ) {
+                    (_, Some(password)) => password.as_ref().to_vec().into(),
+                    (Some(path), None) => {
+                        read_password(path).map_err(Error::UnableToReadKeystorePassword)?
+                    }
+                    (None, None) => return Err(Error::MissingKeystorePassword),
+                };
+
+                let keypair = keystore
+                    .decrypt_keypair(password.as_bytes())
+                    .map_err(Error::UnableToReencryptKeystore)?;
+                let new_keystore = KeystoreBuilder::new(
+                    &keypair,
+                    new_password.as_ref(),
+                    keystore.path().unwrap_or_else(String::new),
+                )
+                .map_err(Error::UnableToReencryptKeystore)?
+                .description(keystore.description().unwrap_or("").to_string())
+                .kdf(kdf)
+                .build()
+                .map_err(Error::UnableToReencryptKeystore)?;
+
+                let new_keystore_json = new_keystore
+                    .to_json_string()
+                    .map_err(Error::UnableToReencryptKeystore)?;
+
+                let new_signing_definition = SigningDefinition::LocalKeystore {
+                    voting_keystore_path: voting_keystore_path.clone(),
+                    voting_keystore_password_path: voting_keystore_password_path.clone(),
+                    voting_keystore_password: voting_keystore_password
+                        .as_ref()
+                        .map(|_| new_password.clone()),
+                };
+
+                Ok(ReencryptedVotingKeystore {
+                    voting_public_key: self.voting_public_key.clone(),
+                    old_signing_definition: self.signing_definition.clone(),
+                    new_signing_definition,
+                    new_keystore_json,
+                    password: new_password,
+                    keystore: new_keystore,
+                    keypair,
+                })
+            }
+        }
+    }
+}
+
+/// A voting keystore which has been re-encrypted in memory by
+/// `ValidatorDefinition::reencrypt_voting_keystore`, but not yet written to disk.
+pub struct ReencryptedVotingKeystore {
+    voting_public_key: PublicKey,
+    old_signing_definition: SigningDefinition,
+    new_signing_definition: SigningDefinition,
+    new_keystore_json: String,
+    /// The password of the re-encrypted keystore.
+    pub password: ZeroizeString,
+    /// The re-encrypted keystore.
+    pub keystore: Keystore,
+    /// The keypair of the keystore.
+    pub keypair: Keypair,
 }
 
 /// A list of `ValidatorDefinition` that serves as a serde-able configuration file which defines a
@@ -239,11 +339,111 @@ impl ValidatorDefinitions {
         let config_path = validators_dir.as_ref().join(CONFIG_FILENAME);
         let bytes = serde_yaml::to_vec(self).map_err(Error::UnableToEncodeFile)?;
 
-        if config_path.exists() {
-            fs::write(config_path, &bytes).map_err(Error::UnableToWriteFile)
-        } else {
-            create_with_600_perms(&config_path, &bytes).map_err(Error::UnableToWriteFile)
+        write_file_atomically(&config_path, &bytes).map_err(Error::UnableToWriteFile)
+    }
+
+    /// Replaces a voting keystore with one re-encrypted by
+    /// `ValidatorDefinition::reencrypt_voting_keystore`, replacing its password wherever it is
+    /// stored and saving `self` to `validators_dir`.
+    ///
+    /// ## Notes
+    ///
+    /// The new keystore and password file are first written and flushed to new files alongside
+    /// the originals. Saving `self` with the definition pointing at those files is the single
+    /// switch-over point: a crash before it leaves the original keystore in use and a crash after
+    /// it leaves the new keystore in use. Only then are the original files replaced, the
+    /// definition pointed back at them and the new files removed. If that last step fails the
+    /// definition keeps pointing at the new files, which is still a complete re-encryption, so
+    /// `Ok` is returned.
+    ///
+    /// Only the signing definition is replaced, so other changes to the definition since it was
+    /// re-encrypted (e.g., to `enabled`) are preserved. Returns an error if the signing definition
+    /// itself has changed, or the definition has been removed.
+    pub fn commit_reencrypted_voting_keystore<P: AsRef<Path>>(
+        &mut self,
+        validators_dir: P,
+        reencrypted: &ReencryptedVotingKeystore,
+    ) -> Result<(), Error> {
+        let index = self
+            .0
+            .iter()
+            .position(|def| {
+                def.voting_public_key == reencrypted.voting_public_key
+                    && def.signing_definition == reencrypted.old_signing_definition
+            })
+            .ok_or(Error::ReencryptedDefinitionChanged)?;
+
+        let (keystore_path, password_path, password) = match &reencrypted.new_signing_definition {
+            SigningDefinition::LocalKeystore {
+                voting_keystore_path,
+                voting_keystore_password_path,
+                voting_keystore_password,
+            } => (
+                voting_keystore_path,
+                voting_keystore_password_path,
+                voting_keystore_password,
+            ),
+        };
+        let uuid = reencrypted.keystore.uuid();
+        let staged_keystore_path = staged_path(keystore_path, uuid);
+        let staged_password_path = password_path.as_ref().map(|path| staged_path(path, uuid));
+        let remove_staged_files = || {
+            let _ = fs::remove_file(&staged_keystore_path);
+            if let Some(path) = &staged_password_path {
+                let _ = fs::remove_file(path);
+            }
+        };
+
+        let staged = write_file_synced(
+            &staged_keystore_path,
+            reencrypted.new_keystore_json.as_bytes(),
+        )
+        .and_then(|()| sync_parent_dir(&staged_keystore_path))
+        .and_then(|()| match &staged_password_path {
+            Some(path) => write_file_synced(path, reencrypted.password.as_ref())
+                .and_then(|()| sync_parent_dir(path)),
+            None => Ok(()),
+        });
+        if let Err(e) = staged {
+            remove_staged_files();
+            return Err(Error::UnableToWriteKeystore(e));
+        }
+
+        let staged_signing_definition = SigningDefinition::LocalKeystore {
+            voting_keystore_path: staged_keystore_path.clone(),
+            voting_keystore_password_path: staged_password_path.clone(),
+            voting_keystore_password: password.clone(),
+        };
+        let old_signing_definition = std::mem::replace(
+            &mut self.0[index].signing_definition,
+            staged_signing_definition,
+        );
+        if let Err(e) = self.save(&validators_dir) {
+            self.0[index].signing_definition = old_signing_definition;
+            remove_staged_files();
+            return Err(e);
+        }
+
+        // The original files are no longer referenced, so they can be replaced.
+        let replaced =
+            write_file_atomically(keystore_path, reencrypted.new_keystore_json.as_bytes())
+                .and_then(|()| match password_path {
+                    Some(path) => write_file_atomically(path, reencrypted.password.as_ref()),
+                    None => Ok(()),
+                });
+        if replaced.is_ok() {
+            let staged_signing_definition = std::mem::replace(
+                &mut self.0[index].signing_definition,
+                reencrypted.new_signing_definition.clone(),
+            );
+            if self.save(&validators_dir).is_ok() {
+                remove_staged_files();
+            } else {
+                self.0[index].signing_definition = staged_signing_definition;
+            }
         }
+
+        Ok(())
     }
 
     /// Adds a new `ValidatorDefinition` to `self`.
@@ -262,6 +462,47 @@ impl ValidatorDefinitions {
     }
 }
 
+/// Writes `bytes` to a new file with `600` permissions and then renames it to `path`, so that
+/// `path` is never observed in a partially-written state.
+///
+/// Both the file and the rename are flushed to disk before returning.
+fn write_file_atomically(path: &Path, bytes: &[u8]) -> Result<(), io::Error> {
+    let temp_path = temp_path(path);
+    write_file_synced(&temp_path, bytes)?;
+    fs::rename(&temp_path, path)?;
+    sync_parent_dir(path)
+}
+
+/// Writes `bytes` to a new file at `path` with `600` permissions and flushes it to disk.
+fn write_file_synced(path: &Path, bytes: &[u8]) -> Result<(), io::Error> {
+    create_with_600_perms(path, bytes)?;
+    OpenOptions::new().write(true).open(path)?.sync_all()
+}
+
+/// Flushes the directory containing `path` to disk, so that the creation or renaming of `path`
+/// survives a crash.
+fn sync_parent_dir(path: &Path) -> Result<(), io::Error> {
+    match path.parent() {
+        Some(dir) if dir != Path::new("") => File::open(dir)?.sync_all(),
+        _ => File::open(".")?.sync_all(),
+    }
+}
+
+/// Returns the path of the temporary file used to replace `path`.
+fn temp_path(path: &Path) -> PathBuf {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    temp_path.into()
+}
+
+/// Returns the path which a re-encrypted keystore (or its password) with the given `uuid` is
+/// written to before it replaces `path`.
+fn staged_path(path: &Path, uuid: &Uuid) -> PathBuf {
+    let mut staged_path = path.as_os_str().to_owned();
+    staged_path.push(format!(".{}", uuid));
+    staged_path.into()
+}
+
 /// Perform an exhaustive tree search of `dir`, adding any discovered voting keystore paths to
 /// `matches`.
 ///
@@ -335,6 +576,245 @@ pub fn is_voting_keystore(file_name: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use eth2_keystore::{
+        json_keystore::{KdfFunction, Pbkdf2, Prf, Scrypt},
+        DKLEN,
+    };
+    use tempfile::tempdir;
+
+    const KEYSTORE_PATH: &str = "m/12381/3600/0/0/0";
+    const OLD_PASSWORD: &str = "old_password";
+
+    /// Returns a cheap `Kdf`, since the default parameters are very slow in debug builds.
+    fn cheap_kdf(function: KdfFunction) -> Kdf {
+        let salt = vec![42; 32].into();
+        match function {
+            KdfFunction::Scrypt => Kdf::Scrypt(Scrypt {
+                dklen: DKLEN,
+                n: 2,
+                p: 1,
+                r: 8,
+                salt,
+            }),
+            KdfFunction::Pbkdf2 => Kdf::Pbkdf2(Pbkdf2 {
+                dklen: DKLEN,
+                c: 2,
+                prf: Prf::HmacSha256,
+                salt,
+            }),
+        }
+    }
+
+    /// Creates a keystore encrypted with `OLD_PASSWORD` in `dir`, with its password in a file,
+    /// and saves definitions containing it to `dir`.
+    fn keystore_fixture(dir: &Path) -> (ValidatorDefinitions, Keypair, PathBuf, PathBuf) {
+        let keystore_path = dir.join(VOTING_KEYSTORE_FILE);
+        let password_path = dir.join("password");
+
+        let keypair = Keypair::random();
+        let keystore =
+            KeystoreBuilder::new(&keypair, OLD_PASSWORD.as_bytes(), KEYSTORE_PATH.into())
+                .unwrap()
+                .kdf(cheap_kdf(KdfFunction::Pbkdf2))
+                .build()
+                .unwrap();
+        fs::write(&keystore_path, keystore.to_json_string().unwrap()).unwrap();
+        fs::write(&password_path, OLD_PASSWORD).unwrap();
+
+        let mut def =
+            ValidatorDefinition::new_keystore_with_password(&keystore_path, None).unwrap();
+        def.signing_definition = SigningDefinition::LocalKeystore {
+            voting_keystore_path: keystore_path.clone(),
+            voting_keystore_password_path: Some(password_path.clone()),
+            voting_keystore_password: None,
+        };
+        let mut defs = ValidatorDefinitions::default();
+        defs.push(def);
+        defs.save(dir).unwrap();
+
+        (defs, keypair, keystore_path, password_path)
+    }
+
+    #[test]
+    fn reencrypt_voting_keystore() {
+        let dir = tempdir().unwrap();
+        let (mut defs, keypair, keystore_path, password_path) = keystore_fixture(dir.path());
+        let old_keystore_json = fs::read(&keystore_path).unwrap();
+
+        // A password stored in a file is replaced in that file. Nothing is written until the
+        // keystore is committed.
+        let new_password = ZeroizeString::from("new_password".to_string());
+        let reencrypted = defs.as_slice()[0]
+            .reencrypt_voting_keystore(new_password.clone(), cheap_kdf(KdfFunction::Scrypt))
+            .unwrap();
+
+        assert_eq!(reencrypted.keypair.pk, keypair.pk);
+        assert_eq!(reencrypted.keystore.kdf().function(), KdfFunction::Scrypt);
+        assert_eq!(reencrypted.keystore.path(), Some(KEYSTORE_PATH.to_string()));
+        assert_eq!(fs::read(&keystore_path).unwrap(), old_keystore_json);
+
+        defs.commit_reencrypted_voting_keystore(dir.path(), &reencrypted)
+            .unwrap();
+
+        assert!(
+            ValidatorDefinitions::open(dir.path()).unwrap().as_slice()[0].signing_definition
+                == SigningDefinition::LocalKeystore {
+                    voting_keystore_path: keystore_path.clone(),
+                    voting_keystore_password_path: Some(password_path.clone()),
+                    voting_keystore_password: None,
+                }
+        );
+        assert_eq!(
+            Keystore::from_json_file(&keystore_path).unwrap(),
+            reencrypted.keystore
+        );
+        let uuid = reencrypted.keystore.uuid();
+        assert!(!staged_path(&keystore_path, uuid).exists());
+        assert!(!staged_path(&password_path, uuid).exists());
+        assert_eq!(
+            read_password(&password_path).unwrap().as_bytes(),
+            new_password.as_ref()
+        );
+        assert_eq!(
+            reencrypted
+                .keystore
+                .decrypt_keypair(new_password.as_ref())
+                .unwrap()
+                .pk,
+            keypair.pk
+        );
+
+        // A password stored in the definition is replaced in the saved definition.
+        let local_keystore = |password: Option<ZeroizeString>| SigningDefinition::LocalKeystore {
+            voting_keystore_path: keystore_path.clone(),
+            voting_keystore_password_path: None,
+            voting_keystore_password: password,
+        };
+        defs.as_mut_slice()[0].signing_definition = local_keystore(Some(new_password));
+        let newer_password = ZeroizeString::from("newer_password".to_string());
+        let reencrypted = defs.as_slice()[0]
+            .reencrypt_voting_keystore(newer_password.clone(), cheap_kdf(KdfFunction::Pbkdf2))
+            .unwrap();
+        defs.commit_reencrypted_voting_keystore(dir.path(), &reencrypted)
+            .unwrap();
+
+        let saved_defs = ValidatorDefinitions::open(dir.path()).unwrap();
+        assert!(
+            saved_defs.as_slice()[0].signing_definition
+                == local_keystore(Some(newer_password.clone()))
+        );
+        assert_eq!(
+            Keystore::from_json_file(&keystore_path)
+                .unwrap()
+                .decrypt_keypair(newer_password.as_ref())
+                .unwrap()
+                .pk,
+            keypair.pk
+        );
+
+        // A keystore without a password cannot be re-encrypted.
+        defs.as_mut_slice()[0].signing_definition = local_keystore(None);
+        assert!(matches!(
+            defs.as_slice()[0]
+                .reencrypt_voting_keystore(newer_password, cheap_kdf(KdfFunction::Pbkdf2)),
+            Err(Error::MissingKeystorePassword)
+        ));
+    }
+
+    #[test]
+    fn reencrypt_voting_keystore_restored_on_failure() {
+        let dir = tempdir().unwrap();
+        let (mut defs, _keypair, keystore_path, password_path) = keystore_fixture(dir.path());
+        let old_keystore_json = fs::read(&keystore_path).unwrap();
+        let old_def = defs.as_slice()[0].clone();
+
+        let reencrypted = defs.as_slice()[0]
+            .reencrypt_voting_keystore(
+                ZeroizeString::from("new_password".to_string()),
+                cheap_kdf(KdfFunction::Pbkdf2),
+            )
+            .unwrap();
+
+        let assert_unchanged = |defs: &ValidatorDefinitions| {
+            assert!(defs.as_slice()[0] == old_def);
+            assert!(ValidatorDefinitions::open(dir.path()).unwrap().as_slice()[0] == old_def);
+            assert_eq!(fs::read(&keystore_path).unwrap(), old_keystore_json);
+            assert_eq!(
+                read_password(&password_path).unwrap().as_bytes(),
+                OLD_PASSWORD.as_bytes()
+            );
+            assert!(!staged_path(&keystore_path, reencrypted.keystore.uuid()).exists());
+            assert!(!staged_path(&password_path, reencrypted.keystore.uuid()).exists());
+        };
+
+        // The definitions cannot be saved to a directory which does not exist.
+        assert!(matches!(
+            defs.commit_reencrypted_voting_keystore(dir.path().join("missing"), &reencrypted),
+            Err(Error::UnableToWriteFile(_))
+        ));
+        assert_unchanged(&defs);
+
+        // The keystore is not replaced if its definition changed after it was re-encrypted.
+        defs.as_mut_slice()[0].signing_definition = SigningDefinition::LocalKeystore {
+            voting_keystore_path: keystore_path.clone(),
+            voting_keystore_password_path: None,
+            voting_keystore_password: Some(ZeroizeString::from(OLD_PASSWORD.to_string())),
+        };
+        assert!(matches!(
+            defs.commit_reencrypted_voting_keystore(dir.path(), &reencrypted),
+            Err(Error::ReencryptedDefinitionChanged)
+        ));
+        defs.as_mut_slice()[0] = old_def.clone();
+        assert_unchanged(&defs);
+    }
+
+    #[test]
+    fn reencrypt_voting_keystore_staged_after_switch_over() {
+        let dir = tempdir().unwrap();
+        let (mut defs, keypair, keystore_path, password_path) = keystore_fixture(dir.path());
+        let old_keystore_json = fs::read(&keystore_path).unwrap();
+
+        let new_password = ZeroizeString::from("new_password".to_string());
+        let reencrypted = defs.as_slice()[0]
+            .reencrypt_voting_keystore(new_password.clone(), cheap_kdf(KdfFunction::Pbkdf2))
+            .unwrap();
+        let staged_keystore_path = staged_path(&keystore_path, reencrypted.keystore.uuid());
+        let staged_password_path = staged_path(&password_path, reencrypted.keystore.uuid());
+
+        // Prevent the original password file from being replaced after the switch-over.
+        fs::create_dir(temp_path(&password_path)).unwrap();
+        defs.commit_reencrypted_voting_keystore(dir.path(), &reencrypted)
+            .unwrap();
+
+        // The definition keeps referring to the new files, which hold the new keystore.
+        let staged_def = SigningDefinition::LocalKeystore {
+            voting_keystore_path: staged_keystore_path.clone(),
+            voting_keystore_password_path: Some(staged_password_path.clone()),
+            voting_keystore_password: None,
+        };
+        assert!(defs.as_slice()[0].signing_definition == staged_def);
+        assert!(
+            ValidatorDefinitions::open(dir.path()).unwrap().as_slice()[0].signing_definition
+                == staged_def
+        );
+        assert_eq!(
+            read_password(&staged_password_path).unwrap().as_bytes(),
+            new_password.as_ref()
+        );
+        assert_eq!(
+            Keystore::from_json_file(&staged_keystore_path)
+                .unwrap()
+                .decrypt_keypair(new_password.as_ref())
+                .unwrap()
+                .pk,
+            keypair.pk
+        );
+        assert_eq!(
+            read_password(&password_path).unwrap().as_bytes(),
+            OLD_PASSWORD.as_bytes()
+        );
+        assert_ne!(fs::read(&keystore_path).unwrap(), old_keystore_json);
+    }
 
     #[test]
     fn voting_keystore_filename_lighthouse() {
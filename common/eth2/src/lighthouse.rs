@@ -2,14 +2,18 @@
 
 use crate::{
     ok_or_error,
-    types::{BeaconState, Epoch, EthSpec, GenericResponse, ValidatorId},
+    types::{
+        BeaconState, BeaconStateMerkleProof, DepositTreeSnapshot, Epoch, EthSpec, GenericResponse,
+        QueryVec, Slot, ValidatorId,
+    },
     BeaconNodeHttpClient, DepositData, Error, Eth1Data, Hash256, StateId, StatusCode,
 };
 use proto_array::core::ProtoArray;
 use reqwest::IntoUrl;
 use serde::{Deserialize, Serialize};
-use ssz::Decode;
+use ssz::{legacy::four_byte_option, Decode};
 use ssz_derive::{Decode, Encode};
+use std::fmt;
 
 pub use eth2_libp2p::{types::SyncState, PeerInfo};
 
@@ -75,6 +79,68 @@ pub struct ValidatorInclusionData {
     pub is_previous_epoch_head_attester: bool,
 }
 
+/// The rewards and penalties, in Gwei, for a single component of a validator's attestations.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RewardsAndPenalties {
+    pub rewards: u64,
+    pub penalties: u64,
+}
+
+/// The rewards and penalties received by a validator for its attestations during an epoch.
+///
+/// These are applied to the validator's balance at the end of the _following_ epoch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorRewardsData {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    /// For attesting to the correct source checkpoint.
+    pub source: RewardsAndPenalties,
+    /// For attesting to the correct target checkpoint.
+    pub target: RewardsAndPenalties,
+    /// For attesting to the correct head block.
+    pub head: RewardsAndPenalties,
+    /// For the speed of inclusion of the validator's attestation, plus any rewards received as a
+    /// proposer for including the attestations of others.
+    pub inclusion_delay: RewardsAndPenalties,
+    /// Incurred when the chain has not finalized for too long.
+    pub inactivity_penalty: RewardsAndPenalties,
+}
+
+/// Selects the validators returned by `lighthouse/validator_rewards/{epoch}`.
+///
+/// All validators are returned if `id` is not supplied.
+#[derive(Clone, Deserialize)]
+pub struct ValidatorRewardsQuery {
+    pub id: Option<QueryVec<ValidatorId>>,
+}
+
+/// The rewards, in Gwei, received by the proposer of a block for the operations it includes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockRewardsData {
+    pub slot: Slot,
+    pub block_root: Hash256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub proposer_index: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub total: u64,
+    /// For including each of the block's attestations, in order.
+    #[serde(with = "serde_utils::quoted_u64_vec")]
+    pub attestation_rewards: Vec<u64>,
+    /// For including each of the block's proposer slashings, in order.
+    #[serde(with = "serde_utils::quoted_u64_vec")]
+    pub proposer_slashing_rewards: Vec<u64>,
+    /// For including each of the block's attester slashings, in order.
+    #[serde(with = "serde_utils::quoted_u64_vec")]
+    pub attester_slashing_rewards: Vec<u64>,
+}
+
+/// Selects the (inclusive) range of slots for `lighthouse/block_rewards`.
+#[derive(Clone, Deserialize)]
+pub struct BlockRewardsQuery {
+    pub start_slot: Slot,
+    pub end_slot: Slot,
+}
+
 #[cfg(target_os = "linux")]
 use {procinfo::pid, psutil::process::Process};
 
@@ -146,6 +212,95 @@ impl Health {
     }
 }
 
+/// Identifies the node of a `BeaconState` to be proven by `lighthouse/beacon/states/{state_id}/proof`.
+///
+/// Exactly one of `gindex` or `path` should be supplied.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StateProofQuery {
+    /// The generalized index of the node.
+    pub gindex: Option<u64>,
+    /// A `.` separated path to the node, e.g. `validators.7.exit_epoch` or `balances.12`.
+    pub path: Option<String>,
+}
+
+/// The kind of a gossip message recorded by the gossip trace.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GossipMessageKind {
+    Block,
+    Attestation,
+    Aggregate,
+}
+
+impl fmt::Display for GossipMessageKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GossipMessageKind::Block => write!(f, "block"),
+            GossipMessageKind::Attestation => write!(f, "attestation"),
+            GossipMessageKind::Aggregate => write!(f, "aggregate"),
+        }
+    }
+}
+
+/// The result of validating a gossip message, as reported to the gossipsub router.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GossipValidationOutcome {
+    Accept,
+    Ignore,
+    Reject,
+}
+
+/// The timing of each stage of processing a single block or attestation received on gossip.
+///
+/// Durations are in milliseconds. A stage which has not (or not yet) been reached is `None`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GossipMessageTrace {
+    pub kind: GossipMessageKind,
+    pub message_id: String,
+    /// The peer from which the message was received.
+    pub peer_id: String,
+    pub slot: Slot,
+    /// The root of the block, or of the block voted for by an attestation.
+    pub beacon_block_root: Hash256,
+    /// The time at which the message was received, in milliseconds since the UNIX epoch.
+    pub seen_timestamp_ms: u64,
+    /// The time spent queued in the `BeaconProcessor` before validation started.
+    pub queued_ms: u64,
+    pub validation_outcome: Option<GossipValidationOutcome>,
+    pub validation_ms: Option<u64>,
+    pub import_ms: Option<u64>,
+    /// Set if the message was valid for gossip, but could not be imported.
+    pub import_error: Option<String>,
+    pub fork_choice_ms: Option<u64>,
+}
+
+/// Filters the messages returned by `lighthouse/gossip/trace`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GossipTraceQuery {
+    pub kind: Option<GossipMessageKind>,
+    pub slot: Option<Slot>,
+    pub peer_id: Option<String>,
+}
+
+/// A block which arrived on gossip after the attestation deadline of its slot.
+///
+/// Durations are in milliseconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LateBlock {
+    pub slot: Slot,
+    pub block_root: Hash256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub proposer_index: u64,
+    /// The time between the start of the slot and the arrival of the block.
+    pub arrival_delay_ms: u64,
+    pub signature_verification_ms: Option<u64>,
+    pub state_transition_ms: Option<u64>,
+    pub fork_choice_ms: Option<u64>,
+    /// The runtime of fork choice when it set the block as the head, if it has done so.
+    pub head_update_ms: Option<u64>,
+}
+
 /// Indicates how up-to-date the Eth1 caches are.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Eth1SyncStatusData {
@@ -176,7 +331,9 @@ pub struct Eth1Block {
     pub hash: Hash256,
     pub timestamp: u64,
     pub number: u64,
+    #[ssz(with = "four_byte_option")]
     pub deposit_root: Option<Hash256>,
+    #[ssz(with = "four_byte_option")]
     pub deposit_count: Option<u64>,
 }
 
@@ -190,6 +347,64 @@ impl Eth1Block {
     }
 }
 
+/// The reason an `Eth1Data` vote was chosen for block production.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Eth1VoteReason {
+    /// The candidate with the most votes in the state, with ties broken by block number.
+    MostVotedCandidate,
+    /// None of the votes in the state are for a candidate, so the latest candidate was chosen.
+    LatestCandidate,
+    /// The eth1 cache does not contain any candidate blocks, so `state.eth1_data` was repeated.
+    NoCandidates,
+    /// The dummy eth1 backend is in use, which votes for deterministic junk.
+    DummyBackend,
+}
+
+impl fmt::Display for Eth1VoteReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Eth1VoteReason::MostVotedCandidate => write!(f, "most_voted_candidate"),
+            Eth1VoteReason::LatestCandidate => write!(f, "latest_candidate"),
+            Eth1VoteReason::NoCandidates => write!(f, "no_candidates"),
+            Eth1VoteReason::DummyBackend => write!(f, "dummy_backend"),
+        }
+    }
+}
+
+/// The number of votes for some `Eth1Data` in a voting period.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Eth1DataVoteTally {
+    pub eth1_data: Eth1Data,
+    pub votes: u64,
+    /// The number of the matching candidate block, or `None` if the vote is not for one of our
+    /// candidate blocks.
+    pub candidate_block_number: Option<u64>,
+}
+
+/// Diagnostics for `Eth1Data` voting in the current voting period.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Eth1VotingStatusData {
+    pub voting_period_start_slot: Slot,
+    pub voting_period_start_timestamp: u64,
+    /// Eth1 blocks with a timestamp in this (inclusive) range are candidates for the vote.
+    pub earliest_candidate_timestamp: u64,
+    pub latest_candidate_timestamp: u64,
+    /// The candidate blocks in the eth1 cache, in ascending order.
+    pub candidate_blocks: Vec<Eth1Block>,
+    /// The votes in the state, from most to least popular.
+    pub votes: Vec<Eth1DataVoteTally>,
+    /// The number of votes required for an `Eth1Data` to be adopted.
+    pub votes_required: u64,
+    /// The vote this node would cast in a block produced at the current slot.
+    pub our_vote: Eth1Data,
+    pub our_vote_reason: Eth1VoteReason,
+    /// The most popular vote in the state, if any.
+    pub leading_vote: Option<Eth1Data>,
+    /// `true` if there is a leading vote and it differs from `our_vote`.
+    pub diverges_from_leading_vote: bool,
+}
+
 impl BeaconNodeHttpClient {
     /// Perform a HTTP GET request, returning `None` on a 404 error.
     async fn get_bytes_opt<U: IntoUrl>(&self, url: U) -> Result<Option<Vec<u8>>, Error> {
@@ -291,6 +506,54 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/validator_rewards/{epoch}?id`
+    ///
+    /// Returns the rewards of all validators if `ids` is `None`.
+    pub async fn get_lighthouse_validator_rewards(
+        &self,
+        epoch: Epoch,
+        ids: Option<&[ValidatorId]>,
+    ) -> Result<GenericResponse<Vec<ValidatorRewardsData>>, Error> {
+        let mut path = self.server.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validator_rewards")
+            .push(&epoch.to_string());
+
+        if let Some(ids) = ids {
+            let id_string = ids
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            path.query_pairs_mut().append_pair("id", &id_string);
+        }
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/block_rewards?start_slot,end_slot`
+    pub async fn get_lighthouse_block_rewards(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+    ) -> Result<GenericResponse<Vec<BlockRewardsData>>, Error> {
+        let mut path = self.server.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("block_rewards");
+
+        path.query_pairs_mut()
+            .append_pair("start_slot", &start_slot.to_string())
+            .append_pair("end_slot", &end_slot.to_string());
+
+        self.get(path).await
+    }
+
     /// `GET lighthouse/eth1/syncing`
     pub async fn get_lighthouse_eth1_syncing(
         &self,
@@ -336,6 +599,38 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/eth1/voting`
+    pub async fn get_lighthouse_eth1_voting(
+        &self,
+    ) -> Result<GenericResponse<Eth1VotingStatusData>, Error> {
+        let mut path = self.server.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("eth1")
+            .push("voting");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/eth1/deposit_snapshot`
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_lighthouse_eth1_deposit_snapshot(
+        &self,
+    ) -> Result<Option<GenericResponse<DepositTreeSnapshot>>, Error> {
+        let mut path = self.server.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("eth1")
+            .push("deposit_snapshot");
+
+        self.get_opt(path).await
+    }
+
     /// `GET lighthouse/beacon/states/{state_id}/ssz`
     pub async fn get_lighthouse_beacon_states_ssz<E: EthSpec>(
         &self,
@@ -357,6 +652,83 @@ impl BeaconNodeHttpClient {
             .transpose()
     }
 
+    /// `GET lighthouse/beacon/states/{state_id}/proof?gindex,path`
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_lighthouse_beacon_states_proof(
+        &self,
+        state_id: StateId,
+        gindex: Option<u64>,
+        path: Option<&str>,
+    ) -> Result<Option<GenericResponse<BeaconStateMerkleProof>>, Error> {
+        let mut url = self.server.clone();
+
+        url.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("beacon")
+            .push("states")
+            .push(&state_id.to_string())
+            .push("proof");
+
+        if let Some(gindex) = gindex {
+            url.query_pairs_mut()
+                .append_pair("gindex", &gindex.to_string());
+        }
+
+        if let Some(path) = path {
+            url.query_pairs_mut().append_pair("path", path);
+        }
+
+        self.get_opt(url).await
+    }
+
+    /// `GET lighthouse/gossip/trace?kind,slot,peer_id`
+    ///
+    /// Returns `Ok(None)` on a 404 error, which indicates that gossip tracing is disabled.
+    pub async fn get_lighthouse_gossip_trace(
+        &self,
+        query: &GossipTraceQuery,
+    ) -> Result<Option<GenericResponse<Vec<GossipMessageTrace>>>, Error> {
+        let mut path = self.server.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("gossip")
+            .push("trace");
+
+        if let Some(kind) = query.kind {
+            path.query_pairs_mut()
+                .append_pair("kind", &kind.to_string());
+        }
+
+        if let Some(slot) = query.slot {
+            path.query_pairs_mut()
+                .append_pair("slot", &slot.to_string());
+        }
+
+        if let Some(peer_id) = &query.peer_id {
+            path.query_pairs_mut().append_pair("peer_id", peer_id);
+        }
+
+        self.get_opt(path).await
+    }
+
+    /// `GET lighthouse/late_blocks`
+    pub async fn get_lighthouse_late_blocks(
+        &self,
+    ) -> Result<GenericResponse<Vec<LateBlock>>, Error> {
+        let mut path = self.server.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("late_blocks");
+
+        self.get(path).await
+    }
+
     /// `GET lighthouse/staking`
     pub async fn get_lighthouse_staking(&self) -> Result<bool, Error> {
         let mut path = self.server.clone();
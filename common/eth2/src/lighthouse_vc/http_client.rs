@@ -314,6 +314,24 @@ impl ValidatorClientHttpClient {
 
         self.patch(path, &ValidatorPatchRequest { enabled }).await
     }
+
+    /// `POST lighthouse/validators/{validator_pubkey}/reencrypt`
+    pub async fn post_lighthouse_validators_reencrypt(
+        &self,
+        voting_pubkey: &PublicKeyBytes,
+        request: &ValidatorReencryptRequest,
+    ) -> Result<(), Error> {
+        let mut path = self.server.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validators")
+            .push(&voting_pubkey.to_string())
+            .push("reencrypt");
+
+        self.post(path, request).await
+    }
 }
 
 /// Returns `Ok(response)` if the response is a `200 OK` response. Otherwise, creates an
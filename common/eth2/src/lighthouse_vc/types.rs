@@ -1,5 +1,5 @@
-use account_utils::ZeroizeString;
-use eth2_keystore::Keystore;
+use account_utils::{eth2_wallet::ValidatorPathTemplate, ZeroizeString};
+use eth2_keystore::{json_keystore::KdfFunction, Keystore};
 use serde::{Deserialize, Serialize};
 
 pub use crate::lighthouse::Health;
@@ -26,6 +26,15 @@ pub struct CreateValidatorsMnemonicRequest {
     pub mnemonic: ZeroizeString,
     #[serde(with = "serde_utils::quoted_u32")]
     pub key_derivation_path_offset: u32,
+    /// The EIP-2334 path of the voting keys, with the key index replaced by `i`.
+    #[serde(default)]
+    pub key_derivation_path_template: ValidatorPathTemplate,
+    /// If non-empty, the voting public key of each of the `validators`. The keys are searched for
+    /// among `key_derivation_path_search_count` indices from `key_derivation_path_offset`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub voting_pubkeys: Vec<PublicKeyBytes>,
+    #[serde(default, with = "serde_utils::quoted_u32")]
+    pub key_derivation_path_search_count: u32,
     pub validators: Vec<ValidatorRequest>,
 }
 
@@ -56,3 +65,11 @@ pub struct KeystoreValidatorsPostRequest {
     pub enable: bool,
     pub keystore: Keystore,
 }
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorReencryptRequest {
+    pub password: ZeroizeString,
+    /// The KDF for the new keystore, defaults to scrypt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kdf: Option<KdfFunction>,
+}
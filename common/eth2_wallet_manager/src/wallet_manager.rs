@@ -118,17 +118,37 @@ impl WalletManager {
     /// - If a wallet with this name already exists.
     /// - If there is a file-system or parsing error.
     pub fn create_wallet(
+        &self,
+        name: String,
+        wallet_type: WalletType,
+        mnemonic: &Mnemonic,
+        password: &[u8],
+    ) -> Result<LockedWallet, Error> {
+        self.create_wallet_with_passphrase(name, wallet_type, mnemonic, "", password)
+    }
+
+    /// Creates a new wallet with the given `name` in `self.dir` with the given `mnemonic` and
+    /// BIP-39 `passphrase` as a seed, encrypted with `password`.
+    ///
+    /// ## Errors
+    ///
+    /// - If a wallet with this name already exists.
+    /// - If there is a file-system or parsing error.
+    pub fn create_wallet_with_passphrase(
         &self,
         name: String,
         _wallet_type: WalletType,
         mnemonic: &Mnemonic,
+        passphrase: &str,
         password: &[u8],
     ) -> Result<LockedWallet, Error> {
         if self.wallets()?.contains_key(&name) {
             return Err(Error::NameAlreadyTaken(name));
         }
 
-        let wallet = WalletBuilder::from_mnemonic(mnemonic, password, name)?.build()?;
+        let wallet =
+            WalletBuilder::from_mnemonic_with_passphrase(mnemonic, passphrase, password, name)?
+                .build()?;
         let uuid = *wallet.uuid();
 
         let wallet_dir = self.dir.join(format!("{}", uuid));
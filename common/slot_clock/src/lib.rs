@@ -59,6 +59,9 @@ pub trait SlotClock: Send + Sync + Sized {
     /// Returns the duration until the first slot of the next epoch.
     fn duration_to_next_epoch(&self, slots_per_epoch: u64) -> Option<Duration>;
 
+    /// Returns the duration between the UNIX epoch and the start of `slot`.
+    fn start_of(&self, slot: Slot) -> Option<Duration>;
+
     /// Returns the first slot to be returned at the genesis time.
     fn genesis_slot(&self) -> Slot;
 
@@ -1,5 +1,4 @@
 use crate::{Error as DirError, ValidatorDir};
-use bls::get_withdrawal_credentials;
 use deposit_contract::{encode_eth1_tx_data, Error as DepositError};
 use eth2_keystore::{Error as KeystoreError, Keystore, KeystoreBuilder, PlainText};
 use rand::{distributions::Alphanumeric, Rng};
@@ -7,7 +6,10 @@ use std::fs::{create_dir_all, File, OpenOptions};
 use std::io::{self, Write};
 use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
-use types::{ChainSpec, DepositData, Hash256, Keypair, Signature};
+use types::{
+    Address, ChainSpec, DepositData, Keypair, Signature, WithdrawalCredentials,
+    WithdrawalCredentialsType,
+};
 
 /// The `Alphanumeric` crate only generates a-z, A-Z, 0-9, therefore it has a range of 62
 /// characters.
@@ -20,6 +22,8 @@ pub const VOTING_KEYSTORE_FILE: &str = "voting-keystore.json";
 pub const WITHDRAWAL_KEYSTORE_FILE: &str = "withdrawal-keystore.json";
 pub const ETH1_DEPOSIT_DATA_FILE: &str = "eth1-deposit-data.rlp";
 pub const ETH1_DEPOSIT_AMOUNT_FILE: &str = "eth1-deposit-gwei.txt";
+pub const ETH1_DEPOSIT_WITHDRAWAL_CREDENTIALS_FILE: &str =
+    "eth1-deposit-withdrawal-credentials.txt";
 
 #[derive(Debug)]
 pub enum Error {
@@ -30,6 +34,8 @@ pub enum Error {
     UnableToSaveDepositData(io::Error),
     DepositAmountAlreadyExists(PathBuf),
     UnableToSaveDepositAmount(io::Error),
+    WithdrawalCredentialsAlreadyExists(PathBuf),
+    UnableToSaveWithdrawalCredentials(io::Error),
     KeystoreAlreadyExists(PathBuf),
     UnableToSaveKeystore(io::Error),
     PasswordAlreadyExists(PathBuf),
@@ -56,6 +62,7 @@ pub struct Builder<'a> {
     pub(crate) voting_keystore: Option<(Keystore, PlainText)>,
     pub(crate) withdrawal_keystore: Option<(Keystore, PlainText)>,
     store_withdrawal_keystore: bool,
+    withdrawal_address: Option<Address>,
     deposit_info: Option<(u64, &'a ChainSpec)>,
 }
 
@@ -68,6 +75,7 @@ impl<'a> Builder<'a> {
             voting_keystore: None,
             withdrawal_keystore: None,
             store_withdrawal_keystore: true,
+            withdrawal_address: None,
             deposit_info: None,
         }
     }
@@ -116,6 +124,15 @@ impl<'a> Builder<'a> {
         self
     }
 
+    /// Upon build, use `0x01` withdrawal credentials committing to the given Eth1 `address` for
+    /// the deposit, rather than BLS credentials derived from the withdrawal keystore.
+    ///
+    /// A withdrawal keystore is not required to create a deposit when an address is supplied.
+    pub fn eth1_withdrawal_address(mut self, address: Address) -> Self {
+        self.withdrawal_address = Some(address);
+        self
+    }
+
     /// If `should_store == true`, the validator keystore will be saved in the `ValidatorDir` (and
     /// the password to it stored in the `password_dir`). If `should_store == false`, the
     /// withdrawal keystore will be dropped after `Self::build`.
@@ -151,77 +168,109 @@ impl<'a> Builder<'a> {
         }
 
         // The withdrawal keystore must be initialized in order to store it or create an eth1
-        // deposit.
-        if (self.store_withdrawal_keystore || self.deposit_info.is_some())
-            && self.withdrawal_keystore.is_none()
-        {
+        // deposit with BLS withdrawal credentials.
+        let withdrawal_keypair_required = self.store_withdrawal_keystore
+            || (self.deposit_info.is_some() && self.withdrawal_address.is_none());
+        if withdrawal_keypair_required && self.withdrawal_keystore.is_none() {
             return Err(Error::UninitializedWithdrawalKeystore);
         };
 
-        if let Some((withdrawal_keystore, withdrawal_password)) = self.withdrawal_keystore {
+        // Attempt to decrypt the withdrawal keypair, only if it is required.
+        let withdrawal_keypair = self
+            .withdrawal_keystore
+            .as_ref()
+            .filter(|_| withdrawal_keypair_required)
+            .map(|(keystore, password)| keystore.decrypt_keypair(password.as_bytes()))
+            .transpose()?;
+
+        // If a deposit amount was specified, create a deposit.
+        if let Some((amount, spec)) = self.deposit_info {
             // Attempt to decrypt the voting keypair.
             let voting_keypair = voting_keystore.decrypt_keypair(voting_password.as_bytes())?;
 
-            // Attempt to decrypt the withdrawal keypair.
-            let withdrawal_keypair =
-                withdrawal_keystore.decrypt_keypair(withdrawal_password.as_bytes())?;
-
-            // If a deposit amount was specified, create a deposit.
-            if let Some((amount, spec)) = self.deposit_info {
-                let withdrawal_credentials = Hash256::from_slice(&get_withdrawal_credentials(
-                    &withdrawal_keypair.pk,
-                    spec.bls_withdrawal_prefix_byte,
-                ));
-
-                let mut deposit_data = DepositData {
-                    pubkey: voting_keypair.pk.clone().into(),
-                    withdrawal_credentials,
-                    amount,
-                    signature: Signature::empty().into(),
+            let (withdrawal_credentials, withdrawal_credentials_type) =
+                match (self.withdrawal_address, &withdrawal_keypair) {
+                    (Some(address), _) => (
+                        WithdrawalCredentials::eth1(address, spec),
+                        WithdrawalCredentialsType::Eth1Address(address),
+                    ),
+                    (None, Some(keypair)) => (
+                        WithdrawalCredentials::bls(&keypair.pk, spec),
+                        WithdrawalCredentialsType::Bls,
+                    ),
+                    (None, None) => return Err(Error::UninitializedWithdrawalKeystore),
                 };
 
-                deposit_data.signature = deposit_data.create_signature(&voting_keypair.sk, &spec);
-
-                let deposit_data =
-                    encode_eth1_tx_data(&deposit_data).map_err(Error::UnableToEncodeDeposit)?;
-
-                // Save `ETH1_DEPOSIT_DATA_FILE` to file.
-                //
-                // This allows us to know the RLP data for the eth1 transaction without needing to know
-                // the withdrawal/voting keypairs again at a later date.
-                let path = dir.join(ETH1_DEPOSIT_DATA_FILE);
-                if path.exists() {
-                    return Err(Error::DepositDataAlreadyExists(path));
-                } else {
-                    let hex = format!("0x{}", hex::encode(&deposit_data));
-                    OpenOptions::new()
-                        .write(true)
-                        .read(true)
-                        .create(true)
-                        .open(path)
-                        .map_err(Error::UnableToSaveDepositData)?
-                        .write_all(hex.as_bytes())
-                        .map_err(Error::UnableToSaveDepositData)?
-                }
+            let mut deposit_data = DepositData {
+                pubkey: voting_keypair.pk.clone().into(),
+                withdrawal_credentials: withdrawal_credentials.into(),
+                amount,
+                signature: Signature::empty().into(),
+            };
+
+            deposit_data.signature = deposit_data.create_signature(&voting_keypair.sk, &spec);
+
+            let deposit_data =
+                encode_eth1_tx_data(&deposit_data).map_err(Error::UnableToEncodeDeposit)?;
+
+            // Save `ETH1_DEPOSIT_DATA_FILE` to file.
+            //
+            // This allows us to know the RLP data for the eth1 transaction without needing to know
+            // the withdrawal/voting keypairs again at a later date.
+            let path = dir.join(ETH1_DEPOSIT_DATA_FILE);
+            if path.exists() {
+                return Err(Error::DepositDataAlreadyExists(path));
+            } else {
+                let hex = format!("0x{}", hex::encode(&deposit_data));
+                OpenOptions::new()
+                    .write(true)
+                    .read(true)
+                    .create(true)
+                    .open(path)
+                    .map_err(Error::UnableToSaveDepositData)?
+                    .write_all(hex.as_bytes())
+                    .map_err(Error::UnableToSaveDepositData)?
+            }
 
-                // Save `ETH1_DEPOSIT_AMOUNT_FILE` to file.
-                //
-                // This allows us to know the intended deposit amount at a later date.
-                let path = dir.join(ETH1_DEPOSIT_AMOUNT_FILE);
-                if path.exists() {
-                    return Err(Error::DepositAmountAlreadyExists(path));
-                } else {
-                    OpenOptions::new()
-                        .write(true)
-                        .read(true)
-                        .create(true)
-                        .open(path)
-                        .map_err(Error::UnableToSaveDepositAmount)?
-                        .write_all(format!("{}", amount).as_bytes())
-                        .map_err(Error::UnableToSaveDepositAmount)?
-                }
+            // Save `ETH1_DEPOSIT_AMOUNT_FILE` to file.
+            //
+            // This allows us to know the intended deposit amount at a later date.
+            let path = dir.join(ETH1_DEPOSIT_AMOUNT_FILE);
+            if path.exists() {
+                return Err(Error::DepositAmountAlreadyExists(path));
+            } else {
+                OpenOptions::new()
+                    .write(true)
+                    .read(true)
+                    .create(true)
+                    .open(path)
+                    .map_err(Error::UnableToSaveDepositAmount)?
+                    .write_all(format!("{}", amount).as_bytes())
+                    .map_err(Error::UnableToSaveDepositAmount)?
+            }
+
+            // Save `ETH1_DEPOSIT_WITHDRAWAL_CREDENTIALS_FILE` to file.
+            //
+            // This allows the kind of withdrawal credentials in the deposit to be checked without
+            // decoding `ETH1_DEPOSIT_DATA_FILE`.
+            let path = dir.join(ETH1_DEPOSIT_WITHDRAWAL_CREDENTIALS_FILE);
+            if path.exists() {
+                return Err(Error::WithdrawalCredentialsAlreadyExists(path));
+            } else {
+                OpenOptions::new()
+                    .write(true)
+                    .read(true)
+                    .create(true)
+                    .open(path)
+                    .map_err(Error::UnableToSaveWithdrawalCredentials)?
+                    .write_all(format!("{}", withdrawal_credentials_type).as_bytes())
+                    .map_err(Error::UnableToSaveWithdrawalCredentials)?
             }
+        }
 
+        if let (Some((withdrawal_keystore, withdrawal_password)), Some(withdrawal_keypair)) =
+            (self.withdrawal_keystore, withdrawal_keypair)
+        {
             if self.password_dir.is_none() && self.store_withdrawal_keystore {
                 return Err(Error::MissingPasswordDir);
             }
@@ -4,10 +4,13 @@ use eth2_keystore::{Keystore, KeystoreBuilder, PlainText};
 use std::fs::{self, File};
 use std::path::Path;
 use tempfile::{tempdir, TempDir};
-use types::{test_utils::generate_deterministic_keypair, EthSpec, Keypair, MainnetEthSpec};
+use types::{
+    test_utils::generate_deterministic_keypair, Address, EthSpec, Keypair, MainnetEthSpec,
+    WithdrawalCredentials, WithdrawalCredentialsType,
+};
 use validator_dir::{
     Builder, BuilderError, ValidatorDir, ETH1_DEPOSIT_DATA_FILE, ETH1_DEPOSIT_TX_HASH_FILE,
-    VOTING_KEYSTORE_FILE, WITHDRAWAL_KEYSTORE_FILE,
+    ETH1_DEPOSIT_WITHDRAWAL_CREDENTIALS_FILE, VOTING_KEYSTORE_FILE, WITHDRAWAL_KEYSTORE_FILE,
 };
 
 /// A very weak password with which to encrypt the keystores.
@@ -152,6 +155,16 @@ impl Harness {
                     .unwrap();
             assert!(hex.starts_with("0x"), "deposit data should have 0x prefix");
             hex::decode(&hex[2..]).unwrap();
+
+            assert_eq!(
+                fs::read_to_string(
+                    validator
+                        .dir()
+                        .join(ETH1_DEPOSIT_WITHDRAWAL_CREDENTIALS_FILE)
+                )
+                .unwrap(),
+                "BLS"
+            );
         } else {
             // If there was no deposit then we should return `Ok(None)`.
             assert!(validator.eth1_deposit_data().unwrap().is_none());
@@ -375,3 +388,50 @@ fn store_withdrawal_keystore_with_eth1_data() {
 
     harness.create_and_test(&config);
 }
+
+#[test]
+fn eth1_withdrawal_address() {
+    let harness = Harness::new();
+    let spec = &MainnetEthSpec::default_spec();
+    let address = Address::repeat_byte(42);
+
+    // Should create deposit data without decrypting the withdrawal keystore.
+    let withdrawal_keystore = KeystoreBuilder::new(
+        &generate_deterministic_keypair(1),
+        INSECURE_PASSWORD,
+        "".into(),
+    )
+    .unwrap()
+    .build()
+    .unwrap();
+    let validator = Builder::new(harness.validators_dir.path().into())
+        .password_dir(harness.password_dir.path())
+        .random_voting_keystore()
+        .unwrap()
+        .withdrawal_keystore(withdrawal_keystore, b"incorrect password")
+        .eth1_withdrawal_address(address)
+        .store_withdrawal_keystore(false)
+        .create_eth1_tx_data(32000000000, spec)
+        .build()
+        .unwrap();
+
+    assert!(!validator.dir().join(WITHDRAWAL_KEYSTORE_FILE).exists());
+    assert_eq!(
+        fs::read_to_string(
+            validator
+                .dir()
+                .join(ETH1_DEPOSIT_WITHDRAWAL_CREDENTIALS_FILE)
+        )
+        .unwrap(),
+        format!("{}", WithdrawalCredentialsType::Eth1Address(address))
+    );
+
+    let data = validator.eth1_deposit_data().unwrap().unwrap();
+    let credentials = WithdrawalCredentials::from(data.deposit_data.withdrawal_credentials);
+
+    assert_eq!(credentials, WithdrawalCredentials::eth1(address, spec));
+    assert_eq!(
+        credentials.credentials_type(spec),
+        Ok(WithdrawalCredentialsType::Eth1Address(address))
+    );
+}
@@ -1,10 +1,10 @@
 use crate::cache_arena;
 use crate::SmallVec8;
 use crate::{Error, Hash256};
-use eth2_hashing::{hash32_concat, ZERO_HASHES};
+use eth2_hashing::{hash32_concat_batch, ZERO_HASHES};
 use smallvec::smallvec;
 use ssz_derive::{Decode, Encode};
-use tree_hash::BYTES_PER_CHUNK;
+use tree_hash::{BYTES_PER_CHUNK, MERKLE_HASH_CHUNK};
 
 type CacheArena = cache_arena::CacheArena<Hash256>;
 type CacheArenaAllocation = cache_arena::CacheArenaAllocation<Hash256>;
@@ -109,6 +109,9 @@ impl TreeHashCache {
         while depth > 0 {
             let new_dirty_indices = lift_dirty(&dirty_indices);
 
+            // Gather the children of all dirty parents, so they can be hashed in a single batch.
+            let mut children = Vec::with_capacity(new_dirty_indices.len() * MERKLE_HASH_CHUNK);
+
             for &idx in &new_dirty_indices {
                 let left_idx = 2 * idx;
                 let right_idx = left_idx + 1;
@@ -121,18 +124,27 @@ impl TreeHashCache {
                     .copied()
                     .unwrap_or_else(|| Hash256::from_slice(&ZERO_HASHES[self.depth - depth]));
 
-                let new_hash = hash32_concat(left.as_bytes(), right.as_bytes());
+                children.extend_from_slice(left.as_bytes());
+                children.extend_from_slice(right.as_bytes());
+            }
+
+            let mut new_hashes = vec![0; new_dirty_indices.len() * BYTES_PER_CHUNK];
+            hash32_concat_batch(&children, &mut new_hashes);
 
+            for (&idx, new_hash) in new_dirty_indices
+                .iter()
+                .zip(new_hashes.chunks_exact(BYTES_PER_CHUNK))
+            {
                 match self.layers[depth - 1].get_mut(arena, idx)? {
                     Some(hash) => {
-                        hash.assign_from_slice(&new_hash);
+                        hash.assign_from_slice(new_hash);
                     }
                     None => {
                         // Parent layer should already contain nodes for all non-dirty indices
                         if idx != self.layers[depth - 1].len(arena)? {
                             return Err(Error::CacheInconsistent);
                         }
-                        self.layers[depth - 1].push(arena, Hash256::from_slice(&new_hash))?;
+                        self.layers[depth - 1].push(arena, Hash256::from_slice(new_hash))?;
                     }
                 }
             }
@@ -158,6 +170,53 @@ impl TreeHashCache {
     pub fn leaves(&mut self) -> &mut CacheArenaAllocation {
         &mut self.layers[self.depth]
     }
+
+    /// Returns the depth of the tree, such that it has capacity for `2^depth` leaves.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Return the leaf at `index` and a Merkle proof of its inclusion, without doing any
+    /// updates/computation.
+    ///
+    /// The proof is in "bottom-up" order and its length is exactly equal to `self.depth()`. Leaves
+    /// and internal nodes which are not stored in the sparse cache are zero hashes.
+    ///
+    /// Returns an error if the cache has leaves but has not yet been used to compute a root.
+    pub fn generate_proof(
+        &self,
+        arena: &CacheArena,
+        index: usize,
+    ) -> Result<(Hash256, Vec<Hash256>), Error> {
+        // A cache without leaves is never marked as initialized, but its (all-zero) tree is valid.
+        if !self.initialized && !self.layers[self.depth].is_empty(arena)? {
+            return Err(Error::CacheNotInitialized);
+        } else if index
+            >= 1_usize
+                .checked_shl(self.depth as u32)
+                .unwrap_or(usize::max_value())
+        {
+            return Err(Error::ProofIndexOutOfBounds(index));
+        }
+
+        let node_or_zero = |layer: usize, i: usize| -> Result<Hash256, Error> {
+            Ok(self.layers[layer]
+                .get(arena, i)?
+                .copied()
+                .unwrap_or_else(|| Hash256::from_slice(&ZERO_HASHES[self.depth - layer])))
+        };
+
+        let leaf = node_or_zero(self.depth, index)?;
+
+        let mut proof = Vec::with_capacity(self.depth);
+        let mut i = index;
+        for layer in (1..=self.depth).rev() {
+            proof.push(node_or_zero(layer, i ^ 1)?);
+            i /= 2;
+        }
+
+        Ok((leaf, proof))
+    }
 }
 
 /// Compute the dirty indices for one layer up.
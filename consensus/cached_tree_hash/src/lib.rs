@@ -25,6 +25,10 @@ pub enum Error {
     CacheArenaError(cache_arena::Error),
     /// Unable to find left index in Merkle tree.
     MissingLeftIdx(usize),
+    /// Unable to produce a proof from a cache which has never computed a root.
+    CacheNotInitialized,
+    /// The leaf index of a proof is not within the capacity of the tree.
+    ProofIndexOutOfBounds(usize),
 }
 
 impl From<cache_arena::Error> for Error {
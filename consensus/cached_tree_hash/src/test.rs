@@ -1,6 +1,7 @@
 use crate::impls::hash256_iter;
 use crate::{CacheArena, CachedTreeHash, Error, Hash256, TreeHashCache};
 use eth2_hashing::ZERO_HASHES;
+use merkle_proof::verify_merkle_proof;
 use quickcheck_macros::quickcheck;
 use ssz_types::{
     typenum::{Unsigned, U16, U255, U256, U257},
@@ -151,3 +152,53 @@ fn variable_list_h256_test<Len: Unsigned>(leaves_and_skips: Vec<(u64, bool)>) ->
     }
     true
 }
+
+#[test]
+fn proof_requires_initialized_cache() {
+    let arena = &mut CacheArena::default();
+    let vec = Vector16::new(int_hashes(0, 16)).unwrap();
+    let cache = vec.new_tree_hash_cache(arena);
+
+    assert_eq!(
+        cache.generate_proof(arena, 0),
+        Err(Error::CacheNotInitialized)
+    );
+}
+
+#[test]
+fn proof_index_out_of_bounds() {
+    let arena = &mut CacheArena::default();
+    let vec = Vector16::new(int_hashes(0, 16)).unwrap();
+    let mut cache = vec.new_tree_hash_cache(arena);
+    vec.recalculate_tree_hash_root(arena, &mut cache).unwrap();
+
+    assert_eq!(
+        cache.generate_proof(arena, 16),
+        Err(Error::ProofIndexOutOfBounds(16))
+    );
+}
+
+#[quickcheck]
+fn quickcheck_generate_proof(int_leaves: Vec<u64>) -> bool {
+    let arena = &mut CacheArena::default();
+    let leaves: Vec<_> = int_leaves
+        .into_iter()
+        .map(Hash256::from_low_u64_be)
+        .take(U256::to_usize())
+        .collect();
+    let list: VariableList<Hash256, U256> = VariableList::new(leaves.clone()).unwrap();
+
+    let mut cache = list.new_tree_hash_cache(arena);
+    list.recalculate_tree_hash_root(arena, &mut cache).unwrap();
+    let root = cache.root(arena);
+    let depth = cache.depth();
+
+    // Check the populated leaves, as well as a zero leaf past the end of the list.
+    (0..=leaves.len())
+        .filter(|i| *i < U256::to_usize())
+        .all(|i| {
+            let (leaf, proof) = cache.generate_proof(arena, i).unwrap();
+            let expected_leaf = leaves.get(i).copied().unwrap_or_else(Hash256::zero);
+            leaf == expected_leaf && verify_merkle_proof(leaf, &proof, depth, i, root)
+        })
+}
@@ -3,18 +3,23 @@ use std::marker::PhantomData;
 use proto_array::{Block as ProtoBlock, ProtoArrayForkChoice};
 use ssz_derive::{Decode, Encode};
 use types::{
-    BeaconBlock, BeaconState, BeaconStateError, Checkpoint, Epoch, EthSpec, Hash256,
+    BeaconBlock, BeaconState, BeaconStateError, ChainSpec, Checkpoint, Epoch, EthSpec, Hash256,
     IndexedAttestation, RelativeEpoch, ShufflingId, Slot,
 };
 
 use crate::ForkChoiceStore;
 use std::cmp::Ordering;
+use std::time::Duration;
 
 /// Defined here:
 ///
 /// https://github.com/ethereum/eth2.0-specs/blob/v0.12.1/specs/phase0/fork-choice.md#configuration
 pub const SAFE_SLOTS_TO_UPDATE_JUSTIFIED: u64 = 8;
 
+/// The number of intervals in a slot. Attestations are due at the end of the first interval, so a
+/// block must arrive within it to receive the proposer boost.
+pub const INTERVALS_PER_SLOT: u64 = 3;
+
 #[derive(Debug)]
 pub enum Error<T> {
     InvalidAttestation(InvalidAttestation),
@@ -150,6 +155,12 @@ where
     store.set_current_slot(time);
 
     let current_slot = store.get_current_slot();
+
+    // Reset the proposer boost at the start of each new slot.
+    if current_slot > previous_slot {
+        store.set_proposer_boost_root(Hash256::zero());
+    }
+
     if !(current_slot > previous_slot && compute_slots_since_epoch_start::<E>(current_slot) == 0) {
         return Ok(());
     }
@@ -335,22 +346,31 @@ where
 
     /// Run the fork choice rule to determine the head.
     ///
+    /// The block identified by `ForkChoiceStore::proposer_boost_root` (if any) receives the
+    /// proposer boost described by `spec.proposer_score_boost`.
+    ///
     /// ## Specification
     ///
     /// Is equivalent to:
     ///
     /// https://github.com/ethereum/eth2.0-specs/blob/v0.12.1/specs/phase0/fork-choice.md#get_head
-    pub fn get_head(&mut self, current_slot: Slot) -> Result<Hash256, Error<T::Error>> {
+    pub fn get_head(
+        &mut self,
+        current_slot: Slot,
+        spec: &ChainSpec,
+    ) -> Result<Hash256, Error<T::Error>> {
         self.update_time(current_slot)?;
 
         let store = &mut self.fc_store;
 
         self.proto_array
-            .find_head(
+            .find_head::<E>(
                 store.justified_checkpoint().epoch,
                 store.justified_checkpoint().root,
                 store.finalized_checkpoint().epoch,
                 store.justified_balances(),
+                store.proposer_boost_root(),
+                spec,
             )
             .map_err(Into::into)
     }
@@ -409,6 +429,7 @@ where
     /// Add `block` to the fork choice DAG.
     ///
     /// - `block_root` is the root of `block.
+    /// - `block_delay` is the time between the start of `block.slot` and the arrival of `block`.
     /// - The root of `state` matches `block.state_root`.
     ///
     /// ## Specification
@@ -429,7 +450,9 @@ where
         current_slot: Slot,
         block: &BeaconBlock<E>,
         block_root: Hash256,
+        block_delay: Duration,
         state: &BeaconState<E>,
+        spec: &ChainSpec,
     ) -> Result<(), Error<T::Error>> {
         let current_slot = self.update_time(current_slot)?;
 
@@ -480,6 +503,13 @@ where
             }));
         }
 
+        // Boost the block if it arrived in its own slot, prior to the attestation deadline.
+        let attestation_deadline =
+            Duration::from_millis(spec.milliseconds_per_slot / INTERVALS_PER_SLOT);
+        if current_slot == block.slot && block_delay < attestation_deadline {
+            self.fc_store.set_proposer_boost_root(block_root);
+        }
+
         // Update justified checkpoint.
         if state.current_justified_checkpoint.epoch > self.fc_store.justified_checkpoint().epoch {
             if state.current_justified_checkpoint.epoch
@@ -58,4 +58,10 @@ pub trait ForkChoiceStore<T: EthSpec>: Sized {
 
     /// Sets the `best_justified_checkpoint`.
     fn set_best_justified_checkpoint(&mut self, checkpoint: Checkpoint);
+
+    /// Returns the `proposer_boost_root`, or `Hash256::zero()` if no block is boosted.
+    fn proposer_boost_root(&self) -> Hash256;
+
+    /// Sets the `proposer_boost_root`.
+    fn set_proposer_boost_root(&mut self, proposer_boost_root: Hash256);
 }
@@ -11,6 +11,7 @@ use fork_choice::{
 };
 use std::fmt;
 use std::sync::Mutex;
+use std::time::Duration;
 use store::{MemoryStore, StoreConfig};
 use types::{
     test_utils::{generate_deterministic_keypair, generate_deterministic_keypairs},
@@ -266,7 +267,14 @@ impl ForkChoiceTest {
             .chain
             .fork_choice
             .write()
-            .on_block(current_slot, &block.message, block.canonical_root(), &state)
+            .on_block(
+                current_slot,
+                &block.message,
+                block.canonical_root(),
+                Duration::from_secs(0),
+                &state,
+                &self.harness.chain.spec,
+            )
             .unwrap();
         self
     }
@@ -300,7 +308,14 @@ impl ForkChoiceTest {
             .chain
             .fork_choice
             .write()
-            .on_block(current_slot, &block.message, block.canonical_root(), &state)
+            .on_block(
+                current_slot,
+                &block.message,
+                block.canonical_root(),
+                Duration::from_secs(0),
+                &state,
+                &self.harness.chain.spec,
+            )
             .err()
             .expect("on_block did not return an error");
         comparison_func(err);
@@ -635,12 +650,7 @@ fn invalid_block_future_slot() {
             |block, _| {
                 block.slot = block.slot + 1;
             },
-            |err| {
-                assert_invalid_block!(
-                    err,
-                    InvalidBlock::FutureSlot { .. }
-                )
-            },
+            |err| assert_invalid_block!(err, InvalidBlock::FutureSlot { .. }),
         );
 }
 
@@ -27,6 +27,10 @@ pub enum MerkleTree {
     ///
     /// It represents a Merkle tree of 2^depth zero leaves.
     Zero(usize),
+    /// Finalized subtree with the hash of its root.
+    ///
+    /// It represents a full subtree whose leaves and internal nodes have been pruned.
+    Finalized(H256),
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -41,6 +45,12 @@ pub enum MerkleTreeError {
     DepthTooSmall,
     // Overflow occurred
     ArithError,
+    // A proof was requested for a leaf within a finalized subtree
+    ProofEncounteredFinalizedNode,
+    // Trying to finalize more leaves than the MerkleTree contains
+    FinalizedNodesExceedLeaves,
+    // The finalized branch does not match the deposit count
+    InvalidFinalizedSnapshot,
 }
 
 impl MerkleTree {
@@ -89,6 +99,7 @@ impl MerkleTree {
 
         match self {
             Leaf(_) => return Err(MerkleTreeError::LeafReached),
+            Finalized(_) => return Err(MerkleTreeError::MerkleTreeFull),
             Zero(_) => {
                 *self = MerkleTree::create(&[elem], depth);
             }
@@ -97,9 +108,11 @@ impl MerkleTree {
                 let right: &mut MerkleTree = &mut *right;
                 match (&*left, &*right) {
                     // Tree is full
-                    (Leaf(_), Leaf(_)) => return Err(MerkleTreeError::MerkleTreeFull),
+                    (Leaf(_), Leaf(_)) | (Finalized(_), Leaf(_)) => {
+                        return Err(MerkleTreeError::MerkleTreeFull)
+                    }
                     // There is a right node so insert in right node
-                    (Node(_, _, _), Node(_, _, _)) => {
+                    (Node(_, _, _), Node(_, _, _)) | (Finalized(_), Node(_, _, _)) => {
                         if let Err(e) = right.push_leaf(elem, depth - 1) {
                             return Err(e);
                         }
@@ -108,8 +121,9 @@ impl MerkleTree {
                     (Zero(_), Zero(_)) => {
                         *left = MerkleTree::create(&[elem], depth - 1);
                     }
-                    // Leaf on left branch and zero on right branch, insert on right side
-                    (Leaf(_), Zero(_)) => {
+                    // Leaf or finalized subtree on left branch and zero on right branch, insert
+                    // on right side
+                    (Leaf(_), Zero(_)) | (Finalized(_), Zero(_)) => {
                         *right = MerkleTree::create(&[elem], depth - 1);
                     }
                     // Try inserting on the left node -> if it fails because it is full, insert in right side.
@@ -141,6 +155,7 @@ impl MerkleTree {
         match *self {
             MerkleTree::Leaf(h) => h,
             MerkleTree::Node(h, _, _) => h,
+            MerkleTree::Finalized(h) => h,
             MerkleTree::Zero(depth) => H256::from_slice(&ZERO_HASHES[depth]),
         }
     }
@@ -148,7 +163,7 @@ impl MerkleTree {
     /// Get a reference to the left and right subtrees if they exist.
     pub fn left_and_right_branches(&self) -> Option<(&Self, &Self)> {
         match *self {
-            MerkleTree::Leaf(_) | MerkleTree::Zero(0) => None,
+            MerkleTree::Leaf(_) | MerkleTree::Zero(0) | MerkleTree::Finalized(_) => None,
             MerkleTree::Node(_, ref l, ref r) => Some((l, r)),
             MerkleTree::Zero(depth) => Some((&ZERO_NODES[depth - 1], &ZERO_NODES[depth - 1])),
         }
@@ -163,14 +178,23 @@ impl MerkleTree {
     ///
     /// The Merkle proof is in "bottom-up" order, starting with a leaf node
     /// and moving up the tree. Its length will be exactly equal to `depth`.
-    pub fn generate_proof(&self, index: usize, depth: usize) -> (H256, Vec<H256>) {
+    ///
+    /// Returns an error if the leaf at `index` is within a finalized subtree.
+    pub fn generate_proof(
+        &self,
+        index: usize,
+        depth: usize,
+    ) -> Result<(H256, Vec<H256>), MerkleTreeError> {
         let mut proof = vec![];
         let mut current_node = self;
         let mut current_depth = depth;
         while current_depth > 0 {
             let ith_bit = (index >> (current_depth - 1)) & 0x01;
-            // Note: unwrap is safe because leaves are only ever constructed at depth == 0.
-            let (left, right) = current_node.left_and_right_branches().unwrap();
+            // Note: leaves are only ever constructed at depth == 0, so the only nodes without
+            // branches above that depth are finalized.
+            let (left, right) = current_node
+                .left_and_right_branches()
+                .ok_or(MerkleTreeError::ProofEncounteredFinalizedNode)?;
 
             // Go right, include the left branch in the proof.
             if ith_bit == 1 {
@@ -183,13 +207,143 @@ impl MerkleTree {
             current_depth -= 1;
         }
 
+        if let MerkleTree::Finalized(_) = current_node {
+            return Err(MerkleTreeError::ProofEncounteredFinalizedNode);
+        }
+
         debug_assert_eq!(proof.len(), depth);
         debug_assert!(current_node.is_leaf());
 
         // Put proof in bottom-up order.
         proof.reverse();
 
-        (current_node.hash(), proof)
+        Ok((current_node.hash(), proof))
+    }
+
+    /// Replace the subtrees containing the first `deposits_to_finalize` leaves of this tree (of
+    /// the given `depth`) with `Finalized` nodes, pruning their contents.
+    ///
+    /// Leaves which have already been finalized are left unchanged.
+    pub fn finalize_deposits(
+        &mut self,
+        deposits_to_finalize: usize,
+        depth: usize,
+    ) -> Result<(), MerkleTreeError> {
+        use MerkleTree::*;
+
+        if deposits_to_finalize == 0 {
+            return Ok(());
+        }
+
+        match self {
+            Finalized(_) => Ok(()),
+            Zero(_) => Err(MerkleTreeError::FinalizedNodesExceedLeaves),
+            Leaf(hash) => {
+                if deposits_to_finalize > 1 {
+                    return Err(MerkleTreeError::FinalizedNodesExceedLeaves);
+                }
+                *self = Finalized(*hash);
+                Ok(())
+            }
+            Node(hash, left, right) => {
+                if depth == 0 {
+                    return Err(MerkleTreeError::Invalid);
+                }
+
+                let subtree_capacity = 2usize.pow(depth as u32 - 1);
+                if deposits_to_finalize > 2 * subtree_capacity {
+                    return Err(MerkleTreeError::FinalizedNodesExceedLeaves);
+                } else if deposits_to_finalize <= subtree_capacity {
+                    left.finalize_deposits(deposits_to_finalize, depth - 1)?;
+                } else {
+                    left.finalize_deposits(subtree_capacity, depth - 1)?;
+                    right.finalize_deposits(deposits_to_finalize - subtree_capacity, depth - 1)?;
+                }
+
+                // Collapse a fully finalized subtree into a single node.
+                if let (Finalized(_), Finalized(_)) = (&**left, &**right) {
+                    *self = Finalized(*hash);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Return the hashes of all `Finalized` nodes, from left to right.
+    ///
+    /// Since finalized subtrees are always full and leftmost, this is also in order of decreasing
+    /// subtree size.
+    pub fn get_finalized_hashes(&self) -> Vec<H256> {
+        let mut hashes = vec![];
+        self.append_finalized_hashes(&mut hashes);
+        hashes
+    }
+
+    fn append_finalized_hashes(&self, hashes: &mut Vec<H256>) {
+        match self {
+            MerkleTree::Zero(_) | MerkleTree::Leaf(_) => {}
+            MerkleTree::Finalized(hash) => hashes.push(*hash),
+            MerkleTree::Node(_, left, right) => {
+                left.append_finalized_hashes(hashes);
+                right.append_finalized_hashes(hashes);
+            }
+        }
+    }
+
+    /// Create a tree of the given `depth` containing `deposit_count` finalized leaves, from the
+    /// `finalized_branch` returned by `Self::get_finalized_hashes`.
+    pub fn from_finalized_snapshot(
+        finalized_branch: &[H256],
+        deposit_count: usize,
+        depth: usize,
+    ) -> Result<Self, MerkleTreeError> {
+        use MerkleTree::*;
+
+        if finalized_branch.is_empty() {
+            return if deposit_count == 0 {
+                Ok(Zero(depth))
+            } else {
+                Err(MerkleTreeError::InvalidFinalizedSnapshot)
+            };
+        }
+
+        if depth > MAX_TREE_DEPTH {
+            return Err(MerkleTreeError::Invalid);
+        }
+
+        let capacity = 2usize.pow(depth as u32);
+        if deposit_count == capacity {
+            return if finalized_branch.len() == 1 {
+                Ok(Finalized(finalized_branch[0]))
+            } else {
+                Err(MerkleTreeError::InvalidFinalizedSnapshot)
+            };
+        } else if deposit_count > capacity || depth == 0 {
+            return Err(MerkleTreeError::InvalidFinalizedSnapshot);
+        }
+
+        let subtree_capacity = capacity / 2;
+        let (left, right) = if deposit_count >= subtree_capacity {
+            (
+                Finalized(finalized_branch[0]),
+                MerkleTree::from_finalized_snapshot(
+                    &finalized_branch[1..],
+                    deposit_count - subtree_capacity,
+                    depth - 1,
+                )?,
+            )
+        } else {
+            (
+                MerkleTree::from_finalized_snapshot(finalized_branch, deposit_count, depth - 1)?,
+                Zero(depth - 1),
+            )
+        };
+
+        let hash = H256::from_slice(&hash32_concat(
+            left.hash().as_bytes(),
+            right.hash().as_bytes(),
+        ));
+        Ok(Node(hash, Box::new(left), Box::new(right)))
     }
 }
 
@@ -257,7 +411,7 @@ mod tests {
         let merkle_root = merkle_tree.hash();
 
         let proofs_ok = (0..leaves.len()).all(|i| {
-            let (leaf, branch) = merkle_tree.generate_proof(i, depth);
+            let (leaf, branch) = merkle_tree.generate_proof(i, depth).unwrap();
             leaf == leaves[i] && verify_merkle_proof(leaf, &branch, depth, i, merkle_root)
         });
 
@@ -276,13 +430,100 @@ mod tests {
 
         let proofs_ok = leaves.into_iter().enumerate().all(|(i, leaf)| {
             assert_eq!(merkle_tree.push_leaf(leaf, depth), Ok(()));
-            let (stored_leaf, branch) = merkle_tree.generate_proof(i, depth);
+            let (stored_leaf, branch) = merkle_tree.generate_proof(i, depth).unwrap();
             stored_leaf == leaf && verify_merkle_proof(leaf, &branch, depth, i, merkle_tree.hash())
         });
 
         TestResult::from_bool(proofs_ok)
     }
 
+    /// Check that we can:
+    /// 1. Finalize the first `n` leaves of a MerkleTree without changing its root.
+    /// 2. Generate valid proofs for the remaining leaves, and only those.
+    /// 3. Rebuild an equivalent tree from the finalized hashes and the remaining leaves.
+    #[quickcheck]
+    fn quickcheck_finalize_and_restore(
+        int_leaves: Vec<u64>,
+        num_finalized: usize,
+        depth: usize,
+    ) -> TestResult {
+        if depth == 0
+            || depth > MAX_TREE_DEPTH
+            || int_leaves.len() > 2usize.pow(depth as u32)
+            || num_finalized > int_leaves.len()
+        {
+            return TestResult::discard();
+        }
+
+        let leaves: Vec<_> = int_leaves.into_iter().map(H256::from_low_u64_be).collect();
+        let mut merkle_tree = MerkleTree::create(&leaves, depth);
+        let merkle_root = merkle_tree.hash();
+
+        merkle_tree.finalize_deposits(num_finalized, depth).unwrap();
+        if merkle_tree.hash() != merkle_root {
+            return TestResult::failed();
+        }
+
+        let proofs_ok = (0..leaves.len()).all(|i| match merkle_tree.generate_proof(i, depth) {
+            Ok((leaf, branch)) => {
+                i >= num_finalized
+                    && leaf == leaves[i]
+                    && verify_merkle_proof(leaf, &branch, depth, i, merkle_root)
+            }
+            Err(e) => i < num_finalized && e == MerkleTreeError::ProofEncounteredFinalizedNode,
+        });
+
+        let mut restored = MerkleTree::from_finalized_snapshot(
+            &merkle_tree.get_finalized_hashes(),
+            num_finalized,
+            depth,
+        )
+        .unwrap();
+        for leaf in &leaves[num_finalized..] {
+            restored.push_leaf(*leaf, depth).unwrap();
+        }
+
+        TestResult::from_bool(proofs_ok && restored.hash() == merkle_root)
+    }
+
+    #[test]
+    fn finalize_small_example() {
+        let depth = 2;
+        let leaves = [
+            H256::from([0xAA; 32]),
+            H256::from([0xBB; 32]),
+            H256::from([0xCC; 32]),
+        ];
+        let mut tree = MerkleTree::create(&leaves, depth);
+
+        assert_eq!(
+            tree.finalize_deposits(4, depth),
+            Err(MerkleTreeError::FinalizedNodesExceedLeaves)
+        );
+
+        tree.finalize_deposits(3, depth).unwrap();
+        let node_b0x = H256::from_slice(&hash32_concat(leaves[0].as_bytes(), leaves[1].as_bytes()));
+        assert_eq!(tree.get_finalized_hashes(), vec![node_b0x, leaves[2]]);
+
+        // Finalizing fewer leaves than have already been finalized is a no-op.
+        tree.finalize_deposits(1, depth).unwrap();
+        assert_eq!(tree.get_finalized_hashes(), vec![node_b0x, leaves[2]]);
+
+        let leaf_b11 = H256::from([0xDD; 32]);
+        tree.push_leaf(leaf_b11, depth).unwrap();
+        let mut all_leaves = leaves.to_vec();
+        all_leaves.push(leaf_b11);
+        assert_eq!(tree.hash(), MerkleTree::create(&all_leaves, depth).hash());
+        assert!(tree.generate_proof(3, depth).is_ok());
+        assert_eq!(
+            tree.push_leaf(leaf_b11, depth),
+            Err(MerkleTreeError::MerkleTreeFull)
+        );
+
+        tree.finalize_deposits(4, depth).unwrap();
+        assert_eq!(tree, MerkleTree::Finalized(tree.hash()));
+    }
+
     #[test]
     fn sparse_zero_correct() {
         let depth = 2;
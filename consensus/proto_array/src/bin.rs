@@ -6,6 +6,14 @@ fn main() {
     write_test_def_to_yaml("no_votes.yaml", get_no_votes_test_definition());
     write_test_def_to_yaml("ffg_01.yaml", get_ffg_case_01_test_definition());
     write_test_def_to_yaml("ffg_02.yaml", get_ffg_case_02_test_definition());
+    write_test_def_to_yaml(
+        "proposer_boost_ex_ante.yaml",
+        get_proposer_boost_ex_ante_test_definition(),
+    );
+    write_test_def_to_yaml(
+        "proposer_boost_balancing.yaml",
+        get_proposer_boost_balancing_test_definition(),
+    );
 }
 
 fn write_test_def_to_yaml(filename: &str, def: ForkChoiceTestDefinition) {
@@ -1,13 +1,15 @@
 mod ffg_updates;
 mod no_votes;
+mod proposer_boost;
 mod votes;
 
 use crate::proto_array_fork_choice::{Block, ProtoArrayForkChoice};
 use serde_derive::{Deserialize, Serialize};
-use types::{Epoch, Hash256, ShufflingId, Slot};
+use types::{ChainSpec, Epoch, Hash256, MainnetEthSpec, ShufflingId, Slot};
 
 pub use ffg_updates::*;
 pub use no_votes::*;
+pub use proposer_boost::*;
 pub use votes::*;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +21,14 @@ pub enum Operation {
         justified_state_balances: Vec<u64>,
         expected_head: Hash256,
     },
+    ProposerBoostFindHead {
+        justified_epoch: Epoch,
+        justified_root: Hash256,
+        finalized_epoch: Epoch,
+        justified_state_balances: Vec<u64>,
+        proposer_boost_root: Hash256,
+        expected_head: Hash256,
+    },
     InvalidFindHead {
         justified_epoch: Epoch,
         justified_root: Hash256,
@@ -55,6 +65,7 @@ pub struct ForkChoiceTestDefinition {
 
 impl ForkChoiceTestDefinition {
     pub fn run(self) {
+        let spec = ChainSpec::mainnet();
         let junk_shuffling_id = ShufflingId::from_components(Epoch::new(0), Hash256::zero());
         let mut fork_choice = ProtoArrayForkChoice::new(
             self.finalized_block_slot,
@@ -77,11 +88,41 @@ impl ForkChoiceTestDefinition {
                     expected_head,
                 } => {
                     let head = fork_choice
-                        .find_head(
+                        .find_head::<MainnetEthSpec>(
+                            justified_epoch,
+                            justified_root,
+                            finalized_epoch,
+                            &justified_state_balances,
+                            Hash256::zero(),
+                            &spec,
+                        )
+                        .unwrap_or_else(|_| {
+                            panic!("find_head op at index {} returned error", op_index)
+                        });
+
+                    assert_eq!(
+                        head, expected_head,
+                        "Operation at index {} failed checks. Operation: {:?}",
+                        op_index, op
+                    );
+                    check_bytes_round_trip(&fork_choice);
+                }
+                Operation::ProposerBoostFindHead {
+                    justified_epoch,
+                    justified_root,
+                    finalized_epoch,
+                    justified_state_balances,
+                    proposer_boost_root,
+                    expected_head,
+                } => {
+                    let head = fork_choice
+                        .find_head::<MainnetEthSpec>(
                             justified_epoch,
                             justified_root,
                             finalized_epoch,
                             &justified_state_balances,
+                            proposer_boost_root,
+                            &spec,
                         )
                         .unwrap_or_else(|_| {
                             panic!("find_head op at index {} returned error", op_index)
@@ -100,11 +141,13 @@ impl ForkChoiceTestDefinition {
                     finalized_epoch,
                     justified_state_balances,
                 } => {
-                    let result = fork_choice.find_head(
+                    let result = fork_choice.find_head::<MainnetEthSpec>(
                         justified_epoch,
                         justified_root,
                         finalized_epoch,
                         &justified_state_balances,
+                        Hash256::zero(),
+                        &spec,
                     );
 
                     assert!(
@@ -0,0 +1,267 @@
+use super::*;
+
+/// With 64 validators of equal balance, the committee weight is two validators worth of balance
+/// and the proposer boost (70% of the committee weight) is worth 1.4 votes.
+const VALIDATOR_COUNT: usize = 64;
+const BALANCE: u64 = 1_000;
+
+/// An attacker withholds a vote for their block and releases it after an honest, timely block
+/// has been built on a competing branch. The boost prevents the attacker's single vote from
+/// re-orging the honest block whilst it is boosted.
+pub fn get_proposer_boost_ex_ante_test_definition() -> ForkChoiceTestDefinition {
+    let balances = vec![BALANCE; VALIDATOR_COUNT];
+    let mut ops = vec![];
+
+    // Add block 1, proposed by the attacker.
+    //
+    //         0
+    //        /
+    //       1
+    ops.push(Operation::ProcessBlock {
+        slot: Slot::new(1),
+        root: get_hash(1),
+        parent_root: get_hash(0),
+        justified_epoch: Epoch::new(1),
+        finalized_epoch: Epoch::new(1),
+    });
+
+    // Add block 2, a timely honest block which does not build upon block 1.
+    //
+    //         0
+    //        / \
+    //       1   2
+    ops.push(Operation::ProcessBlock {
+        slot: Slot::new(2),
+        root: get_hash(2),
+        parent_root: get_hash(0),
+        justified_epoch: Epoch::new(1),
+        finalized_epoch: Epoch::new(1),
+    });
+
+    // The attacker releases their withheld vote for block 1.
+    //
+    //         0
+    //        / \
+    // +vote 1   2
+    ops.push(Operation::ProcessAttestation {
+        validator_index: 0,
+        block_root: get_hash(1),
+        target_epoch: Epoch::new(2),
+    });
+
+    // Block 2 remains the head whilst it is boosted, since the boost outweighs a single vote.
+    //
+    //         0
+    //        / \
+    //       1   2 <- head
+    ops.push(Operation::ProposerBoostFindHead {
+        justified_epoch: Epoch::new(1),
+        justified_root: get_hash(0),
+        finalized_epoch: Epoch::new(1),
+        justified_state_balances: balances.clone(),
+        proposer_boost_root: get_hash(2),
+        expected_head: get_hash(2),
+    });
+
+    // The boost is removed when the next slot starts, so without any honest votes the attacker's
+    // vote decides the head.
+    //
+    //         0
+    //        / \
+    // head-> 1   2
+    ops.push(Operation::FindHead {
+        justified_epoch: Epoch::new(1),
+        justified_root: get_hash(0),
+        finalized_epoch: Epoch::new(1),
+        justified_state_balances: balances.clone(),
+        expected_head: get_hash(1),
+    });
+
+    // The honest committee of slot 2 votes for block 2, which they saw as the head.
+    //
+    //         0
+    //        / \
+    //       1   2 <- +2 votes
+    ops.push(Operation::ProcessAttestation {
+        validator_index: 1,
+        block_root: get_hash(2),
+        target_epoch: Epoch::new(2),
+    });
+    ops.push(Operation::ProcessAttestation {
+        validator_index: 2,
+        block_root: get_hash(2),
+        target_epoch: Epoch::new(2),
+    });
+
+    // Block 2 is the head without any boost.
+    //
+    //         0
+    //        / \
+    //       1   2 <- head
+    ops.push(Operation::FindHead {
+        justified_epoch: Epoch::new(1),
+        justified_root: get_hash(0),
+        finalized_epoch: Epoch::new(1),
+        justified_state_balances: balances,
+        expected_head: get_hash(2),
+    });
+
+    ForkChoiceTestDefinition {
+        finalized_block_slot: Slot::new(0),
+        justified_epoch: Epoch::new(1),
+        finalized_epoch: Epoch::new(1),
+        finalized_root: get_hash(0),
+        operations: ops,
+    }
+}
+
+/// An attacker attempts to keep two branches balanced by splitting their votes. The boost given
+/// to a timely block breaks the tie, allowing honest validators to converge on a single branch.
+pub fn get_proposer_boost_balancing_test_definition() -> ForkChoiceTestDefinition {
+    let balances = vec![BALANCE; VALIDATOR_COUNT];
+    let mut ops = vec![];
+
+    // Add blocks 1 and 2 as competing children of the finalized block.
+    //
+    //         0
+    //        / \
+    //       1   2
+    ops.push(Operation::ProcessBlock {
+        slot: Slot::new(1),
+        root: get_hash(1),
+        parent_root: get_hash(0),
+        justified_epoch: Epoch::new(1),
+        finalized_epoch: Epoch::new(1),
+    });
+    ops.push(Operation::ProcessBlock {
+        slot: Slot::new(1),
+        root: get_hash(2),
+        parent_root: get_hash(0),
+        justified_epoch: Epoch::new(1),
+        finalized_epoch: Epoch::new(1),
+    });
+
+    // The attacker gives each branch a single vote.
+    //
+    //            0
+    //           / \
+    //  +vote   1   2   +vote
+    ops.push(Operation::ProcessAttestation {
+        validator_index: 0,
+        block_root: get_hash(1),
+        target_epoch: Epoch::new(2),
+    });
+    ops.push(Operation::ProcessAttestation {
+        validator_index: 1,
+        block_root: get_hash(2),
+        target_epoch: Epoch::new(2),
+    });
+
+    // The branches are balanced, so the head is decided by the higher root.
+    //
+    //         0
+    //        / \
+    //       1   2 <- head
+    ops.push(Operation::FindHead {
+        justified_epoch: Epoch::new(1),
+        justified_root: get_hash(0),
+        finalized_epoch: Epoch::new(1),
+        justified_state_balances: balances.clone(),
+        expected_head: get_hash(2),
+    });
+
+    // Add block 3, a timely block built upon block 1.
+    //
+    //         0
+    //        / \
+    //       1   2
+    //       |
+    //       3
+    ops.push(Operation::ProcessBlock {
+        slot: Slot::new(2),
+        root: get_hash(3),
+        parent_root: get_hash(1),
+        justified_epoch: Epoch::new(1),
+        finalized_epoch: Epoch::new(1),
+    });
+
+    // The attacker releases a withheld vote for block 2, but the boost given to block 3 outweighs
+    // it and the branches are no longer balanced.
+    //
+    //         0
+    //        / \
+    //       1   2  +vote
+    //       |
+    //       3 <- head
+    ops.push(Operation::ProcessAttestation {
+        validator_index: 2,
+        block_root: get_hash(2),
+        target_epoch: Epoch::new(2),
+    });
+    ops.push(Operation::ProposerBoostFindHead {
+        justified_epoch: Epoch::new(1),
+        justified_root: get_hash(0),
+        finalized_epoch: Epoch::new(1),
+        justified_state_balances: balances.clone(),
+        proposer_boost_root: get_hash(3),
+        expected_head: get_hash(3),
+    });
+
+    // The honest committee of slot 2 votes for block 3.
+    //
+    //         0
+    //        / \
+    //       1   2
+    //       |
+    //       3 <- +2 votes
+    ops.push(Operation::ProcessAttestation {
+        validator_index: 3,
+        block_root: get_hash(3),
+        target_epoch: Epoch::new(2),
+    });
+    ops.push(Operation::ProcessAttestation {
+        validator_index: 4,
+        block_root: get_hash(3),
+        target_epoch: Epoch::new(2),
+    });
+
+    // Block 3 remains the head once the boost has been removed.
+    //
+    //         0
+    //        / \
+    //       1   2
+    //       |
+    //       3 <- head
+    ops.push(Operation::FindHead {
+        justified_epoch: Epoch::new(1),
+        justified_root: get_hash(0),
+        finalized_epoch: Epoch::new(1),
+        justified_state_balances: balances,
+        expected_head: get_hash(3),
+    });
+
+    ForkChoiceTestDefinition {
+        finalized_block_slot: Slot::new(0),
+        justified_epoch: Epoch::new(1),
+        finalized_epoch: Epoch::new(1),
+        finalized_root: get_hash(0),
+        operations: ops,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proposer_boost_ex_ante() {
+        let test = get_proposer_boost_ex_ante_test_definition();
+        test.run();
+    }
+
+    #[test]
+    fn proposer_boost_balancing() {
+        let test = get_proposer_boost_balancing_test_definition();
+        test.run();
+    }
+}
@@ -1,8 +1,9 @@
 use crate::{error::Error, Block};
 use serde_derive::{Deserialize, Serialize};
+use ssz::legacy::four_byte_option;
 use ssz_derive::{Decode, Encode};
 use std::collections::HashMap;
-use types::{Epoch, Hash256, ShufflingId, Slot};
+use types::{ChainSpec, Epoch, EthSpec, Hash256, ShufflingId, Slot};
 
 #[derive(Clone, PartialEq, Debug, Encode, Decode, Serialize, Deserialize)]
 pub struct ProtoNode {
@@ -21,14 +22,25 @@ pub struct ProtoNode {
     pub current_epoch_shuffling_id: ShufflingId,
     pub next_epoch_shuffling_id: ShufflingId,
     pub root: Hash256,
+    #[ssz(with = "four_byte_option")]
     pub parent: Option<usize>,
     pub justified_epoch: Epoch,
     pub finalized_epoch: Epoch,
     weight: u64,
+    #[ssz(with = "four_byte_option")]
     best_child: Option<usize>,
+    #[ssz(with = "four_byte_option")]
     best_descendant: Option<usize>,
 }
 
+/// The proposer boost that was applied to the weights of the tree during the previous call to
+/// `ProtoArray::apply_score_changes`.
+#[derive(PartialEq, Debug, Default, Clone, Copy, Encode, Decode, Serialize, Deserialize)]
+pub struct ProposerBoost {
+    pub root: Hash256,
+    pub score: u64,
+}
+
 #[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
 pub struct ProtoArray {
     /// Do not attempt to prune the tree unless it has at least this many nodes. Small prunes
@@ -38,6 +50,7 @@ pub struct ProtoArray {
     pub finalized_epoch: Epoch,
     pub nodes: Vec<ProtoNode>,
     pub indices: HashMap<Hash256, usize>,
+    pub previous_proposer_boost: ProposerBoost,
 }
 
 impl ProtoArray {
@@ -50,15 +63,20 @@ impl ProtoArray {
     /// For each node, the following is done:
     ///
     /// - Update the node's weight with the corresponding delta.
+    /// - Remove the proposer boost applied by the previous call, if any, and apply the boost for
+    /// `proposer_boost_root`.
     /// - Back-propagate each node's delta to its parents delta.
     /// - Compare the current node with the parents best-child, updating it if the current node
     /// should become the best child.
     /// - If required, update the parents best-descendant with the current node or its best-descendant.
-    pub fn apply_score_changes(
+    pub fn apply_score_changes<E: EthSpec>(
         &mut self,
         mut deltas: Vec<i64>,
         justified_epoch: Epoch,
         finalized_epoch: Epoch,
+        new_balances: &[u64],
+        proposer_boost_root: Hash256,
+        spec: &ChainSpec,
     ) -> Result<(), Error> {
         if deltas.len() != self.indices.len() {
             return Err(Error::InvalidDeltaLen {
@@ -72,6 +90,9 @@ impl ProtoArray {
             self.finalized_epoch = finalized_epoch;
         }
 
+        // The score that will be applied to the `proposer_boost_root` (if any) during this call.
+        let mut proposer_score = 0;
+
         // Iterate backwards through all indices in `self.nodes`.
         for node_index in (0..self.nodes.len()).rev() {
             let node = self
@@ -86,11 +107,34 @@ impl ProtoArray {
                 continue;
             }
 
-            let node_delta = deltas
+            let mut node_delta = deltas
                 .get(node_index)
                 .copied()
                 .ok_or_else(|| Error::InvalidNodeDelta(node_index))?;
 
+            // If we find the node for which the proposer boost was previously applied, decrease
+            // the delta by the previous score amount.
+            if self.previous_proposer_boost.root != Hash256::zero()
+                && self.previous_proposer_boost.root == node.root
+            {
+                node_delta = node_delta
+                    .checked_sub(self.previous_proposer_boost.score as i64)
+                    .ok_or_else(|| Error::DeltaOverflow(node_index))?;
+            }
+
+            // If we find the node matching the current proposer boost root, increase the delta by
+            // the new score amount.
+            if let Some(proposer_score_boost) = spec.proposer_score_boost {
+                if proposer_boost_root != Hash256::zero() && proposer_boost_root == node.root {
+                    proposer_score =
+                        calculate_proposer_boost::<E>(new_balances, proposer_score_boost)
+                            .ok_or_else(|| Error::ProposerBoostOverflow(node_index))?;
+                    node_delta = node_delta
+                        .checked_add(proposer_score as i64)
+                        .ok_or_else(|| Error::DeltaOverflow(node_index))?;
+                }
+            }
+
             // Apply the delta to the node.
             if node_delta < 0 {
                 // Note: I am conflicted about whether to use `saturating_sub` or `checked_sub`
@@ -126,6 +170,12 @@ impl ProtoArray {
             }
         }
 
+        // After applying all deltas, update the `previous_proposer_boost`.
+        self.previous_proposer_boost = ProposerBoost {
+            root: proposer_boost_root,
+            score: proposer_score,
+        };
+
         Ok(())
     }
 
@@ -448,3 +498,21 @@ impl<'a> Iterator for Iter<'a> {
         Some(node)
     }
 }
+
+/// Returns the proposer boost score: the weight of a single slot's committee, computed from the
+/// sum of `validator_balances`, scaled by `proposer_score_boost` percent.
+///
+/// Returns `None` on overflow.
+pub fn calculate_proposer_boost<E: EthSpec>(
+    validator_balances: &[u64],
+    proposer_score_boost: u64,
+) -> Option<u64> {
+    let mut total_balance: u64 = 0;
+    for &balance in validator_balances {
+        total_balance = total_balance.checked_add(balance)?;
+    }
+    let committee_weight = total_balance.checked_div(E::slots_per_epoch())?;
+    committee_weight
+        .checked_mul(proposer_score_boost)?
+        .checked_div(100)
+}
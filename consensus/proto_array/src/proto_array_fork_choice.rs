@@ -1,10 +1,10 @@
 use crate::error::Error;
-use crate::proto_array::ProtoArray;
-use crate::ssz_container::SszContainer;
+use crate::proto_array::{ProposerBoost, ProtoArray};
+use crate::ssz_container::{LegacySszContainer, SszContainer};
 use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
 use std::collections::HashMap;
-use types::{Epoch, Hash256, ShufflingId, Slot};
+use types::{ChainSpec, Epoch, EthSpec, Hash256, ShufflingId, Slot};
 
 pub const DEFAULT_PRUNE_THRESHOLD: usize = 256;
 
@@ -81,6 +81,7 @@ impl ProtoArrayForkChoice {
             finalized_epoch,
             nodes: Vec::with_capacity(1),
             indices: HashMap::with_capacity(1),
+            previous_proposer_boost: ProposerBoost::default(),
         };
 
         let block = Block {
@@ -134,12 +135,19 @@ impl ProtoArrayForkChoice {
             .map_err(|e| format!("process_block_error: {:?}", e))
     }
 
-    pub fn find_head(
+    /// Apply the pending vote and balance changes to the tree and return the head.
+    ///
+    /// If `proposer_boost_root` is non-zero and `spec.proposer_score_boost` is set, the block
+    /// with that root receives an additional, temporary weight which is removed during the next
+    /// call to this function.
+    pub fn find_head<E: EthSpec>(
         &mut self,
         justified_epoch: Epoch,
         justified_root: Hash256,
         finalized_epoch: Epoch,
         justified_state_balances: &[u64],
+        proposer_boost_root: Hash256,
+        spec: &ChainSpec,
     ) -> Result<Hash256, String> {
         let old_balances = &mut self.balances;
 
@@ -154,7 +162,14 @@ impl ProtoArrayForkChoice {
         .map_err(|e| format!("find_head compute_deltas failed: {:?}", e))?;
 
         self.proto_array
-            .apply_score_changes(deltas, justified_epoch, finalized_epoch)
+            .apply_score_changes::<E>(
+                deltas,
+                justified_epoch,
+                finalized_epoch,
+                &new_balances,
+                proposer_boost_root,
+                spec,
+            )
             .map_err(|e| format!("find_head apply_score_changes failed: {:?}", e))?;
 
         *old_balances = new_balances.to_vec();
@@ -246,8 +261,16 @@ impl ProtoArrayForkChoice {
         SszContainer::from(self).as_ssz_bytes()
     }
 
+    /// Decode `Self` from bytes produced by `Self::as_bytes`.
+    ///
+    /// Bytes persisted prior to the introduction of the proposer boost are also accepted.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
         SszContainer::from_ssz_bytes(bytes)
+            .or_else(|e| {
+                LegacySszContainer::from_ssz_bytes(bytes)
+                    .map(Into::into)
+                    .map_err(|_| e)
+            })
             .map(Into::into)
             .map_err(|e| format!("Failed to decode ProtoArrayForkChoice: {:?}", e))
     }
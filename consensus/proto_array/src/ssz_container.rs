@@ -1,5 +1,5 @@
 use crate::{
-    proto_array::{ProtoArray, ProtoNode},
+    proto_array::{ProposerBoost, ProtoArray, ProtoNode},
     proto_array_fork_choice::{ElasticList, ProtoArrayForkChoice, VoteTracker},
 };
 use ssz_derive::{Decode, Encode};
@@ -16,6 +16,37 @@ pub struct SszContainer {
     finalized_epoch: Epoch,
     nodes: Vec<ProtoNode>,
     indices: Vec<(Hash256, usize)>,
+    previous_proposer_boost: ProposerBoost,
+}
+
+/// The `SszContainer` as it was persisted before the addition of `previous_proposer_boost`.
+///
+/// No boost could have been applied to the weights of a legacy container, so it is upgraded with a
+/// zero boost.
+#[derive(Encode, Decode)]
+pub struct LegacySszContainer {
+    votes: Vec<VoteTracker>,
+    balances: Vec<u64>,
+    prune_threshold: usize,
+    justified_epoch: Epoch,
+    finalized_epoch: Epoch,
+    nodes: Vec<ProtoNode>,
+    indices: Vec<(Hash256, usize)>,
+}
+
+impl From<LegacySszContainer> for SszContainer {
+    fn from(from: LegacySszContainer) -> Self {
+        Self {
+            votes: from.votes,
+            balances: from.balances,
+            prune_threshold: from.prune_threshold,
+            justified_epoch: from.justified_epoch,
+            finalized_epoch: from.finalized_epoch,
+            nodes: from.nodes,
+            indices: from.indices,
+            previous_proposer_boost: ProposerBoost::default(),
+        }
+    }
 }
 
 impl From<&ProtoArrayForkChoice> for SszContainer {
@@ -30,6 +61,7 @@ impl From<&ProtoArrayForkChoice> for SszContainer {
             finalized_epoch: proto_array.finalized_epoch,
             nodes: proto_array.nodes.clone(),
             indices: proto_array.indices.iter().map(|(k, v)| (*k, *v)).collect(),
+            previous_proposer_boost: proto_array.previous_proposer_boost,
         }
     }
 }
@@ -42,6 +74,7 @@ impl From<SszContainer> for ProtoArrayForkChoice {
             finalized_epoch: from.finalized_epoch,
             nodes: from.nodes,
             indices: HashMap::from_iter(from.indices.into_iter()),
+            previous_proposer_boost: from.previous_proposer_boost,
         };
 
         Self {
@@ -51,3 +84,41 @@ impl From<SszContainer> for ProtoArrayForkChoice {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ssz::{Decode, Encode};
+    use types::{ShufflingId, Slot};
+
+    #[test]
+    fn decode_legacy_container() {
+        let junk_shuffling_id = ShufflingId::from_components(Epoch::new(0), Hash256::zero());
+        let fork_choice = ProtoArrayForkChoice::new(
+            Slot::new(0),
+            Hash256::zero(),
+            Epoch::new(1),
+            Epoch::new(1),
+            Hash256::from_low_u64_be(1),
+            junk_shuffling_id.clone(),
+            junk_shuffling_id,
+        )
+        .unwrap();
+        let container = SszContainer::from(&fork_choice);
+
+        let legacy = LegacySszContainer {
+            votes: container.votes,
+            balances: container.balances,
+            prune_threshold: container.prune_threshold,
+            justified_epoch: container.justified_epoch,
+            finalized_epoch: container.finalized_epoch,
+            nodes: container.nodes,
+            indices: container.indices,
+        };
+        let legacy_bytes = legacy.as_ssz_bytes();
+
+        assert!(SszContainer::from_ssz_bytes(&legacy_bytes).is_err());
+        let decoded = ProtoArrayForkChoice::from_bytes(&legacy_bytes).unwrap();
+        assert!(decoded == fork_choice);
+    }
+}
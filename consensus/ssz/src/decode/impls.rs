@@ -241,32 +241,25 @@ impl Decode for NonZeroUsize {
     }
 }
 
-/// The SSZ union type.
+/// The SSZ union type, where `None` has selector `0` and `Some` has selector `1`.
+///
+/// See `ssz::legacy` for the four-byte selector encoding used prior to the introduction of the
+/// one-byte union selector.
 impl<T: Decode> Decode for Option<T> {
     fn is_ssz_fixed_len() -> bool {
         false
     }
 
     fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
-        if bytes.len() < BYTES_PER_LENGTH_OFFSET {
-            return Err(DecodeError::InvalidByteLength {
-                len: bytes.len(),
-                expected: BYTES_PER_LENGTH_OFFSET,
-            });
-        }
-
-        let (index_bytes, value_bytes) = bytes.split_at(BYTES_PER_LENGTH_OFFSET);
-
-        let index = read_union_index(index_bytes)?;
-        if index == 0 {
-            Ok(None)
-        } else if index == 1 {
-            Ok(Some(T::from_ssz_bytes(value_bytes)?))
-        } else {
-            Err(DecodeError::BytesInvalid(format!(
-                "{} is not a valid union index for Option<T>",
-                index
-            )))
+        let (selector, body) = split_union_bytes(bytes)?;
+        match selector.into() {
+            0u8 if body.is_empty() => Ok(None),
+            0u8 => Err(DecodeError::BytesInvalid(format!(
+                "None union variant has {} trailing bytes",
+                body.len()
+            ))),
+            1u8 => <T as Decode>::from_ssz_bytes(body).map(Option::Some),
+            other => Err(DecodeError::UnionSelectorInvalid(other)),
         }
     }
 }
@@ -104,13 +104,24 @@ impl<'a> SszEncoder<'a> {
 
     /// Append some `item` to the SSZ bytes.
     pub fn append<T: Encode>(&mut self, item: &T) {
-        if T::is_ssz_fixed_len() {
-            item.ssz_append(&mut self.buf);
+        self.append_parameterized(T::is_ssz_fixed_len(), |buf| item.ssz_append(buf))
+    }
+
+    /// Append some `item` to the SSZ bytes, using the supplied functions in place of its `Encode`
+    /// implementation.
+    ///
+    /// Used by `#[ssz(with = "module")]` fields in `#[derive(Encode)]`.
+    pub fn append_parameterized<F>(&mut self, is_ssz_fixed_len: bool, ssz_append: F)
+    where
+        F: Fn(&mut Vec<u8>),
+    {
+        if is_ssz_fixed_len {
+            ssz_append(self.buf);
         } else {
             self.buf
                 .extend_from_slice(&encode_length(self.offset + self.variable_bytes.len()));
 
-            item.ssz_append(&mut self.variable_bytes);
+            ssz_append(&mut self.variable_bytes);
         }
     }
 
@@ -125,13 +136,6 @@ impl<'a> SszEncoder<'a> {
     }
 }
 
-/// Encode `index` as a little-endian byte array of `BYTES_PER_LENGTH_OFFSET` length.
-///
-/// If `len` is larger than `2 ^ BYTES_PER_LENGTH_OFFSET`, a `debug_assert` is raised.
-pub fn encode_union_index(index: usize) -> [u8; BYTES_PER_LENGTH_OFFSET] {
-    encode_length(index)
-}
-
 /// Encode `len` as a little-endian byte array of `BYTES_PER_LENGTH_OFFSET` length.
 ///
 /// If `len` is larger than `2 ^ BYTES_PER_LENGTH_OFFSET`, a `debug_assert` is raised.
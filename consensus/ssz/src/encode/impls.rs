@@ -201,7 +201,10 @@ impl_encode_for_tuples! {
     }
 }
 
-/// The SSZ "union" type.
+/// The SSZ "union" type, where `None` has selector `0` and `Some` has selector `1`.
+///
+/// See `ssz::legacy` for the four-byte selector encoding used prior to the introduction of the
+/// one-byte union selector.
 impl<T: Encode> Encode for Option<T> {
     fn is_ssz_fixed_len() -> bool {
         false
@@ -214,17 +217,17 @@ impl<T: Encode> Encode for Option<T> {
             } else {
                 some.ssz_bytes_len()
             };
-            len + BYTES_PER_LENGTH_OFFSET
+            len + 1
         } else {
-            BYTES_PER_LENGTH_OFFSET
+            1
         }
     }
 
     fn ssz_append(&self, buf: &mut Vec<u8>) {
         match self {
-            None => buf.extend_from_slice(&encode_union_index(0)),
+            None => buf.push(0),
             Some(t) => {
-                buf.extend_from_slice(&encode_union_index(1));
+                buf.push(1);
                 t.ssz_append(buf);
             }
         }
@@ -439,21 +442,18 @@ mod tests {
 
     #[test]
     fn ssz_encode_option_u16() {
-        assert_eq!(Some(65535_u16).as_ssz_bytes(), vec![1, 0, 0, 0, 255, 255]);
+        assert_eq!(Some(65535_u16).as_ssz_bytes(), vec![1, 255, 255]);
 
         let none: Option<u16> = None;
-        assert_eq!(none.as_ssz_bytes(), vec![0, 0, 0, 0]);
+        assert_eq!(none.as_ssz_bytes(), vec![0]);
     }
 
     #[test]
     fn ssz_encode_option_vec_u16() {
-        assert_eq!(
-            Some(vec![0_u16, 1]).as_ssz_bytes(),
-            vec![1, 0, 0, 0, 0, 0, 1, 0]
-        );
+        assert_eq!(Some(vec![0_u16, 1]).as_ssz_bytes(), vec![1, 0, 0, 1, 0]);
 
         let none: Option<Vec<u16>> = None;
-        assert_eq!(none.as_ssz_bytes(), vec![0, 0, 0, 0]);
+        assert_eq!(none.as_ssz_bytes(), vec![0]);
     }
 
     #[test]
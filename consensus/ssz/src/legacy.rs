@@ -0,0 +1,140 @@
+//! Encodings which pre-date the current SSZ specification and are retained so that data persisted
+//! to disk by earlier versions may still be read.
+
+/// Encodes and decodes `Option<T>` using the four-byte (`BYTES_PER_LENGTH_OFFSET`) union selector
+/// which was specified prior to the one-byte selector.
+///
+/// The `encode` and `decode` modules are designed for use with `#[ssz(with = "...")]`:
+///
+/// ```rust
+/// use ssz_derive::{Encode, Decode};
+/// use ssz::{Decode, Encode};
+///
+/// #[derive(PartialEq, Debug, Encode, Decode)]
+/// struct Foo {
+///     #[ssz(with = "ssz::legacy::four_byte_option")]
+///     a: Option<u64>,
+/// }
+///
+/// let foo = Foo { a: None };
+///
+/// assert_eq!(foo.as_ssz_bytes(), vec![4, 0, 0, 0, 0, 0, 0, 0]);
+/// assert_eq!(Foo::from_ssz_bytes(&foo.as_ssz_bytes()).unwrap(), foo);
+/// ```
+pub mod four_byte_option {
+    pub mod encode {
+        use crate::encode::encode_length;
+        use crate::{Encode, BYTES_PER_LENGTH_OFFSET};
+
+        pub fn is_ssz_fixed_len() -> bool {
+            false
+        }
+
+        pub fn ssz_fixed_len() -> usize {
+            BYTES_PER_LENGTH_OFFSET
+        }
+
+        pub fn ssz_bytes_len<T: Encode>(opt: &Option<T>) -> usize {
+            if let Some(some) = opt {
+                let len = if <T as Encode>::is_ssz_fixed_len() {
+                    <T as Encode>::ssz_fixed_len()
+                } else {
+                    some.ssz_bytes_len()
+                };
+                len + BYTES_PER_LENGTH_OFFSET
+            } else {
+                BYTES_PER_LENGTH_OFFSET
+            }
+        }
+
+        pub fn ssz_append<T: Encode>(opt: &Option<T>, buf: &mut Vec<u8>) {
+            match opt {
+                None => buf.extend_from_slice(&encode_length(0)),
+                Some(t) => {
+                    buf.extend_from_slice(&encode_length(1));
+                    t.ssz_append(buf);
+                }
+            }
+        }
+
+        pub fn as_ssz_bytes<T: Encode>(opt: &Option<T>) -> Vec<u8> {
+            let mut buf = vec![];
+
+            ssz_append(opt, &mut buf);
+
+            buf
+        }
+    }
+
+    pub mod decode {
+        use crate::decode::read_offset;
+        use crate::{Decode, DecodeError, BYTES_PER_LENGTH_OFFSET};
+
+        pub fn is_ssz_fixed_len() -> bool {
+            false
+        }
+
+        pub fn ssz_fixed_len() -> usize {
+            BYTES_PER_LENGTH_OFFSET
+        }
+
+        pub fn from_ssz_bytes<T: Decode>(bytes: &[u8]) -> Result<Option<T>, DecodeError> {
+            if bytes.len() < BYTES_PER_LENGTH_OFFSET {
+                return Err(DecodeError::InvalidByteLength {
+                    len: bytes.len(),
+                    expected: BYTES_PER_LENGTH_OFFSET,
+                });
+            }
+
+            let (index_bytes, value_bytes) = bytes.split_at(BYTES_PER_LENGTH_OFFSET);
+
+            let index = read_offset(index_bytes)?;
+            if index == 0 {
+                Ok(None)
+            } else if index == 1 {
+                Ok(Some(T::from_ssz_bytes(value_bytes)?))
+            } else {
+                Err(DecodeError::BytesInvalid(format!(
+                    "{} is not a valid union index for Option<T>",
+                    index
+                )))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::four_byte_option::{decode, encode};
+
+    #[test]
+    fn four_byte_option_u16() {
+        assert_eq!(
+            encode::as_ssz_bytes(&Some(65535_u16)),
+            vec![1, 0, 0, 0, 255, 255]
+        );
+        assert_eq!(encode::as_ssz_bytes::<u16>(&None), vec![0, 0, 0, 0]);
+
+        assert_eq!(
+            decode::from_ssz_bytes::<u16>(&[1, 0, 0, 0, 255, 255]),
+            Ok(Some(65535))
+        );
+        assert_eq!(decode::from_ssz_bytes::<u16>(&[0, 0, 0, 0]), Ok(None));
+    }
+
+    #[test]
+    fn four_byte_option_vec_u16() {
+        let some = Some(vec![0_u16, 1]);
+        let bytes = encode::as_ssz_bytes(&some);
+
+        assert_eq!(bytes, vec![1, 0, 0, 0, 0, 0, 1, 0]);
+        assert_eq!(encode::ssz_bytes_len(&some), bytes.len());
+        assert_eq!(decode::from_ssz_bytes(&bytes), Ok(some));
+    }
+
+    #[test]
+    fn four_byte_option_invalid_index() {
+        assert!(decode::from_ssz_bytes::<u16>(&[2, 0, 0, 0]).is_err());
+        assert!(decode::from_ssz_bytes::<u16>(&[0, 0, 0]).is_err());
+    }
+}
@@ -33,14 +33,25 @@
 //! ```
 //!
 //! See `examples/` for manual implementations of the `Encode` and `Decode` traits.
+//!
+//! ## `Option<T>` encoding
+//!
+//! `Option<T>` is encoded as the SSZ union `Union[None, T]`, with a one-byte selector. Earlier
+//! versions of this crate used a four-byte selector, so `Option<T>` values persisted by those
+//! versions can no longer be decoded by the default `Decode` implementation. Such fields must be
+//! annotated with `#[ssz(with = "ssz::legacy::four_byte_option")]` to remain readable.
 
 mod decode;
 mod encode;
+pub mod legacy;
+mod union_selector;
 
 pub use decode::{
-    impls::decode_list_of_variable_length_items, Decode, DecodeError, SszDecoder, SszDecoderBuilder,
+    impls::decode_list_of_variable_length_items, split_union_bytes, Decode, DecodeError,
+    SszDecoder, SszDecoderBuilder,
 };
 pub use encode::{Encode, SszEncoder};
+pub use union_selector::UnionSelector;
 
 /// The number of bytes used to represent an offset.
 pub const BYTES_PER_LENGTH_OFFSET: usize = 4;
@@ -50,6 +61,10 @@ pub const MAX_LENGTH_VALUE: usize = (std::u32::MAX >> (8 * (4 - BYTES_PER_LENGTH
 #[cfg(target_pointer_width = "64")]
 pub const MAX_LENGTH_VALUE: usize = (std::u64::MAX >> (8 * (8 - BYTES_PER_LENGTH_OFFSET))) as usize;
 
+/// The highest possible union selector value (higher values are reserved for backwards compatible
+/// extensions).
+pub const MAX_UNION_SELECTOR: u8 = 127;
+
 /// Convenience function to SSZ encode an object supporting ssz::Encode.
 ///
 /// Equivalent to `val.as_ssz_bytes()`.
@@ -305,12 +305,9 @@ mod round_trip {
         };
 
         let bytes = vec![
-            //  1   2   3   4   5   6   7   8   9   10  11  12  13  14  15  16  17  18  19  20  21
-            //      | option<u16>   | offset        | offset        | option<u16    | 1st list
-            42, 00, 14, 00, 00, 00, 18, 00, 00, 00, 24, 00, 00, 00, 00, 00, 00, 00, 01, 00, 00, 00,
-            //  23  24  25  26  27
-            //      | 2nd list
-            00, 00, 00, 00, 00, 00,
+            //  1   2   3   4   5   6   7   8   9   10  11  12  13  14  15  16  17  18  19
+            //      | offset        | offset        | offset        | b  | c          | d
+            42, 00, 14, 00, 00, 00, 15, 00, 00, 00, 18, 00, 00, 00, 00, 01, 00, 00, 00,
         ];
 
         assert_eq!(s.as_ssz_bytes(), bytes);
@@ -348,6 +345,129 @@ mod round_trip {
         round_trip(vec);
     }
 
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct LegacyOptions {
+        a: u16,
+        #[ssz(with = "ssz::legacy::four_byte_option")]
+        b: Option<u16>,
+        #[ssz(with = "ssz::legacy::four_byte_option")]
+        c: Option<Vec<u16>>,
+    }
+
+    #[test]
+    fn legacy_options_encoding() {
+        let s = LegacyOptions {
+            a: 42,
+            b: None,
+            c: Some(vec![0]),
+        };
+
+        let bytes = vec![
+            //  1   2   3   4   5   6   7   8   9   10  11  12  13  14  15  16  17  18  19  20
+            //      | offset        | offset        | b             | c
+            42, 00, 10, 00, 00, 00, 14, 00, 00, 00, 00, 00, 00, 00, 01, 00, 00, 00, 00, 00,
+        ];
+
+        assert_eq!(s.as_ssz_bytes(), bytes);
+    }
+
+    #[test]
+    fn legacy_options_round_trip() {
+        let vec: Vec<LegacyOptions> = vec![
+            LegacyOptions {
+                a: 42,
+                b: Some(12),
+                c: Some(vec![0]),
+            },
+            LegacyOptions {
+                a: 42,
+                b: None,
+                c: None,
+            },
+        ];
+
+        round_trip(vec);
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    #[ssz(enum_behaviour = "union")]
+    enum TwoFixedUnion {
+        U8(u8),
+        U16(u16),
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct TwoFixedUnionStruct {
+        a: TwoFixedUnion,
+    }
+
+    #[test]
+    fn two_fixed_union() {
+        let eight = TwoFixedUnion::U8(1);
+        let sixteen = TwoFixedUnion::U16(1);
+
+        assert_eq!(eight.as_ssz_bytes(), vec![0, 1]);
+        assert_eq!(sixteen.as_ssz_bytes(), vec![1, 1, 0]);
+
+        round_trip(vec![eight, sixteen]);
+    }
+
+    #[test]
+    fn two_fixed_union_in_struct() {
+        let eight = TwoFixedUnionStruct {
+            a: TwoFixedUnion::U8(1),
+        };
+        let sixteen = TwoFixedUnionStruct {
+            a: TwoFixedUnion::U16(1),
+        };
+
+        assert_eq!(eight.as_ssz_bytes(), vec![4, 0, 0, 0, 0, 1]);
+        assert_eq!(sixteen.as_ssz_bytes(), vec![4, 0, 0, 0, 1, 1, 0]);
+
+        round_trip(vec![eight, sixteen]);
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    #[ssz(enum_behaviour = "union")]
+    enum NoneOrVariableLen {
+        None,
+        List(Vec<u16>),
+    }
+
+    #[test]
+    fn none_or_variable_len_union() {
+        assert_eq!(NoneOrVariableLen::None.as_ssz_bytes(), vec![0]);
+        assert_eq!(
+            NoneOrVariableLen::List(vec![1, 2]).as_ssz_bytes(),
+            vec![1, 1, 0, 2, 0]
+        );
+
+        round_trip(vec![
+            NoneOrVariableLen::None,
+            NoneOrVariableLen::List(vec![]),
+            NoneOrVariableLen::List(vec![1, 2]),
+        ]);
+    }
+
+    #[test]
+    fn invalid_union_bytes() {
+        assert_eq!(
+            TwoFixedUnion::from_ssz_bytes(&[2, 1]),
+            Err(DecodeError::UnionSelectorInvalid(2))
+        );
+        assert_eq!(
+            TwoFixedUnion::from_ssz_bytes(&[128, 1]),
+            Err(DecodeError::UnionSelectorInvalid(128))
+        );
+        assert_eq!(
+            TwoFixedUnion::from_ssz_bytes(&[]),
+            Err(DecodeError::OutOfBoundsByte { i: 0 })
+        );
+        assert!(NoneOrVariableLen::from_ssz_bytes(&[0, 1]).is_err());
+        assert!(<Option<u16>>::from_ssz_bytes(&[0, 1]).is_err());
+        assert!(<Option<u16>>::from_ssz_bytes(&[2]).is_err());
+    }
+
     #[test]
     fn tuple_u8_u16() {
         let vec: Vec<(u8, u16)> = vec![
@@ -9,88 +9,172 @@ use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, DeriveInput};
 
-/// Returns a Vec of `syn::Ident` for each named field in the struct, whilst filtering out fields
-/// that should not be serialized.
+/// Returns true if some field has an attribute declaring it should not be serialized.
+///
+/// The field attribute is: `#[ssz(skip_serializing)]`
+fn should_skip_serializing(field: &syn::Field) -> bool {
+    field.attrs.iter().any(|attr| {
+        attr.path.is_ident("ssz")
+            && attr.tokens.to_string().replace(" ", "") == "(skip_serializing)"
+    })
+}
+
+/// Returns the value of the `#[ssz(<name> = "<value>")]` attribute, if it is present.
 ///
 /// # Panics
-/// Any unnamed struct field (like in a tuple struct) will raise a panic at compile time.
-fn get_serializable_named_field_idents<'a>(
-    struct_data: &'a syn::DataStruct,
-) -> Vec<&'a syn::Ident> {
-    struct_data
-        .fields
+/// Panics at compile time if the value is not a string literal.
+fn get_ssz_attribute_value(attrs: &[syn::Attribute], name: &str) -> Option<String> {
+    attrs
         .iter()
-        .filter_map(|f| {
-            if should_skip_serializing(&f) {
-                None
-            } else {
-                Some(match &f.ident {
-                    Some(ref ident) => ident,
-                    _ => panic!("ssz_derive only supports named struct fields."),
-                })
+        .filter(|attr| attr.path.is_ident("ssz"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .find_map(|nested| match nested {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(name_value))
+                if name_value.path.is_ident(name) =>
+            {
+                match name_value.lit {
+                    syn::Lit::Str(value) => Some(value.value()),
+                    _ => panic!("#[ssz({} = ..)] must be a string literal.", name),
+                }
             }
+            _ => None,
         })
-        .collect()
 }
 
-/// Returns a Vec of `syn::Type` for each named field in the struct, whilst filtering out fields
-/// that should not be serialized.
-fn get_serializable_field_types<'a>(struct_data: &'a syn::DataStruct) -> Vec<&'a syn::Type> {
-    struct_data
-        .fields
+/// Returns the module supplied by a `#[ssz(with = "module")]` field attribute, if any.
+fn get_with_module(field: &syn::Field) -> Option<syn::Path> {
+    get_ssz_attribute_value(&field.attrs, "with").map(|module| {
+        syn::parse_str(&module)
+            .unwrap_or_else(|_| panic!("#[ssz(with = \"{}\")] is not a valid path.", module))
+    })
+}
+
+/// Returns `true` if the item has the `#[ssz(enum_behaviour = "union")]` attribute.
+///
+/// # Panics
+/// Panics at compile time if `enum_behaviour` is set to anything other than `"union"`.
+fn is_union_enum(item: &DeriveInput) -> bool {
+    match get_ssz_attribute_value(&item.attrs, "enum_behaviour").as_deref() {
+        Some("union") => true,
+        Some(other) => panic!("\"{}\" is not a supported enum_behaviour.", other),
+        None => false,
+    }
+}
+
+/// Returns the single-field type of each variant of a union enum, or `None` for a unit variant.
+///
+/// # Panics
+/// Panics at compile time if:
+///
+/// - There are more variants than can be represented with `ssz::MAX_UNION_SELECTOR`.
+/// - A variant has anything other than exactly one unnamed field, with the exception of a unit
+/// variant in the first position (the SSZ `None` variant).
+fn get_union_variant_types(enum_data: &syn::DataEnum) -> Vec<Option<&syn::Type>> {
+    // Note: `ssz::MAX_UNION_SELECTOR` is not available to a proc-macro crate.
+    let max_variants = 128;
+
+    if enum_data.variants.len() > max_variants {
+        panic!("union enums may have at most {} variants.", max_variants);
+    }
+
+    enum_data
+        .variants
         .iter()
-        .filter_map(|f| {
-            if should_skip_serializing(&f) {
-                None
-            } else {
-                Some(&f.ty)
+        .enumerate()
+        .map(|(i, variant)| match &variant.fields {
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                Some(&fields.unnamed[0].ty)
             }
+            syn::Fields::Unit if i == 0 => None,
+            syn::Fields::Unit => panic!("only the first variant of a union enum may be a unit."),
+            _ => panic!("union enum variants must have exactly one unnamed field."),
         })
         .collect()
 }
 
-/// Returns true if some field has an attribute declaring it should not be serialized.
-///
-/// The field attribute is: `#[ssz(skip_serializing)]`
-fn should_skip_serializing(field: &syn::Field) -> bool {
-    field.attrs.iter().any(|attr| {
-        attr.path.is_ident("ssz")
-            && attr.tokens.to_string().replace(" ", "") == "(skip_serializing)"
-    })
-}
-
-/// Implements `ssz::Encode` for some `struct`.
+/// Implements `ssz::Encode` for some `struct` or union `enum`.
 ///
 /// Fields are encoded in the order they are defined.
 ///
 /// ## Field attributes
 ///
 /// - `#[ssz(skip_serializing)]`: the field will not be serialized.
+/// - `#[ssz(with = "module")]`: the field will be encoded using the `module::encode` functions
+/// (`is_ssz_fixed_len`, `ssz_fixed_len`, `ssz_bytes_len` and `ssz_append`) instead of its
+/// `Encode` implementation.
+///
+/// ## Enums
+///
+/// Enums are only supported with the `#[ssz(enum_behaviour = "union")]` attribute. Each variant
+/// must hold exactly one value and is encoded as an SSZ union with a one-byte selector equal to the
+/// index of the variant. The first variant may be a unit variant, which represents the SSZ `None`.
 #[proc_macro_derive(Encode, attributes(ssz))]
 pub fn ssz_encode_derive(input: TokenStream) -> TokenStream {
     let item = parse_macro_input!(input as DeriveInput);
 
+    match &item.data {
+        syn::Data::Struct(s) if !is_union_enum(&item) => ssz_encode_derive_struct(&item, s),
+        syn::Data::Enum(e) if is_union_enum(&item) => ssz_encode_derive_enum_union(&item, e),
+        _ => panic!(
+            "ssz_derive only supports structs and enums with #[ssz(enum_behaviour = \"union\")]."
+        ),
+    }
+}
+
+fn ssz_encode_derive_struct(item: &DeriveInput, struct_data: &syn::DataStruct) -> TokenStream {
     let name = &item.ident;
     let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
 
-    let struct_data = match &item.data {
-        syn::Data::Struct(s) => s,
-        _ => panic!("ssz_derive only supports structs."),
-    };
+    let mut is_fixed_lens = vec![];
+    let mut fixed_lens = vec![];
+    let mut bytes_lens = vec![];
+    let mut appends = vec![];
+
+    for field in struct_data
+        .fields
+        .iter()
+        .filter(|f| !should_skip_serializing(f))
+    {
+        let ident = match &field.ident {
+            Some(ref ident) => ident,
+            _ => panic!("ssz_derive only supports named struct fields."),
+        };
+        let ty = &field.ty;
+
+        if let Some(module) = get_with_module(field) {
+            is_fixed_lens.push(quote! { #module::encode::is_ssz_fixed_len() });
+            fixed_lens.push(quote! { #module::encode::ssz_fixed_len() });
+            bytes_lens.push(quote! { #module::encode::ssz_bytes_len(&self.#ident) });
+            appends.push(quote! {
+                encoder.append_parameterized(
+                    #module::encode::is_ssz_fixed_len(),
+                    |buf| #module::encode::ssz_append(&self.#ident, buf)
+                );
+            });
+        } else {
+            is_fixed_lens.push(quote! { <#ty as ssz::Encode>::is_ssz_fixed_len() });
+            fixed_lens.push(quote! { <#ty as ssz::Encode>::ssz_fixed_len() });
+            bytes_lens.push(quote! { self.#ident.ssz_bytes_len() });
+            appends.push(quote! {
+                encoder.append(&self.#ident);
+            });
+        }
+    }
 
-    let field_idents = get_serializable_named_field_idents(&struct_data);
-    let field_idents_a = get_serializable_named_field_idents(&struct_data);
-    let field_types_a = get_serializable_field_types(&struct_data);
-    let field_types_b = field_types_a.clone();
-    let field_types_d = field_types_a.clone();
-    let field_types_e = field_types_a.clone();
-    let field_types_f = field_types_a.clone();
+    let is_fixed_lens_a = &is_fixed_lens;
+    let is_fixed_lens_b = &is_fixed_lens;
+    let fixed_lens_a = &fixed_lens;
+    let fixed_lens_b = &fixed_lens;
 
     let output = quote! {
         impl #impl_generics ssz::Encode for #name #ty_generics #where_clause {
             fn is_ssz_fixed_len() -> bool {
                 #(
-                    <#field_types_a as ssz::Encode>::is_ssz_fixed_len() &&
+                    #is_fixed_lens_a &&
                 )*
                     true
             }
@@ -100,7 +184,7 @@ pub fn ssz_encode_derive(input: TokenStream) -> TokenStream {
                     let mut len: usize = 0;
                     #(
                         len = len
-                            .checked_add(<#field_types_b as ssz::Encode>::ssz_fixed_len())
+                            .checked_add(#fixed_lens_a)
                             .expect("encode ssz_fixed_len length overflow");
                     )*
                     len
@@ -115,16 +199,16 @@ pub fn ssz_encode_derive(input: TokenStream) -> TokenStream {
                 } else {
                     let mut len: usize = 0;
                     #(
-                        if <#field_types_d as ssz::Encode>::is_ssz_fixed_len() {
+                        if #is_fixed_lens_b {
                             len = len
-                                .checked_add(<#field_types_e as ssz::Encode>::ssz_fixed_len())
+                                .checked_add(#fixed_lens_b)
                                 .expect("encode ssz_bytes_len length overflow");
                         } else {
                             len = len
                                 .checked_add(ssz::BYTES_PER_LENGTH_OFFSET)
                                 .expect("encode ssz_bytes_len length overflow for offset");
                             len = len
-                                .checked_add(self.#field_idents_a.ssz_bytes_len())
+                                .checked_add(#bytes_lens)
                                 .expect("encode ssz_bytes_len length overflow for bytes");
                         }
                     )*
@@ -137,14 +221,14 @@ pub fn ssz_encode_derive(input: TokenStream) -> TokenStream {
                 let mut offset: usize = 0;
                 #(
                     offset = offset
-                        .checked_add(<#field_types_f as ssz::Encode>::ssz_fixed_len())
+                        .checked_add(#fixed_lens)
                         .expect("encode ssz_append offset overflow");
                 )*
 
                 let mut encoder = ssz::SszEncoder::container(buf, offset);
 
                 #(
-                    encoder.append(&self.#field_idents);
+                    #appends
                 )*
 
                 encoder.finalize();
@@ -154,6 +238,70 @@ pub fn ssz_encode_derive(input: TokenStream) -> TokenStream {
     output.into()
 }
 
+fn ssz_encode_derive_enum_union(item: &DeriveInput, enum_data: &syn::DataEnum) -> TokenStream {
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
+
+    let mut bytes_lens = vec![];
+    let mut appends = vec![];
+
+    for (i, (variant, ty)) in enum_data
+        .variants
+        .iter()
+        .zip(get_union_variant_types(enum_data))
+        .enumerate()
+    {
+        let variant_name = &variant.ident;
+        let selector = i as u8;
+
+        if ty.is_some() {
+            bytes_lens.push(quote! {
+                #name::#variant_name(inner) => ssz::Encode::ssz_bytes_len(inner)
+                    .checked_add(1)
+                    .expect("encode ssz_bytes_len length overflow"),
+            });
+            appends.push(quote! {
+                #name::#variant_name(inner) => {
+                    buf.push(#selector);
+                    ssz::Encode::ssz_append(inner, buf);
+                }
+            });
+        } else {
+            bytes_lens.push(quote! {
+                #name::#variant_name => 1,
+            });
+            appends.push(quote! {
+                #name::#variant_name => buf.push(#selector),
+            });
+        }
+    }
+
+    let output = quote! {
+        impl #impl_generics ssz::Encode for #name #ty_generics #where_clause {
+            fn is_ssz_fixed_len() -> bool {
+                false
+            }
+
+            fn ssz_bytes_len(&self) -> usize {
+                match self {
+                    #(
+                        #bytes_lens
+                    )*
+                }
+            }
+
+            fn ssz_append(&self, buf: &mut Vec<u8>) {
+                match self {
+                    #(
+                        #appends
+                    )*
+                }
+            }
+        }
+    };
+    output.into()
+}
+
 /// Returns true if some field has an attribute declaring it should not be deserialized.
 ///
 /// The field attribute is: `#[ssz(skip_deserializing)]`
@@ -164,7 +312,7 @@ fn should_skip_deserializing(field: &syn::Field) -> bool {
     })
 }
 
-/// Implements `ssz::Decode` for some `struct`.
+/// Implements `ssz::Decode` for some `struct` or union `enum`.
 ///
 /// Fields are decoded in the order they are defined.
 ///
@@ -173,18 +321,31 @@ fn should_skip_deserializing(field: &syn::Field) -> bool {
 /// - `#[ssz(skip_deserializing)]`: during de-serialization the field will be instantiated from a
 /// `Default` implementation. The decoder will assume that the field was not serialized at all
 /// (e.g., if it has been serialized, an error will be raised instead of `Default` overriding it).
-#[proc_macro_derive(Decode)]
+/// - `#[ssz(with = "module")]`: the field will be decoded using the `module::decode` functions
+/// (`is_ssz_fixed_len`, `ssz_fixed_len` and `from_ssz_bytes`) instead of its `Decode`
+/// implementation.
+///
+/// ## Enums
+///
+/// Enums are only supported with the `#[ssz(enum_behaviour = "union")]` attribute, see
+/// `ssz_encode_derive` for details.
+#[proc_macro_derive(Decode, attributes(ssz))]
 pub fn ssz_decode_derive(input: TokenStream) -> TokenStream {
     let item = parse_macro_input!(input as DeriveInput);
 
+    match &item.data {
+        syn::Data::Struct(s) if !is_union_enum(&item) => ssz_decode_derive_struct(&item, s),
+        syn::Data::Enum(e) if is_union_enum(&item) => ssz_decode_derive_enum_union(&item, e),
+        _ => panic!(
+            "ssz_derive only supports structs and enums with #[ssz(enum_behaviour = \"union\")]."
+        ),
+    }
+}
+
+fn ssz_decode_derive_struct(item: &DeriveInput, struct_data: &syn::DataStruct) -> TokenStream {
     let name = &item.ident;
     let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
 
-    let struct_data = match &item.data {
-        syn::Data::Struct(s) => s,
-        _ => panic!("ssz_derive only supports structs."),
-    };
-
     let mut register_types = vec![];
     let mut field_names = vec![];
     let mut fixed_decodes = vec![];
@@ -210,6 +371,32 @@ pub fn ssz_decode_derive(input: TokenStream) -> TokenStream {
                     fixed_decodes.push(quote! {
                         let #ident = <_>::default();
                     });
+                } else if let Some(module) = get_with_module(field) {
+                    register_types.push(quote! {
+                        builder.register_type_parameterized(
+                            #module::decode::is_ssz_fixed_len(),
+                            #module::decode::ssz_fixed_len(),
+                        )?;
+                    });
+
+                    decodes.push(quote! {
+                        let #ident = decoder.decode_next_with(|slice| #module::decode::from_ssz_bytes(slice))?;
+                    });
+
+                    fixed_decodes.push(quote! {
+                        let #ident = decode_field!(
+                            #module::decode::ssz_fixed_len(),
+                            #module::decode::from_ssz_bytes
+                        );
+                    });
+
+                    is_fixed_lens.push(quote! {
+                        #module::decode::is_ssz_fixed_len()
+                    });
+
+                    fixed_lens.push(quote! {
+                        #module::decode::ssz_fixed_len()
+                    });
                 } else {
                     let ty = &field.ty;
 
@@ -222,7 +409,10 @@ pub fn ssz_decode_derive(input: TokenStream) -> TokenStream {
                     });
 
                     fixed_decodes.push(quote! {
-                        let #ident = decode_field!(#ty);
+                        let #ident = decode_field!(
+                            <#ty as ssz::Decode>::ssz_fixed_len(),
+                            <#ty as ssz::Decode>::from_ssz_bytes
+                        );
                     });
 
                     is_fixed_lens.push(quote! {
@@ -274,10 +464,10 @@ pub fn ssz_decode_derive(input: TokenStream) -> TokenStream {
                     let mut end = start;
 
                     macro_rules! decode_field {
-                        ($type: ty) => {{
+                        ($fixed_len: expr, $from_ssz_bytes: expr) => {{
                             start = end;
                             end = end
-                                .checked_add(<$type as ssz::Decode>::ssz_fixed_len())
+                                .checked_add($fixed_len)
                                 .ok_or_else(|| ssz::DecodeError::OutOfBoundsByte {
                                     i: usize::max_value()
                                 })?;
@@ -286,7 +476,7 @@ pub fn ssz_decode_derive(input: TokenStream) -> TokenStream {
                                     len: bytes.len(),
                                     expected: end
                                 })?;
-                            <$type as ssz::Decode>::from_ssz_bytes(slice)?
+                            $from_ssz_bytes(slice)?
                         }};
                     }
 
@@ -324,3 +514,54 @@ pub fn ssz_decode_derive(input: TokenStream) -> TokenStream {
     };
     output.into()
 }
+
+fn ssz_decode_derive_enum_union(item: &DeriveInput, enum_data: &syn::DataEnum) -> TokenStream {
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
+
+    let mut decodes = vec![];
+
+    for (i, (variant, ty)) in enum_data
+        .variants
+        .iter()
+        .zip(get_union_variant_types(enum_data))
+        .enumerate()
+    {
+        let variant_name = &variant.ident;
+        let selector = i as u8;
+
+        if let Some(ty) = ty {
+            decodes.push(quote! {
+                #selector => <#ty as ssz::Decode>::from_ssz_bytes(body).map(#name::#variant_name),
+            });
+        } else {
+            decodes.push(quote! {
+                #selector if body.is_empty() => Ok(#name::#variant_name),
+                #selector => Err(ssz::DecodeError::BytesInvalid(format!(
+                    "unit union variant has {} trailing bytes",
+                    body.len()
+                ))),
+            });
+        }
+    }
+
+    let output = quote! {
+        impl #impl_generics ssz::Decode for #name #ty_generics #where_clause {
+            fn is_ssz_fixed_len() -> bool {
+                false
+            }
+
+            fn from_ssz_bytes(bytes: &[u8]) -> std::result::Result<Self, ssz::DecodeError> {
+                let (selector, body) = ssz::split_union_bytes(bytes)?;
+
+                match selector.into() {
+                    #(
+                        #decodes
+                    )*
+                    other => Err(ssz::DecodeError::UnionSelectorInvalid(other)),
+                }
+            }
+        }
+    };
+    output.into()
+}
@@ -2,12 +2,13 @@ use eth2_hashing::hash;
 use int_to_bytes::int_to_bytes32;
 use merkle_proof::{MerkleTree, MerkleTreeError};
 use safe_arith::SafeArith;
-use types::Hash256;
+use types::{DepositTreeSnapshot, FinalizedExecutionBlock, Hash256};
 
 /// Emulates the eth1 deposit contract merkle tree.
 pub struct DepositDataTree {
     tree: MerkleTree,
     mix_in_length: usize,
+    finalized_execution_block: Option<FinalizedExecutionBlock>,
     depth: usize,
 }
 
@@ -17,10 +18,30 @@ impl DepositDataTree {
         Self {
             tree: MerkleTree::create(leaves, depth),
             mix_in_length,
+            finalized_execution_block: None,
             depth,
         }
     }
 
+    /// Restore a tree from a snapshot of its finalized subtrees.
+    ///
+    /// Only deposits made after the snapshot can be proven by the restored tree.
+    pub fn from_snapshot(
+        snapshot: &DepositTreeSnapshot,
+        depth: usize,
+    ) -> Result<Self, MerkleTreeError> {
+        Ok(Self {
+            tree: MerkleTree::from_finalized_snapshot(
+                &snapshot.finalized,
+                snapshot.deposit_count as usize,
+                depth,
+            )?,
+            mix_in_length: snapshot.deposit_count as usize,
+            finalized_execution_block: Some(snapshot.into()),
+            depth,
+        })
+    }
+
     /// Returns 32 bytes representing the "mix in length" for the merkle root of this tree.
     fn length_bytes(&self) -> Vec<u8> {
         int_to_bytes32(self.mix_in_length as u64)
@@ -38,10 +59,12 @@ impl DepositDataTree {
     ///
     /// The Merkle proof is in "bottom-up" order, starting with a leaf node
     /// and moving up the tree. Its length will be exactly equal to `depth + 1`.
-    pub fn generate_proof(&self, index: usize) -> (Hash256, Vec<Hash256>) {
-        let (root, mut proof) = self.tree.generate_proof(index, self.depth);
+    ///
+    /// Returns an error if the leaf has been finalized.
+    pub fn generate_proof(&self, index: usize) -> Result<(Hash256, Vec<Hash256>), MerkleTreeError> {
+        let (root, mut proof) = self.tree.generate_proof(index, self.depth)?;
         proof.push(Hash256::from_slice(&self.length_bytes()));
-        (root, proof)
+        Ok((root, proof))
     }
 
     /// Add a deposit to the merkle tree.
@@ -50,4 +73,29 @@ impl DepositDataTree {
         self.mix_in_length.safe_add_assign(1)?;
         Ok(())
     }
+
+    /// Finalize the first `finalized_execution_block.deposit_count` deposits,
+    /// discarding the leaves and branches that are no longer needed to extend the tree.
+    pub fn finalize(
+        &mut self,
+        finalized_execution_block: FinalizedExecutionBlock,
+    ) -> Result<(), MerkleTreeError> {
+        self.tree
+            .finalize_deposits(finalized_execution_block.deposit_count as usize, self.depth)?;
+        self.finalized_execution_block = Some(finalized_execution_block);
+        Ok(())
+    }
+
+    /// Returns a snapshot of the finalized portion of the tree, or `None` if nothing has been
+    /// finalized.
+    pub fn get_snapshot(&self) -> Option<DepositTreeSnapshot> {
+        let finalized_execution_block = self.finalized_execution_block.as_ref()?;
+        Some(DepositTreeSnapshot {
+            finalized: self.tree.get_finalized_hashes(),
+            deposit_root: finalized_execution_block.deposit_root,
+            deposit_count: finalized_execution_block.deposit_count,
+            execution_block_hash: finalized_execution_block.block_hash,
+            execution_block_height: finalized_execution_block.block_height,
+        })
+    }
 }
@@ -10,7 +10,7 @@ pub use get_attesting_indices::get_attesting_indices;
 pub use get_base_reward::get_base_reward;
 pub use get_indexed_attestation::get_indexed_attestation;
 pub use initiate_validator_exit::initiate_validator_exit;
-pub use slash_validator::slash_validator;
+pub use slash_validator::{get_slashing_rewards, slash_validator};
 
 use safe_arith::{ArithError, SafeArith};
 use types::{BeaconState, EthSpec};
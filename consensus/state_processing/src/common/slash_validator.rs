@@ -41,9 +41,8 @@ pub fn slash_validator<T: EthSpec>(
     // Apply proposer and whistleblower rewards
     let proposer_index = state.get_beacon_proposer_index(state.slot, spec)?;
     let whistleblower_index = opt_whistleblower_index.unwrap_or(proposer_index);
-    let whistleblower_reward =
-        validator_effective_balance.safe_div(spec.whistleblower_reward_quotient)?;
-    let proposer_reward = whistleblower_reward.safe_div(spec.proposer_reward_quotient)?;
+    let (whistleblower_reward, proposer_reward) =
+        get_slashing_rewards(validator_effective_balance, spec)?;
 
     // Ensure the whistleblower index is in the validator registry.
     if state.validators.get(whistleblower_index).is_none() {
@@ -61,3 +60,20 @@ pub fn slash_validator<T: EthSpec>(
 
     Ok(())
 }
+
+/// Returns the `(whistleblower_reward, proposer_reward)` for slashing a validator with the given
+/// effective balance.
+///
+/// The whistleblower receives `whistleblower_reward - proposer_reward`, so when the proposer is
+/// the whistleblower (as for all slashings included in blocks) it receives `whistleblower_reward`.
+///
+/// Spec v0.12.1
+pub fn get_slashing_rewards(
+    validator_effective_balance: u64,
+    spec: &ChainSpec,
+) -> Result<(u64, u64), Error> {
+    let whistleblower_reward =
+        validator_effective_balance.safe_div(spec.whistleblower_reward_quotient)?;
+    let proposer_reward = whistleblower_reward.safe_div(spec.proposer_reward_quotient)?;
+    Ok((whistleblower_reward, proposer_reward))
+}
@@ -10,6 +10,7 @@ pub use self::verify_attester_slashing::{
     get_slashable_indices, get_slashable_indices_modular, verify_attester_slashing,
 };
 pub use self::verify_proposer_slashing::verify_proposer_slashing;
+pub use block_rewards::{compute_block_reward, BlockReward};
 pub use block_signature_verifier::BlockSignatureVerifier;
 pub use is_valid_indexed_attestation::is_valid_indexed_attestation;
 pub use verify_attestation::{
@@ -21,6 +22,7 @@ pub use verify_deposit::{
 pub use verify_exit::{verify_exit, verify_exit_time_independent_only};
 
 pub mod block_processing_builder;
+mod block_rewards;
 pub mod block_signature_verifier;
 pub mod errors;
 mod is_valid_indexed_attestation;
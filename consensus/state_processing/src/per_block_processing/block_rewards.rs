@@ -0,0 +1,177 @@
+use super::errors::{BlockProcessingError, HeaderInvalid, IntoWithIndex};
+use super::get_slashable_indices_modular;
+use crate::common::{get_attesting_indices, get_base_reward, get_slashing_rewards};
+use safe_arith::{ArithError, SafeArith};
+use std::collections::HashSet;
+use types::*;
+
+/// The rewards earned by the proposer of a block, in Gwei.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BlockReward {
+    /// The reward for including each of the attestations in the block, in order.
+    ///
+    /// An attestation only earns a reward for the validators which have not already had an
+    /// attestation included for the same epoch. Since these rewards are paid during the epoch
+    /// transition, they are computed using the balances at the slot of the block and may differ
+    /// slightly from the reward that is eventually paid.
+    pub attestation_rewards: Vec<u64>,
+    /// The reward for including each of the proposer slashings in the block, in order.
+    pub proposer_slashing_rewards: Vec<u64>,
+    /// The reward for including each of the attester slashings in the block, in order.
+    pub attester_slashing_rewards: Vec<u64>,
+}
+
+impl BlockReward {
+    /// Returns the sum of all rewards.
+    pub fn total(&self) -> Result<u64, ArithError> {
+        self.attestation_rewards
+            .iter()
+            .chain(&self.proposer_slashing_rewards)
+            .chain(&self.attester_slashing_rewards)
+            .try_fold(0_u64, |total, reward| total.safe_add(*reward))
+    }
+}
+
+/// Computes the rewards earned by the proposer of `block`, given the `state` prior to processing
+/// the block.
+///
+/// The `state` must have been advanced to the slot of the block and have its previous and current
+/// committee caches built. The operations in the block are assumed to be valid, i.e., `block`
+/// should have been (or be about to be) applied to `state` by `per_block_processing`.
+///
+/// Spec v0.12.1
+pub fn compute_block_reward<T: EthSpec>(
+    state: &BeaconState<T>,
+    block: &BeaconBlock<T>,
+    spec: &ChainSpec,
+) -> Result<BlockReward, BlockProcessingError> {
+    if state.slot != block.slot {
+        return Err(BlockProcessingError::HeaderInvalid {
+            reason: HeaderInvalid::StateSlotMismatch,
+        });
+    }
+
+    let current_epoch = state.current_epoch();
+
+    // Validators slashed earlier in the block cannot be slashed again, nor do they earn the proposer
+    // an attestation reward.
+    let mut slashed_in_block = HashSet::new();
+
+    let proposer_slashing_rewards = block
+        .body
+        .proposer_slashings
+        .iter()
+        .map(|proposer_slashing| {
+            let slashed_index = proposer_slashing.signed_header_1.message.proposer_index as usize;
+            slashed_in_block.insert(slashed_index);
+            get_proposer_slashing_reward(state, slashed_index, spec)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let attester_slashing_rewards = block
+        .body
+        .attester_slashings
+        .iter()
+        .enumerate()
+        .map(|(i, attester_slashing)| {
+            let slashable_indices =
+                get_slashable_indices_modular(state, attester_slashing, |index, validator| {
+                    validator.is_slashable_at(current_epoch)
+                        && !slashed_in_block.contains(&(index as usize))
+                })
+                .map_err(|e| e.into_with_index(i))?;
+
+            slashable_indices
+                .into_iter()
+                .try_fold(0_u64, |total, slashed_index| {
+                    slashed_in_block.insert(slashed_index as usize);
+                    let reward = get_proposer_slashing_reward(state, slashed_index as usize, spec)?;
+                    Ok(total.safe_add(reward)?)
+                })
+        })
+        .collect::<Result<Vec<_>, BlockProcessingError>>()?;
+
+    let total_active_balance = state.get_total_balance(
+        state.get_cached_active_validator_indices(RelativeEpoch::Current)?,
+        spec,
+    )?;
+    let mut included_previous = get_included_validators(state, &state.previous_epoch_attestations)?;
+    let mut included_current = get_included_validators(state, &state.current_epoch_attestations)?;
+
+    let attestation_rewards = block
+        .body
+        .attestations
+        .iter()
+        .map(|attestation| {
+            let included = if attestation.data.target.epoch == current_epoch {
+                &mut included_current
+            } else {
+                &mut included_previous
+            };
+
+            let committee =
+                state.get_beacon_committee(attestation.data.slot, attestation.data.index)?;
+            let attesting_indices =
+                get_attesting_indices::<T>(committee.committee, &attestation.aggregation_bits)?;
+
+            let mut reward = 0_u64;
+            for index in attesting_indices {
+                // The proposer is only rewarded for the first inclusion of each validator's
+                // attestation, as this is the inclusion with the minimum delay.
+                if !included.insert(index) {
+                    continue;
+                }
+
+                let is_slashed = state
+                    .validators
+                    .get(index)
+                    .ok_or(BeaconStateError::UnknownValidator(index as u64))?
+                    .slashed;
+                if is_slashed || slashed_in_block.contains(&index) {
+                    continue;
+                }
+
+                let base_reward = get_base_reward(state, index, total_active_balance, spec)?;
+                reward.safe_add_assign(base_reward.safe_div(spec.proposer_reward_quotient)?)?;
+            }
+
+            Ok(reward)
+        })
+        .collect::<Result<Vec<_>, BlockProcessingError>>()?;
+
+    Ok(BlockReward {
+        attestation_rewards,
+        proposer_slashing_rewards,
+        attester_slashing_rewards,
+    })
+}
+
+/// Returns the reward earned by a proposer for slashing the validator at `slashed_index`.
+fn get_proposer_slashing_reward<T: EthSpec>(
+    state: &BeaconState<T>,
+    slashed_index: usize,
+    spec: &ChainSpec,
+) -> Result<u64, BlockProcessingError> {
+    let effective_balance = state.get_effective_balance(slashed_index, spec)?;
+    let (whistleblower_reward, _) = get_slashing_rewards(effective_balance, spec)?;
+    Ok(whistleblower_reward)
+}
+
+/// Returns the indices of all validators with an attestation in `pending_attestations`.
+fn get_included_validators<T: EthSpec>(
+    state: &BeaconState<T>,
+    pending_attestations: &[PendingAttestation<T>],
+) -> Result<HashSet<usize>, BlockProcessingError> {
+    let mut included = HashSet::new();
+    for pending_attestation in pending_attestations {
+        let committee = state.get_beacon_committee(
+            pending_attestation.data.slot,
+            pending_attestation.data.index,
+        )?;
+        included.extend(get_attesting_indices::<T>(
+            committee.committee,
+            &pending_attestation.aggregation_bits,
+        )?);
+    }
+    Ok(included)
+}
@@ -2,6 +2,7 @@
 
 use super::block_processing_builder::BlockProcessingBuilder;
 use super::errors::*;
+use crate::per_block_processing::{compute_block_reward, BlockReward};
 use crate::{per_block_processing, BlockSignatureStrategy};
 use types::test_utils::{
     AttestationTestTask, AttesterSlashingTestTask, DepositTestTask, ProposerSlashingTestTask,
@@ -884,6 +885,93 @@ fn invalid_proposer_slashing_proposal_epoch_mismatch() {
     );
 }
 
+/// Processes `block` and checks that the increase in the proposer's balance matches the slashing
+/// rewards computed by `compute_block_reward`.
+fn check_slashing_rewards(
+    block: &SignedBeaconBlock<E>,
+    state: &mut BeaconState<E>,
+    spec: &ChainSpec,
+) -> BlockReward {
+    let reward = compute_block_reward(state, &block.message, spec).unwrap();
+
+    let proposer_index = block.message.proposer_index as usize;
+    let balance_before = state.balances[proposer_index];
+
+    per_block_processing(
+        state,
+        block,
+        None,
+        BlockSignatureStrategy::VerifyIndividual,
+        spec,
+    )
+    .unwrap();
+
+    let slashing_rewards = reward
+        .proposer_slashing_rewards
+        .iter()
+        .chain(&reward.attester_slashing_rewards)
+        .sum::<u64>();
+    assert!(slashing_rewards > 0);
+    assert_eq!(
+        state.balances[proposer_index],
+        balance_before + slashing_rewards
+    );
+
+    reward
+}
+
+#[test]
+fn block_reward_proposer_slashings() {
+    let spec = MainnetEthSpec::default_spec();
+    let builder = get_builder(&spec, EPOCH_OFFSET, VALIDATOR_COUNT);
+    let test_task = ProposerSlashingTestTask::Valid;
+    let (block, mut state) = builder.build_with_proposer_slashing(test_task, 2, None, None, &spec);
+
+    let reward = check_slashing_rewards(&block, &mut state, &spec);
+    assert_eq!(reward.proposer_slashing_rewards.len(), 2);
+    assert!(reward.attester_slashing_rewards.is_empty());
+}
+
+#[test]
+fn block_reward_attester_slashings() {
+    let spec = MainnetEthSpec::default_spec();
+    let builder = get_builder(&spec, EPOCH_OFFSET, VALIDATOR_COUNT);
+    let test_task = AttesterSlashingTestTask::Valid;
+    let (block, mut state) = builder.build_with_attester_slashing(test_task, 1, None, None, &spec);
+
+    let reward = check_slashing_rewards(&block, &mut state, &spec);
+    assert!(reward.proposer_slashing_rewards.is_empty());
+    assert_eq!(reward.attester_slashing_rewards.len(), 1);
+}
+
+#[test]
+fn block_reward_attestations() {
+    let spec = MainnetEthSpec::default_spec();
+    let builder = get_builder(&spec, EPOCH_OFFSET, VALIDATOR_COUNT);
+    let test_task = AttestationTestTask::Valid;
+    let (block, mut state) = builder.build_with_n_attestations(test_task, 2, None, None, &spec);
+
+    let reward = compute_block_reward(&state, &block.message, &spec).unwrap();
+    assert_eq!(reward.attestation_rewards.len(), 2);
+    assert!(reward.attestation_rewards.iter().all(|reward| *reward > 0));
+    assert_eq!(
+        reward.total(),
+        Ok(reward.attestation_rewards.iter().sum::<u64>())
+    );
+
+    // Once the attestations have been included, including them again earns nothing.
+    per_block_processing(
+        &mut state,
+        &block,
+        None,
+        BlockSignatureStrategy::VerifyIndividual,
+        &spec,
+    )
+    .unwrap();
+    let reward = compute_block_reward(&state, &block.message, &spec).unwrap();
+    assert_eq!(reward.attestation_rewards, vec![0, 0]);
+}
+
 fn get_builder(
     spec: &ChainSpec,
     epoch_offset: u64,
@@ -7,12 +7,16 @@ pub mod apply_rewards;
 pub mod errors;
 pub mod process_slashings;
 pub mod registry_updates;
+pub mod single_pass;
 pub mod tests;
 pub mod validator_statuses;
 
-pub use apply_rewards::process_rewards_and_penalties;
+pub use apply_rewards::{
+    get_attestation_deltas, process_rewards_and_penalties, AttestationDelta, Delta,
+};
 pub use process_slashings::process_slashings;
 pub use registry_updates::process_registry_updates;
+pub use single_pass::{process_epoch_single_pass, SinglePassConfig};
 pub use validator_statuses::{TotalBalances, ValidatorStatus, ValidatorStatuses};
 
 /// Provides a summary of validator participation during the epoch.
@@ -44,21 +48,16 @@ pub fn per_epoch_processing<T: EthSpec>(
     // Justification and finalization.
     process_justification_and_finalization(state, &validator_statuses.total_balances)?;
 
-    // Rewards and Penalties.
-    process_rewards_and_penalties(state, &mut validator_statuses, spec)?;
-
-    // Registry Updates.
-    process_registry_updates(state, spec)?;
-
-    // Slashings.
-    process_slashings(
+    // Rewards and penalties, registry updates, slashings and effective balance updates.
+    process_epoch_single_pass(
         state,
-        validator_statuses.total_balances.current_epoch(),
+        &validator_statuses,
+        SinglePassConfig::enable_all(),
         spec,
     )?;
 
-    // Final updates.
-    process_final_updates(state, spec)?;
+    // Final updates, excluding the effective balance updates of the single pass.
+    process_non_validator_updates(state)?;
 
     // Rotate the epoch caches to suit the epoch transition.
     state.advance_caches();
@@ -159,20 +158,17 @@ pub fn process_final_updates<T: EthSpec>(
     state: &mut BeaconState<T>,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
-    let current_epoch = state.current_epoch();
-    let next_epoch = state.next_epoch()?;
-
-    // Reset eth1 data votes.
-    if state
-        .slot
-        .safe_add(1)?
-        .safe_rem(T::SlotsPerEth1VotingPeriod::to_u64())?
-        == 0
-    {
-        state.eth1_data_votes = VariableList::empty();
-    }
+    process_effective_balance_updates(state, spec)?;
+    process_non_validator_updates(state)
+}
 
-    // Update effective balances with hysteresis (lag).
+/// Update effective balances with hysteresis (lag).
+///
+/// Spec v0.12.1
+pub fn process_effective_balance_updates<T: EthSpec>(
+    state: &mut BeaconState<T>,
+    spec: &ChainSpec,
+) -> Result<(), Error> {
     let hysteresis_increment = spec
         .effective_balance_increment
         .safe_div(spec.hysteresis_quotient)?;
@@ -191,6 +187,26 @@ pub fn process_final_updates<T: EthSpec>(
         }
     }
 
+    Ok(())
+}
+
+/// The portion of `process_final_updates` which does not visit the validator registry.
+///
+/// Spec v0.12.1
+pub fn process_non_validator_updates<T: EthSpec>(state: &mut BeaconState<T>) -> Result<(), Error> {
+    let current_epoch = state.current_epoch();
+    let next_epoch = state.next_epoch()?;
+
+    // Reset eth1 data votes.
+    if state
+        .slot
+        .safe_add(1)?
+        .safe_rem(T::SlotsPerEth1VotingPeriod::to_u64())?
+        == 0
+    {
+        state.eth1_data_votes = VariableList::empty();
+    }
+
     // Reset slashings
     state.set_slashings(next_epoch, 0)?;
 
@@ -6,10 +6,10 @@ use safe_arith::SafeArith;
 use types::*;
 
 /// Use to track the changes to a validators balance.
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Debug, PartialEq)]
 pub struct Delta {
-    rewards: u64,
-    penalties: u64,
+    pub rewards: u64,
+    pub penalties: u64,
 }
 
 impl Delta {
@@ -26,12 +26,36 @@ impl Delta {
     }
 
     /// Combine two deltas.
-    fn combine(&mut self, other: Delta) -> Result<(), Error> {
+    pub(crate) fn combine(&mut self, other: Delta) -> Result<(), Error> {
         self.reward(other.rewards)?;
         self.penalize(other.penalties)
     }
 }
 
+/// The changes to a validator's balance due to each component of the attestation rewards.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct AttestationDelta {
+    pub source_delta: Delta,
+    pub target_delta: Delta,
+    pub head_delta: Delta,
+    /// Includes the rewards received as a proposer for including the attestations of others.
+    pub inclusion_delay_delta: Delta,
+    pub inactivity_penalty_delta: Delta,
+}
+
+impl AttestationDelta {
+    /// Flatten the components into a single delta.
+    pub fn flatten(self) -> Result<Delta, Error> {
+        let mut result = Delta::default();
+        result.combine(self.source_delta)?;
+        result.combine(self.target_delta)?;
+        result.combine(self.head_delta)?;
+        result.combine(self.inclusion_delay_delta)?;
+        result.combine(self.inactivity_penalty_delta)?;
+        Ok(result)
+    }
+}
+
 /// Apply attester and proposer rewards.
 ///
 /// Spec v0.12.1
@@ -51,11 +75,12 @@ pub fn process_rewards_and_penalties<T: EthSpec>(
         return Err(Error::ValidatorStatusesInconsistent);
     }
 
-    let deltas = get_attestation_deltas(state, &validator_statuses, spec)?;
+    let deltas = get_attestation_deltas(state, validator_statuses, spec)?;
 
     // Apply the deltas, erroring on overflow above but not on overflow below (saturating at 0
     // instead).
-    for (i, delta) in deltas.iter().enumerate() {
+    for (i, delta) in deltas.into_iter().enumerate() {
+        let delta = delta.flatten()?;
         state.balances[i] = state.balances[i].safe_add(delta.rewards)?;
         state.balances[i] = state.balances[i].saturating_sub(delta.penalties);
     }
@@ -63,20 +88,21 @@ pub fn process_rewards_and_penalties<T: EthSpec>(
     Ok(())
 }
 
-/// Apply rewards for participation in attestations during the previous epoch.
+/// Compute the rewards and penalties for participation in attestations during the previous
+/// epoch, broken down by component.
+///
+/// The `validator_statuses` must have processed the attestations of `state`. The result is
+/// indexed by validator index.
 ///
 /// Spec v0.12.1
-fn get_attestation_deltas<T: EthSpec>(
+pub fn get_attestation_deltas<T: EthSpec>(
     state: &BeaconState<T>,
     validator_statuses: &ValidatorStatuses,
     spec: &ChainSpec,
-) -> Result<Vec<Delta>, Error> {
-    let finality_delay = state
-        .previous_epoch()
-        .safe_sub(state.finalized_checkpoint.epoch)?
-        .as_u64();
+) -> Result<Vec<AttestationDelta>, Error> {
+    let finality_delay = get_finality_delay(state)?;
 
-    let mut deltas = vec![Delta::default(); state.validators.len()];
+    let mut deltas = vec![AttestationDelta::default(); state.validators.len()];
 
     let total_balances = &validator_statuses.total_balances;
 
@@ -89,37 +115,92 @@ fn get_attestation_deltas<T: EthSpec>(
         .enumerate()
         .filter(|(_, validator)| is_eligible_validator(validator))
     {
-        let base_reward = get_base_reward(state, index, total_balances.current_epoch(), spec)?;
-
-        let source_delta =
-            get_source_delta(validator, base_reward, total_balances, finality_delay, spec)?;
-        let target_delta =
-            get_target_delta(validator, base_reward, total_balances, finality_delay, spec)?;
-        let head_delta =
-            get_head_delta(validator, base_reward, total_balances, finality_delay, spec)?;
-        let (inclusion_delay_delta, proposer_delta) =
-            get_inclusion_delay_delta(validator, base_reward, spec)?;
-        let inactivity_penalty_delta =
-            get_inactivity_penalty_delta(validator, base_reward, finality_delay, spec)?;
-
-        deltas[index].combine(source_delta)?;
-        deltas[index].combine(target_delta)?;
-        deltas[index].combine(head_delta)?;
-        deltas[index].combine(inclusion_delay_delta)?;
-        deltas[index].combine(inactivity_penalty_delta)?;
+        let (delta, proposer_delta) = get_validator_attestation_delta(
+            state,
+            index,
+            validator,
+            total_balances,
+            finality_delay,
+            spec,
+        )?;
 
-        if let Some((proposer_index, proposer_delta)) = proposer_delta {
-            if proposer_index >= deltas.len() {
-                return Err(Error::ValidatorStatusesInconsistent);
-            }
+        // Combine rather than assign, so that rewards already received as a proposer are kept.
+        let validator_delta = &mut deltas[index];
+        validator_delta.source_delta.combine(delta.source_delta)?;
+        validator_delta.target_delta.combine(delta.target_delta)?;
+        validator_delta.head_delta.combine(delta.head_delta)?;
+        validator_delta
+            .inclusion_delay_delta
+            .combine(delta.inclusion_delay_delta)?;
+        validator_delta
+            .inactivity_penalty_delta
+            .combine(delta.inactivity_penalty_delta)?;
 
-            deltas[proposer_index].combine(proposer_delta)?;
+        if let Some((proposer_index, proposer_delta)) = proposer_delta {
+            deltas
+                .get_mut(proposer_index)
+                .ok_or(Error::ValidatorStatusesInconsistent)?
+                .inclusion_delay_delta
+                .combine(proposer_delta)?;
         }
     }
 
     Ok(deltas)
 }
 
+/// The number of epochs since the last finalized checkpoint, as seen from the previous epoch.
+pub(crate) fn get_finality_delay<T: EthSpec>(state: &BeaconState<T>) -> Result<u64, Error> {
+    Ok(state
+        .previous_epoch()
+        .safe_sub(state.finalized_checkpoint.epoch)?
+        .as_u64())
+}
+
+/// Compute the attestation rewards and penalties of a single eligible validator.
+///
+/// The inclusion delay reward of the proposer who included the validator's attestation is
+/// returned separately, alongside the index of that proposer.
+pub(crate) fn get_validator_attestation_delta<T: EthSpec>(
+    state: &BeaconState<T>,
+    index: usize,
+    validator: &ValidatorStatus,
+    total_balances: &TotalBalances,
+    finality_delay: u64,
+    spec: &ChainSpec,
+) -> Result<(AttestationDelta, Option<(usize, Delta)>), Error> {
+    let base_reward = get_base_reward(state, index, total_balances.current_epoch(), spec)?;
+
+    let (inclusion_delay_delta, proposer_delta) =
+        get_inclusion_delay_delta(validator, base_reward, spec)?;
+
+    let delta = AttestationDelta {
+        source_delta: get_source_delta(
+            validator,
+            base_reward,
+            total_balances,
+            finality_delay,
+            spec,
+        )?,
+        target_delta: get_target_delta(
+            validator,
+            base_reward,
+            total_balances,
+            finality_delay,
+            spec,
+        )?,
+        head_delta: get_head_delta(validator, base_reward, total_balances, finality_delay, spec)?,
+        inclusion_delay_delta,
+        inactivity_penalty_delta: get_inactivity_penalty_delta(
+            validator,
+            base_reward,
+            finality_delay,
+            spec,
+        )?,
+    };
+
+    Ok((delta, proposer_delta))
+}
+
 fn get_attestation_component_delta(
     index_in_unslashed_attesting_indices: bool,
     attesting_balance: u64,
@@ -266,14 +347,14 @@ fn get_inactivity_penalty_delta(
 /// Compute the reward awarded to a proposer for including an attestation from a validator.
 ///
 /// The `base_reward` param should be the `base_reward` of the attesting validator.
-fn get_proposer_reward(base_reward: u64, spec: &ChainSpec) -> Result<u64, Error> {
+pub(crate) fn get_proposer_reward(base_reward: u64, spec: &ChainSpec) -> Result<u64, Error> {
     Ok(base_reward.safe_div(spec.proposer_reward_quotient)?)
 }
 
 /// Is the validator eligible for penalties and rewards at the current epoch?
 ///
 /// Spec: v0.12.1
-fn is_eligible_validator(validator: &ValidatorStatus) -> bool {
+pub(crate) fn is_eligible_validator(validator: &ValidatorStatus) -> bool {
     validator.is_active_in_previous_epoch
         || (validator.is_slashed && !validator.is_withdrawable_in_current_epoch)
 }
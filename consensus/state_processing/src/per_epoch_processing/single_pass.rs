@@ -0,0 +1,235 @@
+use super::apply_rewards::{
+    get_finality_delay, get_proposer_reward, get_validator_attestation_delta,
+    is_eligible_validator, Delta,
+};
+use super::validator_statuses::ValidatorStatuses;
+use super::Error;
+use crate::common::{get_base_reward, initiate_validator_exit};
+use itertools::Itertools;
+use safe_arith::{SafeArith, SafeArithIter};
+use types::*;
+
+/// Selects which of the per-validator epoch transitions are applied by
+/// `process_epoch_single_pass`.
+///
+/// All transitions are enabled during block processing. The other configurations exist so that
+/// each transition can be tested in isolation against the consensus-spec test vectors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SinglePassConfig {
+    pub rewards_and_penalties: bool,
+    pub registry_updates: bool,
+    pub slashings: bool,
+    pub effective_balance_updates: bool,
+}
+
+impl Default for SinglePassConfig {
+    fn default() -> Self {
+        Self::enable_all()
+    }
+}
+
+impl SinglePassConfig {
+    pub fn enable_all() -> Self {
+        Self {
+            rewards_and_penalties: true,
+            registry_updates: true,
+            slashings: true,
+            effective_balance_updates: true,
+        }
+    }
+
+    pub fn disable_all() -> Self {
+        Self {
+            rewards_and_penalties: false,
+            registry_updates: false,
+            slashings: false,
+            effective_balance_updates: false,
+        }
+    }
+}
+
+/// Applies the rewards and penalties, registry updates, slashings and effective balance updates
+/// of the epoch transition, visiting each validator only once.
+///
+/// The result is identical to calling `process_rewards_and_penalties`,
+/// `process_registry_updates`, `process_slashings` and the effective balance portion of
+/// `process_final_updates` in sequence. This holds because each of those functions only reads
+/// and writes the fields of the validator it is visiting, with three exceptions which are handled
+/// explicitly:
+///
+/// - Proposers are rewarded for the attestations of other validators. These rewards are computed
+///   from `validator_statuses` before visiting the registry.
+/// - Ejections read the exit queue, which is modified by earlier ejections. Validators are visited
+///   in index order, the same order as the spec, and the exit cache is kept up-to-date.
+/// - The activation queue is sorted across all validators. Eligible validators are collected
+///   whilst visiting the registry and activated afterwards; none of the other transitions read
+///   the `activation_epoch`.
+///
+/// The `validator_statuses` must have processed the attestations of `state`.
+pub fn process_epoch_single_pass<T: EthSpec>(
+    state: &mut BeaconState<T>,
+    validator_statuses: &ValidatorStatuses,
+    config: SinglePassConfig,
+    spec: &ChainSpec,
+) -> Result<(), Error> {
+    // Guard against an out-of-bounds during the validator balance update.
+    if validator_statuses.statuses.len() != state.balances.len()
+        || validator_statuses.statuses.len() != state.validators.len()
+    {
+        return Err(Error::ValidatorStatusesInconsistent);
+    }
+
+    let current_epoch = state.current_epoch();
+    let total_balances = &validator_statuses.total_balances;
+
+    // Rewards and penalties are not applied in the genesis epoch.
+    let rewards_and_penalties = config.rewards_and_penalties && current_epoch != T::genesis_epoch();
+    let (finality_delay, proposer_rewards) = if rewards_and_penalties {
+        (
+            get_finality_delay(state)?,
+            get_proposer_rewards(state, validator_statuses, spec)?,
+        )
+    } else {
+        (0, vec![])
+    };
+
+    let next_epoch = current_epoch.safe_add(1)?;
+    let mut activation_queue = vec![];
+
+    let total_balance = total_balances.current_epoch();
+    let slashings_withdrawable_epoch =
+        current_epoch.safe_add(T::EpochsPerSlashingsVector::to_u64().safe_div(2)?)?;
+    let adjusted_total_slashing_balance = if config.slashings {
+        let sum_slashings = state.get_all_slashings().iter().copied().safe_sum()?;
+        std::cmp::min(
+            sum_slashings.safe_mul(spec.proportional_slashing_multiplier)?,
+            total_balance,
+        )
+    } else {
+        0
+    };
+
+    let hysteresis_increment = spec
+        .effective_balance_increment
+        .safe_div(spec.hysteresis_quotient)?;
+    let downward_threshold = hysteresis_increment.safe_mul(spec.hysteresis_downward_multiplier)?;
+    let upward_threshold = hysteresis_increment.safe_mul(spec.hysteresis_upward_multiplier)?;
+
+    for (index, status) in validator_statuses.statuses.iter().enumerate() {
+        // Rewards and penalties.
+        if rewards_and_penalties {
+            let mut delta = if is_eligible_validator(status) {
+                let (delta, _) = get_validator_attestation_delta(
+                    state,
+                    index,
+                    status,
+                    total_balances,
+                    finality_delay,
+                    spec,
+                )?;
+                delta.flatten()?
+            } else {
+                Delta::default()
+            };
+            delta.reward(proposer_rewards[index])?;
+
+            // Error on overflow above but not on overflow below (saturating at 0 instead).
+            let balance = &mut state.balances[index];
+            *balance = balance.safe_add(delta.rewards)?;
+            *balance = balance.saturating_sub(delta.penalties);
+        }
+
+        // Registry updates.
+        if config.registry_updates {
+            let validator = &mut state.validators[index];
+            if validator.is_eligible_for_activation_queue(spec) {
+                validator.activation_eligibility_epoch = next_epoch;
+            }
+            if validator.is_active_at(current_epoch)
+                && validator.effective_balance <= spec.ejection_balance
+            {
+                initiate_validator_exit(state, index, spec)?;
+            }
+
+            let validator = &state.validators[index];
+            if validator.is_eligible_for_activation(state, spec) {
+                activation_queue.push((validator.activation_eligibility_epoch, index));
+            }
+        }
+
+        // Slashings.
+        if config.slashings {
+            let validator = &state.validators[index];
+            if validator.slashed && slashings_withdrawable_epoch == validator.withdrawable_epoch {
+                let increment = spec.effective_balance_increment;
+                let penalty_numerator = validator
+                    .effective_balance
+                    .safe_div(increment)?
+                    .safe_mul(adjusted_total_slashing_balance)?;
+                let penalty = penalty_numerator
+                    .safe_div(total_balance)?
+                    .safe_mul(increment)?;
+
+                state.balances[index] = state.balances[index].saturating_sub(penalty);
+            }
+        }
+
+        // Effective balance updates.
+        if config.effective_balance_updates {
+            let balance = state.balances[index];
+            let validator = &mut state.validators[index];
+            if balance.safe_add(downward_threshold)? < validator.effective_balance
+                || validator.effective_balance.safe_add(upward_threshold)? < balance
+            {
+                validator.effective_balance = std::cmp::min(
+                    balance.safe_sub(balance.safe_rem(spec.effective_balance_increment)?)?,
+                    spec.max_effective_balance,
+                );
+            }
+        }
+    }
+
+    // Dequeue validators for activation up to churn limit.
+    if config.registry_updates {
+        let churn_limit = state.get_churn_limit(spec)? as usize;
+        let delayed_activation_epoch = state.compute_activation_exit_epoch(current_epoch, spec)?;
+        for (_, index) in activation_queue.into_iter().sorted().take(churn_limit) {
+            state.validators[index].activation_epoch = delayed_activation_epoch;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the inclusion delay rewards earned by each validator as a proposer, indexed by
+/// validator index.
+///
+/// This only visits `validator_statuses`, not the validator registry.
+fn get_proposer_rewards<T: EthSpec>(
+    state: &BeaconState<T>,
+    validator_statuses: &ValidatorStatuses,
+    spec: &ChainSpec,
+) -> Result<Vec<u64>, Error> {
+    let mut proposer_rewards = vec![0; validator_statuses.statuses.len()];
+    let total_active_balance = validator_statuses.total_balances.current_epoch();
+
+    // Spec: `index in get_unslashed_attesting_indices(state, matching_source_attestations)`
+    for (index, status) in validator_statuses
+        .statuses
+        .iter()
+        .enumerate()
+        .filter(|(_, status)| status.is_previous_epoch_attester && !status.is_slashed)
+    {
+        let inclusion_info = status
+            .inclusion_info
+            .ok_or(Error::ValidatorStatusesInconsistent)?;
+        let base_reward = get_base_reward(state, index, total_active_balance, spec)?;
+
+        let reward = proposer_rewards
+            .get_mut(inclusion_info.proposer_index)
+            .ok_or(Error::ValidatorStatusesInconsistent)?;
+        reward.safe_add_assign(get_proposer_reward(base_reward, spec)?)?;
+    }
+
+    Ok(proposer_rewards)
+}
@@ -1,5 +1,9 @@
 #![cfg(test)]
-use crate::per_epoch_processing::per_epoch_processing;
+use crate::per_epoch_processing::{
+    get_attestation_deltas, per_epoch_processing, process_effective_balance_updates,
+    process_epoch_single_pass, process_justification_and_finalization, process_registry_updates,
+    process_rewards_and_penalties, process_slashings, SinglePassConfig, ValidatorStatuses,
+};
 use env_logger::{Builder, Env};
 use types::test_utils::TestingBeaconStateBuilder;
 use types::*;
@@ -21,3 +25,111 @@ fn runs_without_error() {
 
     per_epoch_processing(&mut state, &spec).unwrap();
 }
+
+#[test]
+fn attestation_deltas_match_balance_changes() {
+    let spec = MinimalEthSpec::default_spec();
+
+    let mut builder: TestingBeaconStateBuilder<MinimalEthSpec> =
+        TestingBeaconStateBuilder::from_deterministic_keypairs(8, &spec);
+
+    let target_slot =
+        (MinimalEthSpec::genesis_epoch() + 4).end_slot(MinimalEthSpec::slots_per_epoch());
+    builder.teleport_to_slot(target_slot);
+
+    let (mut state, _keypairs) = builder.build();
+
+    let mut post_state = state.clone();
+    per_epoch_processing(&mut post_state, &spec).unwrap();
+
+    state
+        .build_committee_cache(RelativeEpoch::Previous, &spec)
+        .unwrap();
+    state
+        .build_committee_cache(RelativeEpoch::Current, &spec)
+        .unwrap();
+    let mut validator_statuses = ValidatorStatuses::new(&state, &spec).unwrap();
+    validator_statuses
+        .process_attestations(&state, &spec)
+        .unwrap();
+    process_justification_and_finalization(&mut state, &validator_statuses.total_balances).unwrap();
+
+    let deltas = get_attestation_deltas(&state, &validator_statuses, &spec).unwrap();
+    assert_eq!(deltas.len(), state.validators.len());
+
+    for (i, delta) in deltas.into_iter().enumerate() {
+        // No attestations were included, so every validator missed every component.
+        assert!(delta.source_delta.penalties > 0);
+        assert!(delta.target_delta.penalties > 0);
+        assert!(delta.head_delta.penalties > 0);
+
+        let delta = delta.flatten().unwrap();
+        assert_eq!(
+            post_state.balances[i],
+            (state.balances[i] + delta.rewards).saturating_sub(delta.penalties),
+            "validator {}",
+            i
+        );
+    }
+}
+
+#[test]
+fn single_pass_matches_sequential_processing() {
+    let spec = MinimalEthSpec::default_spec();
+
+    let mut builder: TestingBeaconStateBuilder<MinimalEthSpec> =
+        TestingBeaconStateBuilder::from_deterministic_keypairs(16, &spec);
+
+    let target_slot =
+        (MinimalEthSpec::genesis_epoch() + 4).end_slot(MinimalEthSpec::slots_per_epoch());
+    builder.teleport_to_slot(target_slot);
+
+    let (mut state, _keypairs) = builder.build();
+    let current_epoch = state.current_epoch();
+
+    // Give the registry a mix of ejections, slashings, pending activations and balance changes.
+    state.validators[1].effective_balance = spec.ejection_balance;
+    state.validators[2].slashed = true;
+    state.validators[2].withdrawable_epoch =
+        current_epoch + <MinimalEthSpec as EthSpec>::EpochsPerSlashingsVector::to_u64() / 2;
+    state
+        .set_slashings(current_epoch, spec.max_effective_balance)
+        .unwrap();
+    state.validators[3].activation_epoch = spec.far_future_epoch;
+    state.validators[3].activation_eligibility_epoch = Epoch::new(0);
+    state.validators[4].activation_epoch = spec.far_future_epoch;
+    state.validators[4].activation_eligibility_epoch = spec.far_future_epoch;
+    state.balances[5] -= spec.effective_balance_increment;
+
+    state.build_all_committee_caches(&spec).unwrap();
+    let mut validator_statuses = ValidatorStatuses::new(&state, &spec).unwrap();
+    validator_statuses
+        .process_attestations(&state, &spec)
+        .unwrap();
+    process_justification_and_finalization(&mut state, &validator_statuses.total_balances).unwrap();
+
+    let mut sequential = state.clone();
+    process_rewards_and_penalties(&mut sequential, &mut validator_statuses, &spec).unwrap();
+    process_registry_updates(&mut sequential, &spec).unwrap();
+    process_slashings(
+        &mut sequential,
+        validator_statuses.total_balances.current_epoch(),
+        &spec,
+    )
+    .unwrap();
+    process_effective_balance_updates(&mut sequential, &spec).unwrap();
+
+    let mut single_pass = state;
+    process_epoch_single_pass(
+        &mut single_pass,
+        &validator_statuses,
+        SinglePassConfig::enable_all(),
+        &spec,
+    )
+    .unwrap();
+
+    assert_eq!(single_pass.balances, sequential.balances);
+    assert_eq!(single_pass.validators, sequential.validators);
+    assert!(single_pass.validators[1].exit_epoch != spec.far_future_epoch);
+    assert!(single_pass.validators[3].activation_epoch != spec.far_future_epoch);
+}
@@ -144,6 +144,29 @@ impl TreeHash for H256 {
     }
 }
 
+/// The SSZ "union" type, where `None` has selector `0` and `Some` has selector `1`.
+impl<T: TreeHash> TreeHash for Option<T> {
+    fn tree_hash_type() -> TreeHashType {
+        TreeHashType::Container
+    }
+
+    fn tree_hash_packed_encoding(&self) -> Vec<u8> {
+        unreachable!("Union should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("Union should never be packed.")
+    }
+
+    fn tree_hash_root(&self) -> Hash256 {
+        match self {
+            None => mix_in_selector(&Hash256::zero(), 0),
+            Some(inner) => mix_in_selector(&inner.tree_hash_root(), 1),
+        }
+        .expect("selectors are within MAX_UNION_SELECTOR")
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -14,9 +14,18 @@ pub const BYTES_PER_CHUNK: usize = 32;
 pub const HASHSIZE: usize = 32;
 pub const MERKLE_HASH_CHUNK: usize = 2 * BYTES_PER_CHUNK;
 
+/// The minimum number of leaves with values for which `merkle_root` uses `merkleize_padded`, which
+/// hashes each height of the tree in a single batch, instead of `MerkleHasher`.
+pub const BATCH_MERKLEIZE_MIN_LEAVES: usize = 16;
+
+/// The highest possible union selector value (higher values are reserved for backwards compatible
+/// extensions).
+pub const MAX_UNION_SELECTOR: u8 = 127;
+
 pub type Hash256 = ethereum_types::H256;
 
-/// Convenience method for `MerkleHasher` which also provides some fast-paths for small trees.
+/// Convenience method for `MerkleHasher` and `merkleize_padded` which also provides some
+/// fast-paths for small trees.
 ///
 /// `minimum_leaf_count` will only be used if it is greater than or equal to the minimum number of leaves that can be created from `bytes`.
 pub fn merkle_root(bytes: &[u8], minimum_leaf_count: usize) -> Hash256 {
@@ -44,6 +53,9 @@ pub fn merkle_root(bytes: &[u8], minimum_leaf_count: usize) -> Hash256 {
         let digest = context.finish();
 
         Hash256::from_slice(digest.as_ref())
+    } else if (bytes.len() + (HASHSIZE - 1)) / HASHSIZE >= BATCH_MERKLEIZE_MIN_LEAVES {
+        // If there are enough leaves with values, hash each height of the tree in a batch.
+        merkleize_padded(bytes, minimum_leaf_count)
     } else {
         // If there are 3 or more leaves, use `MerkleHasher`.
         let mut hasher = MerkleHasher::with_leaves(leaves);
@@ -68,6 +80,29 @@ pub fn mix_in_length(root: &Hash256, length: usize) -> Hash256 {
     Hash256::from_slice(&eth2_hashing::hash32_concat(root.as_bytes(), &length_bytes)[..])
 }
 
+/// Returns `Some(root)` created by hashing `root` and `selector`, if `selector <=
+/// MAX_UNION_SELECTOR`. Otherwise, returns `None`.
+///
+/// Used in `TreeHash` for the "union" type.
+///
+/// ## Specification
+///
+/// ```ignore,text
+/// mix_in_selector: Given a Merkle root root and a type selector selector ("uint256" little-endian
+/// serialization) return hash(root + selector).
+/// ```
+pub fn mix_in_selector(root: &Hash256, selector: u8) -> Option<Hash256> {
+    if selector > MAX_UNION_SELECTOR {
+        return None;
+    }
+
+    let mut chunk = [0; BYTES_PER_CHUNK];
+    chunk[0] = selector;
+
+    let root = eth2_hashing::hash32_concat(root.as_bytes(), &chunk);
+    Some(Hash256::from_slice(&root))
+}
+
 /// Returns a cached padding node for a given height.
 fn get_zero_hash(height: usize) -> &'static [u8] {
     if height <= ZERO_HASHES_MAX_INDEX {
@@ -159,4 +194,20 @@ mod test {
             &hash[..]
         );
     }
+
+    #[test]
+    fn mix_selector() {
+        let hash = {
+            let mut preimage = vec![42; BYTES_PER_CHUNK];
+            preimage.append(&mut vec![1]);
+            preimage.append(&mut vec![0; BYTES_PER_CHUNK - 1]);
+            eth2_hashing::hash(&preimage)
+        };
+
+        let root = Hash256::from_slice(&[42; BYTES_PER_CHUNK]);
+
+        assert_eq!(mix_in_selector(&root, 1).unwrap().as_bytes(), &hash[..]);
+        assert!(mix_in_selector(&root, MAX_UNION_SELECTOR).is_some());
+        assert_eq!(mix_in_selector(&root, MAX_UNION_SELECTOR + 1), None);
+    }
 }
@@ -0,0 +1,60 @@
+use tree_hash::{mix_in_selector, Hash256, TreeHash};
+use tree_hash_derive::TreeHash;
+
+#[derive(TreeHash)]
+#[tree_hash(enum_behaviour = "union")]
+enum FixedUnion {
+    U8(u8),
+    U16(u16),
+}
+
+#[derive(TreeHash)]
+#[tree_hash(enum_behaviour = "union")]
+enum OptionalUnion {
+    None,
+    Root(Hash256),
+}
+
+#[test]
+fn fixed_union() {
+    assert_eq!(
+        FixedUnion::U8(1).tree_hash_root(),
+        mix_in_selector(&1_u8.tree_hash_root(), 0).unwrap()
+    );
+    assert_eq!(
+        FixedUnion::U16(1).tree_hash_root(),
+        mix_in_selector(&1_u16.tree_hash_root(), 1).unwrap()
+    );
+    assert_ne!(
+        FixedUnion::U8(1).tree_hash_root(),
+        FixedUnion::U16(1).tree_hash_root()
+    );
+}
+
+#[test]
+fn optional_union() {
+    let root = Hash256::repeat_byte(42);
+
+    assert_eq!(
+        OptionalUnion::None.tree_hash_root(),
+        mix_in_selector(&Hash256::zero(), 0).unwrap()
+    );
+    assert_eq!(
+        OptionalUnion::Root(root).tree_hash_root(),
+        mix_in_selector(&root, 1).unwrap()
+    );
+}
+
+#[test]
+fn option_matches_optional_union() {
+    let root = Hash256::repeat_byte(42);
+
+    assert_eq!(
+        None::<Hash256>.tree_hash_root(),
+        OptionalUnion::None.tree_hash_root()
+    );
+    assert_eq!(
+        Some(root).tree_hash_root(),
+        OptionalUnion::Root(root).tree_hash_root()
+    );
+}
@@ -78,21 +78,63 @@ fn should_skip_hashing(field: &syn::Field) -> bool {
     })
 }
 
-/// Implements `tree_hash::TreeHash` for some `struct`.
+/// Returns `true` if the item has the `#[tree_hash(enum_behaviour = "union")]` attribute.
+///
+/// # Panics
+/// Panics at compile time if `enum_behaviour` is set to anything other than `"union"`.
+fn is_union_enum(item: &DeriveInput) -> bool {
+    use syn::{Lit, MetaNameValue, NestedMeta};
+
+    let enum_behaviour = item
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("tree_hash"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .find_map(|nested| match nested {
+            NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                path,
+                lit: Lit::Str(value),
+                ..
+            })) if path.is_ident("enum_behaviour") => Some(value.value()),
+            _ => None,
+        });
+
+    match enum_behaviour.as_deref() {
+        Some("union") => true,
+        Some(other) => panic!("\"{}\" is not a supported enum_behaviour.", other),
+        None => false,
+    }
+}
+
+/// Implements `tree_hash::TreeHash` for some `struct` or union `enum`.
 ///
 /// Fields are hashed in the order they are defined.
+///
+/// Enums are only supported with the `#[tree_hash(enum_behaviour = "union")]` attribute. Each
+/// variant must hold exactly one value, whose root is mixed in with a selector equal to the index
+/// of the variant. The first variant may be a unit variant, which represents the SSZ `None`.
 #[proc_macro_derive(TreeHash, attributes(tree_hash))]
 pub fn tree_hash_derive(input: TokenStream) -> TokenStream {
     let item = parse_macro_input!(input as DeriveInput);
 
+    match &item.data {
+        syn::Data::Struct(s) if !is_union_enum(&item) => tree_hash_derive_struct(&item, s),
+        syn::Data::Enum(e) if is_union_enum(&item) => tree_hash_derive_enum_union(&item, e),
+        _ => panic!(
+            "tree_hash_derive only supports structs and enums with \
+             #[tree_hash(enum_behaviour = \"union\")]."
+        ),
+    }
+}
+
+fn tree_hash_derive_struct(item: &DeriveInput, struct_data: &syn::DataStruct) -> TokenStream {
     let name = &item.ident;
     let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
 
-    let struct_data = match &item.data {
-        syn::Data::Struct(s) => s,
-        _ => panic!("tree_hash_derive only supports structs."),
-    };
-
     let idents = get_hashable_fields(&struct_data);
     let num_leaves = idents.len();
 
@@ -124,3 +166,70 @@ pub fn tree_hash_derive(input: TokenStream) -> TokenStream {
     };
     output.into()
 }
+
+fn tree_hash_derive_enum_union(item: &DeriveInput, enum_data: &syn::DataEnum) -> TokenStream {
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
+
+    // Note: this is `tree_hash::MAX_UNION_SELECTOR + 1`.
+    let max_variants = 128;
+
+    if enum_data.variants.len() > max_variants {
+        panic!("union enums may have at most {} variants.", max_variants);
+    }
+
+    let roots = enum_data
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(i, variant)| {
+            let variant_name = &variant.ident;
+            let selector = i as u8;
+
+            match &variant.fields {
+                syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => quote! {
+                    #name::#variant_name(inner) => tree_hash::mix_in_selector(
+                        &tree_hash::TreeHash::tree_hash_root(inner),
+                        #selector
+                    ),
+                },
+                syn::Fields::Unit if i == 0 => quote! {
+                    #name::#variant_name => tree_hash::mix_in_selector(
+                        &tree_hash::Hash256::zero(),
+                        #selector
+                    ),
+                },
+                syn::Fields::Unit => {
+                    panic!("only the first variant of a union enum may be a unit.")
+                }
+                _ => panic!("union enum variants must have exactly one unnamed field."),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let output = quote! {
+        impl #impl_generics tree_hash::TreeHash for #name #ty_generics #where_clause {
+            fn tree_hash_type() -> tree_hash::TreeHashType {
+                tree_hash::TreeHashType::Container
+            }
+
+            fn tree_hash_packed_encoding(&self) -> Vec<u8> {
+                unreachable!("Enum should never be packed.")
+            }
+
+            fn tree_hash_packing_factor() -> usize {
+                unreachable!("Enum should never be packed.")
+            }
+
+            fn tree_hash_root(&self) -> tree_hash::Hash256 {
+                match self {
+                    #(
+                        #roots
+                    )*
+                }
+                .expect("derive macro should prevent out-of-bounds selectors")
+            }
+        }
+    };
+    output.into()
+}
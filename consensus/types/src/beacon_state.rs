@@ -23,12 +23,14 @@ use tree_hash_derive::TreeHash;
 pub use self::committee_cache::CommitteeCache;
 pub use clone_config::CloneConfig;
 pub use eth_spec::*;
+pub use proof::{BeaconStateMerkleProof, BEACON_STATE_FIELDS, VALIDATOR_FIELDS};
 pub use tree_hash_cache::BeaconTreeHashCache;
 
 #[macro_use]
 mod committee_cache;
 mod clone_config;
 mod exit_cache;
+mod proof;
 mod pubkey_cache;
 mod tests;
 mod tree_hash_cache;
@@ -80,6 +82,8 @@ pub enum Error {
     InvalidValidatorPubkey(ssz::DecodeError),
     ValidatorRegistryShrunk,
     TreeHashCacheInconsistent,
+    InvalidGeneralizedIndex(usize),
+    UnknownProofPath(String),
     InvalidDepositState {
         deposit_count: u64,
         deposit_index: u64,
@@ -136,6 +140,18 @@ impl From<BeaconStateHash> for Hash256 {
     }
 }
 
+/// The list type of the validator registry and balances.
+///
+/// These are the largest lists in the state, so the `tree-states` feature stores them as
+/// `PersistentList`s, which are cheap to clone and hash inline.
+#[cfg(not(feature = "tree-states"))]
+pub type RegistryList<T, N> = VariableList<T, N>;
+#[cfg(feature = "tree-states")]
+pub type RegistryList<T, N> = crate::persistent_list::PersistentList<T, N>;
+
+/// Whether the `tree-states` feature is enabled, for consumers which adapt to it at runtime.
+pub const TREE_STATES: bool = cfg!(feature = "tree-states");
+
 /// The state of the `BeaconChain` at some slot.
 ///
 /// Spec v0.12.1
@@ -178,11 +194,18 @@ where
     pub eth1_deposit_index: u64,
 
     // Registry
-    #[compare_fields(as_slice)]
-    pub validators: VariableList<Validator, T::ValidatorRegistryLimit>,
-    #[compare_fields(as_slice)]
-    #[serde(with = "ssz_types::serde_utils::quoted_u64_var_list")]
-    pub balances: VariableList<u64, T::ValidatorRegistryLimit>,
+    #[cfg_attr(not(feature = "tree-states"), compare_fields(as_slice))]
+    pub validators: RegistryList<Validator, T::ValidatorRegistryLimit>,
+    #[cfg_attr(not(feature = "tree-states"), compare_fields(as_slice))]
+    #[cfg_attr(
+        not(feature = "tree-states"),
+        serde(with = "ssz_types::serde_utils::quoted_u64_var_list")
+    )]
+    #[cfg_attr(
+        feature = "tree-states",
+        serde(with = "crate::persistent_list::quoted_u64_persistent_list")
+    )]
+    pub balances: RegistryList<u64, T::ValidatorRegistryLimit>,
 
     // Randomness
     pub randao_mixes: FixedVector<Hash256, T::EpochsPerHistoricalVector>,
@@ -259,8 +282,8 @@ impl<T: EthSpec> BeaconState<T> {
             eth1_deposit_index: 0,
 
             // Validator registry
-            validators: VariableList::empty(), // Set later.
-            balances: VariableList::empty(),   // Set later.
+            validators: RegistryList::empty(), // Set later.
+            balances: RegistryList::empty(),   // Set later.
 
             // Randomness
             randao_mixes: FixedVector::from_elem(Hash256::zero()),
@@ -1196,8 +1219,8 @@ impl<T: EthSpec> arbitrary::Arbitrary for BeaconState<T> {
             eth1_data: Eth1Data::arbitrary(u)?,
             eth1_data_votes: <VariableList<Eth1Data, T::SlotsPerEth1VotingPeriod>>::arbitrary(u)?,
             eth1_deposit_index: u64::arbitrary(u)?,
-            validators: <VariableList<Validator, T::ValidatorRegistryLimit>>::arbitrary(u)?,
-            balances: <VariableList<u64, T::ValidatorRegistryLimit>>::arbitrary(u)?,
+            validators: <RegistryList<Validator, T::ValidatorRegistryLimit>>::arbitrary(u)?,
+            balances: <RegistryList<u64, T::ValidatorRegistryLimit>>::arbitrary(u)?,
             randao_mixes: <FixedVector<Hash256, T::EpochsPerHistoricalVector>>::arbitrary(u)?,
             slashings: <FixedVector<u64, T::EpochsPerSlashingsVector>>::arbitrary(u)?,
             previous_epoch_attestations: <VariableList<
@@ -5,6 +5,7 @@ use crate::*;
 use core::num::NonZeroUsize;
 use safe_arith::SafeArith;
 use serde_derive::{Deserialize, Serialize};
+use ssz::{legacy::four_byte_option, Decode, DecodeError, Encode};
 use ssz_derive::{Decode, Encode};
 use std::ops::Range;
 use swap_or_not_shuffle::shuffle_list;
@@ -15,9 +16,10 @@ mod tests;
 /// read the committees for the given epoch.
 #[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Encode, Decode)]
 pub struct CommitteeCache {
+    #[ssz(with = "four_byte_option")]
     initialized_epoch: Option<Epoch>,
     shuffling: Vec<usize>,
-    shuffling_positions: Vec<Option<NonZeroUsize>>,
+    shuffling_positions: Vec<NonZeroUsizeOption>,
     committees_per_slot: u64,
     slots_per_epoch: u64,
 }
@@ -63,9 +65,9 @@ impl CommitteeCache {
             return Err(Error::TooManyValidators);
         }
 
-        let mut shuffling_positions = vec![None; state.validators.len()];
+        let mut shuffling_positions = vec![<_>::default(); state.validators.len()];
         for (i, v) in shuffling.iter().enumerate() {
-            shuffling_positions[*v] = NonZeroUsize::new(i + 1);
+            shuffling_positions[*v] = NonZeroUsize::new(i + 1).into();
         }
 
         Ok(CommitteeCache {
@@ -258,6 +260,7 @@ impl CommitteeCache {
     fn shuffled_position(&self, validator_index: usize) -> Option<usize> {
         self.shuffling_positions
             .get(validator_index)?
+            .0
             .and_then(|p| Some(p.get() - 1))
     }
 }
@@ -266,10 +269,15 @@ impl CommitteeCache {
 /// `epoch`.
 ///
 /// Spec v0.12.1
-pub fn get_active_validator_indices(validators: &[Validator], epoch: Epoch) -> Vec<usize> {
+pub fn get_active_validator_indices<'a, V>(validators: V, epoch: Epoch) -> Vec<usize>
+where
+    V: IntoIterator<Item = &'a Validator>,
+    V::IntoIter: ExactSizeIterator,
+{
+    let validators = validators.into_iter();
     let mut active = Vec::with_capacity(validators.len());
 
-    for (index, validator) in validators.iter().enumerate() {
+    for (index, validator) in validators.enumerate() {
         if validator.is_active_at(epoch) {
             active.push(index)
         }
@@ -280,6 +288,50 @@ pub fn get_active_validator_indices(validators: &[Validator], epoch: Epoch) -> V
     active
 }
 
+/// A shuffling position that is SSZ encoded with the legacy four-byte union selector, so that
+/// committee caches persisted to disk remain readable.
+#[derive(Debug, Default, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct NonZeroUsizeOption(Option<NonZeroUsize>);
+
+impl From<Option<NonZeroUsize>> for NonZeroUsizeOption {
+    fn from(opt: Option<NonZeroUsize>) -> Self {
+        Self(opt)
+    }
+}
+
+impl Encode for NonZeroUsizeOption {
+    fn is_ssz_fixed_len() -> bool {
+        four_byte_option::encode::is_ssz_fixed_len()
+    }
+
+    fn ssz_fixed_len() -> usize {
+        four_byte_option::encode::ssz_fixed_len()
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        four_byte_option::encode::ssz_bytes_len(&self.0)
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        four_byte_option::encode::ssz_append(&self.0, buf)
+    }
+}
+
+impl Decode for NonZeroUsizeOption {
+    fn is_ssz_fixed_len() -> bool {
+        four_byte_option::decode::is_ssz_fixed_len()
+    }
+
+    fn ssz_fixed_len() -> usize {
+        four_byte_option::decode::ssz_fixed_len()
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        four_byte_option::decode::from_ssz_bytes(bytes).map(Self)
+    }
+}
+
 #[cfg(feature = "arbitrary-fuzz")]
 impl arbitrary::Arbitrary for CommitteeCache {
     fn arbitrary(_u: &mut arbitrary::Unstructured<'_>) -> arbitrary::Result<Self> {
@@ -99,7 +99,7 @@ fn shuffles_for_the_right_epoch() {
     let assert_shuffling_positions_accurate = |cache: &CommitteeCache| {
         for (i, v) in cache.shuffling.iter().enumerate() {
             assert_eq!(
-                cache.shuffling_positions[*v].unwrap().get() - 1,
+                cache.shuffling_positions[*v].0.unwrap().get() - 1,
                 i,
                 "Shuffling position inaccurate"
             );
@@ -118,3 +118,16 @@ fn shuffles_for_the_right_epoch() {
     assert_eq!(cache.shuffling, shuffling_with_seed(next_seed));
     assert_shuffling_positions_accurate(&cache);
 }
+
+#[test]
+fn legacy_option_encoding() {
+    assert_eq!(NonZeroUsizeOption::default().as_ssz_bytes(), vec![0; 4]);
+    assert_eq!(
+        NonZeroUsizeOption::from(NonZeroUsize::new(1)).as_ssz_bytes(),
+        vec![1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0]
+    );
+
+    let cache = CommitteeCache::default();
+    let bytes = cache.as_ssz_bytes();
+    assert_eq!(CommitteeCache::from_ssz_bytes(&bytes), Ok(cache));
+}
@@ -12,11 +12,10 @@ pub struct ExitCache {
 
 impl ExitCache {
     /// Build the cache if not initialized.
-    pub fn build(
-        &mut self,
-        validators: &[Validator],
-        spec: &ChainSpec,
-    ) -> Result<(), BeaconStateError> {
+    pub fn build<'a, V>(&mut self, validators: V, spec: &ChainSpec) -> Result<(), BeaconStateError>
+    where
+        V: IntoIterator<Item = &'a Validator>,
+    {
         if self.initialized {
             return Ok(());
         }
@@ -24,7 +23,7 @@ impl ExitCache {
         self.initialized = true;
         // Add all validators with a non-default exit epoch to the cache.
         validators
-            .iter()
+            .into_iter()
             .filter(|validator| validator.exit_epoch != spec.far_future_epoch)
             .try_for_each(|validator| self.record_validator_exit(validator.exit_epoch))
     }
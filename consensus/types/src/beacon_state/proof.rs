@@ -0,0 +1,228 @@
+//! Provides Merkle proofs of the inclusion of some field (or part of a field) in a `BeaconState`.
+//!
+//! Nodes are addressed by their *generalized index*, as defined in the SSZ spec: the root of the
+//! state has index `1` and the children of node `i` are `2 * i` and `2 * i + 1`.
+#![allow(clippy::integer_arithmetic)]
+
+use super::{BeaconState, Error};
+use crate::{EthSpec, Hash256, Unsigned};
+use cached_tree_hash::int_log;
+use merkle_proof::verify_merkle_proof;
+use serde_derive::{Deserialize, Serialize};
+use std::mem::size_of;
+
+/// The names of the fields of a `BeaconState`, in the order they are hashed.
+pub const BEACON_STATE_FIELDS: [&str; 21] = [
+    "genesis_time",
+    "genesis_validators_root",
+    "slot",
+    "fork",
+    "latest_block_header",
+    "block_roots",
+    "state_roots",
+    "historical_roots",
+    "eth1_data",
+    "eth1_data_votes",
+    "eth1_deposit_index",
+    "validators",
+    "balances",
+    "randao_mixes",
+    "slashings",
+    "previous_epoch_attestations",
+    "current_epoch_attestations",
+    "justification_bits",
+    "previous_justified_checkpoint",
+    "current_justified_checkpoint",
+    "finalized_checkpoint",
+];
+
+/// The names of the fields of a `Validator`, in the order they are hashed.
+pub const VALIDATOR_FIELDS: [&str; 8] = [
+    "pubkey",
+    "withdrawal_credentials",
+    "effective_balance",
+    "slashed",
+    "activation_eligibility_epoch",
+    "activation_epoch",
+    "exit_epoch",
+    "withdrawable_epoch",
+];
+
+/// The depth of the Merkle tree formed by the fields of a `BeaconState`.
+pub const BEACON_STATE_TREE_DEPTH: usize = 5;
+
+/// The depth of the Merkle tree formed by the fields of a `Validator`.
+pub const VALIDATOR_TREE_DEPTH: usize = 3;
+
+/// The path segment which identifies the length of a list field.
+const LENGTH_PATH: &str = "length";
+
+/// A Merkle proof that `leaf` is the node at `generalized_index` of the state with `state_root`.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct BeaconStateMerkleProof {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub generalized_index: u64,
+    pub leaf: Hash256,
+    /// The sibling nodes of the path from `leaf` to `state_root`, in "bottom-up" order.
+    pub branch: Vec<Hash256>,
+    pub state_root: Hash256,
+}
+
+impl BeaconStateMerkleProof {
+    /// Returns `true` if `self.branch` proves `self.leaf` at `self.generalized_index` in the tree
+    /// with root `self.state_root`.
+    pub fn verify(&self) -> bool {
+        let generalized_index = self.generalized_index as usize;
+        if generalized_index == 0 {
+            return false;
+        }
+        let depth = generalized_index_depth(generalized_index);
+        verify_merkle_proof(
+            self.leaf,
+            &self.branch,
+            depth,
+            generalized_index - (1 << depth),
+            self.state_root,
+        )
+    }
+}
+
+/// Returns the depth of the node with the given `generalized_index`, where the root has depth 0.
+///
+/// ## Panics
+///
+/// If `generalized_index == 0`.
+pub fn generalized_index_depth(generalized_index: usize) -> usize {
+    8 * size_of::<usize>() - 1 - generalized_index.leading_zeros() as usize
+}
+
+/// Describes how the elements of a `BeaconState` list or vector field are laid out in its tree.
+struct FieldLayout {
+    /// `true` for lists, which mix their length into their root.
+    is_list: bool,
+    /// The depth of the tree of chunks, excluding the length mix-in.
+    depth: usize,
+    /// The number of elements packed into each chunk.
+    items_per_chunk: usize,
+    /// The maximum number of elements in the field.
+    limit: usize,
+}
+
+impl FieldLayout {
+    fn new(is_list: bool, limit: usize, items_per_chunk: usize) -> Self {
+        Self {
+            is_list,
+            depth: int_log(limit / items_per_chunk),
+            items_per_chunk,
+            limit,
+        }
+    }
+
+    fn for_field<T: EthSpec>(field_index: usize) -> Option<Self> {
+        let u64s_per_chunk = 32 / size_of::<u64>();
+        match BEACON_STATE_FIELDS[field_index] {
+            "block_roots" | "state_roots" => {
+                Some(Self::new(false, T::SlotsPerHistoricalRoot::to_usize(), 1))
+            }
+            "historical_roots" => Some(Self::new(true, T::HistoricalRootsLimit::to_usize(), 1)),
+            "eth1_data_votes" => Some(Self::new(true, T::SlotsPerEth1VotingPeriod::to_usize(), 1)),
+            "validators" => Some(Self::new(true, T::ValidatorRegistryLimit::to_usize(), 1)),
+            "balances" => Some(Self::new(
+                true,
+                T::ValidatorRegistryLimit::to_usize(),
+                u64s_per_chunk,
+            )),
+            "randao_mixes" => Some(Self::new(
+                false,
+                T::EpochsPerHistoricalVector::to_usize(),
+                1,
+            )),
+            "slashings" => Some(Self::new(
+                false,
+                T::EpochsPerSlashingsVector::to_usize(),
+                u64s_per_chunk,
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl<T: EthSpec> BeaconState<T> {
+    /// Returns the generalized index of the node identified by `path`.
+    ///
+    /// The first element of `path` is the name of a state field (e.g., `["slot"]`). List and vector
+    /// fields may be followed by an element index (e.g., `["block_roots", "3"]`) or, for lists,
+    /// `"length"`. Validators may additionally be followed by a validator field (e.g.,
+    /// `["validators", "7", "exit_epoch"]`).
+    ///
+    /// Elements of `balances` and `slashings` are packed four to a chunk, so the returned index
+    /// identifies the chunk which contains the element.
+    pub fn generalized_index_for_path(path: &[&str]) -> Result<usize, Error> {
+        let unknown = || Error::UnknownProofPath(path.join("/"));
+
+        let (field, rest) = path.split_first().ok_or_else(unknown)?;
+        let field_index = BEACON_STATE_FIELDS
+            .iter()
+            .position(|name| name == field)
+            .ok_or_else(unknown)?;
+        let field_gindex = (1 << BEACON_STATE_TREE_DEPTH) + field_index;
+
+        let (element, rest) = match rest.split_first() {
+            Some(split) => split,
+            None => return Ok(field_gindex),
+        };
+        let layout = FieldLayout::for_field::<T>(field_index).ok_or_else(unknown)?;
+
+        if layout.is_list && *element == LENGTH_PATH {
+            return if rest.is_empty() {
+                Ok(2 * field_gindex + 1)
+            } else {
+                Err(unknown())
+            };
+        }
+
+        let element_index = element
+            .parse::<usize>()
+            .ok()
+            .filter(|i| *i < layout.limit)
+            .ok_or_else(unknown)?;
+
+        let data_gindex = if layout.is_list {
+            2 * field_gindex
+        } else {
+            field_gindex
+        };
+        let chunk_gindex = (data_gindex << layout.depth) + element_index / layout.items_per_chunk;
+
+        match rest {
+            [] => Ok(chunk_gindex),
+            [validator_field] if BEACON_STATE_FIELDS[field_index] == "validators" => {
+                let validator_field_index = VALIDATOR_FIELDS
+                    .iter()
+                    .position(|name| name == validator_field)
+                    .ok_or_else(unknown)?;
+                Ok((chunk_gindex << VALIDATOR_TREE_DEPTH) + validator_field_index)
+            }
+            _ => Err(unknown()),
+        }
+    }
+
+    /// Returns a Merkle proof of the node at `generalized_index` against the root of `self`.
+    ///
+    /// Proofs may be generated for any field of the state. Proofs for nodes *within* a field are
+    /// supported for the list and vector fields which have a tree hash cache (e.g., `validators`,
+    /// `balances` and `block_roots`), as well as for the fields of each validator.
+    ///
+    /// Initializes and updates the tree hash cache.
+    pub fn compute_merkle_proof(
+        &mut self,
+        generalized_index: usize,
+    ) -> Result<BeaconStateMerkleProof, Error> {
+        self.update_tree_hash_cache()?;
+
+        self.tree_hash_cache
+            .as_ref()
+            .ok_or(Error::TreeHashCacheNotInitialized)?
+            .generate_proof(self, generalized_index)
+    }
+}
@@ -255,6 +255,121 @@ fn tree_hash_cache() {
     assert_eq!(root.as_bytes(), &state.tree_hash_root()[..]);
 }
 
+#[test]
+fn merkle_proofs() {
+    type E = MinimalEthSpec;
+    let spec = E::default_spec();
+    let builder: TestingBeaconStateBuilder<E> =
+        TestingBeaconStateBuilder::from_deterministic_keypairs(16, &spec);
+    let (mut state, _keypairs) = builder.build();
+    state.block_roots[3] = Hash256::repeat_byte(3);
+    state.balances[5] = 42;
+
+    let paths: &[&[&str]] = &[
+        &["slot"],
+        &["finalized_checkpoint"],
+        &["block_roots", "3"],
+        &["state_roots", "0"],
+        &["historical_roots"],
+        &["historical_roots", "length"],
+        &["eth1_data_votes", "0"],
+        &["validators", "7"],
+        &["validators", "7", "exit_epoch"],
+        &["validators", "length"],
+        &["balances", "5"],
+        &["randao_mixes", "1"],
+        &["slashings", "2"],
+    ];
+
+    for path in paths {
+        let generalized_index = BeaconState::<E>::generalized_index_for_path(path).unwrap();
+        let proof = state.compute_merkle_proof(generalized_index).unwrap();
+        assert!(proof.verify(), "proof for {:?} should verify", path);
+        assert_eq!(proof.state_root, state.tree_hash_root(), "{:?}", path);
+    }
+
+    let leaf = |state: &mut BeaconState<E>, path: &[&str]| {
+        let generalized_index = BeaconState::<E>::generalized_index_for_path(path).unwrap();
+        state.compute_merkle_proof(generalized_index).unwrap().leaf
+    };
+
+    assert_eq!(leaf(&mut state, &["slot"]), state.slot.tree_hash_root());
+    assert_eq!(
+        leaf(&mut state, &["block_roots", "3"]),
+        Hash256::repeat_byte(3)
+    );
+    assert_eq!(
+        leaf(&mut state, &["validators", "7"]),
+        state.validators[7].tree_hash_root()
+    );
+    assert_eq!(
+        leaf(&mut state, &["validators", "7", "exit_epoch"]),
+        state.validators[7].exit_epoch.tree_hash_root()
+    );
+    assert_eq!(
+        &leaf(&mut state, &["validators", "length"]).as_bytes()[0..8],
+        &16_u64.to_le_bytes()
+    );
+    // Balances are packed four to a chunk, so balance 5 is the second `u64` of chunk 1.
+    assert_eq!(
+        &leaf(&mut state, &["balances", "5"]).as_bytes()[8..16],
+        &42_u64.to_le_bytes()
+    );
+
+    // A zero-valued leaf outside of the populated part of a list can be proven too.
+    let proof = state
+        .compute_merkle_proof(
+            BeaconState::<E>::generalized_index_for_path(&["validators", "1000"]).unwrap(),
+        )
+        .unwrap();
+    assert_eq!(proof.leaf, Hash256::zero());
+    assert!(proof.verify());
+
+    // A tampered proof must not verify.
+    let mut proof = state.compute_merkle_proof(32 + 2).unwrap();
+    proof.leaf = Hash256::repeat_byte(1);
+    assert!(!proof.verify());
+}
+
+#[test]
+fn merkle_proof_invalid_indices() {
+    type E = MinimalEthSpec;
+    let spec = E::default_spec();
+    let builder: TestingBeaconStateBuilder<E> =
+        TestingBeaconStateBuilder::from_deterministic_keypairs(4, &spec);
+    let (mut state, _keypairs) = builder.build();
+
+    for path in &[
+        &[][..],
+        &["unknown"][..],
+        &["slot", "0"][..],
+        &["block_roots", "length"][..],
+        &["balances", "1", "exit_epoch"][..],
+        &["validators", "1", "unknown"][..],
+        &["block_roots", "999999"][..],
+    ] {
+        assert_eq!(
+            BeaconState::<E>::generalized_index_for_path(path),
+            Err(BeaconStateError::UnknownProofPath(path.join("/")))
+        );
+    }
+
+    // The root, internal nodes of the state and padding fields can't be proven.
+    for &index in &[0, 1, 2, 31, 32 + 21, 63] {
+        assert_eq!(
+            state.compute_merkle_proof(index),
+            Err(BeaconStateError::InvalidGeneralizedIndex(index))
+        );
+    }
+
+    // Fields without a tree hash cache can only be proven as a whole.
+    let fork_index = BeaconState::<E>::generalized_index_for_path(&["fork"]).unwrap();
+    assert_eq!(
+        state.compute_merkle_proof(fork_index * 2),
+        Err(BeaconStateError::InvalidGeneralizedIndex(fork_index * 2))
+    );
+}
+
 /// Tests committee-specific components
 #[cfg(test)]
 mod committees {
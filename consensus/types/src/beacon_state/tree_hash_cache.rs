@@ -1,27 +1,43 @@
 #![allow(clippy::integer_arithmetic)]
 
+use super::proof::{generalized_index_depth, BeaconStateMerkleProof, BEACON_STATE_TREE_DEPTH};
 use super::Error;
+#[cfg(feature = "tree-states")]
+use crate::RegistryList;
 use crate::{BeaconState, EthSpec, Hash256, Slot, Unsigned, Validator};
-use cached_tree_hash::{int_log, CacheArena, CachedTreeHash, TreeHashCache};
-use rayon::prelude::*;
+use cached_tree_hash::{CacheArena, CachedTreeHash, TreeHashCache};
+use merkle_proof::MerkleTree;
 use ssz_derive::{Decode, Encode};
 use ssz_types::VariableList;
+use tree_hash::{mix_in_length, MerkleHasher, TreeHash};
+
+// Only required by the validators cache.
+#[cfg(not(feature = "tree-states"))]
+use cached_tree_hash::int_log;
+#[cfg(not(feature = "tree-states"))]
+use rayon::prelude::*;
+#[cfg(not(feature = "tree-states"))]
 use std::cmp::Ordering;
+#[cfg(not(feature = "tree-states"))]
 use std::iter::ExactSizeIterator;
-use tree_hash::{mix_in_length, MerkleHasher, TreeHash};
 
 /// The number of fields on a beacon state.
 const NUM_BEACON_STATE_HASHING_FIELDS: usize = 20;
 
 /// The number of nodes in the Merkle tree of a validator record.
+#[cfg(not(feature = "tree-states"))]
 const NODES_PER_VALIDATOR: usize = 15;
 
+/// A leaf and the "bottom-up" list of sibling nodes which prove its inclusion in some tree.
+type Proof = (Hash256, Vec<Hash256>);
+
 /// The number of validator record tree hash caches stored in each arena.
 ///
 /// This is primarily used for concurrency; if we have 16 validators and set `VALIDATORS_PER_ARENA
 /// == 8` then it is possible to do a 2-core concurrent hash.
 ///
 /// Do not set to 0.
+#[cfg(not(feature = "tree-states"))]
 const VALIDATORS_PER_ARENA: usize = 4_096;
 
 #[derive(Debug, PartialEq, Clone, Encode, Decode)]
@@ -76,6 +92,11 @@ impl<T: EthSpec> Eth1DataVotesTreeHashCache<T> {
             .recalculate_tree_hash_root(&mut self.arena, &mut self.tree_hash_cache)
             .map_err(Into::into)
     }
+
+    /// Returns the root computed by the last call to `Self::recalculate_tree_hash_root`.
+    fn root(&self) -> Hash256 {
+        mix_in_length(&self.tree_hash_cache.root(&self.arena), self.roots.len())
+    }
 }
 
 /// A cache that performs a caching tree hash of the entire `BeaconState` struct.
@@ -84,16 +105,19 @@ pub struct BeaconTreeHashCache<T: EthSpec> {
     /// Tracks the previously generated state root to ensure the next state root provided descends
     /// directly from this state.
     previous_state: Option<(Hash256, Slot)>,
-    // Validators cache
+    // Validators cache, not required when the validators are hashed inline.
+    #[cfg(not(feature = "tree-states"))]
     validators: ValidatorsListTreeHashCache,
     // Arenas
     fixed_arena: CacheArena,
+    #[cfg(not(feature = "tree-states"))]
     balances_arena: CacheArena,
     slashings_arena: CacheArena,
     // Caches
     block_roots: TreeHashCache,
     state_roots: TreeHashCache,
     historical_roots: TreeHashCache,
+    #[cfg(not(feature = "tree-states"))]
     balances: TreeHashCache,
     randao_mixes: TreeHashCache,
     slashings: TreeHashCache,
@@ -112,9 +136,12 @@ impl<T: EthSpec> BeaconTreeHashCache<T> {
         let historical_roots = state.historical_roots.new_tree_hash_cache(&mut fixed_arena);
         let randao_mixes = state.randao_mixes.new_tree_hash_cache(&mut fixed_arena);
 
+        #[cfg(not(feature = "tree-states"))]
         let validators = ValidatorsListTreeHashCache::new::<T>(&state.validators[..]);
 
+        #[cfg(not(feature = "tree-states"))]
         let mut balances_arena = CacheArena::default();
+        #[cfg(not(feature = "tree-states"))]
         let balances = state.balances.new_tree_hash_cache(&mut balances_arena);
 
         let mut slashings_arena = CacheArena::default();
@@ -122,13 +149,16 @@ impl<T: EthSpec> BeaconTreeHashCache<T> {
 
         Self {
             previous_state: None,
+            #[cfg(not(feature = "tree-states"))]
             validators,
             fixed_arena,
+            #[cfg(not(feature = "tree-states"))]
             balances_arena,
             slashings_arena,
             block_roots,
             state_roots,
             historical_roots,
+            #[cfg(not(feature = "tree-states"))]
             balances,
             randao_mixes,
             slashings,
@@ -163,6 +193,8 @@ impl<T: EthSpec> BeaconTreeHashCache<T> {
             }
         }
 
+        let (validators_root, balances_root) = self.recalculate_registry_roots(state)?;
+
         let mut hasher = MerkleHasher::with_leaves(NUM_BEACON_STATE_HASHING_FIELDS);
 
         hasher.write(state.genesis_time.tree_hash_root().as_bytes())?;
@@ -195,17 +227,8 @@ impl<T: EthSpec> BeaconTreeHashCache<T> {
                 .as_bytes(),
         )?;
         hasher.write(state.eth1_deposit_index.tree_hash_root().as_bytes())?;
-        hasher.write(
-            self.validators
-                .recalculate_tree_hash_root(&state.validators[..])?
-                .as_bytes(),
-        )?;
-        hasher.write(
-            state
-                .balances
-                .recalculate_tree_hash_root(&mut self.balances_arena, &mut self.balances)?
-                .as_bytes(),
-        )?;
+        hasher.write(validators_root.as_bytes())?;
+        hasher.write(balances_root.as_bytes())?;
         hasher.write(
             state
                 .randao_mixes
@@ -248,15 +271,359 @@ impl<T: EthSpec> BeaconTreeHashCache<T> {
     }
 
     /// Updates the cache and provides the root of the given `validators`.
+    #[cfg(not(feature = "tree-states"))]
     pub fn recalculate_validators_tree_hash_root(
         &mut self,
         validators: &[Validator],
     ) -> Result<Hash256, Error> {
         self.validators.recalculate_tree_hash_root(validators)
     }
+
+    /// Provides the root of the given `validators`, which are hashed inline.
+    #[cfg(feature = "tree-states")]
+    pub fn recalculate_validators_tree_hash_root(
+        &mut self,
+        validators: &RegistryList<Validator, T::ValidatorRegistryLimit>,
+    ) -> Result<Hash256, Error> {
+        Ok(validators.tree_hash_root())
+    }
+
+    /// Updates the caches and returns the roots of `state.validators` and `state.balances`.
+    #[cfg(not(feature = "tree-states"))]
+    fn recalculate_registry_roots(
+        &mut self,
+        state: &BeaconState<T>,
+    ) -> Result<(Hash256, Hash256), Error> {
+        let validators_root = self
+            .validators
+            .recalculate_tree_hash_root(&state.validators[..])?;
+        let balances_root = state
+            .balances
+            .recalculate_tree_hash_root(&mut self.balances_arena, &mut self.balances)?;
+        Ok((validators_root, balances_root))
+    }
+
+    /// Returns the roots of `state.validators` and `state.balances`, which are hashed inline.
+    #[cfg(feature = "tree-states")]
+    fn recalculate_registry_roots(
+        &mut self,
+        state: &BeaconState<T>,
+    ) -> Result<(Hash256, Hash256), Error> {
+        Ok((
+            state.validators.tree_hash_root(),
+            state.balances.tree_hash_root(),
+        ))
+    }
+
+    /// Returns the roots of `state.validators` and `state.balances` from the caches.
+    #[cfg(not(feature = "tree-states"))]
+    fn registry_roots(&self, state: &BeaconState<T>) -> (Hash256, Hash256) {
+        (
+            self.validators.root(state.validators.len()),
+            mix_in_length(
+                &self.balances.root(&self.balances_arena),
+                state.balances.len(),
+            ),
+        )
+    }
+
+    /// Returns the roots of `state.validators` and `state.balances`, which are hashed inline.
+    #[cfg(feature = "tree-states")]
+    fn registry_roots(&self, state: &BeaconState<T>) -> (Hash256, Hash256) {
+        (
+            state.validators.tree_hash_root(),
+            state.balances.tree_hash_root(),
+        )
+    }
+
+    /// Returns a Merkle proof of the node at `generalized_index` of `state`.
+    ///
+    /// The cache must have been updated with `state` (via `Self::recalculate_tree_hash_root`)
+    /// before calling this function. See `BeaconState::compute_merkle_proof` for the nodes which
+    /// are supported.
+    pub fn generate_proof(
+        &self,
+        state: &BeaconState<T>,
+        generalized_index: usize,
+    ) -> Result<BeaconStateMerkleProof, Error> {
+        let state_root = match self.previous_state {
+            Some((root, slot)) if slot == state.slot => root,
+            _ => return Err(Error::TreeHashCacheNotInitialized),
+        };
+
+        let invalid_index = || Error::InvalidGeneralizedIndex(generalized_index);
+
+        if generalized_index == 0 {
+            return Err(invalid_index());
+        }
+
+        // Split the index into the field of the state and the node within that field.
+        let sub_depth = generalized_index_depth(generalized_index)
+            .checked_sub(BEACON_STATE_TREE_DEPTH)
+            .ok_or_else(invalid_index)?;
+        let field_index = (generalized_index >> sub_depth) - (1 << BEACON_STATE_TREE_DEPTH);
+        let sub_index = generalized_index & ((1 << sub_depth) - 1);
+
+        let field_roots = self.field_roots(state);
+
+        if field_index >= field_roots.len() {
+            return Err(invalid_index());
+        }
+
+        let (leaf, mut branch) = if sub_depth == 0 {
+            (field_roots[field_index], vec![])
+        } else {
+            self.field_proof(state, field_index, sub_depth, sub_index)?
+                .ok_or_else(invalid_index)?
+        };
+
+        let (_, field_branch) = MerkleTree::create(&field_roots, BEACON_STATE_TREE_DEPTH)
+            .generate_proof(field_index, BEACON_STATE_TREE_DEPTH)
+            .map_err(|_| invalid_index())?;
+        branch.extend(field_branch);
+
+        Ok(BeaconStateMerkleProof {
+            generalized_index: generalized_index as u64,
+            leaf,
+            branch,
+            state_root,
+        })
+    }
+
+    /// Returns the tree hash root of each field of `state`, reading the roots of the cached fields
+    /// from `self` rather than recomputing them.
+    fn field_roots(&self, state: &BeaconState<T>) -> Vec<Hash256> {
+        let (validators_root, balances_root) = self.registry_roots(state);
+
+        vec![
+            state.genesis_time.tree_hash_root(),
+            state.genesis_validators_root.tree_hash_root(),
+            state.slot.tree_hash_root(),
+            state.fork.tree_hash_root(),
+            state.latest_block_header.tree_hash_root(),
+            self.block_roots.root(&self.fixed_arena),
+            self.state_roots.root(&self.fixed_arena),
+            mix_in_length(
+                &self.historical_roots.root(&self.fixed_arena),
+                state.historical_roots.len(),
+            ),
+            state.eth1_data.tree_hash_root(),
+            self.eth1_data_votes.root(),
+            state.eth1_deposit_index.tree_hash_root(),
+            validators_root,
+            balances_root,
+            self.randao_mixes.root(&self.fixed_arena),
+            self.slashings.root(&self.slashings_arena),
+            state.previous_epoch_attestations.tree_hash_root(),
+            state.current_epoch_attestations.tree_hash_root(),
+            state.justification_bits.tree_hash_root(),
+            state.previous_justified_checkpoint.tree_hash_root(),
+            state.current_justified_checkpoint.tree_hash_root(),
+            state.finalized_checkpoint.tree_hash_root(),
+        ]
+    }
+
+    /// Returns a proof of the node at `index` in the `depth`-deep subtree of the field at
+    /// `field_index`, or `None` if there is no such node in the cache.
+    fn field_proof(
+        &self,
+        state: &BeaconState<T>,
+        field_index: usize,
+        depth: usize,
+        index: usize,
+    ) -> Result<Option<Proof>, Error> {
+        match field_index {
+            5 => cached_proof(&self.block_roots, &self.fixed_arena, depth, index),
+            6 => cached_proof(&self.state_roots, &self.fixed_arena, depth, index),
+            7 => list_proof(
+                self.historical_roots.root(&self.fixed_arena),
+                state.historical_roots.len(),
+                depth,
+                index,
+                |depth, index| {
+                    cached_proof(&self.historical_roots, &self.fixed_arena, depth, index)
+                },
+            ),
+            9 => list_proof(
+                self.eth1_data_votes
+                    .tree_hash_cache
+                    .root(&self.eth1_data_votes.arena),
+                state.eth1_data_votes.len(),
+                depth,
+                index,
+                |depth, index| {
+                    cached_proof(
+                        &self.eth1_data_votes.tree_hash_cache,
+                        &self.eth1_data_votes.arena,
+                        depth,
+                        index,
+                    )
+                },
+            ),
+            11 | 12 => self.registry_proof(state, field_index, depth, index),
+            13 => cached_proof(&self.randao_mixes, &self.fixed_arena, depth, index),
+            14 => cached_proof(&self.slashings, &self.slashings_arena, depth, index),
+            _ => Ok(None),
+        }
+    }
+}
+
+impl<T: EthSpec> BeaconTreeHashCache<T> {
+    /// Returns a proof of a node in `state.validators` (`field_index == 11`) or `state.balances`
+    /// (`field_index == 12`), from the caches.
+    #[cfg(not(feature = "tree-states"))]
+    fn registry_proof(
+        &self,
+        state: &BeaconState<T>,
+        field_index: usize,
+        depth: usize,
+        index: usize,
+    ) -> Result<Option<Proof>, Error> {
+        if field_index == 11 {
+            list_proof(
+                self.validators.list_cache.root(&self.validators.list_arena),
+                state.validators.len(),
+                depth,
+                index,
+                |depth, index| self.validators.generate_proof(depth, index),
+            )
+        } else {
+            list_proof(
+                self.balances.root(&self.balances_arena),
+                state.balances.len(),
+                depth,
+                index,
+                |depth, index| cached_proof(&self.balances, &self.balances_arena, depth, index),
+            )
+        }
+    }
+
+    /// Returns a proof of a node in `state.validators` (`field_index == 11`) or `state.balances`
+    /// (`field_index == 12`), which are hashed inline.
+    #[cfg(feature = "tree-states")]
+    fn registry_proof(
+        &self,
+        state: &BeaconState<T>,
+        field_index: usize,
+        depth: usize,
+        index: usize,
+    ) -> Result<Option<Proof>, Error> {
+        if field_index == 11 {
+            let validators = &state.validators;
+            list_proof(
+                validators.data_tree_hash_root(),
+                validators.len(),
+                depth,
+                index,
+                |depth, index| validator_proof(validators, depth, index),
+            )
+        } else {
+            let balances = &state.balances;
+            list_proof(
+                balances.data_tree_hash_root(),
+                balances.len(),
+                depth,
+                index,
+                |depth, index| Ok(balances.generate_proof(depth, index)),
+            )
+        }
+    }
+}
+
+/// Returns a proof of the node at `index` in the `depth`-deep tree of `validators`, or of a field
+/// of a validator if `depth` extends into the tree of a `Validator`.
+#[cfg(feature = "tree-states")]
+fn validator_proof<N: Unsigned>(
+    validators: &RegistryList<Validator, N>,
+    depth: usize,
+    index: usize,
+) -> Result<Option<Proof>, Error> {
+    let list_depth = RegistryList::<Validator, N>::data_depth();
+
+    let validator_depth = match depth.checked_sub(list_depth) {
+        Some(0) | None => return Ok(validators.generate_proof(depth, index)),
+        Some(validator_depth) => validator_depth,
+    };
+    let validator_index = index >> validator_depth;
+    let field_index = index & ((1 << validator_depth) - 1);
+
+    let validator = match validators.get(validator_index) {
+        Some(validator) => validator,
+        None => return Ok(None),
+    };
+    let mut arena = CacheArena::default();
+    let mut cache = validator.new_tree_hash_cache(&mut arena);
+    validator.recalculate_tree_hash_root(&mut arena, &mut cache)?;
+
+    let validator_proof = cached_proof(&cache, &arena, validator_depth, field_index)?;
+    let list_proof = validators.generate_proof(list_depth, validator_index);
+
+    Ok(validator_proof
+        .zip(list_proof)
+        .map(|((leaf, mut branch), (_, list_branch))| {
+            branch.extend(list_branch);
+            (leaf, branch)
+        }))
+}
+
+/// Returns a proof of the leaf at `index` of `cache`, or `None` if `depth` does not refer to the
+/// leaves of `cache`.
+fn cached_proof(
+    cache: &TreeHashCache,
+    arena: &CacheArena,
+    depth: usize,
+    index: usize,
+) -> Result<Option<Proof>, Error> {
+    if depth == cache.depth() {
+        Ok(Some(cache.generate_proof(arena, index)?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Returns a proof of the node at `index` in the `depth`-deep subtree of a list with `len`
+/// elements and a data root (i.e., the root before mixing in the length) of `data_root`.
+///
+/// The left subtree of a list contains its data, which is proven with `data_proof`. The right
+/// subtree is a single chunk containing its length.
+fn list_proof<F>(
+    data_root: Hash256,
+    len: usize,
+    depth: usize,
+    index: usize,
+    data_proof: F,
+) -> Result<Option<Proof>, Error>
+where
+    F: FnOnce(usize, usize) -> Result<Option<Proof>, Error>,
+{
+    let mut length_chunk = [0; 32];
+    length_chunk[0..8].copy_from_slice(&(len as u64).to_le_bytes());
+    let length_leaf = Hash256::from(length_chunk);
+
+    let data_depth = depth - 1;
+
+    if index >> data_depth == 1 {
+        return Ok(if data_depth == 0 {
+            Some((length_leaf, vec![data_root]))
+        } else {
+            None
+        });
+    }
+
+    let proof = if data_depth == 0 {
+        Some((data_root, vec![]))
+    } else {
+        data_proof(data_depth, index)?
+    };
+
+    Ok(proof.map(|(leaf, mut branch)| {
+        branch.push(length_leaf);
+        (leaf, branch)
+    }))
 }
 
 /// A specialized cache for computing the tree hash root of `state.validators`.
+#[cfg(not(feature = "tree-states"))]
 #[derive(Debug, PartialEq, Clone, Default, Encode, Decode)]
 struct ValidatorsListTreeHashCache {
     list_arena: CacheArena,
@@ -264,6 +631,7 @@ struct ValidatorsListTreeHashCache {
     values: ParallelValidatorTreeHash,
 }
 
+#[cfg(not(feature = "tree-states"))]
 impl ValidatorsListTreeHashCache {
     /// Instantiates a new cache.
     ///
@@ -305,17 +673,64 @@ impl ValidatorsListTreeHashCache {
 
         Ok(mix_in_length(&list_root, validators.len()))
     }
+
+    /// Returns the root computed by the last call to `Self::recalculate_tree_hash_root`.
+    fn root(&self, len: usize) -> Hash256 {
+        mix_in_length(&self.list_cache.root(&self.list_arena), len)
+    }
+
+    /// Returns a proof of the node at `index` in the `depth`-deep tree of validator roots, or of a
+    /// field of a validator if `depth` extends into the tree of a `Validator`.
+    fn generate_proof(&self, depth: usize, index: usize) -> Result<Option<Proof>, Error> {
+        let list_depth = self.list_cache.depth();
+
+        if depth == list_depth {
+            return cached_proof(&self.list_cache, &self.list_arena, depth, index);
+        }
+
+        let validator_depth = match depth.checked_sub(list_depth) {
+            Some(validator_depth) => validator_depth,
+            None => return Ok(None),
+        };
+        let validator_index = index >> validator_depth;
+        let field_index = index & ((1 << validator_depth) - 1);
+
+        let (arena, caches) = match self
+            .values
+            .arenas
+            .get(validator_index / VALIDATORS_PER_ARENA)
+        {
+            Some(arena_and_caches) => arena_and_caches,
+            None => return Ok(None),
+        };
+        let validator_proof = match caches.get(validator_index % VALIDATORS_PER_ARENA) {
+            Some(cache) => cached_proof(cache, arena, validator_depth, field_index)?,
+            None => None,
+        };
+
+        if let Some((leaf, mut branch)) = validator_proof {
+            let (_, list_branch) = self
+                .list_cache
+                .generate_proof(&self.list_arena, validator_index)?;
+            branch.extend(list_branch);
+            Ok(Some((leaf, branch)))
+        } else {
+            Ok(None)
+        }
+    }
 }
 
 /// Provides a wrapper around some `iter` if the number of items in the iterator is known to the
 /// programmer but not the compiler. This allows use of `ExactSizeIterator` in some occasions.
 ///
 /// Care should be taken to ensure `len` is accurate.
+#[cfg(not(feature = "tree-states"))]
 struct ForcedExactSizeIterator<I> {
     iter: I,
     len: usize,
 }
 
+#[cfg(not(feature = "tree-states"))]
 impl<V, I: Iterator<Item = V>> Iterator for ForcedExactSizeIterator<I> {
     type Item = V;
 
@@ -324,6 +739,7 @@ impl<V, I: Iterator<Item = V>> Iterator for ForcedExactSizeIterator<I> {
     }
 }
 
+#[cfg(not(feature = "tree-states"))]
 impl<V, I: Iterator<Item = V>> ExactSizeIterator for ForcedExactSizeIterator<I> {
     fn len(&self) -> usize {
         self.len
@@ -332,12 +748,14 @@ impl<V, I: Iterator<Item = V>> ExactSizeIterator for ForcedExactSizeIterator<I>
 
 /// Provides a cache for each of the `Validator` objects in `state.validators` and computes the
 /// roots of these using Rayon parallelization.
+#[cfg(not(feature = "tree-states"))]
 #[derive(Debug, PartialEq, Clone, Default, Encode, Decode)]
 pub struct ParallelValidatorTreeHash {
     /// Each arena and its associated sub-trees.
     arenas: Vec<(CacheArena, Vec<TreeHashCache>)>,
 }
 
+#[cfg(not(feature = "tree-states"))]
 impl ParallelValidatorTreeHash {
     /// Instantiates a new cache.
     ///
@@ -436,6 +854,7 @@ mod test {
     use super::*;
 
     #[test]
+    #[cfg(not(feature = "tree-states"))]
     fn validator_node_count() {
         let mut arena = CacheArena::default();
         let v = Validator::default();
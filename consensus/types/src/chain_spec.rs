@@ -5,6 +5,9 @@ use std::fs::File;
 use std::path::Path;
 use tree_hash::TreeHash;
 
+/// The spec value of `PROPOSER_SCORE_BOOST`, used when a config does not specify it.
+const DEFAULT_PROPOSER_SCORE_BOOST: u64 = 70;
+
 /// Each of the BLS signature domains.
 ///
 /// Spec v0.12.1
@@ -65,6 +68,8 @@ pub struct ChainSpec {
     pub genesis_fork_version: [u8; 4],
     #[serde(with = "serde_utils::u8_hex")]
     pub bls_withdrawal_prefix_byte: u8,
+    #[serde(with = "serde_utils::u8_hex")]
+    pub eth1_address_withdrawal_prefix_byte: u8,
 
     /*
      * Time parameters
@@ -102,6 +107,9 @@ pub struct ChainSpec {
      * Fork choice
      */
     pub safe_slots_to_update_justified: u64,
+    /// The percentage of a slot's committee weight that is temporarily added to a block received
+    /// in its own slot prior to the attestation deadline. `None` disables the proposer boost.
+    pub proposer_score_boost: Option<u64>,
 
     /*
      * Eth1
@@ -278,6 +286,7 @@ impl ChainSpec {
              */
             genesis_fork_version: [0; 4],
             bls_withdrawal_prefix_byte: 0,
+            eth1_address_withdrawal_prefix_byte: 1,
 
             /*
              * Time parameters
@@ -316,6 +325,7 @@ impl ChainSpec {
              * Fork choice
              */
             safe_slots_to_update_justified: 8,
+            proposer_score_boost: Some(DEFAULT_PROPOSER_SCORE_BOOST),
 
             /*
              * Eth1
@@ -521,6 +531,10 @@ pub struct YamlConfig {
     proportional_slashing_multiplier: u64,
     #[serde(with = "serde_utils::quoted_u64")]
     safe_slots_to_update_justified: u64,
+    /// Defaults to the spec value when absent. The proposer boost is only disabled by an explicit
+    /// `null`.
+    #[serde(default = "default_proposer_score_boost", with = "quoted_u64_option")]
+    proposer_score_boost: Option<u64>,
 
     #[serde(with = "serde_utils::u32_hex")]
     domain_beacon_proposer: u32,
@@ -588,6 +602,35 @@ impl Default for YamlConfig {
     }
 }
 
+/// Serde support for an optional integer which is quoted when present.
+fn default_proposer_score_boost() -> Option<u64> {
+    Some(DEFAULT_PROPOSER_SCORE_BOOST)
+}
+
+mod quoted_u64_option {
+    use serde::{Deserializer, Serializer};
+    use serde_derive::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(transparent)]
+    struct QuotedU64(#[serde(with = "serde_utils::quoted_u64")] u64);
+
+    pub fn serialize<S>(value: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serde::Serialize::serialize(&value.map(QuotedU64), serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value: Option<QuotedU64> = serde::Deserialize::deserialize(deserializer)?;
+        Ok(value.map(|quoted| quoted.0))
+    }
+}
+
 #[allow(clippy::integer_arithmetic)] // Arith cannot overflow or panic.
 fn milliseconds_to_seconds(millis: u64) -> u64 {
     millis / 1000
@@ -646,6 +689,7 @@ impl YamlConfig {
             min_slashing_penalty_quotient: spec.min_slashing_penalty_quotient,
             genesis_fork_version: spec.genesis_fork_version,
             safe_slots_to_update_justified: spec.safe_slots_to_update_justified,
+            proposer_score_boost: spec.proposer_score_boost,
             domain_beacon_proposer: spec.domain_beacon_proposer,
             domain_beacon_attester: spec.domain_beacon_attester,
             domain_randao: spec.domain_randao,
@@ -727,6 +771,7 @@ impl YamlConfig {
              * Fork Choice
              */
             safe_slots_to_update_justified: self.safe_slots_to_update_justified,
+            proposer_score_boost: self.proposer_score_boost,
             /*
              * Validator
              */
@@ -793,6 +838,7 @@ impl YamlConfig {
             attestation_propagation_slot_range: chain_spec.attestation_propagation_slot_range,
             maximum_gossip_clock_disparity_millis: chain_spec.maximum_gossip_clock_disparity_millis,
             attestation_subnet_count: chain_spec.attestation_subnet_count,
+            eth1_address_withdrawal_prefix_byte: chain_spec.eth1_address_withdrawal_prefix_byte,
             /*
              * Constants, not configurable.
              */
@@ -856,6 +902,50 @@ mod yaml_tests {
         assert_eq!(from, yamlconfig);
     }
 
+    #[test]
+    fn proposer_score_boost_round_trip() {
+        let mut spec = ChainSpec::mainnet();
+
+        for proposer_score_boost in vec![Some(40), None] {
+            spec.proposer_score_boost = proposer_score_boost;
+            let yamlconfig = YamlConfig::from_spec::<MainnetEthSpec>(&spec);
+            let yaml = serde_yaml::to_string(&yamlconfig).expect("failed to serialize");
+            assert!(yaml.contains("PROPOSER_SCORE_BOOST"));
+
+            let from: YamlConfig = serde_yaml::from_str(&yaml).expect("error while deserializing");
+            assert_eq!(from, yamlconfig);
+            let new_spec = from
+                .apply_to_chain_spec::<MainnetEthSpec>(&ChainSpec::mainnet())
+                .expect("should have applied spec");
+            assert_eq!(new_spec.proposer_score_boost, proposer_score_boost);
+        }
+
+        // An unquoted value, as found in spec config files, should also be accepted.
+        let yaml = serde_yaml::to_string(&YamlConfig::default())
+            .expect("failed to serialize")
+            .replace("PROPOSER_SCORE_BOOST: \"70\"", "PROPOSER_SCORE_BOOST: 25");
+        let from: YamlConfig = serde_yaml::from_str(&yaml).expect("error while deserializing");
+        assert_eq!(from.proposer_score_boost, Some(25));
+
+        // A config without the key, such as one predating the proposer boost, uses the spec value.
+        let yaml = serde_yaml::to_string(&YamlConfig::default())
+            .expect("failed to serialize")
+            .replace("PROPOSER_SCORE_BOOST: \"70\"\n", "");
+        assert!(!yaml.contains("PROPOSER_SCORE_BOOST"));
+        let from: YamlConfig = serde_yaml::from_str(&yaml).expect("error while deserializing");
+        assert_eq!(
+            from.proposer_score_boost,
+            Some(DEFAULT_PROPOSER_SCORE_BOOST)
+        );
+
+        // Only an explicit `null` disables the proposer boost.
+        let yaml = serde_yaml::to_string(&YamlConfig::default())
+            .expect("failed to serialize")
+            .replace("PROPOSER_SCORE_BOOST: \"70\"", "PROPOSER_SCORE_BOOST: null");
+        let from: YamlConfig = serde_yaml::from_str(&yaml).expect("error while deserializing");
+        assert_eq!(from.proposer_score_boost, None);
+    }
+
     #[test]
     fn apply_to_spec() {
         let mut spec = ChainSpec::minimal();
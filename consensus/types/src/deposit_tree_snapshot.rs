@@ -0,0 +1,178 @@
+use crate::{Hash256, DEPOSIT_TREE_DEPTH};
+use eth2_hashing::{hash32_concat, ZERO_HASHES};
+use int_to_bytes::int_to_bytes32;
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+
+/// The eth1 block at which the deposit contract tree was finalized.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode)]
+pub struct FinalizedExecutionBlock {
+    pub deposit_root: Hash256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub deposit_count: u64,
+    pub block_hash: Hash256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub block_height: u64,
+}
+
+impl From<&DepositTreeSnapshot> for FinalizedExecutionBlock {
+    fn from(snapshot: &DepositTreeSnapshot) -> Self {
+        Self {
+            deposit_root: snapshot.deposit_root,
+            deposit_count: snapshot.deposit_count,
+            block_hash: snapshot.execution_block_hash,
+            block_height: snapshot.execution_block_height,
+        }
+    }
+}
+
+/// A compact representation of the finalized portion of the deposit contract tree, as described
+/// in EIP-4881.
+///
+/// Holds the roots of the (full) finalized subtrees, from left to right, which is sufficient to
+/// append new deposits and compute the deposit root.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode)]
+pub struct DepositTreeSnapshot {
+    pub finalized: Vec<Hash256>,
+    pub deposit_root: Hash256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub deposit_count: u64,
+    pub execution_block_hash: Hash256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub execution_block_height: u64,
+}
+
+impl Default for DepositTreeSnapshot {
+    fn default() -> Self {
+        let mut snapshot = DepositTreeSnapshot {
+            finalized: vec![],
+            deposit_root: Hash256::zero(),
+            deposit_count: 0,
+            execution_block_hash: Hash256::zero(),
+            execution_block_height: 0,
+        };
+        // Always `Some` for an empty snapshot.
+        snapshot.deposit_root = snapshot.calculate_root().unwrap_or_default();
+        snapshot
+    }
+}
+
+impl DepositTreeSnapshot {
+    /// Computes the deposit root (with the deposit count mixed in) from the finalized hashes.
+    ///
+    /// Returns `None` if `self.finalized` is inconsistent with `self.deposit_count`.
+    pub fn calculate_root(&self) -> Option<Hash256> {
+        let mut size = self.deposit_count;
+        let mut index = self.finalized.len();
+        let mut deposit_root = [0; 32];
+        for height in 0..DEPOSIT_TREE_DEPTH {
+            deposit_root = if (size & 1) == 1 {
+                index = index.checked_sub(1)?;
+                hash32_concat(self.finalized.get(index)?.as_bytes(), &deposit_root)
+            } else {
+                hash32_concat(&deposit_root, ZERO_HASHES.get(height)?)
+            };
+            size /= 2;
+        }
+
+        // Every finalized hash must have been used, and the count must fit in the tree.
+        if index != 0 || size != 0 {
+            return None;
+        }
+
+        Some(Hash256::from_slice(&hash32_concat(
+            &deposit_root,
+            &int_to_bytes32(self.deposit_count),
+        )))
+    }
+
+    /// Returns `true` if `self.deposit_root` matches the root computed from the finalized hashes.
+    pub fn is_valid(&self) -> bool {
+        self.calculate_root() == Some(self.deposit_root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use merkle_proof::MerkleTree;
+    use ssz::{Decode, Encode};
+
+    fn snapshot_of(num_leaves: u64, num_finalized: u64) -> DepositTreeSnapshot {
+        let leaves = (0..num_leaves)
+            .map(Hash256::from_low_u64_be)
+            .collect::<Vec<_>>();
+        let mut tree = MerkleTree::create(&leaves[..num_finalized as usize], DEPOSIT_TREE_DEPTH);
+        let root = Hash256::from_slice(&hash32_concat(
+            tree.hash().as_bytes(),
+            &int_to_bytes32(num_finalized),
+        ));
+        tree.finalize_deposits(num_finalized as usize, DEPOSIT_TREE_DEPTH)
+            .unwrap();
+
+        DepositTreeSnapshot {
+            finalized: tree.get_finalized_hashes(),
+            deposit_root: root,
+            deposit_count: num_finalized,
+            execution_block_hash: Hash256::repeat_byte(42),
+            execution_block_height: 42,
+        }
+    }
+
+    #[test]
+    fn calculate_root() {
+        assert!(DepositTreeSnapshot::default().is_valid());
+
+        for num_finalized in 0..70 {
+            let snapshot = snapshot_of(70, num_finalized);
+            assert_eq!(
+                snapshot.finalized.len(),
+                num_finalized.count_ones() as usize
+            );
+            assert!(snapshot.is_valid(), "{} finalized", num_finalized);
+        }
+    }
+
+    #[test]
+    fn invalid_snapshots() {
+        let snapshot = snapshot_of(16, 13);
+
+        let mut missing_hash = snapshot.clone();
+        missing_hash.finalized.pop();
+        assert_eq!(missing_hash.calculate_root(), None);
+
+        let mut extra_hash = snapshot.clone();
+        extra_hash.finalized.push(Hash256::zero());
+        assert_eq!(extra_hash.calculate_root(), None);
+
+        let mut wrong_count = snapshot.clone();
+        wrong_count.deposit_count = 12;
+        assert_eq!(wrong_count.calculate_root(), None);
+
+        // Same number of finalized subtrees, but a different shape.
+        let mut wrong_shape = snapshot.clone();
+        wrong_shape.deposit_count = 14;
+        assert!(!wrong_shape.is_valid());
+
+        let mut wrong_root = snapshot;
+        wrong_root.deposit_root = Hash256::zero();
+        assert!(!wrong_root.is_valid());
+    }
+
+    #[test]
+    fn ssz_and_json_round_trip() {
+        let snapshot = snapshot_of(16, 13);
+
+        let bytes = snapshot.as_ssz_bytes();
+        assert_eq!(
+            DepositTreeSnapshot::from_ssz_bytes(&bytes).unwrap(),
+            snapshot
+        );
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert_eq!(
+            serde_json::from_str::<DepositTreeSnapshot>(&json).unwrap(),
+            snapshot
+        );
+    }
+}
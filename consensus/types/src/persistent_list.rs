@@ -0,0 +1,875 @@
+//! Provides `PersistentList`, a list which is stored as a persistent, structurally-shared Merkle
+//! tree.
+//!
+//! Cloning a `PersistentList` is cheap: the clone shares all of its nodes with the original and a
+//! node is only copied when it is mutated in one of the lists. Each node stores its own tree hash
+//! root once computed, so hashing a list only visits the nodes which have been modified since the
+//! last hash, without the need for a separate tree hash cache.
+//!
+//! It is used in place of `VariableList` for the large lists of the `BeaconState` when the
+//! `tree-states` feature is enabled.
+#![allow(clippy::integer_arithmetic)]
+
+use crate::test_utils::TestRandom;
+use crate::Hash256;
+use eth2_hashing::{hash32_concat, ZERO_HASHES, ZERO_HASHES_MAX_INDEX};
+use rand::RngCore;
+use serde::ser::SerializeSeq;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use ssz::{Decode, DecodeError, Encode, SszEncoder, BYTES_PER_LENGTH_OFFSET};
+use ssz_types::{typenum::Unsigned, Error, VariableList};
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+use std::sync::{Arc, RwLock};
+use tree_hash::{mix_in_length, TreeHash, TreeHashType, BYTES_PER_CHUNK};
+
+/// Subtrees which are at least this deep are hashed in parallel.
+const PARALLEL_HASHING_DEPTH: usize = 8;
+
+type Proof = (Hash256, Vec<Hash256>);
+
+/// A tree hash root which is computed lazily and shared between all lists that share its node.
+#[derive(Default)]
+struct HashCell(RwLock<Option<Hash256>>);
+
+impl HashCell {
+    fn get(&self) -> Option<Hash256> {
+        self.0.read().ok().and_then(|hash| *hash)
+    }
+
+    fn set(&self, hash: Hash256) {
+        if let Ok(mut cell) = self.0.write() {
+            *cell = Some(hash);
+        }
+    }
+
+    /// Clear the hash, as required whenever the node is mutated.
+    fn clear(&mut self) {
+        if let Ok(cell) = self.0.get_mut() {
+            *cell = None;
+        }
+    }
+}
+
+impl Clone for HashCell {
+    fn clone(&self) -> Self {
+        HashCell(RwLock::new(self.get()))
+    }
+}
+
+/// A node in the tree of a `PersistentList`.
+///
+/// A leaf holds a single chunk of the list: one value for composite types, or as many values as
+/// fit into a 32-byte chunk for basic types.
+#[derive(Clone)]
+enum Tree<T> {
+    Leaf {
+        hash: HashCell,
+        values: Vec<T>,
+    },
+    Node {
+        hash: HashCell,
+        left: Arc<Tree<T>>,
+        right: Arc<Tree<T>>,
+    },
+    /// A subtree of the given depth which holds no values.
+    Zero(usize),
+}
+
+impl<T: Clone> Tree<T> {
+    fn empty_leaf() -> Self {
+        Tree::Leaf {
+            hash: HashCell::default(),
+            values: vec![],
+        }
+    }
+
+    fn node(left: Arc<Self>, right: Arc<Self>) -> Self {
+        Tree::Node {
+            hash: HashCell::default(),
+            left,
+            right,
+        }
+    }
+
+    /// Returns the leaf at `chunk_index` of this subtree of the given `depth`, or `None` if it has
+    /// not been created.
+    fn leaf(&self, depth: usize, chunk_index: usize) -> Option<&[T]> {
+        match self {
+            Tree::Leaf { values, .. } => Some(values),
+            Tree::Node { left, right, .. } => {
+                let half = 1 << (depth - 1);
+                if chunk_index < half {
+                    left.leaf(depth - 1, chunk_index)
+                } else {
+                    right.leaf(depth - 1, chunk_index - half)
+                }
+            }
+            Tree::Zero(_) => None,
+        }
+    }
+
+    /// Returns a mutable reference to the leaf at `chunk_index`, creating it if required.
+    ///
+    /// Any nodes on the path to the leaf which are shared with another list are copied, and the
+    /// hash of every node on the path is cleared.
+    fn leaf_mut(tree: &mut Arc<Self>, depth: usize, chunk_index: usize) -> &mut Vec<T> {
+        let node = Arc::make_mut(tree);
+
+        if let Tree::Zero(zero_depth) = *node {
+            *node = if zero_depth == 0 {
+                Self::empty_leaf()
+            } else {
+                Self::node(
+                    Arc::new(Tree::Zero(zero_depth - 1)),
+                    Arc::new(Tree::Zero(zero_depth - 1)),
+                )
+            };
+        }
+
+        match node {
+            Tree::Leaf { hash, values } => {
+                hash.clear();
+                values
+            }
+            Tree::Node { hash, left, right } => {
+                hash.clear();
+                let half = 1 << (depth - 1);
+                if chunk_index < half {
+                    Self::leaf_mut(left, depth - 1, chunk_index)
+                } else {
+                    Self::leaf_mut(right, depth - 1, chunk_index - half)
+                }
+            }
+            Tree::Zero(_) => unreachable!("zero node was replaced above"),
+        }
+    }
+
+    /// Collects a mutable reference to every leaf, copying any shared nodes and clearing every
+    /// hash.
+    fn leaves_mut<'a>(tree: &'a mut Arc<Self>, leaves: &mut Vec<&'a mut Vec<T>>) {
+        match Arc::make_mut(tree) {
+            Tree::Leaf { hash, values } => {
+                hash.clear();
+                leaves.push(values);
+            }
+            Tree::Node { hash, left, right } => {
+                hash.clear();
+                Self::leaves_mut(left, leaves);
+                Self::leaves_mut(right, leaves);
+            }
+            Tree::Zero(_) => (),
+        }
+    }
+
+    /// Returns the node at `index` of the layer `depth` levels below this subtree, or `None` if
+    /// that node lies in an uncreated (all-zero) subtree.
+    fn descendant(&self, depth: usize, index: usize) -> Option<&Self> {
+        if depth == 0 {
+            return Some(self);
+        }
+
+        match self {
+            Tree::Node { left, right, .. } => {
+                let half = 1 << (depth - 1);
+                if index < half {
+                    left.descendant(depth - 1, index)
+                } else {
+                    right.descendant(depth - 1, index - half)
+                }
+            }
+            Tree::Leaf { .. } | Tree::Zero(_) => None,
+        }
+    }
+}
+
+impl<T: Clone + TreeHash + Send + Sync> Tree<T> {
+    /// Returns the root of this subtree of the given `depth`, computing and storing the root of
+    /// any node which does not have one.
+    fn tree_hash(&self, depth: usize) -> Hash256 {
+        match self {
+            Tree::Leaf { hash, values } => {
+                if let Some(root) = hash.get() {
+                    return root;
+                }
+
+                let root = match T::tree_hash_type() {
+                    TreeHashType::Basic => {
+                        let mut chunk = [0; BYTES_PER_CHUNK];
+                        let item_len = BYTES_PER_CHUNK / T::tree_hash_packing_factor();
+                        for (i, value) in values.iter().enumerate() {
+                            chunk[i * item_len..(i + 1) * item_len]
+                                .copy_from_slice(&value.tree_hash_packed_encoding());
+                        }
+                        Hash256::from(chunk)
+                    }
+                    TreeHashType::Container | TreeHashType::List | TreeHashType::Vector => values
+                        .first()
+                        .map_or_else(|| zero_hash(0), TreeHash::tree_hash_root),
+                };
+                hash.set(root);
+                root
+            }
+            Tree::Node { hash, left, right } => {
+                if let Some(root) = hash.get() {
+                    return root;
+                }
+
+                let (left_root, right_root) = if depth >= PARALLEL_HASHING_DEPTH {
+                    rayon::join(|| left.tree_hash(depth - 1), || right.tree_hash(depth - 1))
+                } else {
+                    (left.tree_hash(depth - 1), right.tree_hash(depth - 1))
+                };
+
+                let root =
+                    Hash256::from(hash32_concat(left_root.as_bytes(), right_root.as_bytes()));
+                hash.set(root);
+                root
+            }
+            Tree::Zero(zero_depth) => zero_hash(*zero_depth),
+        }
+    }
+}
+
+/// Returns the root of a Merkle tree of the given `depth` with all-zero leaves.
+fn zero_hash(depth: usize) -> Hash256 {
+    Hash256::from_slice(&ZERO_HASHES[depth])
+}
+
+/// A list of at most `N` values, stored as a persistent Merkle tree.
+///
+/// Provides the subset of the `VariableList` API which is used by the `BeaconState`, so that the
+/// two can be exchanged by the `tree-states` feature.
+pub struct PersistentList<T, N> {
+    tree: Arc<Tree<T>>,
+    /// The depth of `tree`, which holds `2^depth` leaves.
+    depth: usize,
+    len: usize,
+    _phantom: PhantomData<N>,
+}
+
+impl<T: Clone + TreeHash, N: Unsigned> PersistentList<T, N> {
+    /// Returns `Ok` if the given `vec` does not exceed the maximum length of `Self`. Otherwise
+    /// returns `Err(OutOfBounds { .. })`.
+    pub fn new(vec: Vec<T>) -> Result<Self, Error> {
+        if vec.len() <= N::to_usize() {
+            Ok(Self::from_vec(vec))
+        } else {
+            Err(Error::OutOfBounds {
+                i: vec.len(),
+                len: Self::max_len(),
+            })
+        }
+    }
+
+    /// Create an empty list.
+    pub fn empty() -> Self {
+        Self {
+            tree: Arc::new(Tree::Zero(0)),
+            depth: 0,
+            len: 0,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the number of values presently in `self`.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// True if `self` does not contain any values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the type-level maximum length.
+    pub fn max_len() -> usize {
+        N::to_usize()
+    }
+
+    /// Returns a reference to the value at `index`, or `None` if it is out of bounds.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+
+        let leaf_len = Self::leaf_len();
+        self.tree
+            .leaf(self.depth, index / leaf_len)
+            .and_then(|values| values.get(index % leaf_len))
+    }
+
+    /// Returns a mutable reference to the value at `index`, or `None` if it is out of bounds.
+    ///
+    /// The nodes on the path to the value are copied if they are shared with another list, and
+    /// their hashes are cleared. Prefer `get` if the value might not be modified.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.len {
+            return None;
+        }
+
+        let leaf_len = Self::leaf_len();
+        Tree::leaf_mut(&mut self.tree, self.depth, index / leaf_len).get_mut(index % leaf_len)
+    }
+
+    /// Appends `value` to the back of `self`.
+    ///
+    /// Returns `Err(OutOfBounds { .. })` when appending `value` would exceed the maximum length.
+    pub fn push(&mut self, value: T) -> Result<(), Error> {
+        if self.len >= Self::max_len() {
+            return Err(Error::OutOfBounds {
+                i: self.len.saturating_add(1),
+                len: Self::max_len(),
+            });
+        }
+
+        let leaf_len = Self::leaf_len();
+        let chunk_index = self.len / leaf_len;
+
+        // Double the capacity of the tree if it is full.
+        if chunk_index >= 1 << self.depth {
+            let old_tree = std::mem::replace(&mut self.tree, Arc::new(Tree::Zero(0)));
+            self.tree = Arc::new(Tree::node(old_tree, Arc::new(Tree::Zero(self.depth))));
+            self.depth += 1;
+        }
+
+        Tree::leaf_mut(&mut self.tree, self.depth, chunk_index).push(value);
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Returns an iterator over the values of `self`.
+    pub fn iter(&self) -> Iter<T> {
+        Iter {
+            stack: vec![&self.tree],
+            leaf: [].iter(),
+            remaining: self.len,
+        }
+    }
+
+    /// Returns an iterator which allows modifying each value of `self`.
+    ///
+    /// Every node is copied if it is shared with another list, and every hash is cleared. Prefer
+    /// `get_mut` if only a few values will be modified.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        let mut leaves = vec![];
+        Tree::leaves_mut(&mut self.tree, &mut leaves);
+        leaves.into_iter().flat_map(|values| values.iter_mut())
+    }
+
+    /// Returns the root of the data tree of `self`, i.e., the tree hash root before the length is
+    /// mixed in.
+    pub fn data_tree_hash_root(&self) -> Hash256
+    where
+        T: Send + Sync,
+    {
+        self.data_node_hash(0, 0)
+    }
+
+    /// Returns a proof of the node at `index` of the layer `depth` levels below the root of the
+    /// data tree of `self` (i.e., the tree before the length is mixed in).
+    ///
+    /// Returns `None` if `depth` is greater than the depth of the data tree or `index` is not in
+    /// that layer. The branch is ordered from the bottom of the tree upwards.
+    pub fn generate_proof(&self, depth: usize, index: usize) -> Option<Proof>
+    where
+        T: Send + Sync,
+    {
+        if depth > Self::data_depth() || index >= 1 << depth {
+            return None;
+        }
+
+        let leaf = self.data_node_hash(depth, index);
+        let branch = (1..=depth)
+            .rev()
+            .map(|layer| self.data_node_hash(layer, (index >> (depth - layer)) ^ 1))
+            .collect();
+
+        Some((leaf, branch))
+    }
+
+    /// Returns the hash of the node at `index` of the layer `depth` levels below the root of the
+    /// data tree.
+    fn data_node_hash(&self, depth: usize, index: usize) -> Hash256
+    where
+        T: Send + Sync,
+    {
+        let height = Self::data_depth() - depth;
+
+        if height >= self.depth {
+            // The node contains the whole of `self.tree`, or is entirely beyond it.
+            if index == 0 {
+                (self.depth..height).fold(self.tree.tree_hash(self.depth), |root, layer| {
+                    Hash256::from(hash32_concat(root.as_bytes(), &ZERO_HASHES[layer]))
+                })
+            } else {
+                zero_hash(height)
+            }
+        } else {
+            // The node is within `self.tree`, or beside it.
+            let layer_depth = self.depth - height;
+            if index >= 1 << layer_depth {
+                zero_hash(height)
+            } else {
+                self.tree
+                    .descendant(layer_depth, index)
+                    .map_or_else(|| zero_hash(height), |node| node.tree_hash(height))
+            }
+        }
+    }
+
+    /// The number of values stored in each leaf of the tree.
+    fn leaf_len() -> usize {
+        match T::tree_hash_type() {
+            TreeHashType::Basic => T::tree_hash_packing_factor(),
+            TreeHashType::Container | TreeHashType::List | TreeHashType::Vector => 1,
+        }
+    }
+
+    /// The depth of the data tree of a list with the maximum length.
+    pub fn data_depth() -> usize {
+        let leaf_len = Self::leaf_len();
+        let max_leaves = (Self::max_len() + leaf_len - 1) / leaf_len;
+        max_leaves.next_power_of_two().trailing_zeros() as usize
+    }
+
+    /// Builds the tree bottom-up from `vec`, which must not exceed the maximum length.
+    fn from_vec(vec: Vec<T>) -> Self {
+        let len = vec.len();
+        let leaf_len = Self::leaf_len();
+
+        let mut layer: Vec<Arc<Tree<T>>> = vec
+            .chunks(leaf_len)
+            .map(|values| {
+                Arc::new(Tree::Leaf {
+                    hash: HashCell::default(),
+                    values: values.to_vec(),
+                })
+            })
+            .collect();
+        let mut depth = 0;
+
+        if layer.is_empty() {
+            return Self::empty();
+        }
+
+        while layer.len() > 1 {
+            layer = layer
+                .chunks(2)
+                .map(|pair| {
+                    let left = pair[0].clone();
+                    let right = pair
+                        .get(1)
+                        .cloned()
+                        .unwrap_or_else(|| Arc::new(Tree::Zero(depth)));
+                    Arc::new(Tree::node(left, right))
+                })
+                .collect();
+            depth += 1;
+        }
+
+        Self {
+            tree: layer.remove(0),
+            depth,
+            len,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// An iterator over the values of a `PersistentList`, in order.
+pub struct Iter<'a, T> {
+    stack: Vec<&'a Arc<Tree<T>>>,
+    leaf: std::slice::Iter<'a, T>,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            if let Some(value) = self.leaf.next() {
+                self.remaining -= 1;
+                return Some(value);
+            }
+
+            let top: &'a Arc<Tree<T>> = self.stack.last()?;
+            match &**top {
+                Tree::Leaf { values, .. } => {
+                    self.stack.pop();
+                    self.leaf = values.iter();
+                }
+                Tree::Node { left, right, .. } => {
+                    self.stack.pop();
+                    self.stack.push(right);
+                    self.stack.push(left);
+                }
+                // Values are stored contiguously from the left, so there are no values beyond the
+                // first zero subtree.
+                Tree::Zero(_) => self.stack.clear(),
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T: Clone + TreeHash, N: Unsigned> IntoIterator for &'a PersistentList<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T, N> Clone for PersistentList<T, N> {
+    /// Clones the list by sharing all of its nodes, without copying any values.
+    fn clone(&self) -> Self {
+        Self {
+            tree: self.tree.clone(),
+            depth: self.depth,
+            len: self.len,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Clone + TreeHash, N: Unsigned> Default for PersistentList<T, N> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<T: Clone + TreeHash, N: Unsigned> From<Vec<T>> for PersistentList<T, N> {
+    /// Creates a list from `vec`, truncating it to the maximum length.
+    fn from(mut vec: Vec<T>) -> Self {
+        vec.truncate(N::to_usize());
+        Self::from_vec(vec)
+    }
+}
+
+impl<T: Clone + TreeHash, N: Unsigned> From<VariableList<T, N>> for PersistentList<T, N> {
+    fn from(list: VariableList<T, N>) -> Self {
+        Self::from_vec(list.into())
+    }
+}
+
+impl<T: Clone + TreeHash, N: Unsigned> From<PersistentList<T, N>> for Vec<T> {
+    fn from(list: PersistentList<T, N>) -> Vec<T> {
+        list.iter().cloned().collect()
+    }
+}
+
+impl<T: Clone + TreeHash, N: Unsigned> Index<usize> for PersistentList<T, N> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        let len = self.len;
+        self.get(index).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                len, index
+            )
+        })
+    }
+}
+
+impl<T: Clone + TreeHash, N: Unsigned> IndexMut<usize> for PersistentList<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        let len = self.len;
+        self.get_mut(index).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                len, index
+            )
+        })
+    }
+}
+
+impl<T: Clone + TreeHash + PartialEq, N: Unsigned> PartialEq for PersistentList<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len
+            && (Arc::ptr_eq(&self.tree, &other.tree) || self.iter().eq(other.iter()))
+    }
+}
+
+impl<T: Clone + TreeHash + fmt::Debug, N: Unsigned> fmt::Debug for PersistentList<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, N> TreeHash for PersistentList<T, N>
+where
+    T: Clone + TreeHash + Send + Sync,
+    N: Unsigned,
+{
+    fn tree_hash_type() -> TreeHashType {
+        TreeHashType::List
+    }
+
+    fn tree_hash_packed_encoding(&self) -> Vec<u8> {
+        unreachable!("List should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("List should never be packed.")
+    }
+
+    fn tree_hash_root(&self) -> Hash256 {
+        debug_assert!(Self::data_depth() <= ZERO_HASHES_MAX_INDEX);
+
+        mix_in_length(&self.data_tree_hash_root(), self.len)
+    }
+}
+
+impl<T: Clone + TreeHash + Encode, N: Unsigned> Encode for PersistentList<T, N> {
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        if T::is_ssz_fixed_len() {
+            T::ssz_fixed_len() * self.len
+        } else {
+            self.iter().map(Encode::ssz_bytes_len).sum::<usize>()
+                + BYTES_PER_LENGTH_OFFSET * self.len
+        }
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        if T::is_ssz_fixed_len() {
+            buf.reserve(T::ssz_fixed_len() * self.len);
+
+            for value in self {
+                value.ssz_append(buf);
+            }
+        } else {
+            let mut encoder = SszEncoder::container(buf, self.len * BYTES_PER_LENGTH_OFFSET);
+
+            for value in self {
+                encoder.append(value);
+            }
+
+            encoder.finalize();
+        }
+    }
+}
+
+impl<T: Clone + TreeHash + Decode, N: Unsigned> Decode for PersistentList<T, N> {
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        VariableList::<T, N>::from_ssz_bytes(bytes).map(Into::into)
+    }
+}
+
+impl<T: Clone + TreeHash + Serialize, N: Unsigned> Serialize for PersistentList<T, N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len))?;
+        for value in self {
+            seq.serialize_element(value)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de, T, N> Deserialize<'de> for PersistentList<T, N>
+where
+    T: Clone + TreeHash + Deserialize<'de>,
+    N: Unsigned,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let vec = Vec::<T>::deserialize(deserializer)?;
+        Self::new(vec).map_err(|e| serde::de::Error::custom(format!("PersistentList: {:?}", e)))
+    }
+}
+
+impl<T: Clone + TreeHash + TestRandom, N: Unsigned> TestRandom for PersistentList<T, N> {
+    fn random_for_test(rng: &mut impl RngCore) -> Self {
+        VariableList::<T, N>::random_for_test(rng).into()
+    }
+}
+
+#[cfg(feature = "arbitrary-fuzz")]
+impl<T, N> arbitrary::Arbitrary for PersistentList<T, N>
+where
+    T: Clone + TreeHash + arbitrary::Arbitrary,
+    N: 'static + Unsigned,
+{
+    fn arbitrary(u: &mut arbitrary::Unstructured<'_>) -> arbitrary::Result<Self> {
+        VariableList::<T, N>::arbitrary(u).map(Into::into)
+    }
+}
+
+/// Formats `PersistentList<u64, N>` using quotes.
+///
+/// E.g., `PersistentList::from(vec![0, 1, 2])` serializes as `["0", "1", "2"]`.
+pub mod quoted_u64_persistent_list {
+    use super::PersistentList;
+    use serde::{Deserializer, Serializer};
+    use ssz_types::{serde_utils::quoted_u64_var_list, typenum::Unsigned};
+
+    pub fn serialize<S, N>(value: &PersistentList<u64, N>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        N: Unsigned,
+    {
+        let values: Vec<u64> = value.iter().copied().collect();
+        quoted_u64_var_list::serialize(&values, serializer)
+    }
+
+    pub fn deserialize<'de, D, N>(deserializer: D) -> Result<PersistentList<u64, N>, D::Error>
+    where
+        D: Deserializer<'de>,
+        N: Unsigned,
+    {
+        quoted_u64_var_list::deserialize(deserializer).map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{SeedableRng, XorShiftRng};
+    use crate::Validator;
+    use ssz_types::typenum::{U1024, U1099511627776};
+
+    type Balances = PersistentList<u64, U1099511627776>;
+    type Validators = PersistentList<Validator, U1099511627776>;
+
+    fn random_validators(n: usize) -> Vec<Validator> {
+        let mut rng = XorShiftRng::from_seed([42; 16]);
+        (0..n)
+            .map(|_| Validator::random_for_test(&mut rng))
+            .collect()
+    }
+
+    #[test]
+    fn push_get_and_iter() {
+        let mut list = PersistentList::<u64, U1024>::empty();
+        for i in 0..100 {
+            list.push(i).unwrap();
+            assert_eq!(list.len(), i as usize + 1);
+        }
+
+        for i in 0..100 {
+            assert_eq!(list.get(i as usize), Some(&i));
+            assert_eq!(list[i as usize], i);
+        }
+        assert_eq!(list.get(100), None);
+        assert_eq!(list.iter().len(), 100);
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            (0..100).collect::<Vec<_>>()
+        );
+        assert_eq!(list, PersistentList::from((0..100).collect::<Vec<_>>()));
+    }
+
+    #[test]
+    fn push_beyond_max_len() {
+        let mut list = PersistentList::<u64, U1024>::from(vec![0; 1024]);
+        assert!(list.push(0).is_err());
+        assert!(PersistentList::<u64, U1024>::new(vec![0; 1025]).is_err());
+    }
+
+    #[test]
+    fn clones_are_independent() {
+        let original = Balances::from(vec![1; 33]);
+        let mut clone = original.clone();
+
+        clone[7] = 42;
+        *clone.iter_mut().last().unwrap() = 43;
+        clone.push(44).unwrap();
+
+        assert_eq!(original.len(), 33);
+        assert!(original.iter().all(|balance| *balance == 1));
+        assert_eq!(clone[7], 42);
+        assert_eq!(clone[32], 43);
+        assert_eq!(clone[33], 44);
+    }
+
+    #[test]
+    fn tree_hash_matches_variable_list() {
+        for n in &[0, 1, 3, 4, 5, 31, 64, 65] {
+            let values: Vec<u64> = (0..*n).collect();
+            let list = Balances::from(values.clone());
+            let variable_list = VariableList::<u64, U1099511627776>::from(values);
+            assert_eq!(
+                list.tree_hash_root(),
+                variable_list.tree_hash_root(),
+                "n = {}",
+                n
+            );
+
+            let validators = random_validators(*n as usize);
+            let list = Validators::from(validators.clone());
+            let variable_list = VariableList::<Validator, U1099511627776>::from(validators);
+            assert_eq!(
+                list.tree_hash_root(),
+                variable_list.tree_hash_root(),
+                "n = {}",
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn tree_hash_after_mutation() {
+        let validators = random_validators(20);
+        let mut list = Validators::from(validators.clone());
+        let mut variable_list = VariableList::<Validator, U1099511627776>::from(validators);
+
+        // Compute and store the hashes prior to mutating.
+        list.tree_hash_root();
+        let clone = list.clone();
+
+        list[3].effective_balance = 7;
+        variable_list[3].effective_balance = 7;
+        list.push(variable_list[0].clone()).unwrap();
+        variable_list.push(variable_list[0].clone()).unwrap();
+
+        assert_eq!(list.tree_hash_root(), variable_list.tree_hash_root());
+        assert_ne!(clone.tree_hash_root(), list.tree_hash_root());
+    }
+
+    #[test]
+    fn ssz_round_trip() {
+        let validators = random_validators(9);
+        let list = Validators::from(validators.clone());
+        let bytes = list.as_ssz_bytes();
+        assert_eq!(
+            bytes,
+            VariableList::<Validator, U1099511627776>::from(validators).as_ssz_bytes()
+        );
+        assert_eq!(Validators::from_ssz_bytes(&bytes).unwrap(), list);
+    }
+
+    #[test]
+    fn proofs_verify() {
+        let list = Balances::from((0..13).collect::<Vec<_>>());
+        let data_root = list.data_tree_hash_root();
+        let depth = Balances::data_depth();
+
+        for index in 0..8 {
+            let (leaf, branch) = list.generate_proof(depth, index).unwrap();
+            assert_eq!(branch.len(), depth);
+            assert!(merkle_proof::verify_merkle_proof(
+                leaf, &branch, depth, index, data_root
+            ));
+        }
+
+        assert_eq!(list.generate_proof(depth + 1, 0), None);
+        assert_eq!(list.generate_proof(1, 2), None);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::*;
+use bls::get_withdrawal_credentials;
+use std::fmt;
+
+/// The index of the first byte of the address in Eth1 address credentials. The bytes between the
+/// prefix and the address must be zero.
+const ETH1_ADDRESS_OFFSET: usize = 12;
+
+/// The 32-byte `withdrawal_credentials` of a validator, as included in a `DepositData`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct WithdrawalCredentials(Hash256);
+
+/// The kind of withdrawal credentials, as indicated by the prefix byte.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum WithdrawalCredentialsType {
+    /// `BLS_WITHDRAWAL_PREFIX`: commits to the hash of a BLS withdrawal public key.
+    Bls,
+    /// `ETH1_ADDRESS_WITHDRAWAL_PREFIX`: commits to an Eth1 address.
+    Eth1Address(Address),
+}
+
+/// Returned when some withdrawal credentials do not match any known format.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum InvalidWithdrawalCredentials {
+    UnknownPrefix(u8),
+    NonZeroEth1AddressPadding,
+}
+
+impl WithdrawalCredentials {
+    /// Credentials committing to the hash of `withdrawal_public_key`.
+    pub fn bls(withdrawal_public_key: &PublicKey, spec: &ChainSpec) -> Self {
+        let bytes =
+            get_withdrawal_credentials(withdrawal_public_key, spec.bls_withdrawal_prefix_byte);
+        Self(Hash256::from_slice(&bytes))
+    }
+
+    /// Credentials committing to the given Eth1 `withdrawal_address`.
+    pub fn eth1(withdrawal_address: Address, spec: &ChainSpec) -> Self {
+        let mut bytes = [0; 32];
+        bytes[0] = spec.eth1_address_withdrawal_prefix_byte;
+        bytes[ETH1_ADDRESS_OFFSET..].copy_from_slice(withdrawal_address.as_bytes());
+        Self(Hash256::from(bytes))
+    }
+
+    /// Returns the type of `self`, or an error if `self` is not in a recognised format.
+    pub fn credentials_type(
+        &self,
+        spec: &ChainSpec,
+    ) -> Result<WithdrawalCredentialsType, InvalidWithdrawalCredentials> {
+        let bytes = self.0.as_bytes();
+        let prefix = bytes[0];
+
+        if prefix == spec.bls_withdrawal_prefix_byte {
+            Ok(WithdrawalCredentialsType::Bls)
+        } else if prefix == spec.eth1_address_withdrawal_prefix_byte {
+            let padding = &bytes[1..ETH1_ADDRESS_OFFSET];
+            let address = &bytes[ETH1_ADDRESS_OFFSET..];
+
+            if padding.iter().all(|byte| *byte == 0) {
+                Ok(WithdrawalCredentialsType::Eth1Address(Address::from_slice(
+                    address,
+                )))
+            } else {
+                Err(InvalidWithdrawalCredentials::NonZeroEth1AddressPadding)
+            }
+        } else {
+            Err(InvalidWithdrawalCredentials::UnknownPrefix(prefix))
+        }
+    }
+}
+
+impl From<Hash256> for WithdrawalCredentials {
+    fn from(bytes: Hash256) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<WithdrawalCredentials> for Hash256 {
+    fn from(credentials: WithdrawalCredentials) -> Self {
+        credentials.0
+    }
+}
+
+impl fmt::Display for WithdrawalCredentialsType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WithdrawalCredentialsType::Bls => write!(f, "BLS"),
+            WithdrawalCredentialsType::Eth1Address(address) => {
+                write!(f, "Eth1 address {:?}", address)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::generate_deterministic_keypair;
+
+    #[test]
+    fn bls_credentials() {
+        let spec = &MainnetEthSpec::default_spec();
+        let keypair = generate_deterministic_keypair(0);
+
+        let credentials = WithdrawalCredentials::bls(&keypair.pk, spec);
+        let bytes: Hash256 = credentials.into();
+
+        assert_eq!(
+            bytes.as_bytes(),
+            &get_withdrawal_credentials(&keypair.pk, spec.bls_withdrawal_prefix_byte)[..]
+        );
+        assert_eq!(
+            credentials.credentials_type(spec),
+            Ok(WithdrawalCredentialsType::Bls)
+        );
+    }
+
+    #[test]
+    fn eth1_credentials() {
+        let spec = &MainnetEthSpec::default_spec();
+        let address = Address::repeat_byte(42);
+
+        let credentials = WithdrawalCredentials::eth1(address, spec);
+        let bytes: Hash256 = credentials.into();
+
+        assert_eq!(bytes[0], spec.eth1_address_withdrawal_prefix_byte);
+        assert!(bytes[1..12].iter().all(|byte| *byte == 0));
+        assert_eq!(&bytes[12..], address.as_bytes());
+        assert_eq!(
+            credentials.credentials_type(spec),
+            Ok(WithdrawalCredentialsType::Eth1Address(address))
+        );
+    }
+
+    #[test]
+    fn invalid_credentials() {
+        let spec = &MainnetEthSpec::default_spec();
+
+        let mut bytes = [0; 32];
+        bytes[0] = spec.eth1_address_withdrawal_prefix_byte;
+        bytes[1] = 1;
+        assert_eq!(
+            WithdrawalCredentials::from(Hash256::from(bytes)).credentials_type(spec),
+            Err(InvalidWithdrawalCredentials::NonZeroEth1AddressPadding)
+        );
+
+        bytes[0] = 42;
+        assert_eq!(
+            WithdrawalCredentials::from(Hash256::from(bytes)).credentials_type(spec),
+            Err(InvalidWithdrawalCredentials::UnknownPrefix(42))
+        );
+    }
+}
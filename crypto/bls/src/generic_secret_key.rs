@@ -3,6 +3,7 @@ use crate::{
     generic_signature::{GenericSignature, TSignature},
     Error, Hash256, ZeroizeHash,
 };
+use std::iter;
 use std::marker::PhantomData;
 
 /// The byte-length of a BLS secret key.
@@ -25,6 +26,10 @@ pub trait TSecretKey<SignaturePoint, PublicKeyPoint>: Sized {
 
     /// Deserialize `self` from compressed bytes.
     fn deserialize(bytes: &[u8]) -> Result<Self, Error>;
+
+    /// Returns the evaluation at `x` of the polynomial with the given `coefficients` (ordered from
+    /// the constant term upwards).
+    fn evaluate_polynomial(coefficients: &[&Self], x: u64) -> Result<Self, Error>;
 }
 
 #[derive(Clone)]
@@ -71,6 +76,41 @@ where
         self.point.serialize()
     }
 
+    /// Splits `self` into `shares` secret key shares using Shamir's secret sharing, such that
+    /// signatures from any `threshold` of the shares may be combined into a signature from `self`
+    /// (see `GenericSignature::combine_shares`).
+    ///
+    /// Each share is returned alongside its identifier, which is its (non-zero) evaluation point
+    /// on the secret polynomial. Identifiers run from `1` to `shares`, inclusive.
+    ///
+    /// ## Note
+    ///
+    /// The shares are sensitive cryptographic material; any `threshold` of them are sufficient to
+    /// produce signatures from `self`.
+    pub fn split(&self, threshold: u64, shares: u64) -> Result<Vec<(u64, Self)>, Error> {
+        if threshold == 0 || threshold > shares {
+            return Err(Error::InvalidThreshold { threshold, shares });
+        }
+
+        let random_coefficients = (1..threshold).map(|_| Sec::random()).collect::<Vec<_>>();
+        let coefficients = iter::once(&self.point)
+            .chain(random_coefficients.iter())
+            .collect::<Vec<_>>();
+
+        (1..=shares)
+            .map(|id| {
+                Ok((
+                    id,
+                    Self {
+                        point: Sec::evaluate_polynomial(&coefficients, id)?,
+                        _phantom_signature: PhantomData,
+                        _phantom_public_key: PhantomData,
+                    },
+                ))
+            })
+            .collect()
+    }
+
     /// Deserialize `self` from compressed bytes.
     pub fn deserialize(bytes: &[u8]) -> Result<Self, Error> {
         if bytes.len() != SECRET_KEY_BYTES_LEN {
@@ -6,6 +6,7 @@ use serde::de::{Deserialize, Deserializer};
 use serde::ser::{Serialize, Serializer};
 use serde_utils::hex::encode as hex_encode;
 use ssz::{Decode, Encode};
+use std::collections::HashSet;
 use std::fmt;
 use std::marker::PhantomData;
 use tree_hash::TreeHash;
@@ -35,6 +36,12 @@ pub trait TSignature<GenericPublicKey>: Sized + Clone {
 
     /// Returns `true` if `self` is a signature across `msg` by `pubkey`.
     fn verify(&self, pubkey: &GenericPublicKey, msg: Hash256) -> bool;
+
+    /// Combines signatures from secret key shares, each paired with the identifier of its share,
+    /// via Lagrange interpolation at zero.
+    ///
+    /// The identifiers have already been checked to be non-zero and unique.
+    fn combine_shares(shares: &[(u64, &Self)]) -> Result<Self, Error>;
 }
 
 /// A BLS signature that is generic across:
@@ -102,6 +109,37 @@ where
         }
     }
 
+    /// Combines signatures across the same message from at least `threshold` of the shares
+    /// produced by `GenericSecretKey::split`, returning the signature of the original secret key.
+    ///
+    /// Each signature must be paired with the identifier of the share that produced it. Supplying
+    /// fewer than `threshold` shares will produce a signature that fails verification.
+    pub fn combine_shares(shares: &[(u64, &Self)]) -> Result<Self, Error> {
+        if shares.is_empty() {
+            return Err(Error::InvalidSignatureShares);
+        }
+
+        let mut ids = HashSet::with_capacity(shares.len());
+        let points = shares
+            .iter()
+            .map(|(id, signature)| {
+                if *id == 0 || !ids.insert(*id) {
+                    return Err(Error::InvalidShareId(*id));
+                }
+
+                signature
+                    .point()
+                    .map(|point| (*id, point))
+                    .ok_or(Error::InvalidSignatureShares)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let point = Sig::combine_shares(&points)?;
+        let is_infinity = point.serialize()[..] == INFINITY_SIGNATURE[..];
+
+        Ok(Self::from_point(point, is_infinity))
+    }
+
     /// Deserialize `self` from compressed bytes.
     pub fn deserialize(bytes: &[u8]) -> Result<Self, Error> {
         let point = if bytes == &NONE_SIGNATURE[..] {
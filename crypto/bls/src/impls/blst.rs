@@ -2,17 +2,21 @@ use crate::{
     generic_aggregate_public_key::TAggregatePublicKey,
     generic_aggregate_signature::TAggregateSignature,
     generic_public_key::{GenericPublicKey, TPublicKey, PUBLIC_KEY_BYTES_LEN},
-    generic_secret_key::TSecretKey,
+    generic_secret_key::{TSecretKey, SECRET_KEY_BYTES_LEN},
     generic_signature::{TSignature, SIGNATURE_BYTES_LEN},
+    impls::zeroize_with,
     Error, Hash256, ZeroizeHash, INFINITY_SIGNATURE,
 };
 pub use blst::min_pk as blst_core;
-use blst::{blst_scalar, BLST_ERROR};
+use blst::{blst_fr, blst_p2, blst_p2_affine, blst_scalar, BLST_ERROR};
 use rand::Rng;
 use std::iter::ExactSizeIterator;
+use zeroize::Zeroize;
 
 pub const DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
 pub const RAND_BITS: usize = 64;
+/// The number of bits in the order of the scalar field.
+const SCALAR_BITS: usize = 255;
 
 /// Provides the externally-facing, core BLS types.
 pub mod types {
@@ -118,6 +122,135 @@ pub fn verify_signature_sets<'a>(
     err == blst::BLST_ERROR::BLST_SUCCESS
 }
 
+/// Returns the element of the scalar field represented by the 32 big-endian `bytes`.
+fn fr_from_bendian(bytes: &[u8; SECRET_KEY_BYTES_LEN]) -> blst_fr {
+    let mut scalar = blst_scalar::default();
+    let mut fr = blst_fr::default();
+    unsafe {
+        blst::blst_scalar_from_bendian(&mut scalar, bytes.as_ptr());
+        blst::blst_fr_from_scalar(&mut fr, &scalar);
+    }
+    zeroize_with(&mut scalar, blst_scalar::default());
+    fr
+}
+
+/// Returns `x` as an element of the scalar field.
+fn fr_from_u64(x: u64) -> blst_fr {
+    let vals = [x, 0, 0, 0];
+    let mut scalar = blst_scalar::default();
+    let mut fr = blst_fr::default();
+    unsafe {
+        blst::blst_scalar_from_uint64(&mut scalar, vals.as_ptr());
+        blst::blst_fr_from_scalar(&mut fr, &scalar);
+    }
+    fr
+}
+
+/// Evaluates the polynomial over the scalar field with the given big-endian `coefficients`
+/// (ordered from the constant term upwards) at `x`, returning the result as big-endian bytes.
+///
+/// The coefficients are secret, so every intermediate value is zeroized before returning.
+fn evaluate_scalar_polynomial(coefficients: &[ZeroizeHash], x: u64) -> ZeroizeHash {
+    let x = fr_from_u64(x);
+    let mut result = blst_fr::default();
+    let result_ptr: *mut blst_fr = &mut result;
+
+    // Horner's method.
+    for coefficient in coefficients.iter().rev() {
+        let mut bytes = [0; SECRET_KEY_BYTES_LEN];
+        bytes.copy_from_slice(coefficient.as_bytes());
+        let mut coefficient = fr_from_bendian(&bytes);
+        bytes.zeroize();
+
+        unsafe {
+            blst::blst_fr_mul(result_ptr, result_ptr, &x);
+            blst::blst_fr_add(result_ptr, result_ptr, &coefficient);
+        }
+        zeroize_with(&mut coefficient, blst_fr::default());
+    }
+
+    let mut scalar = blst_scalar::default();
+    let mut bytes = ZeroizeHash::zero();
+    unsafe {
+        blst::blst_scalar_from_fr(&mut scalar, &result);
+        blst::blst_bendian_from_scalar(bytes.as_mut_bytes().as_mut_ptr(), &scalar);
+    }
+    zeroize_with(&mut result, blst_fr::default());
+    zeroize_with(&mut scalar, blst_scalar::default());
+    bytes
+}
+
+/// Returns the Lagrange basis polynomial for `id`, evaluated at zero, over the points in `ids`.
+///
+/// All of `ids` must be non-zero and unique.
+fn lagrange_coefficient_at_zero(id: u64, ids: &[u64]) -> blst_scalar {
+    let x_i = fr_from_u64(id);
+    let mut numerator = fr_from_u64(1);
+    let mut denominator = fr_from_u64(1);
+    let numerator_ptr: *mut blst_fr = &mut numerator;
+    let denominator_ptr: *mut blst_fr = &mut denominator;
+
+    for &other in ids.iter().filter(|&&other| other != id) {
+        let x_j = fr_from_u64(other);
+        let mut difference = blst_fr::default();
+        unsafe {
+            blst::blst_fr_sub(&mut difference, &x_j, &x_i);
+            blst::blst_fr_mul(numerator_ptr, numerator_ptr, &x_j);
+            blst::blst_fr_mul(denominator_ptr, denominator_ptr, &difference);
+        }
+    }
+
+    let mut coefficient = blst_fr::default();
+    let coefficient_ptr: *mut blst_fr = &mut coefficient;
+    let mut scalar = blst_scalar::default();
+    unsafe {
+        blst::blst_fr_eucl_inverse(coefficient_ptr, &denominator);
+        blst::blst_fr_mul(coefficient_ptr, coefficient_ptr, &numerator);
+        blst::blst_scalar_from_fr(&mut scalar, &coefficient);
+    }
+    scalar
+}
+
+/// Interpolates the compressed G2 points in `shares` (each paired with its non-zero, unique
+/// evaluation point) at zero, returning the compressed result.
+fn interpolate_g2_points(
+    shares: &[(u64, [u8; SIGNATURE_BYTES_LEN])],
+) -> Result<[u8; SIGNATURE_BYTES_LEN], Error> {
+    let ids = shares.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+
+    // The default (zeroed) point is the point at infinity.
+    let mut result = blst_p2::default();
+    let result_ptr: *mut blst_p2 = &mut result;
+
+    for (id, bytes) in shares {
+        let coefficient = lagrange_coefficient_at_zero(*id, &ids);
+
+        let mut affine = blst_p2_affine::default();
+        let mut point = blst_p2::default();
+        let mut term = blst_p2::default();
+        unsafe {
+            let err = blst::blst_p2_uncompress(&mut affine, bytes.as_ptr());
+            if err != BLST_ERROR::BLST_SUCCESS {
+                return Err(err.into());
+            }
+            blst::blst_p2_from_affine(&mut point, &affine);
+            blst::blst_p2_mult(
+                &mut term,
+                &point,
+                &coefficient as *const blst_scalar as *const _,
+                SCALAR_BITS,
+            );
+            blst::blst_p2_add_or_double(result_ptr, result_ptr, &term);
+        }
+    }
+
+    let mut bytes = [0; SIGNATURE_BYTES_LEN];
+    unsafe {
+        blst::blst_p2_compress(bytes.as_mut_ptr(), &result);
+    }
+    Ok(bytes)
+}
+
 impl TPublicKey for blst_core::PublicKey {
     fn serialize(&self) -> [u8; PUBLIC_KEY_BYTES_LEN] {
         self.compress()
@@ -169,6 +302,15 @@ impl TSignature<blst_core::PublicKey> for blst_core::Signature {
         // Check Signature inside function for subgroup
         self.verify(true, msg.as_bytes(), DST, &[], pubkey, false) == BLST_ERROR::BLST_SUCCESS
     }
+
+    fn combine_shares(shares: &[(u64, &Self)]) -> Result<Self, Error> {
+        let shares = shares
+            .iter()
+            .map(|(id, signature)| (*id, signature.to_bytes()))
+            .collect::<Vec<_>>();
+
+        Self::from_bytes(&interpolate_g2_points(&shares)?).map_err(Into::into)
+    }
 }
 
 /// A wrapper that allows for `PartialEq` and `Clone` impls.
@@ -268,4 +410,14 @@ impl TSecretKey<blst_core::Signature, blst_core::PublicKey> for blst_core::Secre
     fn deserialize(bytes: &[u8]) -> Result<Self, Error> {
         Self::from_bytes(&bytes).map_err(Into::into)
     }
+
+    fn evaluate_polynomial(coefficients: &[&Self], x: u64) -> Result<Self, Error> {
+        let coefficients = coefficients
+            .iter()
+            .map(|coefficient| coefficient.to_bytes().into())
+            .collect::<Vec<ZeroizeHash>>();
+
+        Self::from_bytes(evaluate_scalar_polynomial(&coefficients, x).as_bytes())
+            .map_err(Into::into)
+    }
 }
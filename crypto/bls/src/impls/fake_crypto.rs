@@ -96,6 +96,13 @@ impl TSignature<PublicKey> for Signature {
     fn verify(&self, _pubkey: &PublicKey, _msg: Hash256) -> bool {
         true
     }
+
+    fn combine_shares(shares: &[(u64, &Self)]) -> Result<Self, Error> {
+        shares
+            .first()
+            .map(|(_, signature)| (*signature).clone())
+            .ok_or(Error::InvalidSignatureShares)
+    }
 }
 
 impl PartialEq for Signature {
@@ -194,4 +201,11 @@ impl TSecretKey<Signature, PublicKey> for SecretKey {
         sk.0[..].copy_from_slice(&bytes[0..SECRET_KEY_BYTES_LEN]);
         Ok(sk)
     }
+
+    fn evaluate_polynomial(coefficients: &[&Self], _x: u64) -> Result<Self, Error> {
+        Ok(coefficients
+            .first()
+            .map(|coefficient| (*coefficient).clone())
+            .unwrap_or_else(Self::random))
+    }
 }
@@ -4,11 +4,18 @@ use crate::{
     generic_public_key::{GenericPublicKey, TPublicKey, PUBLIC_KEY_BYTES_LEN},
     generic_secret_key::{TSecretKey, SECRET_KEY_BYTES_LEN},
     generic_signature::{TSignature, SIGNATURE_BYTES_LEN},
+    impls::zeroize_with,
     Error, Hash256, ZeroizeHash,
 };
+use amcl::bls381::{
+    big::{Big, MODBYTES},
+    ecp2::ECP2,
+    rom::CURVE_ORDER,
+};
 pub use milagro_bls as milagro;
 use rand::thread_rng;
 use std::iter::ExactSizeIterator;
+use zeroize::Zeroize;
 
 /// Provides the externally-facing, core BLS types.
 pub mod types {
@@ -104,6 +111,18 @@ impl TSignature<milagro::PublicKey> for milagro::Signature {
     fn verify(&self, pubkey: &milagro::PublicKey, msg: Hash256) -> bool {
         self.verify(msg.as_bytes(), pubkey)
     }
+
+    fn combine_shares(shares: &[(u64, &Self)]) -> Result<Self, Error> {
+        let ids = shares.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+
+        let mut point = ECP2::new();
+        for (id, signature) in shares {
+            let coefficient = lagrange_coefficient_at_zero(*id, &ids);
+            point.add(&signature.point.mul(&coefficient));
+        }
+
+        Ok(milagro::Signature { point })
+    }
 }
 
 impl TAggregateSignature<milagro::PublicKey, milagro::AggregatePublicKey, milagro::Signature>
@@ -180,4 +199,70 @@ impl TSecretKey<milagro::Signature, milagro::PublicKey> for milagro::SecretKey {
     fn deserialize(bytes: &[u8]) -> Result<Self, Error> {
         Self::from_bytes(&bytes).map_err(Into::into)
     }
+
+    fn evaluate_polynomial(coefficients: &[&Self], x: u64) -> Result<Self, Error> {
+        let order = Big::new_ints(&CURVE_ORDER);
+        let x = big_from_u64(x);
+        let mut result = big_from_u64(0);
+
+        // Horner's method.
+        for coefficient in coefficients.iter().rev() {
+            let mut coefficient = big_from_bendian(TSecretKey::serialize(*coefficient).as_bytes());
+            let mut next = Big::modmul(&result, &x, &order);
+            next.add(&coefficient);
+            next.norm();
+            next.rmod(&order);
+
+            zeroize_with(&mut coefficient, Big::new());
+            zeroize_with(&mut result, Big::new());
+            result = next;
+        }
+
+        let mut bytes = [0; MODBYTES];
+        result.to_bytes(&mut bytes);
+        zeroize_with(&mut result, Big::new());
+
+        let secret_key = Self::from_bytes(&bytes[MODBYTES - SECRET_KEY_BYTES_LEN..]);
+        bytes.zeroize();
+        secret_key.map_err(Into::into)
+    }
+}
+
+/// Returns the scalar represented by the big-endian `bytes`, which must be at most `MODBYTES`
+/// long.
+fn big_from_bendian(bytes: &[u8]) -> Big {
+    let mut padded = [0; MODBYTES];
+    padded[MODBYTES - bytes.len()..].copy_from_slice(bytes);
+    let big = Big::from_bytes(&padded);
+    padded.zeroize();
+    big
+}
+
+/// Returns `x` as a scalar.
+fn big_from_u64(x: u64) -> Big {
+    big_from_bendian(&x.to_be_bytes())
+}
+
+/// Returns the Lagrange basis polynomial for `id`, evaluated at zero, over the points in `ids`.
+///
+/// All of `ids` must be non-zero and unique.
+fn lagrange_coefficient_at_zero(id: u64, ids: &[u64]) -> Big {
+    let order = Big::new_ints(&CURVE_ORDER);
+    let x_i = big_from_u64(id);
+    let mut numerator = big_from_u64(1);
+    let mut denominator = big_from_u64(1);
+
+    for &other in ids.iter().filter(|&&other| other != id) {
+        let x_j = big_from_u64(other);
+        let mut difference = Big::modneg(&x_i, &order);
+        difference.add(&x_j);
+        difference.norm();
+        difference.rmod(&order);
+
+        numerator = Big::modmul(&numerator, &x_j, &order);
+        denominator = Big::modmul(&denominator, &difference, &order);
+    }
+
+    denominator.invmodp(&order);
+    Big::modmul(&numerator, &denominator, &order)
 }
@@ -1,3 +1,15 @@
+use std::ptr;
+use std::sync::atomic::{self, Ordering};
+
 pub mod blst;
 pub mod fake_crypto;
 pub mod milagro;
+
+/// Overwrites `secret` with `zero`, for secret values of backend types which do not implement
+/// `Zeroize`. The volatile write prevents the compiler from optimising the overwrite away.
+///
+/// The previous value is not dropped, so `T` must not own any heap allocations.
+pub(crate) fn zeroize_with<T>(secret: &mut T, zero: T) {
+    unsafe { ptr::write_volatile(secret, zero) };
+    atomic::compiler_fence(Ordering::SeqCst);
+}
@@ -60,6 +60,14 @@ pub enum Error {
     InvalidInfinityPublicKey,
     /// The secret key is all zero bytes, which is invalid.
     InvalidZeroSecretKey,
+    /// The threshold for splitting a secret key must be non-zero and no greater than the number of
+    /// shares.
+    InvalidThreshold { threshold: u64, shares: u64 },
+    /// A secret key or signature share had an identifier of zero, or an identifier that was
+    /// already supplied.
+    InvalidShareId(u64),
+    /// No signature shares were supplied, or one of the shares was the empty signature.
+    InvalidSignatureShares,
 }
 
 impl From<AmclError> for Error {
@@ -483,6 +483,98 @@ macro_rules! test_suite {
                 .run_checks()
         }
 
+        struct ThresholdTester {
+            original: SecretKey,
+            shares: Vec<(u64, SecretKey)>,
+            msg: Hash256,
+        }
+
+        impl ThresholdTester {
+            pub fn new(threshold: u64, num_shares: u64) -> Self {
+                let original = SecretKey::random();
+                let shares = original.split(threshold, num_shares).unwrap();
+
+                Self {
+                    original,
+                    shares,
+                    msg: Hash256::from_low_u64_be(42),
+                }
+            }
+
+            pub fn combine(&self, ids: &[u64]) -> Signature {
+                let signatures = ids
+                    .iter()
+                    .map(|id| {
+                        let (_, sk) = self.shares.iter().find(|(i, _)| i == id).unwrap();
+                        (*id, sk.sign(self.msg))
+                    })
+                    .collect::<Vec<_>>();
+                let signatures = signatures
+                    .iter()
+                    .map(|(id, sig)| (*id, sig))
+                    .collect::<Vec<_>>();
+
+                Signature::combine_shares(&signatures).unwrap()
+            }
+
+            pub fn assert_combined_verify(self, ids: &[u64], is_valid: bool) {
+                let signature = self.combine(ids);
+                assert_eq!(
+                    signature.verify(&self.original.public_key(), self.msg),
+                    is_valid
+                );
+                assert_eq!(signature == self.original.sign(self.msg), is_valid);
+            }
+        }
+
+        #[test]
+        fn threshold_1_of_1() {
+            ThresholdTester::new(1, 1).assert_combined_verify(&[1], true)
+        }
+
+        #[test]
+        fn threshold_3_of_5_with_threshold_shares() {
+            ThresholdTester::new(3, 5).assert_combined_verify(&[1, 3, 5], true)
+        }
+
+        #[test]
+        fn threshold_3_of_5_with_all_shares() {
+            ThresholdTester::new(3, 5).assert_combined_verify(&[5, 4, 3, 2, 1], true)
+        }
+
+        #[test]
+        fn threshold_3_of_5_with_too_few_shares() {
+            ThresholdTester::new(3, 5).assert_combined_verify(&[2, 4], false)
+        }
+
+        #[test]
+        fn threshold_share_public_keys_differ() {
+            let tester = ThresholdTester::new(2, 3);
+            let original = tester.original.public_key();
+            for (_, share) in &tester.shares {
+                assert!(share.public_key() != original);
+            }
+        }
+
+        #[test]
+        fn threshold_split_invalid_threshold() {
+            let sk = SecretKey::random();
+            assert!(sk.split(0, 3).is_err());
+            assert!(sk.split(4, 3).is_err());
+        }
+
+        #[test]
+        fn threshold_combine_invalid_shares() {
+            let tester = ThresholdTester::new(2, 3);
+            let sig = tester.shares[0].1.sign(tester.msg);
+            let empty = Signature::empty();
+
+            assert!(Signature::combine_shares(&[]).is_err());
+            assert!(Signature::combine_shares(&[(0, &sig), (2, &sig)]).is_err());
+            assert!(Signature::combine_shares(&[(1, &sig), (1, &sig)]).is_err());
+            assert!(Signature::combine_shares(&[(1, &sig), (2, &empty)]).is_err());
+        }
+
         #[test]
         fn signature_set_3_sets_with_one_invalid_pubkey_infinity_set() {
             SignatureSetTester::default()
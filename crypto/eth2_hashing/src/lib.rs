@@ -4,6 +4,9 @@
 //! 2.0 is using. The hash function has been subject to change during the specification process, so
 //! defining it once in this crate makes it easy to replace.
 
+#[cfg(target_arch = "x86_64")]
+mod sha256_x86;
+
 #[cfg(not(target_arch = "wasm32"))]
 pub use ring::digest::{digest, Context, Digest, SHA256};
 
@@ -58,6 +61,41 @@ pub fn hash32_concat(h1: &[u8], h2: &[u8]) -> [u8; 32] {
     output
 }
 
+/// Compute the hash of each 64-byte chunk of `input` (i.e., each pair of concatenated 32-byte
+/// nodes), writing the 32-byte digests to the corresponding chunks of `output`.
+///
+/// This produces the same result as calling `hash32_concat` on each pair, but hashes many pairs
+/// per call using SHA extensions or AVX2 multi-buffer hashing, when the CPU supports them.
+///
+/// # Panics
+///
+/// Will panic if `input.len()` is not a multiple of 64 or `input.len() != output.len() * 2`.
+pub fn hash32_concat_batch(input: &[u8], output: &mut [u8]) {
+    assert_eq!(input.len() % 64, 0, "input must be a multiple of 64 bytes");
+    assert_eq!(
+        input.len(),
+        output.len() * 2,
+        "input must be twice the output"
+    );
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        if sha256_x86::sha_ni_detected() {
+            // Safe since the CPU supports the required features.
+            unsafe { sha256_x86::hash_pairs_sha_ni(input, output) };
+            return;
+        } else if sha256_x86::avx2_detected() {
+            // Safe since the CPU supports the required features.
+            unsafe { sha256_x86::hash_pairs_avx2(input, output) };
+            return;
+        }
+    }
+
+    for (pair, digest) in input.chunks_exact(64).zip(output.chunks_exact_mut(32)) {
+        digest.copy_from_slice(&hash32_concat(&pair[..32], &pair[32..]));
+    }
+}
+
 /// The max index that can be used with `ZERO_HASHES`.
 #[cfg(feature = "zero_hash_cache")]
 pub const ZERO_HASHES_MAX_INDEX: usize = 48;
@@ -95,6 +133,19 @@ mod tests {
         assert_eq!(expected, output);
     }
 
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_hash32_concat_batch() {
+        let input: Vec<u8> = (0..64 * 19).map(|i| i as u8).collect();
+        let mut output = vec![0; 32 * 19];
+
+        hash32_concat_batch(&input, &mut output);
+
+        for (pair, digest) in input.chunks_exact(64).zip(output.chunks_exact(32)) {
+            assert_eq!(digest, &hash32_concat(&pair[..32], &pair[32..])[..]);
+        }
+    }
+
     #[cfg(feature = "zero_hash_cache")]
     mod zero_hash {
         use super::*;
@@ -0,0 +1,405 @@
+//! SHA-256 implementations for hashing many 64-byte messages at once on `x86_64`.
+//!
+//! Every message is exactly 64 bytes, so the second (padding) block of each message is identical.
+//! The AVX2 implementation exploits this by computing the message schedule of that block once.
+
+use std::arch::x86_64::*;
+
+/// The number of messages hashed in parallel by `hash_pairs_avx2`.
+const AVX2_LANES: usize = 8;
+
+/// The length of each message, in bytes.
+const MESSAGE_LEN: usize = 64;
+
+/// The length of each digest, in bytes.
+const DIGEST_LEN: usize = 32;
+
+const H0: [u32; 8] = [
+    0x6a09_e667,
+    0xbb67_ae85,
+    0x3c6e_f372,
+    0xa54f_f53a,
+    0x510e_527f,
+    0x9b05_688c,
+    0x1f83_d9ab,
+    0x5be0_cd19,
+];
+
+#[rustfmt::skip]
+const K: [u32; 64] = [
+    0x428a_2f98, 0x7137_4491, 0xb5c0_fbcf, 0xe9b5_dba5,
+    0x3956_c25b, 0x59f1_11f1, 0x923f_82a4, 0xab1c_5ed5,
+    0xd807_aa98, 0x1283_5b01, 0x2431_85be, 0x550c_7dc3,
+    0x72be_5d74, 0x80de_b1fe, 0x9bdc_06a7, 0xc19b_f174,
+    0xe49b_69c1, 0xefbe_4786, 0x0fc1_9dc6, 0x240c_a1cc,
+    0x2de9_2c6f, 0x4a74_84aa, 0x5cb0_a9dc, 0x76f9_88da,
+    0x983e_5152, 0xa831_c66d, 0xb003_27c8, 0xbf59_7fc7,
+    0xc6e0_0bf3, 0xd5a7_9147, 0x06ca_6351, 0x1429_2967,
+    0x27b7_0a85, 0x2e1b_2138, 0x4d2c_6dfc, 0x5338_0d13,
+    0x650a_7354, 0x766a_0abb, 0x81c2_c92e, 0x9272_2c85,
+    0xa2bf_e8a1, 0xa81a_664b, 0xc24b_8b70, 0xc76c_51a3,
+    0xd192_e819, 0xd699_0624, 0xf40e_3585, 0x106a_a070,
+    0x19a4_c116, 0x1e37_6c08, 0x2748_774c, 0x34b0_bcb5,
+    0x391c_0cb3, 0x4ed8_aa4a, 0x5b9c_ca4f, 0x682e_6ff3,
+    0x748f_82ee, 0x78a5_636f, 0x84c8_7814, 0x8cc7_0208,
+    0x90be_fffa, 0xa450_6ceb, 0xbef9_a3f7, 0xc671_78f2,
+];
+
+/// Returns the padding block of a 64-byte message: a single `1` bit, followed by zeros and the
+/// big-endian bit-length of the message (512).
+fn padding_block() -> [u8; MESSAGE_LEN] {
+    let mut block = [0; MESSAGE_LEN];
+    block[0] = 0x80;
+    block[MESSAGE_LEN - 2] = 0x02;
+    block
+}
+
+/// Returns `W[t] + K[t]` for each round `t` of the compression of `padding_block()`.
+fn padding_schedule() -> [u32; 64] {
+    let block = padding_block();
+    let mut w = [0u32; 64];
+
+    for (t, word) in block.chunks_exact(4).enumerate() {
+        w[t] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+    }
+    for t in 16..64 {
+        let s0 = w[t - 15].rotate_right(7) ^ w[t - 15].rotate_right(18) ^ (w[t - 15] >> 3);
+        let s1 = w[t - 2].rotate_right(17) ^ w[t - 2].rotate_right(19) ^ (w[t - 2] >> 10);
+        w[t] = w[t - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[t - 7])
+            .wrapping_add(s1);
+    }
+    for (w, k) in w.iter_mut().zip(K.iter()) {
+        *w = w.wrapping_add(*k);
+    }
+
+    w
+}
+
+/// Returns `true` if the CPU supports `hash_pairs_sha_ni`.
+pub fn sha_ni_detected() -> bool {
+    is_x86_feature_detected!("sha")
+        && is_x86_feature_detected!("sse2")
+        && is_x86_feature_detected!("ssse3")
+        && is_x86_feature_detected!("sse4.1")
+}
+
+/// Returns `true` if the CPU supports `hash_pairs_avx2`.
+pub fn avx2_detected() -> bool {
+    is_x86_feature_detected!("avx2")
+}
+
+/// Writes the SHA-256 digest of each 64-byte chunk of `input` to the corresponding 32-byte chunk
+/// of `output`, using the SHA extensions.
+///
+/// # Safety
+///
+/// The CPU must support the features checked by `sha_ni_detected`.
+///
+/// # Panics
+///
+/// If `input.len() != output.len() * 2`.
+#[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+pub unsafe fn hash_pairs_sha_ni(input: &[u8], output: &mut [u8]) {
+    assert_eq!(
+        input.len(),
+        output.len() * 2,
+        "input must be twice the output"
+    );
+
+    let padding = padding_block();
+
+    for (message, digest) in input
+        .chunks_exact(MESSAGE_LEN)
+        .zip(output.chunks_exact_mut(DIGEST_LEN))
+    {
+        let mut state = H0;
+        compress_sha_ni(&mut state, message);
+        compress_sha_ni(&mut state, &padding);
+
+        for (word, bytes) in state.iter().zip(digest.chunks_exact_mut(4)) {
+            bytes.copy_from_slice(&word.to_be_bytes());
+        }
+    }
+}
+
+/// Performs four rounds of the SHA-256 compression function with the message words `w` for
+/// rounds `4 * i..4 * i + 4`.
+macro_rules! rounds4 {
+    ($abef:ident, $cdgh:ident, $w:expr, $i:expr) => {{
+        let k = _mm_loadu_si128(K.as_ptr().add(4 * $i) as *const __m128i);
+        let wk = _mm_add_epi32($w, k);
+        $cdgh = _mm_sha256rnds2_epu32($cdgh, $abef, wk);
+        $abef = _mm_sha256rnds2_epu32($abef, $cdgh, _mm_shuffle_epi32(wk, 0x0E));
+    }};
+}
+
+/// Computes the next four message words from the previous sixteen, stores them in `$w4` and
+/// performs the corresponding four rounds.
+macro_rules! schedule_rounds4 {
+    ($abef:ident, $cdgh:ident, $w0:expr, $w1:expr, $w2:expr, $w3:expr, $w4:ident, $i:expr) => {{
+        let t = _mm_add_epi32(_mm_sha256msg1_epu32($w0, $w1), _mm_alignr_epi8($w3, $w2, 4));
+        $w4 = _mm_sha256msg2_epu32(t, $w3);
+        rounds4!($abef, $cdgh, $w4, $i);
+    }};
+}
+
+/// Applies the SHA-256 compression function to `state` with the 64-byte `block`.
+#[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+unsafe fn compress_sha_ni(state: &mut [u32; 8], block: &[u8]) {
+    debug_assert_eq!(block.len(), MESSAGE_LEN);
+
+    // Converts each little-endian load of four message words to big-endian.
+    let byte_swap = _mm_set_epi64x(
+        0x0c0d_0e0f_0809_0a0b_u64 as i64,
+        0x0405_0607_0001_0203_u64 as i64,
+    );
+
+    let dcba = _mm_loadu_si128(state.as_ptr() as *const __m128i);
+    let hgfe = _mm_loadu_si128(state.as_ptr().add(4) as *const __m128i);
+    let cdab = _mm_shuffle_epi32(dcba, 0xb1);
+    let efgh = _mm_shuffle_epi32(hgfe, 0x1b);
+    let mut abef = _mm_alignr_epi8(cdab, efgh, 8);
+    let mut cdgh = _mm_blend_epi16(efgh, cdab, 0xf0);
+
+    let (abef_save, cdgh_save) = (abef, cdgh);
+
+    let block = block.as_ptr() as *const __m128i;
+    let mut w0 = _mm_shuffle_epi8(_mm_loadu_si128(block), byte_swap);
+    let mut w1 = _mm_shuffle_epi8(_mm_loadu_si128(block.add(1)), byte_swap);
+    let mut w2 = _mm_shuffle_epi8(_mm_loadu_si128(block.add(2)), byte_swap);
+    let mut w3 = _mm_shuffle_epi8(_mm_loadu_si128(block.add(3)), byte_swap);
+    let mut w4;
+
+    rounds4!(abef, cdgh, w0, 0);
+    rounds4!(abef, cdgh, w1, 1);
+    rounds4!(abef, cdgh, w2, 2);
+    rounds4!(abef, cdgh, w3, 3);
+    schedule_rounds4!(abef, cdgh, w0, w1, w2, w3, w4, 4);
+    schedule_rounds4!(abef, cdgh, w1, w2, w3, w4, w0, 5);
+    schedule_rounds4!(abef, cdgh, w2, w3, w4, w0, w1, 6);
+    schedule_rounds4!(abef, cdgh, w3, w4, w0, w1, w2, 7);
+    schedule_rounds4!(abef, cdgh, w4, w0, w1, w2, w3, 8);
+    schedule_rounds4!(abef, cdgh, w0, w1, w2, w3, w4, 9);
+    schedule_rounds4!(abef, cdgh, w1, w2, w3, w4, w0, 10);
+    schedule_rounds4!(abef, cdgh, w2, w3, w4, w0, w1, 11);
+    schedule_rounds4!(abef, cdgh, w3, w4, w0, w1, w2, 12);
+    schedule_rounds4!(abef, cdgh, w4, w0, w1, w2, w3, 13);
+    schedule_rounds4!(abef, cdgh, w0, w1, w2, w3, w4, 14);
+    schedule_rounds4!(abef, cdgh, w1, w2, w3, w4, w0, 15);
+
+    abef = _mm_add_epi32(abef, abef_save);
+    cdgh = _mm_add_epi32(cdgh, cdgh_save);
+
+    let feba = _mm_shuffle_epi32(abef, 0x1b);
+    let dchg = _mm_shuffle_epi32(cdgh, 0xb1);
+    let dcba = _mm_blend_epi16(feba, dchg, 0xf0);
+    let hgef = _mm_alignr_epi8(dchg, feba, 8);
+
+    _mm_storeu_si128(state.as_mut_ptr() as *mut __m128i, dcba);
+    _mm_storeu_si128(state.as_mut_ptr().add(4) as *mut __m128i, hgef);
+}
+
+/// Writes the SHA-256 digest of each 64-byte chunk of `input` to the corresponding 32-byte chunk
+/// of `output`, hashing `AVX2_LANES` messages at a time.
+///
+/// # Safety
+///
+/// The CPU must support the features checked by `avx2_detected`.
+///
+/// # Panics
+///
+/// If `input.len() != output.len() * 2`.
+#[target_feature(enable = "avx2")]
+pub unsafe fn hash_pairs_avx2(input: &[u8], output: &mut [u8]) {
+    assert_eq!(
+        input.len(),
+        output.len() * 2,
+        "input must be twice the output"
+    );
+
+    let padding_schedule = padding_schedule();
+
+    let mut input_chunks = input.chunks_exact(MESSAGE_LEN * AVX2_LANES);
+    let mut output_chunks = output.chunks_exact_mut(DIGEST_LEN * AVX2_LANES);
+
+    for (messages, digests) in (&mut input_chunks).zip(&mut output_chunks) {
+        hash_lanes_avx2(messages, digests, &padding_schedule);
+    }
+
+    // Hash any remaining messages alongside some unused, all-zero messages.
+    let remaining = input_chunks.remainder();
+    if !remaining.is_empty() {
+        let mut messages = [0; MESSAGE_LEN * AVX2_LANES];
+        let mut digests = [0; DIGEST_LEN * AVX2_LANES];
+        messages[..remaining.len()].copy_from_slice(remaining);
+
+        hash_lanes_avx2(&messages, &mut digests, &padding_schedule);
+
+        let remaining_output = output_chunks.into_remainder();
+        let len = remaining_output.len();
+        remaining_output.copy_from_slice(&digests[..len]);
+    }
+}
+
+/// Rotates each 32-bit lane of `$x` right by `$n` bits.
+macro_rules! rotr {
+    ($x:expr, $n:literal) => {{
+        let x = $x;
+        _mm256_or_si256(_mm256_srli_epi32(x, $n), _mm256_slli_epi32(x, 32 - $n))
+    }};
+}
+
+/// Hashes exactly `AVX2_LANES` messages, one in each 32-bit lane.
+#[target_feature(enable = "avx2")]
+unsafe fn hash_lanes_avx2(messages: &[u8], digests: &mut [u8], padding_schedule: &[u32; 64]) {
+    debug_assert_eq!(messages.len(), MESSAGE_LEN * AVX2_LANES);
+    debug_assert_eq!(digests.len(), DIGEST_LEN * AVX2_LANES);
+
+    // Transpose the messages such that `w[t]` holds word `t` of every message.
+    let mut w = [_mm256_setzero_si256(); 64];
+    for (t, w) in w.iter_mut().take(16).enumerate() {
+        let mut words = [0u32; AVX2_LANES];
+        for (lane, word) in words.iter_mut().enumerate() {
+            let i = lane * MESSAGE_LEN + t * 4;
+            *word = u32::from_be_bytes([
+                messages[i],
+                messages[i + 1],
+                messages[i + 2],
+                messages[i + 3],
+            ]);
+        }
+        *w = _mm256_loadu_si256(words.as_ptr() as *const __m256i);
+    }
+    for t in 16..64 {
+        let s0 = _mm256_xor_si256(
+            _mm256_xor_si256(rotr!(w[t - 15], 7), rotr!(w[t - 15], 18)),
+            _mm256_srli_epi32(w[t - 15], 3),
+        );
+        let s1 = _mm256_xor_si256(
+            _mm256_xor_si256(rotr!(w[t - 2], 17), rotr!(w[t - 2], 19)),
+            _mm256_srli_epi32(w[t - 2], 10),
+        );
+        w[t] = _mm256_add_epi32(
+            _mm256_add_epi32(w[t - 16], s0),
+            _mm256_add_epi32(w[t - 7], s1),
+        );
+    }
+
+    let mut state = [_mm256_setzero_si256(); 8];
+    for (s, h) in state.iter_mut().zip(H0.iter()) {
+        *s = _mm256_set1_epi32(*h as i32);
+    }
+
+    let initial = state;
+    for (w, k) in w.iter().zip(K.iter()) {
+        round_avx2(
+            &mut state,
+            _mm256_add_epi32(*w, _mm256_set1_epi32(*k as i32)),
+        );
+    }
+    add_state_avx2(&mut state, &initial);
+
+    let intermediate = state;
+    for wk in padding_schedule.iter() {
+        round_avx2(&mut state, _mm256_set1_epi32(*wk as i32));
+    }
+    add_state_avx2(&mut state, &intermediate);
+
+    // Transpose the state back into one digest per message.
+    for (i, s) in state.iter().enumerate() {
+        let mut words = [0u32; AVX2_LANES];
+        _mm256_storeu_si256(words.as_mut_ptr() as *mut __m256i, *s);
+        for (lane, word) in words.iter().enumerate() {
+            let j = lane * DIGEST_LEN + i * 4;
+            digests[j..j + 4].copy_from_slice(&word.to_be_bytes());
+        }
+    }
+}
+
+/// Performs one round of the SHA-256 compression function, where `wk` is the sum of the message
+/// word and round constant.
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn round_avx2(state: &mut [__m256i; 8], wk: __m256i) {
+    let [a, b, c, d, e, f, g, h] = *state;
+
+    let s1 = _mm256_xor_si256(_mm256_xor_si256(rotr!(e, 6), rotr!(e, 11)), rotr!(e, 25));
+    let ch = _mm256_xor_si256(_mm256_and_si256(e, f), _mm256_andnot_si256(e, g));
+    let t1 = _mm256_add_epi32(_mm256_add_epi32(h, s1), _mm256_add_epi32(ch, wk));
+
+    let s0 = _mm256_xor_si256(_mm256_xor_si256(rotr!(a, 2), rotr!(a, 13)), rotr!(a, 22));
+    let maj = _mm256_or_si256(
+        _mm256_and_si256(a, b),
+        _mm256_and_si256(c, _mm256_or_si256(a, b)),
+    );
+    let t2 = _mm256_add_epi32(s0, maj);
+
+    *state = [
+        _mm256_add_epi32(t1, t2),
+        a,
+        b,
+        c,
+        _mm256_add_epi32(d, t1),
+        e,
+        f,
+        g,
+    ];
+}
+
+/// Adds `other` to `state`, lane-wise.
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn add_state_avx2(state: &mut [__m256i; 8], other: &[__m256i; 8]) {
+    for (s, o) in state.iter_mut().zip(other.iter()) {
+        *s = _mm256_add_epi32(*s, *o);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash32_concat;
+
+    /// Returns `n` distinct messages and their digests, as computed by `hash32_concat`.
+    fn messages_and_digests(n: usize) -> (Vec<u8>, Vec<u8>) {
+        let input: Vec<u8> = (0..n * MESSAGE_LEN)
+            .map(|i| (i as u8).wrapping_mul(31) ^ (i / 251) as u8)
+            .collect();
+        let expected = input
+            .chunks_exact(MESSAGE_LEN)
+            .flat_map(|pair| hash32_concat(&pair[..32], &pair[32..]).to_vec())
+            .collect();
+
+        (input, expected)
+    }
+
+    #[test]
+    fn sha_ni_matches_hash32_concat() {
+        if !sha_ni_detected() {
+            return;
+        }
+
+        for &n in &[0, 1, 2, 7, 8, 9, 31, 64] {
+            let (input, expected) = messages_and_digests(n);
+            let mut output = vec![0; n * DIGEST_LEN];
+            unsafe { hash_pairs_sha_ni(&input, &mut output) };
+            assert_eq!(output, expected, "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn avx2_matches_hash32_concat() {
+        if !avx2_detected() {
+            return;
+        }
+
+        for &n in &[0, 1, 2, 7, 8, 9, 31, 64] {
+            let (input, expected) = messages_and_digests(n);
+            let mut output = vec![0; n * DIGEST_LEN];
+            unsafe { hash_pairs_avx2(&input, &mut output) };
+            assert_eq!(output, expected, "n = {}", n);
+        }
+    }
+}
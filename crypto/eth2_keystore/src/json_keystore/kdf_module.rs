@@ -90,6 +90,17 @@ pub struct Pbkdf2 {
     pub salt: HexBytes,
 }
 
+impl Pbkdf2 {
+    pub fn default_pbkdf2(salt: Vec<u8>) -> Self {
+        Self {
+            c: 262144,
+            dklen: DKLEN,
+            prf: Prf::HmacSha256,
+            salt: salt.into(),
+        }
+    }
+}
+
 /// Used for ensuring that serde only decodes valid KDF functions.
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 #[serde(try_from = "String", into = "String")]
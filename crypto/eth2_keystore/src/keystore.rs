@@ -4,7 +4,7 @@
 use crate::derived_key::DerivedKey;
 use crate::json_keystore::{
     Aes128Ctr, ChecksumModule, Cipher, CipherModule, Crypto, EmptyMap, EmptyString, JsonKeystore,
-    Kdf, KdfModule, Scrypt, Sha256Checksum, Version,
+    Kdf, KdfFunction, KdfModule, Pbkdf2, Scrypt, Sha256Checksum, Version,
 };
 use crate::Uuid;
 use aes_ctr::stream_cipher::generic_array::GenericArray;
@@ -317,6 +317,16 @@ pub fn default_kdf(salt: Vec<u8>) -> Kdf {
     Kdf::Scrypt(Scrypt::default_scrypt(salt))
 }
 
+/// Returns a `Kdf` of the given `function`, with its default parameters and a random salt.
+pub fn random_kdf(function: KdfFunction) -> Kdf {
+    let salt = rand::thread_rng().gen::<[u8; SALT_SIZE]>().to_vec();
+
+    match function {
+        KdfFunction::Scrypt => Kdf::Scrypt(Scrypt::default_scrypt(salt)),
+        KdfFunction::Pbkdf2 => Kdf::Pbkdf2(Pbkdf2::default_pbkdf2(salt)),
+    }
+}
+
 /// Returns `(cipher_text, checksum)` for the given `plain_text` encrypted with `Cipher` using a
 /// key derived from `password` via the `Kdf` (key derivation function).
 ///
@@ -4,8 +4,8 @@
 use bls::Keypair;
 use eth2_keystore::{
     default_kdf,
-    json_keystore::{Kdf, Pbkdf2, Prf, Scrypt},
-    Error, Keystore, KeystoreBuilder, DKLEN,
+    json_keystore::{Kdf, KdfFunction, Pbkdf2, Prf, Scrypt},
+    random_kdf, Error, Keystore, KeystoreBuilder, DKLEN,
 };
 use std::fs::OpenOptions;
 use tempfile::tempdir;
@@ -246,6 +246,34 @@ fn custom_pbkdf2_kdf() {
     assert_eq!(keystore.kdf(), &my_kdf);
 }
 
+#[test]
+fn random_kdfs() {
+    let keypair = Keypair::random();
+
+    for function in &[KdfFunction::Scrypt, KdfFunction::Pbkdf2] {
+        let kdf = random_kdf(function.clone());
+        assert_eq!(kdf.function(), *function);
+
+        let keystore = KeystoreBuilder::new(&keypair, GOOD_PASSWORD, "".into())
+            .unwrap()
+            .kdf(kdf.clone())
+            .build()
+            .unwrap();
+
+        assert_eq!(keystore.kdf(), &kdf);
+        assert_eq!(
+            keystore.decrypt_keypair(GOOD_PASSWORD).unwrap().pk,
+            keypair.pk
+        );
+    }
+
+    assert_ne!(
+        random_kdf(KdfFunction::Scrypt),
+        random_kdf(KdfFunction::Scrypt),
+        "salts should be random"
+    );
+}
+
 #[test]
 fn utf8_control_characters() {
     let keypair = Keypair::random();
@@ -1,9 +1,14 @@
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 use std::iter::Iterator;
+use std::str::FromStr;
 
 pub const PURPOSE: u32 = 12381;
 pub const COIN_TYPE: u32 = 3600;
 
+/// The node in a `ValidatorPathTemplate` which is replaced by the validator index.
+pub const INDEX_NODE: &str = "i";
+
 pub enum KeyType {
     Voting,
     Withdrawal,
@@ -39,3 +44,207 @@ impl fmt::Display for ValidatorPath {
         Ok(())
     }
 }
+
+/// A template for the EIP-2334 path of a validator voting key, where at most one node may be the
+/// validator index (written as `i`, e.g., `m/12381/3600/i/0/0`).
+///
+/// As in EIP-2334, the withdrawal key is the parent of the voting key.
+///
+/// A template without an index node is an explicit path, which always produces the same keys.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidatorPathTemplate(Vec<Option<u32>>);
+
+impl ValidatorPathTemplate {
+    /// Returns the template for the voting keys described in EIP-2334, `m/12381/3600/i/0/0`.
+    pub fn eip2334() -> Self {
+        Self(vec![Some(PURPOSE), Some(COIN_TYPE), None, Some(0), Some(0)])
+    }
+
+    /// Returns `true` if the path produced by `self` depends upon the validator index.
+    pub fn has_index(&self) -> bool {
+        self.0.iter().any(Option::is_none)
+    }
+
+    /// Returns the path of the `key_type` for the validator at `index`.
+    pub fn path(&self, index: u32, key_type: KeyType) -> ValidatorPath {
+        let mut nodes = self
+            .0
+            .iter()
+            .map(|node| node.unwrap_or(index))
+            .collect::<Vec<_>>();
+
+        match key_type {
+            KeyType::Voting => {}
+            KeyType::Withdrawal => {
+                nodes.pop();
+            }
+        }
+
+        ValidatorPath(nodes)
+    }
+
+    /// Returns the number of leading nodes which are the same in the voting key path of every
+    /// index, so that their key need only be derived once when searching many indices.
+    ///
+    /// Always less than the length of the path, so that every voting key is a descendant of the
+    /// shared nodes.
+    pub fn shared_prefix_len(&self) -> usize {
+        self.0
+            .iter()
+            .take_while(|node| node.is_some())
+            .count()
+            .min(self.0.len() - 1)
+    }
+}
+
+impl Default for ValidatorPathTemplate {
+    fn default() -> Self {
+        Self::eip2334()
+    }
+}
+
+impl FromStr for ValidatorPathTemplate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('/');
+
+        if parts.next() != Some("m") {
+            return Err(format!("Path {:?} must start with \"m\"", s));
+        }
+
+        let nodes = parts
+            .map(|part| {
+                if part == INDEX_NODE {
+                    Ok(None)
+                } else {
+                    part.parse::<u32>()
+                        .map(Some)
+                        .map_err(|e| format!("Invalid node {:?} in path {:?}: {}", part, s, e))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // The withdrawal key is the parent of the voting key, so at least two nodes are required
+        // to avoid using the master key.
+        if nodes.len() < 2 {
+            return Err(format!("Path {:?} must have at least two nodes", s));
+        }
+
+        if nodes.iter().filter(|node| node.is_none()).count() > 1 {
+            return Err(format!(
+                "Path {:?} must have at most one {:?} node",
+                s, INDEX_NODE
+            ));
+        }
+
+        Ok(Self(nodes))
+    }
+}
+
+impl fmt::Display for ValidatorPathTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m")?;
+
+        for node in &self.0 {
+            match node {
+                Some(node) => write!(f, "/{}", node)?,
+                None => write!(f, "/{}", INDEX_NODE)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Serialize for ValidatorPathTemplate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for ValidatorPathTemplate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn eip2334_template_matches_validator_path() {
+        let template = ValidatorPathTemplate::eip2334();
+
+        for index in &[0, 1, 42, u32::max_value()] {
+            assert_eq!(
+                template.path(*index, KeyType::Voting).to_string(),
+                ValidatorPath::new(*index, KeyType::Voting).to_string()
+            );
+            assert_eq!(
+                template.path(*index, KeyType::Withdrawal).to_string(),
+                ValidatorPath::new(*index, KeyType::Withdrawal).to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn shared_prefix_len() {
+        for (s, len) in &[
+            ("m/12381/3600/i/0/0", 2),
+            ("m/12381/3600/0/i", 3),
+            ("m/i/0", 0),
+            ("m/12381/60/1/0", 3),
+        ] {
+            let template: ValidatorPathTemplate = s.parse().unwrap();
+            assert_eq!(template.shared_prefix_len(), *len, "{}", s);
+        }
+    }
+
+    #[test]
+    fn template_string_round_trip() {
+        for s in &["m/12381/3600/i/0/0", "m/12381/3600/0/i", "m/12381/60/1/0"] {
+            let template: ValidatorPathTemplate = s.parse().unwrap();
+            assert_eq!(&template.to_string(), s);
+        }
+
+        assert_eq!(
+            "m/12381/3600/i/0/0".parse::<ValidatorPathTemplate>(),
+            Ok(ValidatorPathTemplate::eip2334())
+        );
+    }
+
+    #[test]
+    fn explicit_path() {
+        let template: ValidatorPathTemplate = "m/12381/60/7/0".parse().unwrap();
+
+        assert!(!template.has_index());
+        assert_eq!(
+            template.path(42, KeyType::Voting).to_string(),
+            "m/12381/60/7/0"
+        );
+        assert_eq!(
+            template.path(42, KeyType::Withdrawal).to_string(),
+            "m/12381/60/7"
+        );
+    }
+
+    #[test]
+    fn invalid_templates() {
+        for s in &[
+            "",
+            "12381/3600/i/0/0",
+            "m",
+            "m/i",
+            "m/12381/i/i",
+            "m/12381/3600/x/0",
+            "m/12381/3600'/i/0",
+            "m/12381/-1/i",
+        ] {
+            assert!(s.parse::<ValidatorPathTemplate>().is_err(), "{}", s);
+        }
+    }
+}
@@ -3,7 +3,7 @@ use crate::{
         Aes128Ctr, ChecksumModule, Cipher, CipherModule, Crypto, EmptyMap, EmptyString, JsonWallet,
         Kdf, KdfModule, Sha256Checksum, TypeField, Version,
     },
-    KeyType, ValidatorPath,
+    KeyType, ValidatorPath, ValidatorPathTemplate,
 };
 use eth2_keystore::{
     decrypt, default_kdf, encrypt, keypair_from_secret, Keystore, KeystoreBuilder, IV_SIZE,
@@ -41,6 +41,37 @@ pub struct ValidatorKeystores {
     pub withdrawal: Keystore,
 }
 
+impl ValidatorKeystores {
+    /// Derives the keystores for the validator at `index` of `template` from the BIP-39 `seed`,
+    /// encrypting each with the given password.
+    ///
+    /// This function should only be used for key recovery since it can easily lead to key
+    /// duplication.
+    pub fn recover_from_seed(
+        seed: &[u8],
+        template: &ValidatorPathTemplate,
+        index: u32,
+        voting_keystore_password: &[u8],
+        withdrawal_keystore_password: &[u8],
+    ) -> Result<Self, Error> {
+        let derive = |key_type: KeyType, password: &[u8]| -> Result<Keystore, Error> {
+            let path = template.path(index, key_type);
+            let secret = recover_validator_secret_from_path(seed, &path)?;
+
+            let keypair = keypair_from_secret(secret.as_bytes())?;
+
+            KeystoreBuilder::new(&keypair, password, format!("{}", path))?
+                .build()
+                .map_err(Into::into)
+        };
+
+        Ok(Self {
+            voting: derive(KeyType::Voting, voting_keystore_password)?,
+            withdrawal: derive(KeyType::Withdrawal, withdrawal_keystore_password)?,
+        })
+    }
+}
+
 /// Constructs a `Keystore`.
 ///
 /// Generates the KDF `salt` and AES `IV` using `rand::thread_rng()`.
@@ -66,7 +97,22 @@ impl<'a> WalletBuilder<'a> {
         password: &'a [u8],
         name: String,
     ) -> Result<Self, Error> {
-        let seed = Bip39Seed::new(mnemonic, "");
+        Self::from_mnemonic_with_passphrase(mnemonic, "", password, name)
+    }
+
+    /// Creates a new builder for a seed specified as a BIP-39 `Mnemonic`, protected by the BIP-39
+    /// `passphrase` (sometimes referred to as the "25th word").
+    ///
+    /// ## Errors
+    ///
+    /// Returns `Error::EmptyPassword` if `password == ""`.
+    pub fn from_mnemonic_with_passphrase(
+        mnemonic: &Mnemonic,
+        passphrase: &str,
+        password: &'a [u8],
+        name: String,
+    ) -> Result<Self, Error> {
+        let seed = Bip39Seed::new(mnemonic, passphrase);
 
         Self::from_seed_bytes(seed.as_bytes(), password, name)
     }
@@ -311,9 +357,21 @@ pub fn recover_validator_secret_from_mnemonic(
     key_type: KeyType,
 ) -> Result<(PlainText, ValidatorPath), Error> {
     let path = ValidatorPath::new(index, key_type);
-    let master = DerivedKey::from_seed(secret).map_err(|()| Error::EmptyPassword)?;
+    let secret = recover_validator_secret_from_path(secret, &path)?;
+
+    Ok((secret, path))
+}
+
+/// Returns the secret at `path`, derived from the BIP-39 `seed`.
+///
+/// This function should only be used for key recovery since it can easily lead to key duplication.
+pub fn recover_validator_secret_from_path(
+    seed: &[u8],
+    path: &ValidatorPath,
+) -> Result<PlainText, Error> {
+    let master = DerivedKey::from_seed(seed).map_err(|()| Error::EmptyPassword)?;
 
     let destination = path.iter_nodes().fold(master, |dk, i| dk.child(*i));
 
-    Ok((destination.secret().to_vec().into(), path))
+    Ok(destination.secret().to_vec().into())
 }
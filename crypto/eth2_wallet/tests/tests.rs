@@ -98,6 +98,43 @@ fn mnemonic_equality() {
     );
 }
 
+#[test]
+fn mnemonic_with_passphrase() {
+    let m = Mnemonic::from_phrase(MNEMONIC, Language::English).unwrap();
+    let passphrase = "TREZOR";
+
+    let with_passphrase =
+        WalletBuilder::from_mnemonic_with_passphrase(&m, passphrase, WALLET_PASSWORD, NAME.into())
+            .expect("should init builder")
+            .build()
+            .expect("should build wallet");
+
+    let without_passphrase = WalletBuilder::from_mnemonic(&m, WALLET_PASSWORD, NAME.into())
+        .expect("should init builder")
+        .build()
+        .expect("should build wallet");
+
+    assert_eq!(
+        with_passphrase
+            .decrypt_seed(WALLET_PASSWORD)
+            .unwrap()
+            .as_bytes(),
+        Seed::new(&m, passphrase).as_bytes(),
+        "wallet should use the passphrase-protected seed"
+    );
+    assert_ne!(
+        with_passphrase
+            .decrypt_seed(WALLET_PASSWORD)
+            .unwrap()
+            .as_bytes(),
+        without_passphrase
+            .decrypt_seed(WALLET_PASSWORD)
+            .unwrap()
+            .as_bytes(),
+        "passphrase should change the seed"
+    );
+}
+
 #[test]
 fn metadata() {
     let wallet = wallet_from_seed();
@@ -2,7 +2,7 @@ use clap::ArgMatches;
 use clap_utils::{parse_required, parse_ssz_required};
 use deposit_contract::{decode_eth1_tx_data, DEPOSIT_DATA_LEN};
 use tree_hash::TreeHash;
-use types::EthSpec;
+use types::{EthSpec, WithdrawalCredentials};
 
 pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
     let rlp_bytes = parse_ssz_required::<Vec<u8>>(matches, "deposit-data")?;
@@ -27,5 +27,17 @@ pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
         ));
     }
 
+    let spec = T::default_spec();
+    let credentials_type = WithdrawalCredentials::from(deposit_data.withdrawal_credentials)
+        .credentials_type(&spec)
+        .map_err(|e| {
+            format!(
+                "Withdrawal credentials {:?} are invalid: {:?}",
+                deposit_data.withdrawal_credentials, e
+            )
+        })?;
+
+    println!("Withdrawal credentials: {}", credentials_type);
+
     Ok(())
 }
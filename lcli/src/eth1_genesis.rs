@@ -1,7 +1,7 @@
 use clap::ArgMatches;
 use environment::Environment;
 use eth2_testnet_config::Eth2TestnetConfig;
-use genesis::{Eth1Config, Eth1GenesisService};
+use genesis::{Eth1Config, Eth1Endpoint, Eth1GenesisService};
 use ssz::Encode;
 use std::cmp::max;
 use std::path::PathBuf;
@@ -15,11 +15,11 @@ pub const ETH1_GENESIS_UPDATE_INTERVAL: Duration = Duration::from_millis(7_000);
 pub fn run<T: EthSpec>(mut env: Environment<T>, matches: &ArgMatches<'_>) -> Result<(), String> {
     let endpoints = matches
         .value_of("eth1-endpoint")
-        .map(|e| vec![String::from(e)])
+        .map(|e| vec![Eth1Endpoint::from(e)])
         .or_else(|| {
             matches
                 .value_of("eth1-endpoints")
-                .map(|s| s.split(',').map(String::from).collect())
+                .map(|s| s.split(',').map(Eth1Endpoint::from).collect())
         });
 
     let testnet_dir = matches
@@ -10,9 +10,11 @@ mod bls_sign_msg;
 mod bls_verify_msg;
 mod common;
 mod epoch_processing;
+mod fork_choice;
 mod genesis_initialization;
 mod genesis_validity;
 mod operations;
+mod rewards;
 mod sanity_blocks;
 mod sanity_slots;
 mod shuffling;
@@ -26,9 +28,11 @@ pub use bls_sign_msg::*;
 pub use bls_verify_msg::*;
 pub use common::SszStaticType;
 pub use epoch_processing::*;
+pub use fork_choice::*;
 pub use genesis_initialization::*;
 pub use genesis_validity::*;
 pub use operations::*;
+pub use rewards::*;
 pub use sanity_blocks::*;
 pub use sanity_slots::*;
 pub use shuffling::*;
@@ -6,9 +6,10 @@ use crate::type_name;
 use crate::type_name::TypeName;
 use serde_derive::Deserialize;
 use state_processing::per_epoch_processing::{
-    errors::EpochProcessingError, process_final_updates, process_justification_and_finalization,
+    errors::EpochProcessingError, process_epoch_single_pass, process_final_updates,
+    process_justification_and_finalization, process_non_validator_updates,
     process_registry_updates, process_rewards_and_penalties, process_slashings,
-    validator_statuses::ValidatorStatuses,
+    validator_statuses::ValidatorStatuses, SinglePassConfig,
 };
 use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
@@ -33,6 +34,26 @@ pub struct EpochProcessing<E: EthSpec, T: EpochTransition<E>> {
 
 pub trait EpochTransition<E: EthSpec>: TypeName + Debug + Sync {
     fn run(state: &mut BeaconState<E>, spec: &ChainSpec) -> Result<(), EpochProcessingError>;
+
+    /// Run the transition using `process_epoch_single_pass`, for those transitions which it
+    /// implements.
+    fn run_single_pass(
+        state: &mut BeaconState<E>,
+        spec: &ChainSpec,
+    ) -> Result<(), EpochProcessingError> {
+        Self::run(state, spec)
+    }
+}
+
+/// Run `process_epoch_single_pass` with only the transitions selected by `config`.
+fn run_single_pass_with_config<E: EthSpec>(
+    state: &mut BeaconState<E>,
+    config: SinglePassConfig,
+    spec: &ChainSpec,
+) -> Result<(), EpochProcessingError> {
+    let mut validator_statuses = ValidatorStatuses::new(state, spec)?;
+    validator_statuses.process_attestations(state, spec)?;
+    process_epoch_single_pass(state, &validator_statuses, config, spec)
 }
 
 #[derive(Debug)]
@@ -69,12 +90,34 @@ impl<E: EthSpec> EpochTransition<E> for RewardsAndPenalties {
         validator_statuses.process_attestations(state, spec)?;
         process_rewards_and_penalties(state, &mut validator_statuses, spec)
     }
+
+    fn run_single_pass(
+        state: &mut BeaconState<E>,
+        spec: &ChainSpec,
+    ) -> Result<(), EpochProcessingError> {
+        let config = SinglePassConfig {
+            rewards_and_penalties: true,
+            ..SinglePassConfig::disable_all()
+        };
+        run_single_pass_with_config(state, config, spec)
+    }
 }
 
 impl<E: EthSpec> EpochTransition<E> for RegistryUpdates {
     fn run(state: &mut BeaconState<E>, spec: &ChainSpec) -> Result<(), EpochProcessingError> {
         process_registry_updates(state, spec)
     }
+
+    fn run_single_pass(
+        state: &mut BeaconState<E>,
+        spec: &ChainSpec,
+    ) -> Result<(), EpochProcessingError> {
+        let config = SinglePassConfig {
+            registry_updates: true,
+            ..SinglePassConfig::disable_all()
+        };
+        run_single_pass_with_config(state, config, spec)
+    }
 }
 
 impl<E: EthSpec> EpochTransition<E> for Slashings {
@@ -88,12 +131,35 @@ impl<E: EthSpec> EpochTransition<E> for Slashings {
         )?;
         Ok(())
     }
+
+    fn run_single_pass(
+        state: &mut BeaconState<E>,
+        spec: &ChainSpec,
+    ) -> Result<(), EpochProcessingError> {
+        let config = SinglePassConfig {
+            slashings: true,
+            ..SinglePassConfig::disable_all()
+        };
+        run_single_pass_with_config(state, config, spec)
+    }
 }
 
 impl<E: EthSpec> EpochTransition<E> for FinalUpdates {
     fn run(state: &mut BeaconState<E>, spec: &ChainSpec) -> Result<(), EpochProcessingError> {
         process_final_updates(state, spec)
     }
+
+    fn run_single_pass(
+        state: &mut BeaconState<E>,
+        spec: &ChainSpec,
+    ) -> Result<(), EpochProcessingError> {
+        let config = SinglePassConfig {
+            effective_balance_updates: true,
+            ..SinglePassConfig::disable_all()
+        };
+        run_single_pass_with_config(state, config, spec)?;
+        process_non_validator_updates(state)
+    }
 }
 
 impl<E: EthSpec, T: EpochTransition<E>> LoadCase for EpochProcessing<E, T> {
@@ -131,18 +197,26 @@ impl<E: EthSpec, T: EpochTransition<E>> Case for EpochProcessing<E, T> {
     }
 
     fn result(&self, _case_index: usize) -> Result<(), Error> {
-        let mut state = self.pre.clone();
-        let mut expected = self.post.clone();
-
         let spec = &E::default_spec();
 
-        let mut result = (|| {
-            // Processing requires the committee caches.
-            state.build_all_committee_caches(spec)?;
+        // Check both the standalone transition and its single-pass equivalent.
+        let transitions: [fn(&mut BeaconState<E>, &ChainSpec) -> _; 2] =
+            [T::run, T::run_single_pass];
+
+        for transition in transitions.iter() {
+            let mut state = self.pre.clone();
+            let mut expected = self.post.clone();
+
+            let mut result = (|| {
+                // Processing requires the committee caches.
+                state.build_all_committee_caches(spec)?;
 
-            T::run(&mut state, spec).map(|_| state)
-        })();
+                transition(&mut state, spec).map(|_| state)
+            })();
 
-        compare_beacon_state_results_without_caches(&mut result, &mut expected)
+            compare_beacon_state_results_without_caches(&mut result, &mut expected)?;
+        }
+
+        Ok(())
     }
 }
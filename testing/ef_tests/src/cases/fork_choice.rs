@@ -0,0 +1,504 @@
+use super::*;
+use crate::bls_setting::BlsSetting;
+use crate::decode::{ssz_decode_file, yaml_decode_file};
+use ::fork_choice::{ForkChoice, ForkChoiceStore};
+use serde_derive::Deserialize;
+use state_processing::{
+    common::get_indexed_attestation, per_block_processing,
+    per_block_processing::is_valid_indexed_attestation, per_slot_processing,
+    BlockSignatureStrategy, SlotProcessingError, VerifySignatures,
+};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::time::Duration;
+use types::{
+    Attestation, BeaconBlock, BeaconState, ChainSpec, Checkpoint, EthSpec, Hash256, RelativeEpoch,
+    SignedBeaconBlock, Slot,
+};
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Metadata {
+    pub description: Option<String>,
+    pub bls_setting: Option<BlsSetting>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Head {
+    slot: Slot,
+    root: Hash256,
+}
+
+/// The expected values of the fork choice store at some step of the test.
+///
+/// Only the values which are present are checked.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Checks {
+    head: Option<Head>,
+    time: Option<u64>,
+    genesis_time: Option<u64>,
+    justified_checkpoint_root: Option<Hash256>,
+    finalized_checkpoint_root: Option<Hash256>,
+    best_justified_checkpoint: Option<Hash256>,
+}
+
+/// A single step of a fork choice test.
+///
+/// `B` and `A` are the block and attestation, which are first loaded as the names of the files
+/// which contain them.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged, deny_unknown_fields)]
+pub enum Step<B, A> {
+    Tick {
+        tick: u64,
+    },
+    Block {
+        block: B,
+        #[serde(default = "default_valid")]
+        valid: bool,
+    },
+    Attestation {
+        attestation: A,
+        #[serde(default = "default_valid")]
+        valid: bool,
+    },
+    Checks {
+        checks: Box<Checks>,
+    },
+}
+
+fn default_valid() -> bool {
+    true
+}
+
+#[derive(Debug)]
+pub struct ForkChoiceTest<E: EthSpec> {
+    pub metadata: Metadata,
+    pub anchor_state: BeaconState<E>,
+    pub anchor_block: BeaconBlock<E>,
+    pub steps: Vec<Step<SignedBeaconBlock<E>, Attestation<E>>>,
+}
+
+impl<E: EthSpec> LoadCase for ForkChoiceTest<E> {
+    fn load_from_dir(path: &Path) -> Result<Self, Error> {
+        let metadata_path = path.join("meta.yaml");
+        let metadata: Metadata = if metadata_path.is_file() {
+            yaml_decode_file(&metadata_path)?
+        } else {
+            Metadata::default()
+        };
+        let anchor_state = ssz_decode_file(&path.join("anchor_state.ssz"))?;
+        let anchor_block = ssz_decode_file(&path.join("anchor_block.ssz"))?;
+        let steps: Vec<Step<String, String>> = yaml_decode_file(&path.join("steps.yaml"))?;
+        let steps = steps
+            .into_iter()
+            .map(|step| {
+                Ok(match step {
+                    Step::Tick { tick } => Step::Tick { tick },
+                    Step::Block { block, valid } => Step::Block {
+                        block: ssz_decode_file(&path.join(format!("{}.ssz", block)))?,
+                        valid,
+                    },
+                    Step::Attestation { attestation, valid } => Step::Attestation {
+                        attestation: ssz_decode_file(&path.join(format!("{}.ssz", attestation)))?,
+                        valid,
+                    },
+                    Step::Checks { checks } => Step::Checks { checks },
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Self {
+            metadata,
+            anchor_state,
+            anchor_block,
+            steps,
+        })
+    }
+}
+
+impl<E: EthSpec> Case for ForkChoiceTest<E> {
+    fn description(&self) -> String {
+        self.metadata
+            .description
+            .clone()
+            .unwrap_or_else(String::new)
+    }
+
+    fn result(&self, _case_index: usize) -> Result<(), Error> {
+        self.metadata.bls_setting.unwrap_or_default().check()?;
+
+        // The v1.0.x test vectors predate the proposer boost, so it must be disabled for their
+        // expected heads to hold.
+        let mut spec = E::default_spec();
+        spec.proposer_score_boost = None;
+        let mut tester = Tester::new(&self.anchor_state, &self.anchor_block, &spec)?;
+
+        for step in &self.steps {
+            match step {
+                Step::Tick { tick } => tester.set_tick(*tick)?,
+                Step::Block { block, valid } => {
+                    check_validity("block", tester.process_block(block), *valid)?
+                }
+                Step::Attestation { attestation, valid } => check_validity(
+                    "attestation",
+                    tester.process_attestation(attestation),
+                    *valid,
+                )?,
+                Step::Checks { checks } => tester.check(checks)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Drives `ForkChoice` through the steps of a single test.
+struct Tester<'a, E: EthSpec> {
+    fork_choice: ForkChoice<TestStore<E>, E>,
+    genesis_time: u64,
+    time: u64,
+    current_slot: Slot,
+    spec: &'a ChainSpec,
+}
+
+impl<'a, E: EthSpec> Tester<'a, E> {
+    fn new(
+        anchor_state: &BeaconState<E>,
+        anchor_block: &BeaconBlock<E>,
+        spec: &'a ChainSpec,
+    ) -> Result<Self, Error> {
+        let anchor_root = anchor_block.canonical_root();
+        let fc_store = TestStore::new(anchor_root, anchor_state.clone(), spec.clone());
+        let fork_choice =
+            ForkChoice::from_genesis(fc_store, anchor_root, anchor_block, anchor_state)
+                .map_err(|e| internal_error("unable to initialize fork choice", e))?;
+
+        Ok(Self {
+            fork_choice,
+            genesis_time: anchor_state.genesis_time,
+            time: anchor_state.genesis_time
+                + anchor_state.slot.as_u64() * (spec.milliseconds_per_slot / 1_000),
+            current_slot: anchor_state.slot,
+            spec,
+        })
+    }
+
+    /// Sets the store time, in seconds since the UNIX epoch.
+    ///
+    /// `ForkChoice` only tracks time with slot granularity.
+    fn set_tick(&mut self, tick: u64) -> Result<(), Error> {
+        self.time = tick;
+        self.current_slot = self.slot_at_time(tick);
+        self.fork_choice
+            .update_time(self.current_slot)
+            .map_err(|e| internal_error("unable to update time", e))?;
+        Ok(())
+    }
+
+    fn slot_at_time(&self, time: u64) -> Slot {
+        let seconds_per_slot = self.spec.milliseconds_per_slot / 1_000;
+        Slot::new(time.saturating_sub(self.genesis_time) / seconds_per_slot)
+    }
+
+    /// Applies `block` to the state of its parent and then adds it to fork choice.
+    fn process_block(&mut self, signed_block: &SignedBeaconBlock<E>) -> Result<(), String> {
+        let block = &signed_block.message;
+        let block_root = block.canonical_root();
+
+        let mut state = self
+            .fork_choice
+            .fc_store()
+            .get_state(&block.parent_root)
+            .ok_or_else(|| format!("unknown parent {:?}", block.parent_root))?
+            .clone();
+
+        while state.slot < block.slot {
+            per_slot_processing(&mut state, None, self.spec).map_err(|e| format!("{:?}", e))?;
+        }
+        state
+            .build_committee_cache(RelativeEpoch::Previous, self.spec)
+            .and_then(|()| state.build_committee_cache(RelativeEpoch::Current, self.spec))
+            .map_err(|e| format!("{:?}", e))?;
+
+        per_block_processing(
+            &mut state,
+            signed_block,
+            Some(block_root),
+            BlockSignatureStrategy::VerifyIndividual,
+            self.spec,
+        )
+        .map_err(|e| format!("{:?}", e))?;
+
+        if block.state_root != state.canonical_root() {
+            return Err("state root mismatch".into());
+        }
+
+        // The block is considered to arrive at the current store time.
+        let slot_start =
+            self.genesis_time + block.slot.as_u64() * (self.spec.milliseconds_per_slot / 1_000);
+        let block_delay = Duration::from_secs(self.time.saturating_sub(slot_start));
+
+        self.fork_choice
+            .on_block(
+                self.current_slot,
+                block,
+                block_root,
+                block_delay,
+                &state,
+                self.spec,
+            )
+            .map_err(|e| format!("{:?}", e))
+    }
+
+    /// Verifies `attestation` against the state of its target checkpoint and then adds it to
+    /// fork choice.
+    fn process_attestation(&mut self, attestation: &Attestation<E>) -> Result<(), String> {
+        let target = attestation.data.target;
+
+        let mut state = self
+            .fork_choice
+            .fc_store()
+            .checkpoint_state(&target)
+            .map_err(|e| format!("{:?}", e))?;
+        state
+            .build_committee_cache(RelativeEpoch::Current, self.spec)
+            .map_err(|e| format!("{:?}", e))?;
+
+        let committee = state
+            .get_beacon_committee(attestation.data.slot, attestation.data.index)
+            .map_err(|e| format!("{:?}", e))?;
+        let indexed_attestation = get_indexed_attestation(committee.committee, attestation)
+            .map_err(|e| format!("{:?}", e))?;
+        is_valid_indexed_attestation(
+            &state,
+            &indexed_attestation,
+            VerifySignatures::True,
+            self.spec,
+        )
+        .map_err(|e| format!("{:?}", e))?;
+
+        self.fork_choice
+            .on_attestation(self.current_slot, &indexed_attestation)
+            .map_err(|e| format!("{:?}", e))
+    }
+
+    fn check(&mut self, checks: &Checks) -> Result<(), Error> {
+        if let Some(expected) = checks.head {
+            let root = self
+                .fork_choice
+                .get_head(self.current_slot, self.spec)
+                .map_err(|e| internal_error("unable to find head", e))?;
+            let slot = self
+                .fork_choice
+                .get_block(&root)
+                .ok_or_else(|| Error::InternalError(format!("unknown head {:?}", root)))?
+                .slot;
+            check_equal("head", Head { slot, root }, expected)?;
+        }
+
+        if let Some(time) = checks.time {
+            check_equal(
+                "time",
+                self.fork_choice.fc_store().get_current_slot(),
+                self.slot_at_time(time),
+            )?;
+        }
+
+        if let Some(genesis_time) = checks.genesis_time {
+            check_equal("genesis_time", self.genesis_time, genesis_time)?;
+        }
+
+        let fc_store = self.fork_choice.fc_store();
+        if let Some(root) = checks.justified_checkpoint_root {
+            check_equal(
+                "justified_checkpoint_root",
+                fc_store.justified_checkpoint().root,
+                root,
+            )?;
+        }
+        if let Some(root) = checks.finalized_checkpoint_root {
+            check_equal(
+                "finalized_checkpoint_root",
+                fc_store.finalized_checkpoint().root,
+                root,
+            )?;
+        }
+        if let Some(root) = checks.best_justified_checkpoint {
+            check_equal(
+                "best_justified_checkpoint",
+                fc_store.best_justified_checkpoint().root,
+                root,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn check_validity(name: &str, result: Result<(), String>, valid: bool) -> Result<(), Error> {
+    match (result, valid) {
+        (Ok(()), true) | (Err(_), false) => Ok(()),
+        (Ok(()), false) => Err(Error::DidntFail(format!("{} should be invalid", name))),
+        (Err(e), true) => Err(Error::NotEqual(format!("{} should be valid: {}", name, e))),
+    }
+}
+
+fn check_equal<T: Debug + PartialEq>(name: &str, result: T, expected: T) -> Result<(), Error> {
+    if result == expected {
+        Ok(())
+    } else {
+        Err(Error::NotEqual(format!(
+            "{}: got {:?} | expected {:?}",
+            name, result, expected
+        )))
+    }
+}
+
+fn internal_error(msg: &str, e: impl Debug) -> Error {
+    Error::InternalError(format!("{}: {:?}", msg, e))
+}
+
+#[derive(Debug)]
+pub enum TestStoreError {
+    MissingState(Hash256),
+    SlotProcessing(SlotProcessingError),
+}
+
+/// An in-memory `ForkChoiceStore` which retains the post-state of every block.
+#[derive(Debug)]
+struct TestStore<E: EthSpec> {
+    states: HashMap<Hash256, BeaconState<E>>,
+    time: Slot,
+    justified_checkpoint: Checkpoint,
+    justified_balances: Vec<u64>,
+    best_justified_checkpoint: Checkpoint,
+    finalized_checkpoint: Checkpoint,
+    proposer_boost_root: Hash256,
+    spec: ChainSpec,
+}
+
+impl<E: EthSpec> TestStore<E> {
+    /// Returns the store described by `get_forkchoice_store` in the specification.
+    fn new(anchor_root: Hash256, anchor_state: BeaconState<E>, spec: ChainSpec) -> Self {
+        let checkpoint = Checkpoint {
+            epoch: anchor_state.current_epoch(),
+            root: anchor_root,
+        };
+        let justified_balances = get_effective_balances(&anchor_state);
+        let time = anchor_state.slot;
+
+        let mut states = HashMap::new();
+        states.insert(anchor_root, anchor_state);
+
+        Self {
+            states,
+            time,
+            justified_checkpoint: checkpoint,
+            justified_balances,
+            best_justified_checkpoint: checkpoint,
+            finalized_checkpoint: checkpoint,
+            proposer_boost_root: Hash256::zero(),
+            spec,
+        }
+    }
+
+    /// Returns the post-state of the block with `block_root`.
+    fn get_state(&self, block_root: &Hash256) -> Option<&BeaconState<E>> {
+        self.states.get(block_root)
+    }
+
+    /// Returns the state of `checkpoint.root` advanced to the first slot of `checkpoint.epoch`, as
+    /// per `store.checkpoint_states` in the specification.
+    fn checkpoint_state(&self, checkpoint: &Checkpoint) -> Result<BeaconState<E>, TestStoreError> {
+        let mut state = self
+            .get_state(&checkpoint.root)
+            .ok_or(TestStoreError::MissingState(checkpoint.root))?
+            .clone();
+
+        let epoch_start_slot = checkpoint.epoch.start_slot(E::slots_per_epoch());
+        while state.slot < epoch_start_slot {
+            per_slot_processing(&mut state, None, &self.spec)
+                .map_err(TestStoreError::SlotProcessing)?;
+        }
+
+        Ok(state)
+    }
+}
+
+/// Returns the effective balances of the active validators, as used by `get_latest_attesting_balance`.
+fn get_effective_balances<E: EthSpec>(state: &BeaconState<E>) -> Vec<u64> {
+    state
+        .validators
+        .iter()
+        .map(|validator| {
+            if validator.is_active_at(state.current_epoch()) {
+                validator.effective_balance
+            } else {
+                0
+            }
+        })
+        .collect()
+}
+
+impl<E: EthSpec> ForkChoiceStore<E> for TestStore<E> {
+    type Error = TestStoreError;
+
+    fn get_current_slot(&self) -> Slot {
+        self.time
+    }
+
+    fn set_current_slot(&mut self, slot: Slot) {
+        self.time = slot
+    }
+
+    fn on_verified_block(
+        &mut self,
+        _block: &BeaconBlock<E>,
+        block_root: Hash256,
+        state: &BeaconState<E>,
+    ) -> Result<(), Self::Error> {
+        self.states.insert(block_root, state.clone());
+        Ok(())
+    }
+
+    fn justified_checkpoint(&self) -> &Checkpoint {
+        &self.justified_checkpoint
+    }
+
+    fn justified_balances(&self) -> &[u64] {
+        &self.justified_balances
+    }
+
+    fn best_justified_checkpoint(&self) -> &Checkpoint {
+        &self.best_justified_checkpoint
+    }
+
+    fn finalized_checkpoint(&self) -> &Checkpoint {
+        &self.finalized_checkpoint
+    }
+
+    fn set_finalized_checkpoint(&mut self, checkpoint: Checkpoint) {
+        self.finalized_checkpoint = checkpoint
+    }
+
+    fn set_justified_checkpoint(&mut self, checkpoint: Checkpoint) -> Result<(), Self::Error> {
+        let state = self.checkpoint_state(&checkpoint)?;
+        self.justified_balances = get_effective_balances(&state);
+        self.justified_checkpoint = checkpoint;
+        Ok(())
+    }
+
+    fn set_best_justified_checkpoint(&mut self, checkpoint: Checkpoint) {
+        self.best_justified_checkpoint = checkpoint
+    }
+
+    fn proposer_boost_root(&self) -> Hash256 {
+        self.proposer_boost_root
+    }
+
+    fn set_proposer_boost_root(&mut self, proposer_boost_root: Hash256) {
+        self.proposer_boost_root = proposer_boost_root
+    }
+}
@@ -109,6 +109,19 @@ macro_rules! type_dispatch {
             _ => Err(Error::FailedToParseTest(format!("unsupported: {}", $value))),
         }
     };
+    ($function:ident,
+     ($($arg:expr),*),
+     $base_ty:tt,
+     <$($param_ty:ty),*>,
+     [ $value:expr => test_union ] $($rest:tt)*) => {
+        match $value {
+            "OptionU16" => type_dispatch!($function, ($($arg),*), $base_ty, <$($param_ty,)* Option<u16>>, $($rest)*),
+            "OptionVarTestStruct" => type_dispatch!($function, ($($arg),*), $base_ty, <$($param_ty,)* Option<VarTestStruct>>, $($rest)*),
+            "UnionU8U16" => type_dispatch!($function, ($($arg),*), $base_ty, <$($param_ty,)* UnionU8U16>, $($rest)*),
+            "UnionSmallVarTestStruct" => type_dispatch!($function, ($($arg),*), $base_ty, <$($param_ty,)* UnionSmallVarTestStruct>, $($rest)*),
+            _ => Err(Error::FailedToParseTest(format!("unsupported: {}", $value))),
+        }
+    };
     // No base type: apply type params to function
     ($function:ident, ($($arg:expr),*), _, <$($param_ty:ty),*>,) => {
         $function::<$($param_ty),*>($($arg),*)
@@ -189,6 +202,17 @@ impl Case for SszGeneric {
                     [type_name => test_container]
                 )?;
             }
+            "union" => {
+                let type_name = parts[0];
+
+                type_dispatch!(
+                    ssz_generic_test,
+                    (&self.path),
+                    _,
+                    <>,
+                    [type_name => test_union]
+                )?;
+            }
             _ => panic!("unsupported handler: {}", self.handler_name),
         }
         Ok(())
@@ -278,6 +302,51 @@ struct BitsStruct {
     E: BitVector<U8>,
 }
 
+// Unions for SSZ generic tests, where `Option<T>` is tested as `Union[None, T]`.
+#[derive(Debug, Clone, PartialEq, Decode, Encode, TreeHash)]
+#[ssz(enum_behaviour = "union")]
+#[tree_hash(enum_behaviour = "union")]
+enum UnionU8U16 {
+    U8(u8),
+    U16(u16),
+}
+
+#[derive(Debug, Clone, PartialEq, Decode, Encode, TreeHash)]
+#[ssz(enum_behaviour = "union")]
+#[tree_hash(enum_behaviour = "union")]
+enum UnionSmallVarTestStruct {
+    Small(SmallTestStruct),
+    Var(VarTestStruct),
+}
+
+/// A union value as given in `value.yaml`.
+#[derive(Deserialize)]
+struct UnionValue {
+    selector: u8,
+    value: serde_yaml::Value,
+}
+
+/// Implements `Deserialize` for a union from a `UnionValue`, taking the variants in selector order.
+macro_rules! impl_union_deserialize {
+    ($union:ident, $($selector:expr => $variant:ident),*) => {
+        impl<'de> serde::Deserialize<'de> for $union {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let UnionValue { selector, value } = UnionValue::deserialize(deserializer)?;
+                match selector {
+                    $($selector => serde_yaml::from_value(value).map($union::$variant),)*
+                    other => {
+                        return Err(D::Error::custom(format!("invalid selector: {}", other)))
+                    }
+                }
+                .map_err(D::Error::custom)
+            }
+        }
+    };
+}
+
+impl_union_deserialize!(UnionU8U16, 0 => U8, 1 => U16);
+impl_union_deserialize!(UnionSmallVarTestStruct, 0 => Small, 1 => Var);
+
 fn byte_list_from_hex_str<'de, D, N: Unsigned>(
     deserializer: D,
 ) -> Result<VariableList<u8, N>, D::Error>
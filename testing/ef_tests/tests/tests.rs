@@ -219,6 +219,7 @@ fn ssz_generic() {
     SszGenericHandler::<Boolean>::run();
     SszGenericHandler::<Uints>::run();
     SszGenericHandler::<Containers>::run();
+    SszGenericHandler::<Union>::run();
 }
 
 #[test]
@@ -251,6 +252,28 @@ fn epoch_processing_final_updates() {
     EpochProcessingHandler::<MainnetEthSpec, FinalUpdates>::run();
 }
 
+#[test]
+fn rewards() {
+    RewardsHandler::<MinimalEthSpec, RewardsBasic>::run();
+    RewardsHandler::<MainnetEthSpec, RewardsBasic>::run();
+    RewardsHandler::<MinimalEthSpec, RewardsLeak>::run();
+    RewardsHandler::<MainnetEthSpec, RewardsLeak>::run();
+    RewardsHandler::<MinimalEthSpec, RewardsRandom>::run();
+    RewardsHandler::<MainnetEthSpec, RewardsRandom>::run();
+}
+
+#[test]
+fn fork_choice_get_head() {
+    ForkChoiceHandler::<MinimalEthSpec, ForkChoiceGetHead>::run();
+    ForkChoiceHandler::<MainnetEthSpec, ForkChoiceGetHead>::run();
+}
+
+#[test]
+fn fork_choice_on_block() {
+    ForkChoiceHandler::<MinimalEthSpec, ForkChoiceOnBlock>::run();
+    ForkChoiceHandler::<MainnetEthSpec, ForkChoiceOnBlock>::run();
+}
+
 #[test]
 fn finality() {
     FinalityHandler::<MinimalEthSpec>::run();
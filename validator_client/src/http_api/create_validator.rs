@@ -1,6 +1,9 @@
 use crate::ValidatorStore;
 use account_utils::{
-    eth2_wallet::{bip39::Mnemonic, WalletBuilder},
+    eth2_wallet::{
+        bip39::{Mnemonic, Seed},
+        ValidatorKeystores, ValidatorPathTemplate,
+    },
     random_mnemonic, random_password, ZeroizeString,
 };
 use eth2::lighthouse_vc::types::{self as api_types};
@@ -19,38 +22,37 @@ use validator_dir::Builder as ValidatorDirBuilder;
 ///
 /// If `mnemonic_opt` is not supplied it will be randomly generated and returned in the response.
 ///
-/// If `key_derivation_path_offset` is supplied then the EIP-2334 validator index will start at
-/// this point.
+/// The keys of each validator are derived at the index of `key_derivation_path_template` given by
+/// the corresponding entry of `key_derivation_path_indices`. If `key_derivation_path_indices` is
+/// not supplied then the validators are derived at consecutive indices, starting from 0.
 pub async fn create_validators<P: AsRef<Path>, T: 'static + SlotClock, E: EthSpec>(
     mnemonic_opt: Option<Mnemonic>,
-    key_derivation_path_offset: Option<u32>,
+    key_derivation_path_template: &ValidatorPathTemplate,
+    key_derivation_path_indices: Option<&[u32]>,
     validator_requests: &[api_types::ValidatorRequest],
     validator_dir: P,
     validator_store: &ValidatorStore<T, E>,
     spec: &ChainSpec,
 ) -> Result<(Vec<api_types::CreatedValidator>, Mnemonic), warp::Rejection> {
     let mnemonic = mnemonic_opt.unwrap_or_else(random_mnemonic);
+    let seed = Seed::new(&mnemonic, "");
 
-    let wallet_password = random_password();
-    let mut wallet =
-        WalletBuilder::from_mnemonic(&mnemonic, wallet_password.as_bytes(), String::new())
-            .and_then(|builder| builder.build())
-            .map_err(|e| {
-                warp_utils::reject::custom_server_error(format!(
-                    "unable to create EIP-2386 wallet: {:?}",
-                    e
-                ))
-            })?;
+    let indices = match key_derivation_path_indices {
+        Some(indices) => indices.to_vec(),
+        None => (0..validator_requests.len() as u32).collect(),
+    };
 
-    if let Some(nextaccount) = key_derivation_path_offset {
-        wallet.set_nextaccount(nextaccount).map_err(|()| {
-            warp_utils::reject::custom_server_error("unable to set wallet nextaccount".to_string())
-        })?;
+    if indices.len() != validator_requests.len() {
+        return Err(warp_utils::reject::custom_server_error(format!(
+            "{} derivation path indices for {} validators",
+            indices.len(),
+            validator_requests.len()
+        )));
     }
 
     let mut validators = Vec::with_capacity(validator_requests.len());
 
-    for request in validator_requests {
+    for (request, index) in validator_requests.iter().zip(indices) {
         let voting_password = random_password();
         let withdrawal_password = random_password();
         let voting_password_string = ZeroizeString::from(
@@ -62,18 +64,19 @@ pub async fn create_validators<P: AsRef<Path>, T: 'static + SlotClock, E: EthSpe
             })?,
         );
 
-        let mut keystores = wallet
-            .next_validator(
-                wallet_password.as_bytes(),
-                voting_password.as_bytes(),
-                withdrawal_password.as_bytes(),
-            )
-            .map_err(|e| {
-                warp_utils::reject::custom_server_error(format!(
-                    "unable to create validator keys: {:?}",
-                    e
-                ))
-            })?;
+        let mut keystores = ValidatorKeystores::recover_from_seed(
+            seed.as_bytes(),
+            key_derivation_path_template,
+            index,
+            voting_password.as_bytes(),
+            withdrawal_password.as_bytes(),
+        )
+        .map_err(|e| {
+            warp_utils::reject::custom_server_error(format!(
+                "unable to create validator keys: {:?}",
+                e
+            ))
+        })?;
 
         keystores
             .voting
@@ -2,14 +2,20 @@ mod api_secret;
 mod create_validator;
 mod tests;
 
+use crate::initialized_validators::InitializedValidators;
 use crate::ValidatorStore;
-use account_utils::mnemonic_from_phrase;
+use account_utils::{
+    eth2_wallet::{bip39::Seed, ValidatorPathTemplate},
+    find_voting_key_indices, is_password_sufficiently_complex, mnemonic_from_phrase,
+};
 use create_validator::create_validators;
 use eth2::lighthouse_vc::types::{self as api_types, PublicKey, PublicKeyBytes};
+use eth2_keystore::{json_keystore::KdfFunction, random_kdf};
 use lighthouse_version::version_with_platform;
 use serde::{Deserialize, Serialize};
 use slog::{crit, info, Logger};
 use slot_clock::SlotClock;
+use std::collections::HashSet;
 use std::future::Future;
 use std::marker::PhantomData;
 use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
@@ -29,6 +35,10 @@ use warp::{
 
 pub use api_secret::ApiSecret;
 
+/// The maximum number of derivation path indices which may be searched for voting keys in a single
+/// `POST lighthouse/validators/mnemonic` request, since each index requires a key derivation.
+pub const MAX_KEY_DERIVATION_PATH_SEARCH_COUNT: u32 = 10_000;
+
 #[derive(Debug)]
 pub enum Error {
     Warp(warp::Error),
@@ -275,6 +285,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                     if let Some(runtime) = runtime.upgrade() {
                         let (validators, mnemonic) = runtime.block_on(create_validators(
                             None,
+                            &ValidatorPathTemplate::default(),
                             None,
                             &body,
                             &validator_dir,
@@ -322,9 +333,81 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                                     e
                                 ))
                             })?;
+                        let template = &body.key_derivation_path_template;
+                        let offset = body.key_derivation_path_offset;
+                        if !template.has_index() && body.validators.len() > 1 {
+                            return Err(warp_utils::reject::custom_bad_request(format!(
+                                "derivation path {} only derives a single validator",
+                                template
+                            )));
+                        }
+                        let indices = if body.voting_pubkeys.is_empty() {
+                            (0..body.validators.len() as u32)
+                                .map(|i| offset.checked_add(i))
+                                .collect::<Option<Vec<_>>>()
+                                .ok_or_else(|| {
+                                    warp_utils::reject::custom_bad_request(
+                                        "derivation path offset is too large".to_string(),
+                                    )
+                                })?
+                        } else {
+                            if body.voting_pubkeys.len() != body.validators.len() {
+                                return Err(warp_utils::reject::custom_bad_request(format!(
+                                    "{} voting pubkeys for {} validators",
+                                    body.voting_pubkeys.len(),
+                                    body.validators.len()
+                                )));
+                            }
+                            if body.key_derivation_path_search_count
+                                > MAX_KEY_DERIVATION_PATH_SEARCH_COUNT
+                            {
+                                return Err(warp_utils::reject::custom_bad_request(format!(
+                                    "derivation path search count {} exceeds the maximum of {}",
+                                    body.key_derivation_path_search_count,
+                                    MAX_KEY_DERIVATION_PATH_SEARCH_COUNT
+                                )));
+                            }
+                            let mut unique_pubkeys = HashSet::new();
+                            if let Some(duplicate) = body
+                                .voting_pubkeys
+                                .iter()
+                                .find(|pubkey| !unique_pubkeys.insert(*pubkey))
+                            {
+                                return Err(warp_utils::reject::custom_bad_request(format!(
+                                    "duplicate voting pubkey: {:?}",
+                                    duplicate
+                                )));
+                            }
+                            let voting_pubkeys = body
+                                .voting_pubkeys
+                                .iter()
+                                .map(PublicKeyBytes::decompress)
+                                .collect::<Result<Vec<_>, _>>()
+                                .map_err(|e| {
+                                    warp_utils::reject::custom_bad_request(format!(
+                                        "invalid voting pubkey: {:?}",
+                                        e
+                                    ))
+                                })?;
+                            let last_index = offset
+                                .checked_add(body.key_derivation_path_search_count)
+                                .ok_or_else(|| {
+                                    warp_utils::reject::custom_bad_request(
+                                        "derivation path search range is too large".to_string(),
+                                    )
+                                })?;
+                            find_voting_key_indices(
+                                Seed::new(&mnemonic, "").as_bytes(),
+                                template,
+                                offset..last_index,
+                                &voting_pubkeys,
+                            )
+                            .map_err(warp_utils::reject::custom_bad_request)?
+                        };
                         let (validators, _mnemonic) = runtime.block_on(create_validators(
                             Some(mnemonic),
-                            Some(body.key_derivation_path_offset),
+                            template,
+                            Some(&indices),
                             &body.validators,
                             &validator_dir,
                             &validator_store,
@@ -414,6 +497,74 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             },
         );
 
+    // POST lighthouse/validators/{validator_pubkey}/reencrypt
+    let post_validators_reencrypt = warp::path("lighthouse")
+        .and(warp::path("validators"))
+        .and(warp::path::param::<PublicKey>())
+        .and(warp::path("reencrypt"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(validator_store_filter.clone())
+        .and(signer.clone())
+        .and(runtime_filter.clone())
+        .and_then(
+            |validator_pubkey: PublicKey,
+             body: api_types::ValidatorReencryptRequest,
+             validator_store: ValidatorStore<T, E>,
+             signer,
+             runtime: Weak<Runtime>| {
+                blocking_signed_json_task(signer, move || {
+                    is_password_sufficiently_complex(body.password.as_ref())
+                        .map_err(warp_utils::reject::custom_bad_request)?;
+
+                    let kdf = random_kdf(body.kdf.unwrap_or(KdfFunction::Scrypt));
+
+                    let initialized_validators_rw_lock = validator_store.initialized_validators();
+
+                    // Only hold the lock to copy the definition, since re-encryption is slow.
+                    let def = initialized_validators_rw_lock
+                        .read()
+                        .validator_definitions()
+                        .iter()
+                        .find(|def| def.voting_public_key == validator_pubkey)
+                        .cloned()
+                        .ok_or_else(|| {
+                            warp_utils::reject::custom_not_found(format!(
+                                "no validator for {:?}",
+                                validator_pubkey
+                            ))
+                        })?;
+
+                    let reencrypted =
+                        def.reencrypt_voting_keystore(body.password, kdf)
+                            .map_err(|e| {
+                                warp_utils::reject::custom_server_error(format!(
+                                    "unable to re-encrypt keystore: {:?}",
+                                    e
+                                ))
+                            })?;
+
+                    if let Some(runtime) = runtime.upgrade() {
+                        runtime
+                            .block_on(InitializedValidators::commit_reencrypted_voting_keystore(
+                                &initialized_validators_rw_lock,
+                                reencrypted,
+                            ))
+                            .map_err(|e| {
+                                warp_utils::reject::custom_server_error(format!(
+                                    "unable to re-encrypt keystore: {:?}",
+                                    e
+                                ))
+                            })
+                    } else {
+                        Err(warp_utils::reject::custom_server_error(
+                            "Runtime shutdown".into(),
+                        ))
+                    }
+                })
+            },
+        );
+
     // PATCH lighthouse/validators/{validator_pubkey}
     let patch_validators = warp::path("lighthouse")
         .and(warp::path("validators"))
@@ -478,7 +629,8 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
         .or(warp::post().and(
             post_validators
                 .or(post_validators_keystore)
-                .or(post_validators_mnemonic),
+                .or(post_validators_mnemonic)
+                .or(post_validators_reencrypt),
         ))
         .or(warp::patch().and(patch_validators))
         // Maps errors into HTTP responses.
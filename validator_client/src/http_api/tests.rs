@@ -2,11 +2,16 @@
 #![cfg(not(debug_assertions))]
 
 use crate::{
-    http_api::{ApiSecret, Config as HttpConfig, Context},
+    http_api::{ApiSecret, Config as HttpConfig, Context, MAX_KEY_DERIVATION_PATH_SEARCH_COUNT},
     Config, ForkServiceBuilder, InitializedValidators, ValidatorDefinitions, ValidatorStore,
 };
 use account_utils::{
-    eth2_wallet::WalletBuilder, mnemonic_from_phrase, random_mnemonic, random_password,
+    eth2_wallet::{
+        bip39::Seed, recover_validator_secret_from_path, KeyType, ValidatorPathTemplate,
+        WalletBuilder,
+    },
+    mnemonic_from_phrase, random_mnemonic, random_password,
+    validator_definitions::SigningDefinition,
     ZeroizeString,
 };
 use deposit_contract::decode_eth1_tx_data;
@@ -15,7 +20,7 @@ use eth2::{
     lighthouse_vc::{http_client::ValidatorClientHttpClient, types::*},
     Url,
 };
-use eth2_keystore::KeystoreBuilder;
+use eth2_keystore::{json_keystore::KdfFunction, keypair_from_secret, Keystore, KeystoreBuilder};
 use parking_lot::RwLock;
 use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
 use slot_clock::TestingSlotClock;
@@ -218,6 +223,9 @@ impl ApiTester {
             let request = CreateValidatorsMnemonicRequest {
                 mnemonic: mnemonic.clone(),
                 key_derivation_path_offset: s.key_derivation_path_offset,
+                key_derivation_path_template: ValidatorPathTemplate::default(),
+                voting_pubkeys: vec![],
+                key_derivation_path_search_count: 0,
                 validators: validators.clone(),
             };
             let response = self
@@ -320,6 +328,101 @@ impl ApiTester {
         self
     }
 
+    pub async fn create_hd_validators_from_pubkeys(self, indices: &[u32]) -> Self {
+        let initial_vals = self.vals_total();
+
+        let mnemonic = random_mnemonic();
+        let seed = Seed::new(&mnemonic, "");
+        let template = ValidatorPathTemplate::default();
+
+        let voting_pubkeys = indices
+            .iter()
+            .map(|index| {
+                let secret = recover_validator_secret_from_path(
+                    seed.as_bytes(),
+                    &template.path(*index, KeyType::Voting),
+                )
+                .unwrap();
+                keypair_from_secret(secret.as_bytes()).unwrap().pk.into()
+            })
+            .collect::<Vec<PublicKeyBytes>>();
+
+        let validators = indices
+            .iter()
+            .map(|index| ValidatorRequest {
+                enable: true,
+                description: format!("boi #{}", index),
+                deposit_gwei: E::default_spec().max_effective_balance,
+            })
+            .collect::<Vec<_>>();
+
+        let request = CreateValidatorsMnemonicRequest {
+            mnemonic: ZeroizeString::from(mnemonic.phrase().to_string()),
+            key_derivation_path_offset: 0,
+            key_derivation_path_template: template.clone(),
+            voting_pubkeys: voting_pubkeys.clone(),
+            key_derivation_path_search_count: 8,
+            validators,
+        };
+
+        /*
+         * A search range above the maximum should be rejected.
+         */
+
+        self.client
+            .post_lighthouse_validators_mnemonic(&CreateValidatorsMnemonicRequest {
+                key_derivation_path_search_count: MAX_KEY_DERIVATION_PATH_SEARCH_COUNT + 1,
+                ..request.clone()
+            })
+            .await
+            .unwrap_err();
+
+        /*
+         * Duplicate voting keys should be rejected.
+         */
+
+        self.client
+            .post_lighthouse_validators_mnemonic(&CreateValidatorsMnemonicRequest {
+                voting_pubkeys: vec![voting_pubkeys[0].clone(); voting_pubkeys.len()],
+                ..request.clone()
+            })
+            .await
+            .unwrap_err();
+
+        assert_eq!(self.vals_total(), initial_vals);
+
+        let response = self
+            .client
+            .post_lighthouse_validators_mnemonic(&request)
+            .await
+            .unwrap()
+            .data;
+
+        assert_eq!(
+            response
+                .iter()
+                .map(|validator| validator.voting_pubkey.clone())
+                .collect::<Vec<_>>(),
+            voting_pubkeys
+        );
+        assert_eq!(self.vals_total(), initial_vals + indices.len());
+
+        /*
+         * A key outside of the search range should be rejected.
+         */
+
+        let request = CreateValidatorsMnemonicRequest {
+            key_derivation_path_search_count: 1,
+            ..request
+        };
+        self.client
+            .post_lighthouse_validators_mnemonic(&request)
+            .await
+            .unwrap_err();
+
+        self
+    }
+
     pub async fn create_keystore_validators(self, s: KeystoreValidatorScenario) -> Self {
         let initial_vals = self.vals_total();
         let initial_enabled_vals = self.vals_enabled();
@@ -419,6 +522,60 @@ impl ApiTester {
 
         self
     }
+
+    pub async fn reencrypt_validator(self, index: usize, kdf: KdfFunction) -> Self {
+        let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
+        let voting_pubkey = validator.voting_pubkey.decompress().unwrap();
+
+        // Passwords which are too short should be rejected.
+        let request = ValidatorReencryptRequest {
+            password: "short".to_string().into(),
+            kdf: Some(kdf.clone()),
+        };
+        self.client
+            .post_lighthouse_validators_reencrypt(&validator.voting_pubkey, &request)
+            .await
+            .unwrap_err();
+
+        let password = random_password();
+        let request = ValidatorReencryptRequest {
+            password: String::from_utf8(password.as_ref().to_vec())
+                .unwrap()
+                .into(),
+            kdf: Some(kdf.clone()),
+        };
+        self.client
+            .post_lighthouse_validators_reencrypt(&validator.voting_pubkey, &request)
+            .await
+            .unwrap();
+
+        let keystore = self
+            .initialized_validators
+            .read()
+            .validator_definitions()
+            .iter()
+            .find(|def| def.voting_public_key == voting_pubkey)
+            .map(|def| match &def.signing_definition {
+                SigningDefinition::LocalKeystore {
+                    voting_keystore_path,
+                    ..
+                } => Keystore::from_json_file(voting_keystore_path).unwrap(),
+            })
+            .unwrap();
+
+        assert_eq!(keystore.kdf().function(), kdf);
+        assert_eq!(
+            keystore.decrypt_keypair(password.as_bytes()).unwrap().pk,
+            voting_pubkey
+        );
+        assert!(self
+            .initialized_validators
+            .read()
+            .voting_keypair(&voting_pubkey)
+            .is_some());
+
+        self
+    }
 }
 
 struct HdValidatorScenario {
@@ -510,6 +667,23 @@ fn hd_validator_creation() {
     );
 }
 
+#[test]
+fn hd_validator_creation_from_pubkeys() {
+    let runtime = build_runtime();
+    let weak_runtime = Arc::downgrade(&runtime);
+    runtime.block_on(
+        async {
+            ApiTester::new(weak_runtime)
+                .await
+                .assert_validators_count(0)
+                .create_hd_validators_from_pubkeys(&[5, 2])
+                .await
+                .assert_validators_count(2);
+        }
+        .compat(),
+    );
+}
+
 #[test]
 fn validator_enabling() {
     let runtime = build_runtime();
@@ -575,3 +749,32 @@ fn keystore_validator_creation() {
         .compat(),
     );
 }
+
+#[test]
+fn validator_reencryption() {
+    let runtime = build_runtime();
+    let weak_runtime = Arc::downgrade(&runtime);
+    runtime.block_on(
+        async {
+            ApiTester::new(weak_runtime)
+                .await
+                .create_hd_validators(HdValidatorScenario {
+                    count: 2,
+                    specify_mnemonic: false,
+                    key_derivation_path_offset: 0,
+                    disabled: vec![1],
+                })
+                .await
+                .reencrypt_validator(0, KdfFunction::Pbkdf2)
+                .await
+                .reencrypt_validator(1, KdfFunction::Pbkdf2)
+                .await
+                .assert_enabled_validators_count(1)
+                .assert_validators_count(2)
+                .set_validator_enabled(1, true)
+                .await
+                .assert_enabled_validators_count(2);
+        }
+        .compat(),
+    );
+}
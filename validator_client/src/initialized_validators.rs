@@ -9,17 +9,21 @@
 use account_utils::{
     read_password, read_password_from_user,
     validator_definitions::{
-        self, SigningDefinition, ValidatorDefinition, ValidatorDefinitions, CONFIG_FILENAME,
+        self, ReencryptedVotingKeystore, SigningDefinition, ValidatorDefinition,
+        ValidatorDefinitions, CONFIG_FILENAME,
     },
     ZeroizeString,
 };
 use eth2_keystore::Keystore;
 use lockfile::{Lockfile, LockfileError};
+use parking_lot::RwLock;
+use rayon::prelude::*;
 use slog::{debug, error, info, warn, Logger};
 use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use types::{Keypair, PublicKey};
 
 use crate::key_cache;
@@ -28,6 +32,15 @@ use crate::key_cache::KeyCache;
 // Use TTY instead of stdin to capture passwords from users.
 const USE_STDIN: bool = false;
 
+/// The maximum number of threads used to decrypt keystores at startup.
+///
+/// Each scrypt keystore decryption uses roughly 256MB of memory with the default parameters, so
+/// this bounds memory usage as well as CPU usage.
+const MAX_DECRYPTION_THREADS: usize = 8;
+
+/// Log decryption progress each time this fraction of the keystores have been decrypted.
+const DECRYPTION_PROGRESS_LOG_INTERVAL: usize = 10;
+
 #[derive(Debug)]
 pub enum Error {
     /// Refused to open a validator with an existing lockfile since that validator may be in-use by
@@ -61,6 +74,10 @@ pub enum Error {
     TokioJoin(tokio::task::JoinError),
     /// Cannot initialize the same validator twice.
     DuplicatePublicKey,
+    /// There is no validator definition with the given voting public key.
+    UnknownValidator(Box<PublicKey>),
+    /// The voting keystore could not be re-encrypted and written to disk.
+    UnableToReencryptKeystore(validator_definitions::Error),
 }
 
 impl From<LockfileError> for Error {
@@ -395,19 +412,151 @@ impl InitializedValidators {
         Ok(())
     }
 
+    /// Replaces a voting keystore with one re-encrypted by
+    /// `ValidatorDefinition::reencrypt_voting_keystore`.
+    ///
+    /// The keystore (and password file, if any) is replaced on disk, the `ValidatorDefinitions`
+    /// are saved and the key cache entry for the old keystore is replaced with the new one.
+    ///
+    /// ## Notes
+    ///
+    /// Re-encryption is expensive, so it should be performed on a copy of the definition from
+    /// `Self::validator_definitions` without holding a lock on `initialized_validators`. The key
+    /// cache is likewise decrypted and saved without holding a lock, and the write lock is only
+    /// taken to commit the new keystore. Returns an error (and leaves the key cache untouched) if
+    /// the definition has changed in the meantime.
+    pub async fn commit_reencrypted_voting_keystore(
+        initialized_validators: &RwLock<Self>,
+        reencrypted: ReencryptedVotingKeystore,
+    ) -> Result<(), Error> {
+        let voting_public_key = reencrypted.keypair.pk.clone();
+        let (definitions, validators_dir, log) = {
+            let initialized_validators = initialized_validators.read();
+            (
+                initialized_validators.definitions.as_slice().to_vec(),
+                initialized_validators.validators_dir.clone(),
+                initialized_validators.log.clone(),
+            )
+        };
+        let def = definitions
+            .iter()
+            .find(|def| def.voting_public_key == voting_public_key)
+            .ok_or_else(|| Error::UnknownValidator(Box::new(voting_public_key.clone())))?;
+
+        // Hold the cache lock until the cache is saved so the cache cannot be updated
+        // concurrently with stale data.
+        let key_cache_path = KeyCache::cache_file_path(&validators_dir);
+        let cache_lockfile_path = get_lockfile_path(&key_cache_path)
+            .ok_or_else(|| Error::BadKeyCachePath(key_cache_path))?;
+        let _cache_lockfile = Lockfile::new(cache_lockfile_path)?;
+
+        let mut key_stores = HashMap::new();
+        let cache =
+            KeyCache::open_or_create(&validators_dir).map_err(Error::UnableToOpenKeyCache)?;
+        let mut key_cache =
+            Self::decrypt_key_cache(&definitions, &log, cache, &mut key_stores).await?;
+
+        let old_uuid = match &def.signing_definition {
+            SigningDefinition::LocalKeystore {
+                voting_keystore_path,
+                ..
+            } => match key_stores.get(voting_keystore_path) {
+                Some(keystore) => *keystore.uuid(),
+                None => *open_keystore(voting_keystore_path)?.uuid(),
+            },
+        };
+        key_cache.remove(&old_uuid);
+        if def.enabled {
+            key_cache.add(
+                reencrypted.keypair.clone(),
+                reencrypted.keystore.uuid(),
+                reencrypted.password.as_ref().to_vec().into(),
+            );
+        }
+
+        initialized_validators
+            .write()
+            .swap_reencrypted_voting_keystore(reencrypted)?;
+
+        // A stale cache is detected and discarded on the next start, so a failure to save is
+        // not fatal.
+        let save_log = log.clone();
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = key_cache.save(validators_dir) {
+                warn!(
+                    save_log,
+                    "Error during saving of key_cache";
+                    "err" => format!("{:?}", e)
+                );
+            }
+        })
+        .await
+        .map_err(Error::TokioJoin)?;
+
+        info!(
+            log,
+            "Re-encrypted voting keystore";
+            "voting_pubkey" => format!("{:?}", voting_public_key)
+        );
+
+        Ok(())
+    }
+
+    /// Commits `reencrypted` to `self.definitions` and swaps the new keystore into the
+    /// initialized validator, if it is enabled.
+    fn swap_reencrypted_voting_keystore(
+        &mut self,
+        reencrypted: ReencryptedVotingKeystore,
+    ) -> Result<(), Error> {
+        self.definitions
+            .commit_reencrypted_voting_keystore(&self.validators_dir, &reencrypted)
+            .map_err(Error::UnableToReencryptKeystore)?;
+
+        let voting_public_key = &reencrypted.keypair.pk;
+        let new_keystore_path = self
+            .definitions
+            .as_slice()
+            .iter()
+            .find(|def| def.voting_public_key == *voting_public_key)
+            .map(|def| match &def.signing_definition {
+                SigningDefinition::LocalKeystore {
+                    voting_keystore_path,
+                    ..
+                } => voting_keystore_path.clone(),
+            });
+
+        if let Some(validator) = self.validators.get_mut(voting_public_key) {
+            match &mut validator.signing_method {
+                SigningMethod::LocalKeystore {
+                    voting_keystore_path,
+                    voting_keystore,
+                    ..
+                } => {
+                    if let Some(path) = new_keystore_path {
+                        *voting_keystore_path = path;
+                    }
+                    *voting_keystore = reencrypted.keystore;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Tries to decrypt the key cache.
     ///
     /// Returns `Ok(true)` if decryption was successful, `Ok(false)` if it couldn't get decrypted
     /// and an error if a needed password couldn't get extracted.
     ///
     async fn decrypt_key_cache(
-        &self,
+        definitions: &[ValidatorDefinition],
+        log: &Logger,
         mut cache: KeyCache,
         key_stores: &mut HashMap<PathBuf, Keystore>,
     ) -> Result<KeyCache, Error> {
         //read relevant key_stores
         let mut definitions_map = HashMap::new();
-        for def in self.definitions.as_slice() {
+        for def in definitions {
             match &def.signing_definition {
                 SigningDefinition::LocalKeystore {
                     voting_keystore_path,
@@ -427,7 +576,7 @@ impl InitializedValidators {
         for uuid in cache.uuids() {
             if !definitions_map.contains_key(uuid) {
                 warn!(
-                    self.log,
+                    log,
                     "Unknown uuid in cache";
                     "uuid" => format!("{}", uuid)
                 );
@@ -473,6 +622,125 @@ impl InitializedValidators {
         .map_err(Error::TokioJoin)
     }
 
+    /// Decrypts the voting keystores of all enabled, uninitialized validators which are missing
+    /// from `key_cache`, spreading the work across a bounded pool of threads. Each decrypted
+    /// keypair is added to `key_cache`, so that it can be used by
+    /// `InitializedValidator::from_definition`.
+    ///
+    /// Keystores without a stored password and keystores which cannot be opened, read or
+    /// decrypted are skipped. They are handled (and any error reported) when the validator is
+    /// initialized.
+    async fn decrypt_keystores(
+        &self,
+        key_cache: &mut KeyCache,
+        key_stores: &mut HashMap<PathBuf, Keystore>,
+    ) -> Result<(), Error> {
+        let mut uuids = HashSet::new();
+        let mut jobs = vec![];
+        for def in self.definitions.as_slice() {
+            if !def.enabled || self.validators.contains_key(&def.voting_public_key) {
+                continue;
+            }
+
+            match &def.signing_definition {
+                SigningDefinition::LocalKeystore {
+                    voting_keystore_path,
+                    voting_keystore_password_path,
+                    voting_keystore_password,
+                } => {
+                    use std::collections::hash_map::Entry::*;
+                    let keystore = match key_stores.entry(voting_keystore_path.clone()) {
+                        Vacant(entry) => match open_keystore(voting_keystore_path) {
+                            Ok(keystore) => entry.insert(keystore),
+                            Err(_) => continue,
+                        },
+                        Occupied(entry) => entry.into_mut(),
+                    };
+
+                    if key_cache.get(keystore.uuid()).is_some() || !uuids.insert(*keystore.uuid()) {
+                        continue;
+                    }
+
+                    let password = match (voting_keystore_password_path, voting_keystore_password) {
+                        (_, Some(password)) => password.as_ref().to_vec().into(),
+                        (Some(path), None) => match read_password(path) {
+                            Ok(password) => password,
+                            Err(_) => continue,
+                        },
+                        // The user will be prompted for the password during initialization.
+                        (None, None) => continue,
+                    };
+
+                    jobs.push((keystore.clone(), password));
+                }
+            }
+        }
+
+        if jobs.is_empty() {
+            return Ok(());
+        }
+
+        let total = jobs.len();
+        let num_threads = std::cmp::min(rayon::current_num_threads(), MAX_DECRYPTION_THREADS);
+        info!(
+            self.log,
+            "Decrypting validator keystores";
+            "count" => total,
+            "threads" => num_threads,
+        );
+
+        let log = self.log.clone();
+        // Decrypting keystores can take several seconds each, therefore it's best to keep it off
+        // the core executor.
+        let decrypted = tokio::task::spawn_blocking(move || {
+            let pool = match rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+            {
+                Ok(pool) => pool,
+                Err(e) => {
+                    warn!(
+                        log,
+                        "Unable to build keystore decryption pool";
+                        "error" => format!("{:?}", e)
+                    );
+                    return vec![];
+                }
+            };
+
+            let log_interval = std::cmp::max(total / DECRYPTION_PROGRESS_LOG_INTERVAL, 1);
+            let completed = AtomicUsize::new(0);
+            pool.install(|| {
+                jobs.into_par_iter()
+                    .filter_map(|(keystore, password)| {
+                        let keypair = keystore.decrypt_keypair(password.as_bytes()).ok();
+
+                        let completed = completed.fetch_add(1, Ordering::Relaxed) + 1;
+                        if completed % log_interval == 0 || completed == total {
+                            info!(
+                                log,
+                                "Decrypted validator keystores";
+                                "completed" => completed,
+                                "total" => total,
+                            );
+                        }
+
+                        keypair.map(|keypair| (*keystore.uuid(), keypair, password))
+                    })
+                    .collect::<Vec<_>>()
+            })
+        })
+        .await
+        .map_err(Error::TokioJoin)?;
+
+        // Only the async task mutates the cache, which keeps it consistent with the keystores.
+        for (uuid, keypair, password) in decrypted {
+            key_cache.add(keypair, &uuid, password);
+        }
+
+        Ok(())
+    }
+
     /// Scans `self.definitions` and attempts to initialize and validators which are not already
     /// initialized.
     ///
@@ -496,7 +764,15 @@ impl InitializedValidators {
 
         let cache =
             KeyCache::open_or_create(&self.validators_dir).map_err(Error::UnableToOpenKeyCache)?;
-        let mut key_cache = self.decrypt_key_cache(cache, &mut key_stores).await?;
+        let mut key_cache = Self::decrypt_key_cache(
+            self.definitions.as_slice(),
+            &self.log,
+            cache,
+            &mut key_stores,
+        )
+        .await?;
+        self.decrypt_keystores(&mut key_cache, &mut key_stores)
+            .await?;
 
         let mut disabled_uuids = HashSet::new();
         for def in self.definitions.as_slice() {
@@ -607,3 +883,114 @@ impl InitializedValidators {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use environment::null_logger;
+    use eth2_keystore::{
+        json_keystore::{Kdf, Pbkdf2, Prf},
+        KeystoreBuilder, DKLEN,
+    };
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    const PASSWORD: &str = "password";
+
+    /// Writes a keystore for a random keypair to `dir`, encrypted with `PASSWORD` using a cheap
+    /// `Kdf`, since the default parameters are very slow in debug builds.
+    fn keystore_fixture(dir: &Path, name: &str) -> (Keypair, Keystore, PathBuf) {
+        let keypair = Keypair::random();
+        let keystore = KeystoreBuilder::new(&keypair, PASSWORD.as_bytes(), String::new())
+            .unwrap()
+            .kdf(Kdf::Pbkdf2(Pbkdf2 {
+                dklen: DKLEN,
+                c: 2,
+                prf: Prf::HmacSha256,
+                salt: vec![42; 32].into(),
+            }))
+            .build()
+            .unwrap();
+        let path = dir.join(format!("{}.json", name));
+        std::fs::write(&path, keystore.to_json_string().unwrap()).unwrap();
+        (keypair, keystore, path)
+    }
+
+    #[tokio::test]
+    async fn decrypt_keystores_in_parallel() {
+        let dir = tempdir().unwrap();
+        let mut definitions = ValidatorDefinitions::default();
+
+        let good = (0..4)
+            .map(|i| {
+                let (keypair, keystore, path) = keystore_fixture(dir.path(), &i.to_string());
+                definitions.push(
+                    ValidatorDefinition::new_keystore_with_password(
+                        &path,
+                        Some(PASSWORD.to_string().into()),
+                    )
+                    .unwrap(),
+                );
+                (keypair, keystore)
+            })
+            .collect::<Vec<_>>();
+
+        let (_, wrong_password, path) = keystore_fixture(dir.path(), "wrong_password");
+        definitions.push(
+            ValidatorDefinition::new_keystore_with_password(
+                &path,
+                Some("not_the_password".to_string().into()),
+            )
+            .unwrap(),
+        );
+
+        // Since the wrong password fails initialization first, this validator never reaches the
+        // password prompt.
+        let (_, no_password, path) = keystore_fixture(dir.path(), "no_password");
+        definitions.push(ValidatorDefinition::new_keystore_with_password(&path, None).unwrap());
+
+        let mut validators = InitializedValidators {
+            definitions,
+            validators_dir: dir.path().into(),
+            validators: HashMap::default(),
+            log: null_logger().unwrap(),
+        };
+
+        let mut key_cache = KeyCache::new();
+        let mut key_stores = HashMap::new();
+        validators
+            .decrypt_keystores(&mut key_cache, &mut key_stores)
+            .await
+            .unwrap();
+
+        for (keypair, keystore) in &good {
+            assert_eq!(
+                key_cache.get(keystore.uuid()).map(|kp| kp.pk),
+                Some(keypair.pk.clone())
+            );
+        }
+        assert!(key_cache.get(wrong_password.uuid()).is_none());
+        assert!(key_cache.get(no_password.uuid()).is_none());
+
+        // The bad keystores are left to `InitializedValidator::from_definition`, which reports
+        // the invalid password.
+        let result = validators.update_validators().await;
+        assert!(
+            matches!(
+                result,
+                Err(Error::UnableToDecryptKeystore(
+                    eth2_keystore::Error::InvalidPassword
+                ))
+            ),
+            "{:?}",
+            result
+        );
+        assert_eq!(validators.num_enabled(), good.len());
+        for (keypair, _) in &good {
+            assert_eq!(
+                validators.voting_keypair(&keypair.pk).map(|kp| &kp.pk),
+                Some(&keypair.pk)
+            );
+        }
+    }
+}
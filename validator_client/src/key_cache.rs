@@ -1,4 +1,4 @@
-use account_utils::create_with_600_perms;
+use account_utils::{create_with_600_perms, validator_definitions::KEY_CACHE_FILENAME};
 use bls::{Keypair, PublicKey};
 use eth2_keystore::json_keystore::{
     Aes128Ctr, ChecksumModule, Cipher, CipherModule, Crypto, EmptyMap, EmptyString, KdfModule,
@@ -16,7 +16,7 @@ use std::path::{Path, PathBuf};
 use std::{fs, io};
 
 /// The file name for the serialized `KeyCache` struct.
-pub const CACHE_FILENAME: &str = "validator_key_cache.json";
+pub const CACHE_FILENAME: &str = KEY_CACHE_FILENAME;
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum State {